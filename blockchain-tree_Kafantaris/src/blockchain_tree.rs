@@ -1,9 +1,12 @@
 //! Implementation of [`BlockchainTree`]
 
 use crate::{
+    journal::{
+        TreeJournalEntry, TreeJournalEntryKind, TreeJournalWriter, TREE_JOURNAL_WRITE_INTERVAL,
+    },
     metrics::{MakeCanonicalAction, MakeCanonicalDurationsRecorder, TreeMetrics},
     state::{BlockchainId, TreeState},
-    AppendableChain, BlockIndices, BlockchainTreeConfig, ExecutionData, TreeExternals,
+    AppendableChain, BlockIndices, BlockchainTreeConfig, ExecutionData, TreeExternals, TreeJournal,
 };
 use reth_blockchain_tree_api::{
     error::{BlockchainTreeError, CanonicalError, InsertBlockError, InsertBlockErrorKind},
@@ -28,6 +31,8 @@ use reth_stages_api::{MetricEvent, MetricEventsSender};
 use reth_storage_errors::provider::{ProviderResult, RootMismatch};
 use std::{
     collections::{btree_map::Entry, BTreeMap, HashSet},
+    io,
+    path::{Path, PathBuf},
     sync::Arc,
 };
 use tracing::{debug, error, info, instrument, trace, warn};
@@ -73,6 +78,10 @@ pub struct BlockchainTree<DB, E> {
     sync_metrics_tx: Option<MetricEventsSender>,
     /// Metrics for the blockchain tree.
     metrics: TreeMetrics,
+    /// Path of the journal that the sidechains and buffered blocks are persisted to, if any.
+    journal_path: Option<PathBuf>,
+    /// Writes the journal in the background after canonicalizations, if the tree has a journal.
+    journal_writer: Option<TreeJournalWriter>,
 }
 
 impl<DB, E> BlockchainTree<DB, E> {
@@ -87,6 +96,84 @@ impl<DB, E> BlockchainTree<DB, E> {
     pub fn canon_state_notification_sender(&self) -> CanonStateNotificationSender {
         self.canon_state_notification_sender.clone()
     }
+
+    /// Returns the path of the journal, if the tree has one.
+    pub fn journal_path(&self) -> Option<&Path> {
+        self.journal_path.as_deref()
+    }
+
+    /// Writes the blocks of all sidechains and of the block buffer to the journal, if the tree has
+    /// one.
+    ///
+    /// Blocks that are [`BlockchainTreeConfig::max_blocks_in_chain`] or more blocks below the
+    /// canonical tip are not journaled.
+    ///
+    /// The journal is also written in the background after canonicalizations, at most once per
+    /// [`TREE_JOURNAL_WRITE_INTERVAL`], and when the tree is dropped.
+    pub fn write_journal(&self) -> io::Result<()> {
+        let Some(path) = self.journal_path() else { return Ok(()) };
+
+        let journal = self.journal();
+        journal.write(path)?;
+        debug!(target: "blockchain_tree", blocks = journal.len(), ?path, "Wrote tree journal");
+        Ok(())
+    }
+
+    /// Returns the blocks of all sidechains and of the block buffer that are journaled.
+    fn journal(&self) -> TreeJournal {
+        let lowest_block = self
+            .state
+            .block_indices
+            .canonical_tip()
+            .number
+            .saturating_sub(self.config.max_blocks_in_chain());
+
+        let sidechain_blocks = self
+            .state
+            .chains
+            .values()
+            .flat_map(|chain| chain.blocks().values())
+            .map(|block| (TreeJournalEntryKind::Sidechain, block));
+        let buffered_blocks = self
+            .state
+            .buffered_blocks
+            .blocks()
+            .values()
+            .map(|block| (TreeJournalEntryKind::Buffered, block));
+        let entries = sidechain_blocks
+            .chain(buffered_blocks)
+            .filter(|(_, block)| block.number > lowest_block)
+            .map(|(kind, block)| TreeJournalEntry { kind, block: block.clone() })
+            .collect();
+
+        TreeJournal { entries }
+    }
+
+    /// Hands the journal over to the background writer, so that it is written without blocking
+    /// the canonicalization.
+    fn schedule_journal_write(&self) {
+        if let Some(writer) = &self.journal_writer {
+            writer.send(self.journal());
+        }
+    }
+
+    /// Writes the journal, see [`Self::write_journal`], and logs a failure instead of returning
+    /// it.
+    fn try_write_journal(&self) {
+        if let Err(err) = self.write_journal() {
+            warn!(target: "blockchain_tree", %err, "Failed to write tree journal");
+        }
+    }
+}
+
+impl<DB, E> Drop for BlockchainTree<DB, E> {
+    fn drop(&mut self) {
+        // a background write still in progress must not replace the final journal
+        if let Some(writer) = self.journal_writer.take() {
+            writer.shutdown();
+        }
+        self.try_write_journal();
+    }
 }
 
 impl<DB, E> BlockchainTree<DB, E>
@@ -142,9 +229,72 @@ where
             canon_state_notification_sender,
             sync_metrics_tx: None,
             metrics: Default::default(),
+            journal_path: None,
+            journal_writer: None,
         })
     }
 
+    /// Persists the sidechains and buffered blocks of the tree to the journal at the given path,
+    /// so that they survive a restart, and restores the blocks that are already journaled there.
+    ///
+    /// See also [`crate::journal`].
+    pub fn with_journal(mut self, path: impl Into<PathBuf>) -> Self {
+        let path = path.into();
+        match TreeJournalWriter::spawn(path.clone(), TREE_JOURNAL_WRITE_INTERVAL) {
+            Ok(writer) => self.journal_writer = Some(writer),
+            Err(err) => {
+                warn!(target: "blockchain_tree", %err, "Failed to spawn tree journal writer, the journal is only written on shutdown");
+            }
+        }
+        self.journal_path = Some(path);
+        self.restore_journal();
+        self
+    }
+
+    /// Reinserts the blocks of the journal into the tree.
+    ///
+    /// Sidechain blocks are executed again. Blocks that can no longer be inserted, e.g. because
+    /// they were finalized in the meantime, are skipped.
+    fn restore_journal(&mut self) {
+        let Some(path) = self.journal_path.clone() else { return };
+
+        let journal = match TreeJournal::read(&path) {
+            Ok(Some(journal)) => journal,
+            Ok(None) => return,
+            Err(err) => {
+                warn!(target: "blockchain_tree", %err, ?path, "Failed to read tree journal");
+                return
+            }
+        };
+
+        let (mut sidechain_blocks, buffered_blocks): (Vec<_>, Vec<_>) = journal
+            .entries
+            .into_iter()
+            .partition(|entry| entry.kind == TreeJournalEntryKind::Sidechain);
+
+        // buffered blocks go first, so that they are connected if their parent is reinserted
+        for entry in buffered_blocks {
+            if let Err(err) = self.buffer_block(entry.block) {
+                debug!(target: "blockchain_tree", %err, "Failed to restore buffered block");
+            }
+        }
+
+        // parents have to be inserted before their children
+        sidechain_blocks.sort_unstable_by_key(|entry| entry.block.number);
+        for entry in sidechain_blocks {
+            if let Err(err) = self.insert_block(entry.block, BlockValidationKind::Exhaustive) {
+                debug!(target: "blockchain_tree", %err, "Failed to restore sidechain block");
+            }
+        }
+
+        info!(
+            target: "blockchain_tree",
+            sidechains = self.state.chains.len(),
+            buffered_blocks = self.state.buffered_blocks.blocks().len(),
+            "Restored tree journal"
+        );
+    }
+
     /// Replaces the canon state notification sender.
     ///
     /// Caution: this will close any existing subscriptions to the previous sender.
@@ -1202,6 +1352,9 @@ where
 
         durations_recorder.record_relative(MakeCanonicalAction::ClearTrieUpdatesForOtherChildren);
 
+        // persist the sidechains that are left over after the canonicalization
+        self.schedule_journal_write();
+
         // Send notification about new canonical chain and return outcome of canonicalization.
         let outcome = CanonicalOutcome::Committed { head: chain_notification.tip().header.clone() };
         let _ = self.canon_state_notification_sender.send(chain_notification);
@@ -2502,4 +2655,140 @@ mod tests {
         assert_eq!(tree.block_indices().last_finalized_block(), block1a.number);
     }
 
+    #[test]
+    fn restore_tree_journal() {
+        let data = BlockchainTestData::default_from_number(11);
+        let (block1, exec1) = data.blocks[0].clone();
+        let (block2, exec2) = data.blocks[1].clone();
+        let genesis = data.genesis;
+
+        // blocks are executed once when inserted and once more when restored.
+        let externals = setup_externals(vec![exec2.clone(), exec1.clone(), exec2, exec1]);
+        let restored_externals = TreeExternals {
+            provider_factory: externals.provider_factory.clone(),
+            executor_factory: externals.executor_factory.clone(),
+            consensus: externals.consensus.clone(),
+        };
+
+        // last finalized block would be number 9.
+        setup_genesis(&externals.provider_factory, genesis);
+
+        // make tree that journals its blocks
+        let dir = tempfile::tempdir().unwrap();
+        let journal_path = dir.path().join("journal");
+        let config = BlockchainTreeConfig::new(1, 2, 3, 2);
+        let mut tree = BlockchainTree::new(externals, config, None)
+            .expect("failed to create tree")
+            .with_journal(&journal_path);
+
+        assert_eq!(
+            tree.insert_block(block1.clone(), BlockValidationKind::Exhaustive).unwrap(),
+            InsertPayloadOk::Inserted(BlockStatus::Valid(BlockAttachment::Canonical))
+        );
+        assert_eq!(
+            tree.insert_block(block2.clone(), BlockValidationKind::Exhaustive).unwrap(),
+            InsertPayloadOk::Inserted(BlockStatus::Valid(BlockAttachment::Canonical))
+        );
+
+        // buffer unconnected block2b
+        let mut block2b = block2.clone();
+        block2b.set_hash(B256::new([0x99; 32]));
+        block2b.set_parent_hash(B256::new([0x88; 32]));
+        tree.buffer_block(block2b.clone()).unwrap();
+
+        // restart the tree, the journal is written on drop
+        drop(tree);
+        let tree = BlockchainTree::new(restored_externals, config, None)
+            .expect("failed to create tree")
+            .with_journal(&journal_path);
+
+        // Trie state:
+        //      b2 (pending block)   b2b (buffered block)
+        //      |
+        //      |
+        //      b1 (pending block)
+        //    /
+        //  /
+        // g1 (canonical blocks)
+        // |
+        TreeTester::default()
+            .with_chain_num(1)
+            .with_block_to_chain(HashMap::from([
+                (block1.hash(), 0.into()),
+                (block2.hash(), 0.into()),
+            ]))
+            .with_fork_to_child(HashMap::from([(
+                block1.parent_hash,
+                HashSet::from([block1.hash()]),
+            )]))
+            .with_buffered_blocks(HashMap::from([(block2b.hash(), block2b)]))
+            .assert(&tree);
+    }
+
+    #[test]
+    fn tree_journal_survives_crash() {
+        let data = BlockchainTestData::default_from_number(11);
+        let (block1, exec1) = data.blocks[0].clone();
+        let (block2, exec2) = data.blocks[1].clone();
+        let genesis = data.genesis;
+
+        // block2 is executed once when inserted and once more when restored.
+        let externals = setup_externals(vec![exec2.clone(), exec2, exec1]);
+        let restored_externals = TreeExternals {
+            provider_factory: externals.provider_factory.clone(),
+            executor_factory: externals.executor_factory.clone(),
+            consensus: externals.consensus.clone(),
+        };
+
+        // last finalized block would be number 9.
+        setup_genesis(&externals.provider_factory, genesis);
+
+        let dir = tempfile::tempdir().unwrap();
+        let journal_path = dir.path().join("journal");
+        let config = BlockchainTreeConfig::new(1, 2, 3, 2);
+        let mut tree = BlockchainTree::new(externals, config, None)
+            .expect("failed to create tree")
+            .with_journal(&journal_path);
+
+        tree.insert_block(block1.clone(), BlockValidationKind::Exhaustive).unwrap();
+        tree.insert_block(block2.clone(), BlockValidationKind::Exhaustive).unwrap();
+        let mut block2b = block2.clone();
+        block2b.set_hash(B256::new([0x99; 32]));
+        block2b.set_parent_hash(B256::new([0x88; 32]));
+        tree.buffer_block(block2b.clone()).unwrap();
+
+        // the journal is written in the background when block1 is made canonical
+        tree.make_canonical(block1.hash()).unwrap();
+        let deadline = std::time::Instant::now() + std::time::Duration::from_secs(10);
+        while TreeJournal::read(&journal_path).unwrap().map_or(0, |journal| journal.len()) != 2 {
+            assert!(std::time::Instant::now() < deadline, "journal was not written");
+            std::thread::sleep(std::time::Duration::from_millis(10));
+        }
+
+        // crash, so that the journal is not written on drop
+        std::mem::forget(tree);
+        let tree = BlockchainTree::new(restored_externals, config, None)
+            .expect("failed to create tree")
+            .with_journal(&journal_path);
+
+        // Trie state:
+        //      b2 (pending block)   b2b (buffered block)
+        //      |
+        //      |
+        //      b1 (canonical block)
+        //      |
+        //      |
+        // g1 (canonical blocks)
+        // |
+        TreeTester::default()
+            .with_chain_num(1)
+            .with_block_to_chain(HashMap::from([(block2.hash(), 0.into())]))
+            .with_fork_to_child(HashMap::from([(
+                block2.parent_hash,
+                HashSet::from([block2.hash()]),
+            )]))
+            .with_buffered_blocks(HashMap::from([(block2b.hash(), block2b)]))
+            .assert(&tree);
+    }
+
 }
\ No newline at end of file
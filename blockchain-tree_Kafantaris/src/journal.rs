@@ -0,0 +1,345 @@
+//! On-disk journal of the non-canonical state of the [`BlockchainTree`](crate::BlockchainTree).
+//!
+//! Sidechains and buffered blocks are only kept in memory, so they are lost on restart. If the
+//! tree has a journal, see [`BlockchainTree::with_journal`], the blocks of both are written to the
+//! journal and reinserted into the tree when it is created again.
+//!
+//! The journal is written by a background thread after canonicalizations, at most once per
+//! [`TREE_JOURNAL_WRITE_INTERVAL`], and once more when the tree is dropped.
+//!
+//! Only the blocks themselves are journaled. The execution results of sidechain blocks are
+//! recomputed when the blocks are reinserted.
+//!
+//! A journal file starts with the [`TREE_JOURNAL_MAGIC`] bytes, followed by a single version byte
+//! and the RLP encoded list of [`TreeJournalEntry`]s.
+//!
+//! [`BlockchainTree::with_journal`]: crate::BlockchainTree::with_journal
+
+use alloy_rlp::{BufMut, Decodable, Encodable, Header};
+use reth_primitives::{Address, Block, BlockHash, SealedBlockWithSenders};
+use std::{
+    fs::{self, File},
+    io::{self, Write},
+    path::{Path, PathBuf},
+    sync::mpsc::{self, RecvTimeoutError},
+    thread,
+    time::{Duration, Instant},
+};
+use tracing::{debug, warn};
+
+/// The magic bytes every journal file starts with.
+pub const TREE_JOURNAL_MAGIC: [u8; 4] = *b"rbtj";
+
+/// The current version of the journal file format.
+pub const TREE_JOURNAL_VERSION: u8 = 1;
+
+/// The minimum time between two journal writes after canonicalizations.
+pub const TREE_JOURNAL_WRITE_INTERVAL: Duration = Duration::from_secs(10);
+
+/// Where a journaled block was kept in the tree.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum TreeJournalEntryKind {
+    /// The block was part of a sidechain.
+    Sidechain,
+    /// The block was in the buffer of unconnected blocks.
+    Buffered,
+}
+
+/// A single block of a [`TreeJournal`].
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct TreeJournalEntry {
+    /// Where the block was kept in the tree.
+    pub kind: TreeJournalEntryKind,
+    /// The journaled block.
+    pub block: SealedBlockWithSenders,
+}
+
+impl TreeJournalEntry {
+    /// Returns the length of the RLP encoded fields of the entry.
+    fn fields_len(&self) -> usize {
+        (self.kind as u8).length() +
+            self.block.hash().length() +
+            self.block.senders.length() +
+            self.block.block.length()
+    }
+}
+
+impl Encodable for TreeJournalEntry {
+    fn encode(&self, out: &mut dyn BufMut) {
+        Header { list: true, payload_length: self.fields_len() }.encode(out);
+        (self.kind as u8).encode(out);
+        self.block.hash().encode(out);
+        self.block.senders.encode(out);
+        self.block.block.encode(out);
+    }
+
+    fn length(&self) -> usize {
+        let payload_length = self.fields_len();
+        payload_length + alloy_rlp::length_of_length(payload_length)
+    }
+}
+
+impl Decodable for TreeJournalEntry {
+    fn decode(buf: &mut &[u8]) -> alloy_rlp::Result<Self> {
+        let header = Header::decode(buf)?;
+        if !header.list {
+            return Err(alloy_rlp::Error::UnexpectedString)
+        }
+        let remaining = buf.len();
+
+        let kind = match u8::decode(buf)? {
+            0 => TreeJournalEntryKind::Sidechain,
+            1 => TreeJournalEntryKind::Buffered,
+            _ => return Err(alloy_rlp::Error::Custom("invalid journal entry kind")),
+        };
+        // the hash is stored explicitly, so that the block does not have to be resealed
+        let hash = BlockHash::decode(buf)?;
+        let senders = Vec::<Address>::decode(buf)?;
+        let block = Block::decode(buf)?.seal(hash);
+
+        let consumed = remaining - buf.len();
+        if consumed != header.payload_length {
+            return Err(alloy_rlp::Error::ListLengthMismatch {
+                expected: header.payload_length,
+                got: consumed,
+            })
+        }
+
+        let block = SealedBlockWithSenders::new(block, senders)
+            .ok_or(alloy_rlp::Error::Custom("senders do not match block transactions"))?;
+        Ok(Self { kind, block })
+    }
+}
+
+/// The journaled blocks of the tree.
+#[derive(Debug, Clone, Default, PartialEq, Eq)]
+pub struct TreeJournal {
+    /// The journaled blocks, in no particular order.
+    pub entries: Vec<TreeJournalEntry>,
+}
+
+impl TreeJournal {
+    /// Returns the number of journaled blocks.
+    pub fn len(&self) -> usize {
+        self.entries.len()
+    }
+
+    /// Returns `true` if the journal contains no blocks.
+    pub fn is_empty(&self) -> bool {
+        self.entries.is_empty()
+    }
+
+    /// Encodes the journal in the current version of the file format.
+    pub fn encode(&self) -> Vec<u8> {
+        let mut buf = Vec::with_capacity(TREE_JOURNAL_MAGIC.len() + 1);
+        buf.extend_from_slice(&TREE_JOURNAL_MAGIC);
+        buf.push(TREE_JOURNAL_VERSION);
+        alloy_rlp::encode_list(&self.entries, &mut buf);
+        buf
+    }
+
+    /// Decodes a journal from the given file contents.
+    pub fn decode(data: &[u8]) -> io::Result<Self> {
+        let data = data
+            .strip_prefix(&TREE_JOURNAL_MAGIC)
+            .ok_or_else(|| invalid_data("missing journal magic"))?;
+        let (&version, mut data) =
+            data.split_first().ok_or_else(|| invalid_data("missing journal version"))?;
+        if version != TREE_JOURNAL_VERSION {
+            return Err(invalid_data(format!("unsupported journal version {version}")))
+        }
+
+        let entries = Vec::<TreeJournalEntry>::decode(&mut data).map_err(invalid_data)?;
+        Ok(Self { entries })
+    }
+
+    /// Reads the journal from the given file.
+    ///
+    /// Returns `Ok(None)` if the file does not exist.
+    pub fn read(path: &Path) -> io::Result<Option<Self>> {
+        match fs::read(path) {
+            Ok(data) => Self::decode(&data).map(Some),
+            Err(err) if err.kind() == io::ErrorKind::NotFound => Ok(None),
+            Err(err) => Err(err),
+        }
+    }
+
+    /// Writes the journal to the given file, replacing any previous journal.
+    ///
+    /// The journal is written to a temporary file first, which is synced to disk before it is
+    /// renamed, so that a crash never leaves a partially written journal behind.
+    pub fn write(&self, path: &Path) -> io::Result<()> {
+        let parent = path.parent().filter(|parent| !parent.as_os_str().is_empty());
+        if let Some(parent) = parent {
+            fs::create_dir_all(parent)?;
+        }
+
+        let tmp_path = tmp_path(path);
+        let mut file = File::create(&tmp_path)?;
+        file.write_all(&self.encode())?;
+        file.sync_all()?;
+        drop(file);
+
+        fs::rename(&tmp_path, path)?;
+        // make the rename itself durable
+        sync_dir(parent.unwrap_or_else(|| Path::new(".")))
+    }
+}
+
+/// Writes [`TreeJournal`]s to a file on a background thread, at most once per interval.
+///
+/// A journal that is sent while the previous write is less than an interval ago replaces any
+/// journal that is still pending, so that only the latest one is written.
+#[derive(Debug)]
+pub(crate) struct TreeJournalWriter {
+    sender: mpsc::Sender<TreeJournal>,
+    handle: thread::JoinHandle<()>,
+}
+
+impl TreeJournalWriter {
+    /// Spawns the thread that writes the journals to the given file.
+    pub(crate) fn spawn(path: PathBuf, interval: Duration) -> io::Result<Self> {
+        let (sender, receiver) = mpsc::channel();
+        let handle = thread::Builder::new()
+            .name("tree-journal".to_string())
+            .spawn(move || Self::run(&path, &receiver, interval))?;
+        Ok(Self { sender, handle })
+    }
+
+    /// Schedules the journal to be written.
+    pub(crate) fn send(&self, journal: TreeJournal) {
+        let _ = self.sender.send(journal);
+    }
+
+    /// Stops the thread, dropping the pending journal, and waits for a write in progress.
+    pub(crate) fn shutdown(self) {
+        drop(self.sender);
+        let _ = self.handle.join();
+    }
+
+    fn run(path: &Path, receiver: &mpsc::Receiver<TreeJournal>, interval: Duration) {
+        let mut pending = None;
+        let mut next_write = Instant::now();
+        loop {
+            let received = if pending.is_some() {
+                receiver.recv_timeout(next_write.saturating_duration_since(Instant::now()))
+            } else {
+                receiver.recv().map_err(|_| RecvTimeoutError::Disconnected)
+            };
+            match received {
+                Ok(journal) => {
+                    pending = Some(journal);
+                    if Instant::now() < next_write {
+                        continue
+                    }
+                }
+                Err(RecvTimeoutError::Timeout) => {}
+                Err(RecvTimeoutError::Disconnected) => return,
+            }
+
+            let Some(journal) = pending.take() else { continue };
+            match journal.write(path) {
+                Ok(()) => {
+                    debug!(target: "blockchain_tree", blocks = journal.len(), ?path, "Wrote tree journal");
+                }
+                Err(err) => warn!(target: "blockchain_tree", %err, "Failed to write tree journal"),
+            }
+            next_write = Instant::now() + interval;
+        }
+    }
+}
+
+/// Syncs the directory at the given path to disk.
+#[cfg(unix)]
+fn sync_dir(path: &Path) -> io::Result<()> {
+    File::open(path)?.sync_all()
+}
+
+/// Directories can't be synced on this platform.
+#[cfg(not(unix))]
+fn sync_dir(_path: &Path) -> io::Result<()> {
+    Ok(())
+}
+
+/// Returns the path of the temporary file the journal at the given path is written to.
+fn tmp_path(path: &Path) -> PathBuf {
+    let mut tmp_path = path.as_os_str().to_owned();
+    tmp_path.push(".tmp");
+    tmp_path.into()
+}
+
+/// Returns an [`io::ErrorKind::InvalidData`] error for a malformed journal.
+fn invalid_data<E>(err: E) -> io::Error
+where
+    E: Into<Box<dyn std::error::Error + Send + Sync>>,
+{
+    io::Error::new(io::ErrorKind::InvalidData, err)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use reth_primitives::B256;
+    use reth_provider::test_utils::blocks::BlockchainTestData;
+
+    fn journal() -> TreeJournal {
+        let data = BlockchainTestData::default_from_number(11);
+        let (block1, _) = data.blocks[0].clone();
+        let (mut block2, _) = data.blocks[1].clone();
+        // the journal must not reseal blocks
+        block2.set_hash(B256::new([0x34; 32]));
+
+        TreeJournal {
+            entries: vec![
+                TreeJournalEntry { kind: TreeJournalEntryKind::Sidechain, block: block1 },
+                TreeJournalEntry { kind: TreeJournalEntryKind::Buffered, block: block2 },
+            ],
+        }
+    }
+
+    #[test]
+    fn journal_roundtrip() {
+        let journal = journal();
+        let decoded = TreeJournal::decode(&journal.encode()).unwrap();
+        assert_eq!(decoded, journal);
+    }
+
+    #[test]
+    fn rejects_unknown_versions() {
+        let mut data = TreeJournal::default().encode();
+        data[TREE_JOURNAL_MAGIC.len()] = TREE_JOURNAL_VERSION + 1;
+        let err = TreeJournal::decode(&data).unwrap_err();
+        assert_eq!(err.kind(), io::ErrorKind::InvalidData);
+    }
+
+    #[test]
+    fn read_write_journal() {
+        let dir = tempfile::tempdir().unwrap();
+        let path = dir.path().join("tree").join("journal");
+        assert!(TreeJournal::read(&path).unwrap().is_none());
+
+        let journal = journal();
+        journal.write(&path).unwrap();
+        assert_eq!(TreeJournal::read(&path).unwrap(), Some(journal));
+        assert!(!tmp_path(&path).exists());
+    }
+
+    #[test]
+    fn journal_writer_writes_latest_journal() {
+        let dir = tempfile::tempdir().unwrap();
+        let path = dir.path().join("journal");
+        let writer = TreeJournalWriter::spawn(path.clone(), Duration::from_millis(100)).unwrap();
+
+        // the first journal is written right away, the later ones once the interval passed
+        writer.send(TreeJournal::default());
+        writer.send(TreeJournal { entries: journal().entries[..1].to_vec() });
+        writer.send(journal());
+
+        let deadline = Instant::now() + Duration::from_secs(10);
+        while TreeJournal::read(&path).unwrap() != Some(journal()) {
+            assert!(Instant::now() < deadline, "journal was not written");
+            thread::sleep(Duration::from_millis(10));
+        }
+        writer.shutdown();
+    }
+}
@@ -36,6 +36,9 @@ pub use config::BlockchainTreeConfig;
 pub mod externals;
 pub use externals::TreeExternals;
 
+pub mod journal;
+pub use journal::TreeJournal;
+
 pub mod shareable;
 pub use shareable::ShareableBlockchainTree;
 
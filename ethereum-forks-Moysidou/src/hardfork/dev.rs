@@ -18,19 +18,19 @@ pub static DEV_HARDFORKS: Lazy<ChainHardforks> = Lazy::new(|| {
         (EthereumHardfork::Istanbul.boxed(), ForkCondition::Block(0)),
         (EthereumHardfork::Berlin.boxed(), ForkCondition::Block(0)),
         (EthereumHardfork::London.boxed(), ForkCondition::Block(0)),
+        /// Optimism hardforks activated conditionally based on feature flag "optimism", ordered
+        /// by activation like the Ethereum ones
+        #[cfg(feature = "optimism")]
+        (crate::OptimismHardfork::Bedrock.boxed(), ForkCondition::Block(0)),
         (
             EthereumHardfork::Paris.boxed(),
             ForkCondition::TTD { fork_block: None, total_difficulty: U256::ZERO },
         ),
+        #[cfg(feature = "optimism")]
+        (crate::OptimismHardfork::Regolith.boxed(), ForkCondition::Timestamp(0)),
         /// Shanghai and Cancun hardforks activated at timestamp 0
         (EthereumHardfork::Shanghai.boxed(), ForkCondition::Timestamp(0)),
         (EthereumHardfork::Cancun.boxed(), ForkCondition::Timestamp(0)),
-
-        /// Optimism hardforks activated conditionally based on feature flag "optimism"
-        #[cfg(feature = "optimism")]
-        (crate::OptimismHardfork::Regolith.boxed(), ForkCondition::Timestamp(0)),
-        #[cfg(feature = "optimism")]
-        (crate::OptimismHardfork::Bedrock.boxed(), ForkCondition::Block(0)),
         #[cfg(feature = "optimism")]
         (crate::OptimismHardfork::Ecotone.boxed(), ForkCondition::Timestamp(0)),
     ])
@@ -6,7 +6,13 @@ pub use ethereum::EthereumHardforks;
 mod optimism;
 pub use optimism::OptimismHardforks;
 
-use crate::{ForkCondition, Hardfork};
+/// Hardfork schedules loadable from config files
+#[cfg(feature = "serde")]
+mod schedule;
+#[cfg(feature = "serde")]
+pub use schedule::{HardforkSchedule, HardforkScheduleError, ScheduledFork, ScheduledHardfork};
+
+use crate::{ForkCondition, ForkFilterKey, Hardfork};
 use rustc_hash::FxHashMap;
 
 /// Generic trait over a set of ordered hardforks
@@ -72,6 +78,19 @@ impl ChainHardforks {
         self.forks.iter().map(|(f, b)| (&**f, *b))
     }
 
+    /// Get an iterator of the [`ForkFilterKey`]s of all hardforks, to be used with
+    /// [`ForkFilter`](crate::ForkFilter).
+    ///
+    /// TTD based hardforks are only included if their fork block is known.
+    pub fn fork_filter_keys(&self) -> impl Iterator<Item = ForkFilterKey> + '_ {
+        self.forks.iter().filter_map(|(_, condition)| match condition {
+            ForkCondition::Block(block) |
+            ForkCondition::TTD { fork_block: Some(block), .. } => Some(ForkFilterKey::Block(*block)),
+            ForkCondition::Timestamp(time) => Some(ForkFilterKey::Time(*time)),
+            ForkCondition::TTD { fork_block: None, .. } | ForkCondition::Never => None,
+        })
+    }
+
     /// Get last hardfork from the list.
     pub fn last(&self) -> Option<(Box<dyn Hardfork>, ForkCondition)> {
         self.forks.last().map(|(f, b)| (f.clone(), *b))
@@ -0,0 +1,621 @@
+use crate::{ChainHardforks, EthereumHardfork, ForkCondition, Hardfork, OptimismHardfork};
+use alloy_primitives::{BlockNumber, U256};
+use core::{fmt, str::FromStr};
+use serde::{de, ser::SerializeSeq, Deserialize, Deserializer, Serialize, Serializer};
+
+#[cfg(not(feature = "std"))]
+use alloc::{boxed::Box, string::String, vec, vec::Vec};
+
+/// A hardfork of a [`HardforkSchedule`], either an [`EthereumHardfork`] or an
+/// [`OptimismHardfork`].
+#[derive(Debug, Copy, Clone, Eq, PartialEq, Hash)]
+pub enum ScheduledHardfork {
+    /// An Ethereum hardfork.
+    Ethereum(EthereumHardfork),
+    /// An Optimism hardfork.
+    Optimism(OptimismHardfork),
+}
+
+impl ScheduledHardfork {
+    /// Returns the name of the hardfork.
+    pub const fn name(&self) -> &'static str {
+        match self {
+            Self::Ethereum(fork) => fork.name(),
+            Self::Optimism(fork) => fork.name(),
+        }
+    }
+
+    /// Boxes the hardfork and returns it as `Box<dyn Hardfork>`.
+    pub fn boxed(self) -> Box<dyn Hardfork> {
+        match self {
+            Self::Ethereum(fork) => fork.boxed(),
+            Self::Optimism(fork) => fork.boxed(),
+        }
+    }
+
+    /// Returns `true` if `self` is defined after `other` by the same chain.
+    fn follows(&self, other: &Self) -> bool {
+        match (self, other) {
+            (Self::Ethereum(fork), Self::Ethereum(other)) => fork > other,
+            (Self::Optimism(fork), Self::Optimism(other)) => fork > other,
+            _ => false,
+        }
+    }
+}
+
+impl FromStr for ScheduledHardfork {
+    type Err = String;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        EthereumHardfork::from_str(s)
+            .map(Self::Ethereum)
+            .or_else(|_| OptimismHardfork::from_str(s).map(Self::Optimism))
+    }
+}
+
+impl fmt::Display for ScheduledHardfork {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.write_str(self.name())
+    }
+}
+
+impl Serialize for ScheduledHardfork {
+    fn serialize<S: Serializer>(&self, serializer: S) -> Result<S::Ok, S::Error> {
+        serializer.serialize_str(self.name())
+    }
+}
+
+impl<'de> Deserialize<'de> for ScheduledHardfork {
+    fn deserialize<D: Deserializer<'de>>(deserializer: D) -> Result<Self, D::Error> {
+        let name = String::deserialize(deserializer)?;
+        name.parse().map_err(de::Error::custom)
+    }
+}
+
+/// Errors of an invalid [`HardforkSchedule`].
+#[derive(Clone, Debug, thiserror_no_std::Error, PartialEq, Eq)]
+pub enum HardforkScheduleError {
+    /// The hardfork is scheduled more than once.
+    #[error("hardfork {0} is scheduled more than once")]
+    Duplicate(ScheduledHardfork),
+    /// The hardfork is scheduled before a hardfork it follows.
+    #[error("hardfork {fork} is scheduled before {follows}, which it follows")]
+    OutOfOrder {
+        /// The out of order hardfork.
+        fork: ScheduledHardfork,
+        /// The hardfork that is scheduled after it.
+        follows: ScheduledHardfork,
+    },
+    /// A block or TTD based hardfork is scheduled after a timestamp based one, or a block based
+    /// hardfork after a TTD based one whose block is unknown.
+    #[error("hardfork {fork} activated by {condition:?} is scheduled after {previous} activated by {previous_condition:?}")]
+    ConditionOutOfOrder {
+        /// The out of order hardfork.
+        fork: ScheduledHardfork,
+        /// The activation condition of the out of order hardfork.
+        condition: ForkCondition,
+        /// The previous hardfork.
+        previous: ScheduledHardfork,
+        /// The activation condition of the previous hardfork.
+        previous_condition: ForkCondition,
+    },
+    /// The hardfork activates before the previous hardfork.
+    #[error(
+        "hardfork {fork} activates at {activation}, before {previous} at {previous_activation}"
+    )]
+    NotMonotonic {
+        /// The hardfork that activates too early.
+        fork: ScheduledHardfork,
+        /// Activation block, timestamp or total difficulty of the hardfork.
+        activation: U256,
+        /// The previous hardfork.
+        previous: ScheduledHardfork,
+        /// Activation block, timestamp or total difficulty of the previous hardfork.
+        previous_activation: U256,
+    },
+    /// The schedule has neither a list of hardforks, nor a geth `config`, nor any hardfork key of
+    /// a geth chain config.
+    #[error("expected `hardforks`, a geth `config` or geth hardfork keys")]
+    UnknownFormat,
+}
+
+/// A schedule of hardforks and their activation conditions, ordered by activation.
+///
+/// The schedule can be deserialized from two formats:
+///
+/// - the native format, a list of hardforks with their [`ForkCondition`]:
+///
+/// ```json
+/// { "hardforks": [{ "fork": "London", "condition": { "Block": 0 } }] }
+/// ```
+///
+/// - the geth `config` format, either on its own or as part of a genesis file:
+///
+/// ```json
+/// { "config": { "londonBlock": 0, "terminalTotalDifficulty": 0, "shanghaiTime": 0 } }
+/// ```
+///
+/// The schedule is validated with [`HardforkSchedule::validate`] when it's converted into
+/// [`ChainHardforks`], which can be deserialized directly as well.
+#[derive(Debug, Clone, Default, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(try_from = "ScheduleFormat")]
+pub struct HardforkSchedule {
+    /// Hardforks and their activation conditions.
+    pub hardforks: Vec<ScheduledFork>,
+}
+
+/// A hardfork of a [`HardforkSchedule`] with its activation condition.
+#[derive(Debug, Copy, Clone, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(deny_unknown_fields)]
+pub struct ScheduledFork {
+    /// The hardfork.
+    pub fork: ScheduledHardfork,
+    /// The activation condition of the hardfork.
+    pub condition: ForkCondition,
+}
+
+impl HardforkSchedule {
+    /// Validates that the schedule:
+    ///
+    /// - doesn't contain duplicate hardforks,
+    /// - lists hardforks of the same chain in the order they're defined in,
+    /// - lists block based hardforks before TTD based ones, and TTD based hardforks before
+    ///   timestamp based ones. A TTD based hardfork whose block is known is ordered among block
+    ///   based ones by that block instead,
+    /// - has non-decreasing activation blocks, total difficulties and timestamps.
+    ///
+    /// Hardforks that are never activated are only checked for duplicates.
+    pub fn validate(&self) -> Result<(), HardforkScheduleError> {
+        let mut previous: Option<&ScheduledFork> = None;
+        for (index, scheduled) in self.hardforks.iter().enumerate() {
+            let earlier = &self.hardforks[..index];
+            if earlier.iter().any(|earlier| earlier.fork == scheduled.fork) {
+                return Err(HardforkScheduleError::Duplicate(scheduled.fork))
+            }
+            if let Some(follows) =
+                earlier.iter().find(|earlier| earlier.fork.follows(&scheduled.fork))
+            {
+                return Err(HardforkScheduleError::OutOfOrder {
+                    fork: scheduled.fork,
+                    follows: follows.fork,
+                })
+            }
+
+            if scheduled.condition == ForkCondition::Never {
+                continue
+            }
+            if let Some(previous) = previous {
+                validate_successor(previous, scheduled)?;
+            }
+            previous = Some(scheduled);
+        }
+
+        Ok(())
+    }
+}
+
+/// Validates that `fork` can be activated after `previous`.
+fn validate_successor(
+    previous: &ScheduledFork,
+    fork: &ScheduledFork,
+) -> Result<(), HardforkScheduleError> {
+    let ((previous_rank, previous_activation), (rank, activation)) =
+        match (previous.condition, fork.condition) {
+            // TTD based forks are ordered by total difficulty among themselves
+            (
+                ForkCondition::TTD { total_difficulty: previous, .. },
+                ForkCondition::TTD { total_difficulty, .. },
+            ) => ((1, previous), (1, total_difficulty)),
+            (previous, condition) => (activation(&previous), activation(&condition)),
+        };
+
+    if rank < previous_rank {
+        return Err(HardforkScheduleError::ConditionOutOfOrder {
+            fork: fork.fork,
+            condition: fork.condition,
+            previous: previous.fork,
+            previous_condition: previous.condition,
+        })
+    }
+    if rank == previous_rank && activation < previous_activation {
+        return Err(HardforkScheduleError::NotMonotonic {
+            fork: fork.fork,
+            activation,
+            previous: previous.fork,
+            previous_activation,
+        })
+    }
+
+    Ok(())
+}
+
+/// Returns the rank of the activation condition, and the activation block, total difficulty or
+/// timestamp that orders hardforks of the same rank.
+///
+/// Block based forks come first, followed by TTD and timestamp based forks. A TTD based fork whose
+/// block is known, e.g. Paris on chains that launched after the merge, is ranked among block
+/// based forks by that block.
+fn activation(condition: &ForkCondition) -> (u8, U256) {
+    match *condition {
+        ForkCondition::Block(block) | ForkCondition::TTD { fork_block: Some(block), .. } => {
+            (0, U256::from(block))
+        }
+        ForkCondition::TTD { fork_block: None, total_difficulty } => (1, total_difficulty),
+        ForkCondition::Timestamp(timestamp) => (2, U256::from(timestamp)),
+        ForkCondition::Never => (3, U256::ZERO),
+    }
+}
+
+impl TryFrom<HardforkSchedule> for ChainHardforks {
+    type Error = HardforkScheduleError;
+
+    fn try_from(schedule: HardforkSchedule) -> Result<Self, Self::Error> {
+        schedule.validate()?;
+        Ok(Self::new(
+            schedule
+                .hardforks
+                .into_iter()
+                .map(|scheduled| (scheduled.fork.boxed(), scheduled.condition))
+                .collect(),
+        ))
+    }
+}
+
+impl Serialize for ChainHardforks {
+    /// Serializes the hardforks in the native format of [`HardforkSchedule`].
+    fn serialize<S: Serializer>(&self, serializer: S) -> Result<S::Ok, S::Error> {
+        #[derive(Serialize)]
+        struct Entry<'a> {
+            fork: &'a str,
+            condition: ForkCondition,
+        }
+
+        #[derive(Serialize)]
+        struct Schedule<'a> {
+            hardforks: Entries<'a>,
+        }
+
+        struct Entries<'a>(&'a ChainHardforks);
+
+        impl Serialize for Entries<'_> {
+            fn serialize<S: Serializer>(&self, serializer: S) -> Result<S::Ok, S::Error> {
+                let mut seq = serializer.serialize_seq(Some(self.0.len()))?;
+                for (fork, condition) in self.0.forks_iter() {
+                    seq.serialize_element(&Entry { fork: fork.name(), condition })?;
+                }
+                seq.end()
+            }
+        }
+
+        Schedule { hardforks: Entries(self) }.serialize(serializer)
+    }
+}
+
+impl<'de> Deserialize<'de> for ChainHardforks {
+    /// Deserializes and validates a [`HardforkSchedule`].
+    fn deserialize<D: Deserializer<'de>>(deserializer: D) -> Result<Self, D::Error> {
+        HardforkSchedule::deserialize(deserializer)?.try_into().map_err(de::Error::custom)
+    }
+}
+
+/// Formats a [`HardforkSchedule`] can be deserialized from: the native format if `hardforks` is
+/// present, a geth genesis file if `config` is present, and a geth chain config if any of its
+/// hardfork keys is present.
+#[derive(Deserialize)]
+struct ScheduleFormat {
+    hardforks: Option<Vec<ScheduledFork>>,
+    config: Option<GethConfig>,
+    #[serde(flatten)]
+    geth: GethConfig,
+}
+
+impl TryFrom<ScheduleFormat> for HardforkSchedule {
+    type Error = HardforkScheduleError;
+
+    fn try_from(format: ScheduleFormat) -> Result<Self, Self::Error> {
+        match format {
+            ScheduleFormat { hardforks: Some(hardforks), .. } => Ok(Self { hardforks }),
+            ScheduleFormat { config: Some(config), .. } => Ok(config.into()),
+            ScheduleFormat { geth, .. } if geth != GethConfig::default() => Ok(geth.into()),
+            _ => Err(HardforkScheduleError::UnknownFormat),
+        }
+    }
+}
+
+/// Hardfork related keys of a geth chain config. Other keys are ignored.
+#[derive(Default, PartialEq, Eq, Deserialize)]
+#[serde(default, rename_all = "camelCase")]
+struct GethConfig {
+    homestead_block: Option<BlockNumber>,
+    dao_fork_block: Option<BlockNumber>,
+    eip150_block: Option<BlockNumber>,
+    eip158_block: Option<BlockNumber>,
+    byzantium_block: Option<BlockNumber>,
+    constantinople_block: Option<BlockNumber>,
+    petersburg_block: Option<BlockNumber>,
+    istanbul_block: Option<BlockNumber>,
+    muir_glacier_block: Option<BlockNumber>,
+    berlin_block: Option<BlockNumber>,
+    london_block: Option<BlockNumber>,
+    arrow_glacier_block: Option<BlockNumber>,
+    gray_glacier_block: Option<BlockNumber>,
+    bedrock_block: Option<BlockNumber>,
+    terminal_total_difficulty: Option<U256>,
+    merge_netsplit_block: Option<BlockNumber>,
+    regolith_time: Option<u64>,
+    shanghai_time: Option<u64>,
+    canyon_time: Option<u64>,
+    cancun_time: Option<u64>,
+    ecotone_time: Option<u64>,
+    fjord_time: Option<u64>,
+    prague_time: Option<u64>,
+}
+
+impl From<GethConfig> for HardforkSchedule {
+    fn from(config: GethConfig) -> Self {
+        use EthereumHardfork as Eth;
+        use OptimismHardfork as Op;
+        use ScheduledHardfork::{Ethereum, Optimism};
+
+        let blocks = [
+            (Ethereum(Eth::Homestead), config.homestead_block),
+            (Ethereum(Eth::Dao), config.dao_fork_block),
+            (Ethereum(Eth::Tangerine), config.eip150_block),
+            (Ethereum(Eth::SpuriousDragon), config.eip158_block),
+            (Ethereum(Eth::Byzantium), config.byzantium_block),
+            (Ethereum(Eth::Constantinople), config.constantinople_block),
+            (Ethereum(Eth::Petersburg), config.petersburg_block),
+            (Ethereum(Eth::Istanbul), config.istanbul_block),
+            (Ethereum(Eth::MuirGlacier), config.muir_glacier_block),
+            (Ethereum(Eth::Berlin), config.berlin_block),
+            (Ethereum(Eth::London), config.london_block),
+            (Ethereum(Eth::ArrowGlacier), config.arrow_glacier_block),
+            (Ethereum(Eth::GrayGlacier), config.gray_glacier_block),
+            (Optimism(Op::Bedrock), config.bedrock_block),
+        ];
+        let timestamps = [
+            (Optimism(Op::Regolith), config.regolith_time),
+            (Ethereum(Eth::Shanghai), config.shanghai_time),
+            (Optimism(Op::Canyon), config.canyon_time),
+            (Ethereum(Eth::Cancun), config.cancun_time),
+            (Optimism(Op::Ecotone), config.ecotone_time),
+            (Optimism(Op::Fjord), config.fjord_time),
+            (Ethereum(Eth::Prague), config.prague_time),
+        ];
+
+        // Frontier is always active at genesis
+        let mut hardforks = vec![ScheduledFork {
+            fork: Ethereum(Eth::Frontier),
+            condition: ForkCondition::Block(0),
+        }];
+        hardforks.extend(blocks.into_iter().filter_map(|(fork, block)| {
+            block.map(|block| ScheduledFork { fork, condition: ForkCondition::Block(block) })
+        }));
+        if let Some(total_difficulty) = config.terminal_total_difficulty {
+            hardforks.push(ScheduledFork {
+                fork: Ethereum(Eth::Paris),
+                condition: ForkCondition::TTD {
+                    fork_block: config.merge_netsplit_block,
+                    total_difficulty,
+                },
+            });
+        }
+        hardforks.extend(timestamps.into_iter().filter_map(|(fork, timestamp)| {
+            timestamp.map(|timestamp| ScheduledFork {
+                fork,
+                condition: ForkCondition::Timestamp(timestamp),
+            })
+        }));
+
+        Self { hardforks }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::{DisplayHardforks, ForkFilter, Head};
+    use alloy_primitives::B256;
+
+    #[test]
+    fn deserialize_native_schedule() {
+        let schedule = r#"{
+            "hardforks": [
+                { "fork": "Frontier", "condition": { "Block": 0 } },
+                { "fork": "london", "condition": { "Block": 0 } },
+                { "fork": "Bedrock", "condition": { "Block": 10 } },
+                { "fork": "Paris", "condition": { "TTD": { "fork_block": 10, "total_difficulty": "0x0" } } },
+                { "fork": "Regolith", "condition": { "Timestamp": 100 } },
+                { "fork": "Shanghai", "condition": { "Timestamp": 200 } },
+                { "fork": "Canyon", "condition": { "Timestamp": 200 } },
+                { "fork": "Prague", "condition": "Never" }
+            ]
+        }"#;
+
+        let hardforks: ChainHardforks = serde_json::from_str(schedule).unwrap();
+        assert_eq!(hardforks.len(), 8);
+        assert_eq!(hardforks.fork(EthereumHardfork::London), ForkCondition::Block(0));
+        assert_eq!(hardforks.fork(OptimismHardfork::Bedrock), ForkCondition::Block(10));
+        assert_eq!(hardforks.fork(OptimismHardfork::Canyon), ForkCondition::Timestamp(200));
+        assert_eq!(hardforks.fork(EthereumHardfork::Prague), ForkCondition::Never);
+
+        // Serialized hardforks deserialize into the same hardforks
+        let serialized = serde_json::to_string(&hardforks).unwrap();
+        assert_eq!(serde_json::from_str::<ChainHardforks>(&serialized).unwrap(), hardforks);
+    }
+
+    #[test]
+    fn deserialize_geth_schedule() {
+        let genesis = r#"{
+            "config": {
+                "chainId": 1337,
+                "homesteadBlock": 0,
+                "eip150Block": 0,
+                "eip155Block": 0,
+                "eip158Block": 0,
+                "byzantiumBlock": 0,
+                "constantinopleBlock": 0,
+                "petersburgBlock": 0,
+                "istanbulBlock": 0,
+                "berlinBlock": 0,
+                "londonBlock": 5,
+                "terminalTotalDifficulty": "0x10",
+                "mergeNetsplitBlock": 8,
+                "shanghaiTime": 1000,
+                "cancunTime": 2000
+            },
+            "alloc": {}
+        }"#;
+
+        let hardforks: ChainHardforks = serde_json::from_str(genesis).unwrap();
+        assert_eq!(hardforks.fork(EthereumHardfork::Frontier), ForkCondition::Block(0));
+        assert_eq!(hardforks.fork(EthereumHardfork::London), ForkCondition::Block(5));
+        assert_eq!(hardforks.fork(EthereumHardfork::Dao), ForkCondition::Never);
+        assert_eq!(
+            hardforks.fork(EthereumHardfork::Paris),
+            ForkCondition::TTD { fork_block: Some(8), total_difficulty: U256::from(16) }
+        );
+        assert_eq!(hardforks.fork(EthereumHardfork::Cancun), ForkCondition::Timestamp(2000));
+
+        // The config can be used on its own as well
+        let config: serde_json::Value = serde_json::from_str(genesis).unwrap();
+        assert_eq!(
+            serde_json::from_value::<ChainHardforks>(config["config"].clone()).unwrap(),
+            hardforks
+        );
+
+        let display = DisplayHardforks::new(&hardforks, None).to_string();
+        assert!(display.contains("London"));
+        assert!(display.contains("Cancun"));
+
+        let filter = ForkFilter::new(
+            Head { number: 8, timestamp: 1500, ..Default::default() },
+            B256::ZERO,
+            0,
+            hardforks.fork_filter_keys(),
+        );
+        let next = filter.current().next;
+        assert_eq!(next, 2000);
+    }
+
+    #[test]
+    fn invalid_schedules() {
+        let schedule = |hardforks: &[(ScheduledHardfork, ForkCondition)]| HardforkSchedule {
+            hardforks: hardforks
+                .iter()
+                .map(|(fork, condition)| ScheduledFork { fork: *fork, condition: *condition })
+                .collect(),
+        };
+        let london = ScheduledHardfork::Ethereum(EthereumHardfork::London);
+        let berlin = ScheduledHardfork::Ethereum(EthereumHardfork::Berlin);
+        let shanghai = ScheduledHardfork::Ethereum(EthereumHardfork::Shanghai);
+        let bedrock = ScheduledHardfork::Optimism(OptimismHardfork::Bedrock);
+
+        assert_eq!(
+            schedule(&[(berlin, ForkCondition::Block(1)), (berlin, ForkCondition::Block(2))])
+                .validate(),
+            Err(HardforkScheduleError::Duplicate(berlin))
+        );
+        assert_eq!(
+            schedule(&[(london, ForkCondition::Block(1)), (berlin, ForkCondition::Block(2))])
+                .validate(),
+            Err(HardforkScheduleError::OutOfOrder { fork: berlin, follows: london })
+        );
+        assert_eq!(
+            schedule(&[
+                (shanghai, ForkCondition::Timestamp(1)),
+                (bedrock, ForkCondition::Block(2))
+            ])
+            .validate(),
+            Err(HardforkScheduleError::ConditionOutOfOrder {
+                fork: bedrock,
+                condition: ForkCondition::Block(2),
+                previous: shanghai,
+                previous_condition: ForkCondition::Timestamp(1),
+            })
+        );
+        assert_eq!(
+            schedule(&[(berlin, ForkCondition::Block(2)), (bedrock, ForkCondition::Block(1))])
+                .validate(),
+            Err(HardforkScheduleError::NotMonotonic {
+                fork: bedrock,
+                activation: U256::from(1),
+                previous: berlin,
+                previous_activation: U256::from(2),
+            })
+        );
+        assert_eq!(
+            schedule(&[
+                (berlin, ForkCondition::Block(2)),
+                (london, ForkCondition::Never),
+                (bedrock, ForkCondition::Block(2))
+            ])
+            .validate(),
+            Ok(())
+        );
+
+        let err = serde_json::from_str::<ChainHardforks>(
+            r#"{ "config": { "berlinBlock": 10, "londonBlock": 5 } }"#,
+        )
+        .unwrap_err();
+        assert!(err.to_string().contains("London activates at 5, before Berlin at 10"), "{err}");
+
+        // A schedule without any known key is rejected instead of falling back to Frontier only
+        for schedule in ["{}", r#"{ "hardfork": [] }"#, r#"{ "chainId": 1 }"#] {
+            let err = serde_json::from_str::<ChainHardforks>(schedule).unwrap_err();
+            assert!(
+                err.to_string().contains(&HardforkScheduleError::UnknownFormat.to_string()),
+                "{err}"
+            );
+        }
+
+        // A TTD based hardfork whose block is known is ordered by that block
+        let paris = ScheduledHardfork::Ethereum(EthereumHardfork::Paris);
+        let paris_at =
+            |block| ForkCondition::TTD { fork_block: block, total_difficulty: U256::ZERO };
+        assert_eq!(
+            schedule(&[(paris, paris_at(Some(1))), (bedrock, ForkCondition::Block(1))]).validate(),
+            Ok(())
+        );
+        assert_eq!(
+            schedule(&[(paris, paris_at(Some(2))), (bedrock, ForkCondition::Block(1))]).validate(),
+            Err(HardforkScheduleError::NotMonotonic {
+                fork: bedrock,
+                activation: U256::from(1),
+                previous: paris,
+                previous_activation: U256::from(2),
+            })
+        );
+        assert_eq!(
+            schedule(&[(paris, paris_at(None)), (bedrock, ForkCondition::Block(1))]).validate(),
+            Err(HardforkScheduleError::ConditionOutOfOrder {
+                fork: bedrock,
+                condition: ForkCondition::Block(1),
+                previous: paris,
+                previous_condition: paris_at(None),
+            })
+        );
+    }
+
+    #[test]
+    fn roundtrip_builtin_schedules() {
+        let chains: [(&str, ChainHardforks); 9] = [
+            ("mainnet", EthereumHardfork::mainnet().into()),
+            ("goerli", EthereumHardfork::goerli().into()),
+            ("sepolia", EthereumHardfork::sepolia().into()),
+            ("holesky", EthereumHardfork::holesky().into()),
+            ("dev", crate::DEV_HARDFORKS.clone()),
+            ("op mainnet", OptimismHardfork::op_mainnet()),
+            ("op sepolia", OptimismHardfork::op_sepolia()),
+            ("base sepolia", OptimismHardfork::base_sepolia()),
+            ("base mainnet", OptimismHardfork::base_mainnet()),
+        ];
+
+        for (chain, hardforks) in chains {
+            let serialized = serde_json::to_string(&hardforks).unwrap();
+            let deserialized = serde_json::from_str::<ChainHardforks>(&serialized)
+                .unwrap_or_else(|err| panic!("{chain}: {err}"));
+            assert_eq!(deserialized, hardforks, "{chain}");
+        }
+    }
+}
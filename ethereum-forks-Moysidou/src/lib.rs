@@ -29,6 +29,7 @@ mod forkid;
 mod hardfork;
 mod hardforks;
 mod head;
+mod scheduler;
 
 /// Public exports from the crate
 pub use forkid::{
@@ -38,6 +39,11 @@ pub use forkid::{
 pub use hardfork::{EthereumHardfork, Hardfork, OptimismHardfork, DEV_HARDFORKS};
 /// Export the Head structure representing Ethereum block headers
 pub use head::Head;
+/// Exports for scheduling work around hardfork transitions
+pub use scheduler::{
+    ForkActivationDistance, ForkScheduleEvent, ForkScheduleEventKind, ForkScheduler,
+    DEFAULT_LEAD_BLOCKS, DEFAULT_LEAD_SECONDS,
+};
 
 pub use display::DisplayHardforks;      /// Export for displaying hardforks
 pub use forkcondition::ForkCondition;   /// Export for fork conditions
@@ -0,0 +1,273 @@
+use crate::{ChainHardforks, ForkCondition, Hardfork, Head};
+use alloy_primitives::BlockNumber;
+
+#[cfg(not(feature = "std"))]
+use alloc::{boxed::Box, vec::Vec};
+
+/// Default number of blocks before a block based hardfork to emit
+/// [`ForkScheduleEventKind::Upcoming`] at.
+pub const DEFAULT_LEAD_BLOCKS: u64 = 32;
+
+/// Default number of seconds before a timestamp based hardfork to emit
+/// [`ForkScheduleEventKind::Upcoming`] at. One epoch on the beacon chain.
+pub const DEFAULT_LEAD_SECONDS: u64 = 384;
+
+/// How far a hardfork is from being activated.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum ForkActivationDistance {
+    /// Number of blocks until a block based hardfork is activated.
+    Blocks(u64),
+    /// Number of seconds until a timestamp based hardfork is activated.
+    Seconds(u64),
+}
+
+/// Kind of a [`ForkScheduleEvent`].
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum ForkScheduleEventKind {
+    /// The hardfork is about to be activated.
+    ///
+    /// Not emitted for TTD based hardforks with an unknown fork block.
+    Upcoming {
+        /// How far the hardfork is from being activated.
+        remaining: ForkActivationDistance,
+    },
+    /// The head is the first block the hardfork is active at.
+    Activated,
+    /// The head is the first block after the one the hardfork was activated at.
+    PostActivation,
+}
+
+/// An event emitted by the [`ForkScheduler`] for a hardfork, as the head advances.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct ForkScheduleEvent {
+    /// The hardfork.
+    pub fork: Box<dyn Hardfork>,
+    /// The activation condition of the hardfork.
+    pub condition: ForkCondition,
+    /// The kind of the event.
+    pub kind: ForkScheduleEventKind,
+    /// The head the event was emitted at.
+    pub head: Head,
+}
+
+impl ForkScheduleEvent {
+    /// Returns `true` if the event is for the given hardfork.
+    pub fn is_for<H: Hardfork>(&self, fork: H) -> bool {
+        self.fork.name() == fork.name()
+    }
+}
+
+/// Progress of a hardfork through its transition.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, PartialOrd, Ord)]
+enum Phase {
+    Pending,
+    Upcoming,
+    Activated(BlockNumber),
+    Settled,
+}
+
+/// Tracks the hardforks of a chain as the head advances, and emits [`ForkScheduleEvent`]s ahead
+/// of, at, and after their activation.
+///
+/// Unlike [`ForkFilter::set_head`](crate::ForkFilter::set_head), which only reports the transition
+/// itself, the scheduler lets subsystems prepare fork specific state before the first block of
+/// the fork arrives.
+#[derive(Debug, Clone)]
+pub struct ForkScheduler {
+    /// Hardforks with their activation condition and phase.
+    forks: Vec<(Box<dyn Hardfork>, ForkCondition, Phase)>,
+    /// Number of blocks before a block based hardfork to emit the upcoming event at.
+    lead_blocks: u64,
+    /// Number of seconds before a timestamp based hardfork to emit the upcoming event at.
+    lead_seconds: u64,
+}
+
+impl ForkScheduler {
+    /// Creates a new [`ForkScheduler`] for the hardforks at the given head.
+    ///
+    /// No events are emitted for hardforks that are already active at the head.
+    pub fn new(hardforks: &ChainHardforks, head: Head) -> Self {
+        let forks = hardforks
+            .forks_iter()
+            .filter(|(_, condition)| *condition != ForkCondition::Never)
+            .map(|(fork, condition)| {
+                let phase =
+                    if condition.active_at_head(&head) { Phase::Settled } else { Phase::Pending };
+                (dyn_clone::clone_box(fork), condition, phase)
+            })
+            .collect();
+
+        Self { forks, lead_blocks: DEFAULT_LEAD_BLOCKS, lead_seconds: DEFAULT_LEAD_SECONDS }
+    }
+
+    /// Sets the number of blocks before a block based hardfork to emit
+    /// [`ForkScheduleEventKind::Upcoming`] at.
+    pub const fn with_lead_blocks(mut self, lead_blocks: u64) -> Self {
+        self.lead_blocks = lead_blocks;
+        self
+    }
+
+    /// Sets the number of seconds before a timestamp based hardfork to emit
+    /// [`ForkScheduleEventKind::Upcoming`] at.
+    pub const fn with_lead_seconds(mut self, lead_seconds: u64) -> Self {
+        self.lead_seconds = lead_seconds;
+        self
+    }
+
+    /// Advances the scheduler to the new head, and returns the events of all hardforks in the
+    /// order of the schedule.
+    ///
+    /// If the head moves back before the activation of a hardfork, e.g. on a reorg, the hardfork
+    /// is tracked from the start again.
+    pub fn on_new_head(&mut self, head: Head) -> Vec<ForkScheduleEvent> {
+        let mut events = Vec::new();
+        let (lead_blocks, lead_seconds) = (self.lead_blocks, self.lead_seconds);
+
+        for (fork, condition, phase) in &mut self.forks {
+            let mut emit = |kind| {
+                events.push(ForkScheduleEvent {
+                    fork: fork.clone(),
+                    condition: *condition,
+                    kind,
+                    head,
+                })
+            };
+
+            if condition.active_at_head(&head) {
+                match *phase {
+                    Phase::Pending | Phase::Upcoming => {
+                        emit(ForkScheduleEventKind::Activated);
+                        *phase = Phase::Activated(head.number);
+                    }
+                    Phase::Activated(block) if head.number > block => {
+                        emit(ForkScheduleEventKind::PostActivation);
+                        *phase = Phase::Settled;
+                    }
+                    Phase::Activated(_) | Phase::Settled => {}
+                }
+                continue
+            }
+
+            // The head moved back before the activation
+            if *phase > Phase::Upcoming {
+                *phase = Phase::Pending;
+            }
+
+            if *phase == Phase::Pending {
+                let remaining = match *condition {
+                    ForkCondition::Block(block) |
+                    ForkCondition::TTD { fork_block: Some(block), .. } => {
+                        let remaining = block.saturating_sub(head.number);
+                        (remaining <= lead_blocks)
+                            .then_some(ForkActivationDistance::Blocks(remaining))
+                    }
+                    ForkCondition::Timestamp(timestamp) => {
+                        let remaining = timestamp.saturating_sub(head.timestamp);
+                        (remaining <= lead_seconds)
+                            .then_some(ForkActivationDistance::Seconds(remaining))
+                    }
+                    ForkCondition::TTD { fork_block: None, .. } | ForkCondition::Never => None,
+                };
+                if let Some(remaining) = remaining {
+                    emit(ForkScheduleEventKind::Upcoming { remaining });
+                    *phase = Phase::Upcoming;
+                }
+            }
+        }
+
+        events
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::{EthereumHardfork, OptimismHardfork};
+
+    fn head(number: BlockNumber, timestamp: u64) -> Head {
+        Head { number, timestamp, ..Default::default() }
+    }
+
+    fn kinds(events: &[ForkScheduleEvent]) -> Vec<(&'static str, ForkScheduleEventKind)> {
+        events.iter().map(|event| (event.fork.name(), event.kind)).collect()
+    }
+
+    #[test]
+    fn block_and_timestamp_transitions() {
+        let hardforks = ChainHardforks::new(vec![
+            (EthereumHardfork::London.boxed(), ForkCondition::Block(0)),
+            (OptimismHardfork::Bedrock.boxed(), ForkCondition::Block(100)),
+            (EthereumHardfork::Shanghai.boxed(), ForkCondition::Timestamp(10_000)),
+            (EthereumHardfork::Prague.boxed(), ForkCondition::Never),
+        ]);
+        let mut scheduler =
+            ForkScheduler::new(&hardforks, head(0, 0)).with_lead_blocks(10).with_lead_seconds(100);
+
+        assert!(scheduler.on_new_head(head(89, 1_000)).is_empty());
+        assert_eq!(
+            kinds(&scheduler.on_new_head(head(90, 1_012))),
+            vec![(
+                "Bedrock",
+                ForkScheduleEventKind::Upcoming { remaining: ForkActivationDistance::Blocks(10) }
+            )]
+        );
+        assert!(scheduler.on_new_head(head(91, 1_024)).is_empty());
+        assert_eq!(
+            kinds(&scheduler.on_new_head(head(100, 1_036))),
+            vec![("Bedrock", ForkScheduleEventKind::Activated)]
+        );
+        assert_eq!(
+            kinds(&scheduler.on_new_head(head(101, 9_950))),
+            vec![
+                ("Bedrock", ForkScheduleEventKind::PostActivation),
+                (
+                    "Shanghai",
+                    ForkScheduleEventKind::Upcoming {
+                        remaining: ForkActivationDistance::Seconds(50)
+                    }
+                )
+            ]
+        );
+
+        // Jumping straight to the activation skips the upcoming event
+        let mut scheduler = ForkScheduler::new(&hardforks, head(0, 0));
+        let events = scheduler.on_new_head(head(200, 20_000));
+        assert_eq!(
+            kinds(&events),
+            vec![
+                ("Bedrock", ForkScheduleEventKind::Activated),
+                ("Shanghai", ForkScheduleEventKind::Activated)
+            ]
+        );
+        assert!(events[1].is_for(EthereumHardfork::Shanghai));
+        assert_eq!(events[1].head, head(200, 20_000));
+    }
+
+    #[test]
+    fn reorg_before_activation() {
+        let hardforks =
+            ChainHardforks::new(vec![(EthereumHardfork::Cancun.boxed(), ForkCondition::Block(10))]);
+        let mut scheduler = ForkScheduler::new(&hardforks, head(0, 0)).with_lead_blocks(2);
+
+        assert_eq!(scheduler.on_new_head(head(8, 0)).len(), 1);
+        assert_eq!(
+            kinds(&scheduler.on_new_head(head(10, 0))),
+            vec![("Cancun", ForkScheduleEventKind::Activated)]
+        );
+        assert_eq!(
+            kinds(&scheduler.on_new_head(head(9, 0))),
+            vec![(
+                "Cancun",
+                ForkScheduleEventKind::Upcoming { remaining: ForkActivationDistance::Blocks(1) }
+            )]
+        );
+        assert_eq!(
+            kinds(&scheduler.on_new_head(head(10, 0))),
+            vec![("Cancun", ForkScheduleEventKind::Activated)]
+        );
+
+        // Hardforks active at the initial head are not reported
+        let mut scheduler = ForkScheduler::new(&hardforks, head(20, 0));
+        assert!(scheduler.on_new_head(head(21, 0)).is_empty());
+    }
+}
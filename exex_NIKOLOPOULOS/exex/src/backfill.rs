@@ -0,0 +1,281 @@
+use reth_evm::execute::{BatchExecutor, BlockExecutionInput, BlockExecutorProvider};
+use reth_execution_types::{Chain, ExecutionOutcome};
+use reth_node_core::args::DEFAULT_EXEX_BACKFILL_BATCH_SIZE;
+use reth_primitives::BlockNumber;
+use reth_provider::{
+    BlockReader, HeaderProvider, ProviderError, StateProviderFactory, TransactionVariant,
+};
+use reth_prune_types::PruneModes;
+use reth_revm::database::StateProviderDatabase;
+use reth_tasks::TaskExecutor;
+use reth_tracing::tracing::debug;
+use std::{fmt::Debug, ops::RangeInclusive};
+use tokio::sync::mpsc::{self, Receiver};
+
+/// Factory for creating new backfill jobs.
+///
+/// A backfill job re-executes a range of historical blocks on top of the state at the parent of
+/// the first block, so that an `ExEx` that starts behind the node head can derive its state from
+/// blocks committed before it was installed.
+#[derive(Debug, Clone)]
+pub struct BackfillJobFactory<E, P> {
+    executor: E,
+    provider: P,
+    prune_modes: PruneModes,
+    batch_size: u64,
+}
+
+impl<E, P> BackfillJobFactory<E, P> {
+    /// Creates a new [`BackfillJobFactory`].
+    pub fn new(executor: E, provider: P) -> Self {
+        Self {
+            executor,
+            provider,
+            prune_modes: PruneModes::none(),
+            batch_size: DEFAULT_EXEX_BACKFILL_BATCH_SIZE,
+        }
+    }
+
+    /// Sets the prune modes used by the executor.
+    pub fn with_prune_modes(mut self, prune_modes: PruneModes) -> Self {
+        self.prune_modes = prune_modes;
+        self
+    }
+
+    /// Sets the number of blocks executed and delivered in a single batch.
+    ///
+    /// The batch size is at least one block.
+    pub fn with_batch_size(mut self, batch_size: u64) -> Self {
+        self.batch_size = batch_size.max(1);
+        self
+    }
+}
+
+impl<E: Clone, P: Clone> BackfillJobFactory<E, P> {
+    /// Creates a new backfill job for the given range of blocks.
+    pub fn backfill(&self, range: RangeInclusive<BlockNumber>) -> BackfillJob<E, P> {
+        BackfillJob {
+            executor: self.executor.clone(),
+            provider: self.provider.clone(),
+            prune_modes: self.prune_modes.clone(),
+            range,
+            batch_size: self.batch_size,
+        }
+    }
+}
+
+/// Backfill job started for a specific range.
+///
+/// It implements [`Iterator`] that executes blocks in batches according to the provided batch
+/// size, and yields one [`Chain`] per batch.
+#[derive(Debug)]
+pub struct BackfillJob<E, P> {
+    executor: E,
+    provider: P,
+    prune_modes: PruneModes,
+    range: RangeInclusive<BlockNumber>,
+    batch_size: u64,
+}
+
+impl<E, P> Iterator for BackfillJob<E, P>
+where
+    E: BlockExecutorProvider,
+    P: HeaderProvider + BlockReader + StateProviderFactory,
+{
+    type Item = eyre::Result<Chain>;
+
+    fn next(&mut self) -> Option<Self::Item> {
+        if self.range.is_empty() {
+            return None
+        }
+
+        Some(self.execute_batch())
+    }
+}
+
+impl<E, P> BackfillJob<E, P>
+where
+    E: BlockExecutorProvider,
+    P: HeaderProvider + BlockReader + StateProviderFactory,
+{
+    /// Executes the next batch of blocks and advances the range of the job past it.
+    fn execute_batch(&mut self) -> eyre::Result<Chain> {
+        let start = *self.range.start();
+        let end = (start + self.batch_size - 1).min(*self.range.end());
+        // advance the range first, so that a failed batch is not retried forever
+        self.range = end + 1..=*self.range.end();
+
+        debug!(target: "exex::backfill", %start, %end, "Executing batch of blocks");
+
+        // Configure the executor to use the state at the parent of the first block in the batch
+        let mut executor = self.executor.batch_executor(StateProviderDatabase::new(
+            self.provider.history_by_block_number(start.saturating_sub(1))?,
+        ));
+        executor.set_prune_modes(self.prune_modes.clone());
+        executor.set_tip(end);
+
+        let mut blocks = Vec::with_capacity((end - start + 1) as usize);
+        for block_number in start..=end {
+            let td = self
+                .provider
+                .header_td_by_number(block_number)?
+                .ok_or_else(|| ProviderError::HeaderNotFound(block_number.into()))?;
+
+            // Fetch the block with senders for execution
+            let block = self
+                .provider
+                .sealed_block_with_senders(block_number.into(), TransactionVariant::WithHash)?
+                .ok_or_else(|| ProviderError::HeaderNotFound(block_number.into()))?;
+
+            executor
+                .execute_and_verify_one(BlockExecutionInput::new(&block.clone().unseal(), td))?;
+            blocks.push(block);
+        }
+
+        let outcome: ExecutionOutcome = executor.finalize();
+        Ok(Chain::new(blocks, outcome, None))
+    }
+}
+
+/// Spawns backfill jobs on behalf of the [`ExExManager`](crate::ExExManager).
+///
+/// This erases the node types from the backfill jobs, so that the manager doesn't need to be
+/// generic over them.
+pub trait SpawnBackfill: Debug + Send + Sync + 'static {
+    /// Spawns a backfill job for the given range of blocks.
+    ///
+    /// Returns a channel that yields the executed batches in order, and is closed once the job is
+    /// finished.
+    fn spawn_backfill(&self, range: RangeInclusive<BlockNumber>) -> Receiver<eyre::Result<Chain>>;
+}
+
+/// A [`SpawnBackfill`] implementation that runs the jobs of a [`BackfillJobFactory`] as blocking
+/// tasks.
+#[derive(Debug)]
+pub struct BackfillJobSpawner<E, P> {
+    factory: BackfillJobFactory<E, P>,
+    task_executor: TaskExecutor,
+}
+
+impl<E, P> BackfillJobSpawner<E, P> {
+    /// Creates a new [`BackfillJobSpawner`].
+    pub const fn new(factory: BackfillJobFactory<E, P>, task_executor: TaskExecutor) -> Self {
+        Self { factory, task_executor }
+    }
+}
+
+impl<E, P> SpawnBackfill for BackfillJobSpawner<E, P>
+where
+    E: BlockExecutorProvider + Clone + Debug,
+    P: HeaderProvider + BlockReader + StateProviderFactory + Clone + Debug + 'static,
+{
+    fn spawn_backfill(&self, range: RangeInclusive<BlockNumber>) -> Receiver<eyre::Result<Chain>> {
+        // only one batch is buffered, so that the job doesn't run ahead of the `ExEx`
+        let (tx, rx) = mpsc::channel(1);
+        let job = self.factory.backfill(range);
+
+        self.task_executor.spawn_blocking(Box::pin(async move {
+            for batch in job {
+                let is_err = batch.is_err();
+                // the receiver is gone if the manager or the `ExEx` is shutting down
+                if tx.send(batch).await.is_err() || is_err {
+                    break
+                }
+            }
+        }));
+
+        rx
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use reth_blockchain_tree::noop::NoopBlockchainTree;
+    use reth_db::{test_utils::TempDatabase, DatabaseEnv};
+    use reth_evm::test_utils::MockExecutorProvider;
+    use reth_primitives::{SealedBlockWithSenders, B256};
+    use reth_provider::{
+        providers::BlockchainProvider, test_utils::create_test_provider_factory, BlockWriter,
+    };
+    use reth_tasks::TaskManager;
+    use reth_testing_utils::generators::{self, random_block_range};
+    use std::sync::Arc;
+
+    /// Inserts the blocks `0..=3` into a test provider, and returns them.
+    fn setup() -> eyre::Result<(
+        BlockchainProvider<Arc<TempDatabase<DatabaseEnv>>>,
+        Vec<SealedBlockWithSenders>,
+    )> {
+        let mut rng = generators::rng();
+        let blocks = random_block_range(&mut rng, 0..=3, B256::ZERO, 0..2)
+            .into_iter()
+            .map(|block| block.seal_with_senders().expect("failed to recover senders"))
+            .collect::<Vec<_>>();
+
+        let provider_factory = create_test_provider_factory();
+        let provider_rw = provider_factory.provider_rw()?;
+        for block in &blocks {
+            provider_rw.insert_historical_block(block.clone(), None)?;
+        }
+        provider_rw.commit()?;
+
+        let provider =
+            BlockchainProvider::new(provider_factory, Arc::new(NoopBlockchainTree::default()))?;
+        Ok((provider, blocks))
+    }
+
+    #[test]
+    fn backfill_in_batches() -> eyre::Result<()> {
+        let (provider, blocks) = setup()?;
+        let executor = MockExecutorProvider::default();
+        executor.extend(vec![ExecutionOutcome::default(); 2]);
+
+        let factory = BackfillJobFactory::new(executor, provider).with_batch_size(2);
+        let chains = factory.backfill(1..=3).collect::<eyre::Result<Vec<_>>>()?;
+
+        assert_eq!(chains.len(), 2);
+        assert_eq!(chains[0].range(), 1..=2);
+        assert_eq!(chains[1].range(), 3..=3);
+        let backfilled = chains.iter().flat_map(|chain| chain.blocks().values().cloned());
+        assert!(backfilled.eq(blocks[1..].iter().cloned()));
+
+        Ok(())
+    }
+
+    #[test]
+    fn backfill_missing_block() -> eyre::Result<()> {
+        let (provider, _) = setup()?;
+        let executor = MockExecutorProvider::default();
+        executor.extend(vec![ExecutionOutcome::default()]);
+
+        // the job fails on the first block that is not in the database, and doesn't retry it
+        let mut job = BackfillJobFactory::new(executor, provider).backfill(3..=4);
+        assert!(job.next().expect("one batch").is_err());
+        assert!(job.next().is_none());
+
+        Ok(())
+    }
+
+    #[tokio::test]
+    async fn spawn_backfill() -> eyre::Result<()> {
+        let (provider, blocks) = setup()?;
+        let executor = MockExecutorProvider::default();
+        executor.extend(vec![ExecutionOutcome::default(); 3]);
+
+        let tasks = TaskManager::current();
+        let spawner = BackfillJobSpawner::new(
+            BackfillJobFactory::new(executor, provider).with_batch_size(1),
+            tasks.executor(),
+        );
+
+        let mut batches = spawner.spawn_backfill(1..=3);
+        for block in &blocks[1..] {
+            let chain = batches.recv().await.expect("batch")?;
+            assert_eq!(chain.tip(), block);
+        }
+        assert!(batches.recv().await.is_none());
+
+        Ok(())
+    }
+}
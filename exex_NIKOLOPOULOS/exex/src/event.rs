@@ -1,7 +1,7 @@
 use reth_primitives::BlockNumber;
 
 /// Events emitted by an `ExEx`.
-#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+#[derive(Debug, Clone, PartialEq, Eq)]
 /// ExExEvent enum: Describes the purpose of the enum and provides details on its variant.
 pub enum ExExEvent {
     /// Highest block processed by the `ExEx`.
@@ -11,4 +11,32 @@ pub enum ExExEvent {
     ///
     /// On reorgs, it's possible for the height to go down.
     FinishedHeight(BlockNumber),
+    /// Request to backfill the `ExEx` starting from the given block.
+    ///
+    /// The manager re-executes all blocks from the given block up to the node head and delivers
+    /// them as [`ExExNotification::ChainCommitted`](crate::ExExNotification::ChainCommitted)
+    /// batches, before switching back to live notifications.
+    ///
+    /// This is usually emitted once right after launch, by an `ExEx` that starts behind the node
+    /// head.
+    BackfillFrom(BlockNumber),
+    /// Request to rewind the `ExEx` to the given height.
+    ///
+    /// The finished height of the `ExEx` is reset to the given height, and all blocks above it up
+    /// to the node head are delivered again, the same way as for
+    /// [`ExExEvent::BackfillFrom`].
+    ///
+    /// If the manager has no backfill spawner, the blocks can't be delivered again and the `ExEx`
+    /// is considered failed.
+    Rewind(BlockNumber),
+    /// The `ExEx` is alive.
+    ///
+    /// An `ExEx` that takes a long time to process a notification should emit heartbeats, so that
+    /// the manager doesn't consider it unresponsive.
+    Heartbeat,
+    /// The `ExEx` encountered an error it can't recover from.
+    ///
+    /// The manager marks the `ExEx` as failed and stops sending notifications to it. The node
+    /// then decides whether to detach it, so that it no longer holds back pruning.
+    FatalError(String),
 }   // FinishedHeight variant: Explains what this variant represents, including the implications for block pruning and reorganization.
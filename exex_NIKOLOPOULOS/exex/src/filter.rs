@@ -0,0 +1,419 @@
+use crate::ExExNotification;
+use reth_execution_types::{Chain, ChainSplit, ChainSplitTarget};
+use reth_primitives::{Address, BlockNumber, Receipt, TransactionSigned, TxType, B256};
+use std::{collections::HashSet, ops::RangeInclusive, sync::Arc};
+
+/// A declarative filter of the [`ExExNotification`]s delivered to an `ExEx`.
+///
+/// An empty filter matches everything. Otherwise, the chains of the notifications are trimmed to
+/// the blocks in the block range, and committed chains are further reduced to the blocks with at
+/// least one transaction that matches all of the configured criteria:
+///
+/// - the transaction type is one of the [`tx_types`](Self::tx_type), and
+/// - the transaction is sent to one of the [`addresses`](Self::address), or one of its logs is
+///   emitted by one of the addresses and contains one of the [`topics`](Self::topic).
+///
+/// The blocks that are kept are delivered unmodified, with all of their transactions. The
+/// receipts of the dropped blocks are removed, while the bundle state of the chain is never
+/// pruned.
+///
+/// Committed chains without any matching block are not delivered at all. Reorged and reverted
+/// chains are only trimmed to the block range, and skipped if they are entirely outside of it, so
+/// that the `ExEx` is always aware of reorgs of the blocks it has received.
+#[derive(Debug, Clone, Default, PartialEq, Eq)]
+pub struct ExExNotificationFilter {
+    /// Addresses of the transaction recipients and log emitters to match.
+    addresses: HashSet<Address>,
+    /// Log topics to match.
+    topics: HashSet<B256>,
+    /// Transaction types to match.
+    tx_types: HashSet<TxType>,
+    /// Range of blocks to deliver.
+    block_range: Option<RangeInclusive<BlockNumber>>,
+}
+
+impl ExExNotificationFilter {
+    /// Creates a new empty filter, which matches everything.
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Matches transactions sent to the given address, or emitting logs from it.
+    pub fn address(mut self, address: Address) -> Self {
+        self.addresses.insert(address);
+        self
+    }
+
+    /// Matches transactions sent to any of the given addresses, or emitting logs from them.
+    pub fn addresses(mut self, addresses: impl IntoIterator<Item = Address>) -> Self {
+        self.addresses.extend(addresses);
+        self
+    }
+
+    /// Matches transactions emitting logs with the given topic.
+    pub fn topic(mut self, topic: B256) -> Self {
+        self.topics.insert(topic);
+        self
+    }
+
+    /// Matches transactions emitting logs with any of the given topics.
+    pub fn topics(mut self, topics: impl IntoIterator<Item = B256>) -> Self {
+        self.topics.extend(topics);
+        self
+    }
+
+    /// Matches transactions of the given type.
+    pub fn tx_type(mut self, tx_type: TxType) -> Self {
+        self.tx_types.insert(tx_type);
+        self
+    }
+
+    /// Only delivers the blocks in the given range.
+    pub fn block_range(mut self, block_range: RangeInclusive<BlockNumber>) -> Self {
+        self.block_range = Some(block_range);
+        self
+    }
+
+    /// Returns `true` if the filter matches everything.
+    pub fn is_empty(&self) -> bool {
+        self.block_range.is_none() && !self.has_transaction_criteria()
+    }
+
+    /// Returns `true` if the filter drops blocks without matching transactions.
+    fn has_transaction_criteria(&self) -> bool {
+        !self.addresses.is_empty() || !self.topics.is_empty() || !self.tx_types.is_empty()
+    }
+
+    /// Applies the filter to the notification.
+    ///
+    /// Returns `None` if the notification should not be delivered at all.
+    pub fn filter_notification(&self, notification: &ExExNotification) -> Option<ExExNotification> {
+        if self.is_empty() {
+            return Some(notification.clone())
+        }
+
+        match notification {
+            ExExNotification::ChainCommitted { new } => {
+                self.filter_committed_chain(new).map(|new| ExExNotification::ChainCommitted { new })
+            }
+            ExExNotification::ChainReorged { old, new } => {
+                match (self.filter_reverted_chain(old), self.filter_committed_chain(new)) {
+                    (Some(old), Some(new)) => Some(ExExNotification::ChainReorged { old, new }),
+                    (None, Some(new)) => Some(ExExNotification::ChainCommitted { new }),
+                    (Some(old), None) => Some(ExExNotification::ChainReverted { old }),
+                    (None, None) => None,
+                }
+            }
+            ExExNotification::ChainReverted { old } => {
+                self.filter_reverted_chain(old).map(|old| ExExNotification::ChainReverted { old })
+            }
+        }
+    }
+
+    /// Trims the committed chain to the block range and to the blocks with matching transactions.
+    ///
+    /// Returns `None` if no block of the chain is left.
+    fn filter_committed_chain(&self, chain: &Arc<Chain>) -> Option<Arc<Chain>> {
+        let chain = self.filter_reverted_chain(chain)?;
+        if !self.has_transaction_criteria() {
+            return Some(chain)
+        }
+
+        self.drop_unmatched_blocks(&chain).map(Arc::new)
+    }
+
+    /// Trims the reverted chain to the block range.
+    ///
+    /// Returns `None` if the chain is entirely outside the block range.
+    fn filter_reverted_chain(&self, chain: &Arc<Chain>) -> Option<Arc<Chain>> {
+        match &self.block_range {
+            Some(block_range) => Self::trim_chain(chain, block_range),
+            None => Some(chain.clone()),
+        }
+    }
+
+    /// Trims the chain to the given block range.
+    ///
+    /// Returns `None` if the chain is entirely outside the block range.
+    fn trim_chain(
+        chain: &Arc<Chain>,
+        block_range: &RangeInclusive<BlockNumber>,
+    ) -> Option<Arc<Chain>> {
+        let range = chain.range();
+        if range.end() < block_range.start() || range.start() > block_range.end() {
+            return None
+        }
+        if block_range.contains(range.start()) && block_range.contains(range.end()) {
+            return Some(chain.clone())
+        }
+
+        let mut chain = Chain::clone(chain);
+        if range.start() < block_range.start() {
+            // keep the blocks after the start of the block range
+            chain = match std::mem::take(&mut chain)
+                .split(ChainSplitTarget::Number(block_range.start() - 1))
+            {
+                ChainSplit::Split { pending, .. } => pending,
+                ChainSplit::NoSplitPending(chain) | ChainSplit::NoSplitCanonical(chain) => chain,
+            };
+        }
+        if range.end() > block_range.end() {
+            // keep the blocks up to the end of the block range
+            chain = match std::mem::take(&mut chain)
+                .split(ChainSplitTarget::Number(*block_range.end()))
+            {
+                ChainSplit::Split { canonical, .. } => canonical,
+                ChainSplit::NoSplitPending(chain) | ChainSplit::NoSplitCanonical(chain) => chain,
+            };
+        }
+
+        Some(Arc::new(chain))
+    }
+
+    /// Drops the blocks of the chain without any matching transaction, along with their receipts.
+    ///
+    /// Returns `None` if no block matches.
+    fn drop_unmatched_blocks(&self, chain: &Chain) -> Option<Chain> {
+        let mut execution_outcome = chain.execution_outcome().clone();
+        let first_block = execution_outcome.first_block;
+
+        let blocks = chain
+            .blocks()
+            .values()
+            .filter(|block| {
+                let receipts = execution_outcome
+                    .receipts
+                    .receipt_vec
+                    .get((block.number - first_block) as usize);
+                block.body.iter().enumerate().any(|(index, transaction)| {
+                    let receipt = receipts.and_then(|receipts| receipts.get(index));
+                    self.matches_transaction(transaction, receipt.and_then(Option::as_ref))
+                })
+            })
+            .cloned()
+            .collect::<Vec<_>>();
+        if blocks.is_empty() {
+            return None
+        }
+
+        // the receipts are indexed by the block number, so the ones of the dropped blocks are
+        // emptied rather than removed
+        let kept = blocks.iter().map(|block| block.number).collect::<HashSet<_>>();
+        for (number, receipts) in
+            (first_block..).zip(execution_outcome.receipts.receipt_vec.iter_mut())
+        {
+            if !kept.contains(&number) {
+                receipts.clear();
+            }
+        }
+
+        Some(Chain::new(blocks, execution_outcome, None))
+    }
+
+    /// Returns `true` if the transaction with the given receipt matches the filter.
+    fn matches_transaction(
+        &self,
+        transaction: &TransactionSigned,
+        receipt: Option<&Receipt>,
+    ) -> bool {
+        if !self.tx_types.is_empty() && !self.tx_types.contains(&transaction.tx_type()) {
+            return false
+        }
+        if self.addresses.is_empty() && self.topics.is_empty() {
+            return true
+        }
+
+        // the recipient can only match if there are no topics to match
+        if self.topics.is_empty() && transaction.to().is_some_and(|to| self.addresses.contains(&to))
+        {
+            return true
+        }
+
+        receipt.is_some_and(|receipt| {
+            receipt.logs.iter().any(|log| {
+                (self.addresses.is_empty() || self.addresses.contains(&log.address)) &&
+                    (self.topics.is_empty() ||
+                        log.topics().iter().any(|topic| self.topics.contains(topic)))
+            })
+        })
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use reth_execution_types::ExecutionOutcome;
+    use reth_primitives::{Bytes, Log, Receipts, SealedBlockWithSenders};
+    use reth_testing_utils::generators::{self, random_block_range, random_receipt};
+
+    fn chain(range: RangeInclusive<BlockNumber>) -> Arc<Chain> {
+        let mut rng = generators::rng();
+        let blocks = random_block_range(&mut rng, range.clone(), B256::ZERO, 0..1)
+            .into_iter()
+            .map(|block| block.seal_with_senders().expect("failed to recover senders"));
+        let execution_outcome = ExecutionOutcome {
+            receipts: Receipts { receipt_vec: vec![Vec::new(); range.clone().count()] },
+            first_block: *range.start(),
+            ..Default::default()
+        };
+        Arc::new(Chain::new(blocks, execution_outcome, None))
+    }
+
+    /// Returns blocks with two transactions each, and a receipt without logs for every
+    /// transaction.
+    fn blocks_with_receipts(
+        range: RangeInclusive<BlockNumber>,
+    ) -> (Vec<SealedBlockWithSenders>, Vec<Vec<Option<Receipt>>>) {
+        let mut rng = generators::rng();
+        let blocks = random_block_range(&mut rng, range, B256::ZERO, 2..3)
+            .into_iter()
+            .map(|block| block.seal_with_senders().expect("failed to recover senders"))
+            .collect::<Vec<_>>();
+        let receipts = blocks
+            .iter()
+            .map(|block| {
+                block.body.iter().map(|tx| Some(random_receipt(&mut rng, tx, Some(0)))).collect()
+            })
+            .collect();
+        (blocks, receipts)
+    }
+
+    fn chain_with_receipts(
+        blocks: Vec<SealedBlockWithSenders>,
+        receipts: Vec<Vec<Option<Receipt>>>,
+    ) -> Arc<Chain> {
+        let execution_outcome = ExecutionOutcome {
+            first_block: blocks[0].number,
+            receipts: Receipts { receipt_vec: receipts },
+            ..Default::default()
+        };
+        Arc::new(Chain::new(blocks, execution_outcome, None))
+    }
+
+    /// Returns the numbers of the blocks of the chain.
+    fn block_numbers(chain: &Chain) -> Vec<BlockNumber> {
+        chain.blocks().keys().copied().collect()
+    }
+
+    #[test]
+    fn empty_filter_matches_everything() {
+        let notification = ExExNotification::ChainCommitted { new: chain(1..=3) };
+        assert_eq!(
+            ExExNotificationFilter::new().filter_notification(&notification),
+            Some(notification)
+        );
+    }
+
+    #[test]
+    fn block_range() {
+        let filter = ExExNotificationFilter::new().block_range(2..=3);
+
+        let notification = ExExNotification::ChainCommitted { new: chain(4..=5) };
+        assert_eq!(filter.filter_notification(&notification), None);
+
+        let notification = ExExNotification::ChainCommitted { new: chain(1..=5) };
+        let filtered = filter.filter_notification(&notification).unwrap();
+        assert_eq!(filtered.committed_chain().unwrap().range(), 2..=3);
+
+        let notification = ExExNotification::ChainReorged { old: chain(4..=5), new: chain(3..=4) };
+        let filtered = filter.filter_notification(&notification).unwrap();
+        assert!(matches!(filtered, ExExNotification::ChainCommitted { .. }));
+        assert_eq!(filtered.committed_chain().unwrap().range(), 3..=3);
+    }
+
+    #[test]
+    fn skips_commits_without_matching_transactions() {
+        let filter = ExExNotificationFilter::new().address(Address::random());
+
+        let notification = ExExNotification::ChainCommitted { new: chain(1..=3) };
+        assert_eq!(filter.filter_notification(&notification), None);
+
+        // reverts are still delivered, with all of their blocks
+        let notification = ExExNotification::ChainReverted { old: chain(1..=3) };
+        let filtered = filter.filter_notification(&notification).unwrap();
+        assert_eq!(filtered.reverted_chain().unwrap().range(), 1..=3);
+    }
+
+    #[test]
+    fn matches_transaction_recipient() {
+        let (blocks, receipts) = blocks_with_receipts(1..=2);
+        let recipient = blocks[0].body[1].to().expect("random transactions are calls");
+        let expected_block = blocks[0].clone();
+        let expected_receipts = receipts[0].clone();
+
+        let filter = ExExNotificationFilter::new().address(recipient);
+        let notification =
+            ExExNotification::ChainCommitted { new: chain_with_receipts(blocks, receipts) };
+        let filtered = filter.filter_notification(&notification).unwrap();
+        let new = filtered.committed_chain().unwrap();
+        assert_eq!(new.range(), 1..=1);
+        // the matching block is kept with all of its transactions and receipts
+        assert_eq!(new.blocks().values().collect::<Vec<_>>(), vec![&expected_block]);
+        assert_eq!(
+            new.execution_outcome().receipts.receipt_vec,
+            vec![expected_receipts, Vec::new()]
+        );
+
+        // no transaction is sent to the address
+        let filter = ExExNotificationFilter::new().address(Address::random());
+        assert_eq!(filter.filter_notification(&notification), None);
+    }
+
+    #[test]
+    fn matches_log_address_and_topic() {
+        let (blocks, mut receipts) = blocks_with_receipts(1..=2);
+        let emitter = Address::random();
+        let topic = B256::random();
+
+        // a log with both the address and the topic
+        receipts[1][0].as_mut().unwrap().logs.push(Log::new_unchecked(
+            emitter,
+            vec![B256::random(), topic],
+            Bytes::new(),
+        ));
+        // a log with the address, but another topic
+        receipts[0][1].as_mut().unwrap().logs.push(Log::new_unchecked(
+            emitter,
+            vec![B256::random()],
+            Bytes::new(),
+        ));
+        // a log with the topic, but another address
+        receipts[0][0].as_mut().unwrap().logs.push(Log::new_unchecked(
+            Address::random(),
+            vec![topic],
+            Bytes::new(),
+        ));
+
+        let filter = ExExNotificationFilter::new().address(emitter).topic(topic);
+        let notification =
+            ExExNotification::ChainCommitted { new: chain_with_receipts(blocks, receipts) };
+        let filtered = filter.filter_notification(&notification).unwrap();
+        assert_eq!(block_numbers(&filtered.committed_chain().unwrap()), vec![2]);
+
+        // the topic alone matches both logs with the topic
+        let filter = ExExNotificationFilter::new().topic(topic);
+        let filtered = filter.filter_notification(&notification).unwrap();
+        assert_eq!(block_numbers(&filtered.committed_chain().unwrap()), vec![1, 2]);
+    }
+
+    #[test]
+    fn matches_transaction_type() {
+        let (blocks, receipts) = blocks_with_receipts(1..=2);
+        let tx_type = blocks[0].body[0].tx_type();
+        let expected = blocks
+            .iter()
+            .filter(|block| block.body.iter().any(|tx| tx.tx_type() == tx_type))
+            .map(|block| block.number)
+            .collect::<Vec<_>>();
+        let notification =
+            ExExNotification::ChainCommitted { new: chain_with_receipts(blocks, receipts) };
+
+        let filter = ExExNotificationFilter::new().tx_type(tx_type);
+        let filtered = filter.filter_notification(&notification).unwrap();
+        assert_eq!(block_numbers(&filtered.committed_chain().unwrap()), expected);
+
+        // the type and the recipient must both match
+        let filter = ExExNotificationFilter::new().tx_type(tx_type).address(Address::random());
+        assert_eq!(filter.filter_notification(&notification), None);
+    }
+}
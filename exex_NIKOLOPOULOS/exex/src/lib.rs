@@ -23,9 +23,40 @@
 //! event. To clarify: if the `ExEx` emits `ExExEvent::FinishedHeight(0)` it will receive
 //! notifications for any `block_number > 0`.
 //!
+//! # Health
+//!
+//! The manager tracks the health of every `ExEx`. An `ExEx` that takes a long time to process a
+//! notification should emit `ExExEvent::Heartbeat` events, so that it is not considered
+//! unresponsive. An `ExEx` that can't recover from an error should emit an
+//! `ExExEvent::FatalError` event; the node then detaches it, so that it no longer holds back
+//! pruning. An `ExEx` that lost some of its state can emit an `ExExEvent::Rewind` event to receive
+//! all blocks above the given height again.
+//!
+//! # Backfill
+//!
+//! An `ExEx` that starts behind the node head can emit an `ExExEvent::BackfillFrom` event. The
+//! manager then re-executes all blocks from the given block up to the node head and delivers them
+//! as batches of `ExExNotification::ChainCommitted` notifications, before switching to live
+//! notifications.
+//!
+//! # Filtering
+//!
+//! An `ExEx` that is only interested in a subset of the chain can be installed with an
+//! [`ExExNotificationFilter`]. Its notifications are then pruned to the matching blocks and
+//! transactions, and committed chains without any matching transaction are not delivered at all.
+//!
+//! # Persistence
+//!
+//! If the [`ExExManager`] is given a [`Wal`], every notification is persisted before it is
+//! delivered, along with the finished height of each `ExEx`. After a restart, all notifications
+//! above an `ExEx`'s last finished height are delivered again.
+//!
 //! [`Future`]: std::future::Future
 //! [`ExExContext`]: crate::ExExContext
 //! [`CanonStateNotification`]: reth_provider::CanonStateNotification
+//! [`ExExManager`]: crate::ExExManager
+//! [`Wal`]: crate::Wal
+//! [`ExExNotificationFilter`]: crate::ExExNotificationFilter
 
 #![doc(
     html_logo_url = "https://raw.githubusercontent.com/paradigmxyz/reth/main/assets/reth-docs.png",
@@ -35,6 +66,11 @@
 #![cfg_attr(docsrs, feature(doc_cfg, doc_auto_cfg))]
 #![cfg_attr(not(test), warn(unused_crate_dependencies))]
 
+/// the backfill module, which re-executes historical blocks for `ExEx` tasks that start behind
+/// the node head.
+mod backfill;
+pub use backfill::*;
+
 /// the context module, which contains the definition and implementation of the `ExExContext` struct.
 mod context;
 pub use context::*;
@@ -43,6 +79,11 @@ pub use context::*;
 mod event;
 pub use event::*;
 
+/// the filter module, which prunes the notifications delivered to `ExEx` tasks to the blocks and
+/// transactions they are interested in.
+mod filter;
+pub use filter::*;
+
 /// the manager module, which manages the lifecycle and execution of `ExEx` tasks.
 mod manager;
 pub use manager::*;
@@ -51,6 +92,10 @@ pub use manager::*;
 mod notification;
 pub use notification::*;
 
+/// the wal module, which persists notifications so that `ExEx` tasks survive restarts.
+mod wal;
+pub use wal::*;
+
 // re-export ExEx types for easy access.
 #[doc(inline)]
 pub use reth_exex_types::*;
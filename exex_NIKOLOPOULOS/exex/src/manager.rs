@@ -1,10 +1,14 @@
-use crate::{ExExEvent, ExExNotification, FinishedExExHeight};
+use crate::{
+    wal::highest_block, ExExEvent, ExExHealth, ExExNotification, ExExNotificationFilter,
+    FinishedExExHeight, SpawnBackfill, Wal,
+};
 use metrics::Gauge;
+use reth_execution_types::Chain;
 use reth_metrics::{metrics::Counter, Metrics};
 use reth_primitives::BlockNumber;
-use reth_tracing::tracing::debug;
+use reth_tracing::tracing::{debug, warn};
 use std::{
-    collections::VecDeque,
+    collections::{BTreeMap, VecDeque},
     future::{poll_fn, Future},
     pin::Pin,
     sync::{
@@ -12,10 +16,14 @@ use std::{
         Arc,
     },
     task::{ready, Context, Poll},
+    time::Duration,
 };
-use tokio::sync::{
-    mpsc::{self, error::SendError, Receiver, UnboundedReceiver, UnboundedSender},
-    watch,
+use tokio::{
+    sync::{
+        mpsc::{self, error::SendError, Receiver, UnboundedReceiver, UnboundedSender},
+        watch,
+    },
+    time::{Instant, Interval},
 };
 use tokio_util::sync::{PollSendError, PollSender, ReusableBoxFuture};
 
@@ -27,8 +35,22 @@ struct ExExMetrics {
     notifications_sent_total: Counter,
     /// the total number of events an `ExEx` has sent to the manager.
     events_sent_total: Counter,
+    /// the total number of heartbeats an `ExEx` has sent to the manager.
+    heartbeats_total: Counter,
+    /// the total number of rewinds an `ExEx` has requested.
+    rewinds_total: Counter,
+    /// the total number of times an `ExEx` was restarted after a failure.
+    restarts_total: Counter,
+    /// the health of an `ExEx`, see [`ExExHealth::as_metric`].
+    health: Gauge,
+    /// the last finished height of an `ExEx`.
+    finished_height: Gauge,
 }
 
+/// the default duration after which an `ExEx` with pending notifications that hasn't emitted any
+/// event is considered unresponsive.
+pub const DEFAULT_EXEX_HEARTBEAT_TIMEOUT: Duration = Duration::from_secs(60);
+
 /// a handle to an `ExEx` used by the [`ExExManager`] to communicate with `ExEx`'s.
 ///
 /// a handle should be created for each `ExEx` with a unique ID. The channels returned by
@@ -52,6 +74,31 @@ pub struct ExExHandle {
     ///
     /// if this is `None`, the `ExEx` has not emitted a `FinishedHeight` event.
     finished_height: Option<BlockNumber>,
+    /// the finished block number of the `ExEx` that was last persisted to the [`Wal`].
+    persisted_finished_height: Option<BlockNumber>,
+
+    /// channel to receive backfilled batches of blocks from, if the `ExEx` is backfilling.
+    ///
+    /// live notifications are held back in the manager's buffer until the backfill is finished.
+    backfill: Option<Receiver<eyre::Result<Chain>>>,
+
+    /// the health of the `ExEx`.
+    health: ExExHealth,
+    /// the time the `ExEx` last emitted an event, received a notification, or was last fully
+    /// caught up.
+    last_seen: Instant,
+
+    /// the filter of the notifications delivered to the `ExEx`, if any.
+    filter: Option<ExExNotificationFilter>,
+    /// the tip of the last notification delivered to the `ExEx`.
+    last_delivered_tip: Option<BlockNumber>,
+    /// the tip of the last notification skipped by the filter, or of the blocks the filter
+    /// dropped from the end of the last delivered notification, while the `ExEx` was still
+    /// processing earlier notifications.
+    ///
+    /// the `ExEx` is considered to have finished this height as well, as soon as it finishes the
+    /// last delivered tip.
+    skipped_height: Option<BlockNumber>,
 }
 
 impl ExExHandle {
@@ -72,22 +119,55 @@ impl ExExHandle {
                 receiver: event_rx,
                 next_notification_id: 0,
                 finished_height: None,
+                persisted_finished_height: None,
+                backfill: None,
+                health: ExExHealth::Healthy,
+                last_seen: Instant::now(),
+                filter: None,
+                last_delivered_tip: None,
+                skipped_height: None,
             },
             event_tx,
             notification_rx,
         )
     }
 
+    /// sets the filter of the notifications delivered to the `ExEx`.
+    ///
+    /// notifications that don't match the filter at all are not delivered, and the `ExEx` is
+    /// considered to have finished them as soon as it finishes all notifications delivered before.
+    pub fn with_filter(mut self, filter: ExExNotificationFilter) -> Self {
+        self.filter = (!filter.is_empty()).then_some(filter);
+        self
+    }
+
     /// reserves a slot in the `PollSender` channel and sends the notification if the slot was
     /// successfully reserved.
     ///
     /// whe n the notification is sent, it is considered delivered.
+    ///
+    /// `is_replay` denotes that the notification was replayed from the [`Wal`] on startup.
     fn send(
         &mut self,
         cx: &mut Context<'_>,
         (notification_id, notification): &(usize, ExExNotification),
+        is_replay: bool,
     ) -> Poll<Result<(), PollSendError<ExExNotification>>> {
         if let Some(finished_height) = self.finished_height {
+            // skip any replayed notification the ExEx has already processed before the restart,
+            // including reorgs and reverts, since its finished height already accounts for them.
+            if is_replay && finished_height >= highest_block(notification) {
+                debug!(
+                    exex_id = %self.id,
+                    %notification_id,
+                    %finished_height,
+                    "Skipping replayed notification"
+                );
+
+                self.next_notification_id = notification_id + 1;
+                return Poll::Ready(Ok(()))
+            }
+
             match notification {
                 ExExNotification::ChainCommitted { new } => {
                     // skip the chain commit notification if the finished height of the ExEx is
@@ -124,14 +204,40 @@ impl ExExHandle {
             other => return other,
         }
 
+        // apply the filter once the slot is reserved, so that it is applied only once
+        let filtered = match &self.filter {
+            Some(filter) => match filter.filter_notification(notification) {
+                Some(notification) => notification,
+                None => {
+                    debug!(
+                        exex_id = %self.id,
+                        %notification_id,
+                        "Skipping filtered notification"
+                    );
+
+                    if let Some(tip) = notification_tip(notification) {
+                        self.skip_filtered(tip);
+                    }
+                    self.next_notification_id = notification_id + 1;
+                    return Poll::Ready(Ok(()))
+                }
+            },
+            None => notification.clone(),
+        };
+
         debug!(
             exex_id = %self.id,
             %notification_id,
             "Sending notification"
         );
-        match self.sender.send_item(notification.clone()) {
+        let tip = notification_tip(&filtered);
+        let original_tip = notification_tip(notification);
+        match self.sender.send_item(filtered) {
             Ok(()) => {
                 self.next_notification_id = notification_id + 1;
+                self.set_delivered_tip(tip, original_tip);
+                // the channel has a capacity of one, so the ExEx has received the previous one
+                self.last_seen = Instant::now();
                 self.metrics.notifications_sent_total.increment(1);
                 Poll::Ready(Ok(()))
             }
@@ -140,6 +246,167 @@ impl ExExHandle {
     }
 }
 
+impl ExExHandle {
+    /// records that a notification with the given tip was delivered to the `ExEx`.
+    ///
+    /// if the filter dropped the blocks at the end of the notification, the `ExEx` has finished
+    /// the original tip of the notification as soon as it finishes the delivered one.
+    fn set_delivered_tip(&mut self, tip: Option<BlockNumber>, original_tip: Option<BlockNumber>) {
+        self.last_delivered_tip = tip.or(self.last_delivered_tip);
+        if original_tip > tip {
+            self.skipped_height = original_tip;
+        }
+    }
+
+    /// records that a notification with the given tip was skipped by the filter of the `ExEx`.
+    ///
+    /// if the `ExEx` has finished all notifications delivered so far, it has finished the skipped
+    /// notification as well. otherwise, the skipped height is applied once it does.
+    fn skip_filtered(&mut self, tip: BlockNumber) {
+        let caught_up = self.finished_height.is_some_and(|finished_height| {
+            self.last_delivered_tip.map_or(true, |delivered| finished_height >= delivered)
+        });
+
+        if caught_up {
+            self.finished_height = Some(tip);
+            self.metrics.finished_height.set(tip as f64);
+        } else {
+            self.skipped_height = Some(tip);
+        }
+    }
+
+    /// updates the finished height of the `ExEx` from a `FinishedHeight` event, taking the
+    /// notifications skipped by the filter into account.
+    fn set_finished_height(&mut self, height: BlockNumber) {
+        let mut height = height;
+        if let Some(skipped_height) = self.skipped_height {
+            if self.last_delivered_tip.map_or(true, |delivered| height >= delivered) {
+                height = height.max(skipped_height);
+                self.skipped_height = None;
+            }
+        }
+
+        self.finished_height = Some(height);
+        self.metrics.finished_height.set(height as f64);
+    }
+
+    /// updates the health of the `ExEx`, returning `true` if it changed.
+    fn set_health(&mut self, health: ExExHealth) -> bool {
+        if self.health == health {
+            return false
+        }
+
+        debug!(exex_id = %self.id, ?health, "Updating exex health");
+        self.metrics.health.set(health.as_metric() as f64);
+        self.health = health;
+        true
+    }
+
+    /// marks the `ExEx` as failed, dropping any backfill in progress.
+    fn fail(&mut self, reason: String) -> bool {
+        warn!(exex_id = %self.id, %reason, "ExEx failed");
+        self.backfill = None;
+        self.set_health(ExExHealth::Failed { reason })
+    }
+
+    /// restarts the failed `ExEx`, delivering all blocks above its finished height again.
+    ///
+    /// returns `false` if the `ExEx` stopped receiving notifications, since it can't be restarted
+    /// then.
+    fn restart(
+        &mut self,
+        backfill: Option<&dyn SpawnBackfill>,
+        tip: Option<BlockNumber>,
+        next_id: usize,
+    ) -> bool {
+        if self.sender.is_closed() {
+            return false
+        }
+
+        warn!(exex_id = %self.id, finished_height = ?self.finished_height, "Restarting ExEx");
+        self.metrics.restarts_total.increment(1);
+        self.set_health(ExExHealth::Healthy);
+        self.last_seen = Instant::now();
+        self.next_notification_id = next_id;
+        // the ExEx resumes from its finished height, so everything delivered after it is delivered
+        // again
+        self.last_delivered_tip = self.finished_height;
+        self.skipped_height = None;
+        if let Some(finished_height) = self.finished_height {
+            self.start_backfill(backfill, tip, finished_height + 1, next_id);
+        }
+        true
+    }
+
+    /// starts a backfill of the `ExEx` from the given block up to the tip.
+    ///
+    /// `next_id` is the ID of the next notification the manager will receive.
+    fn start_backfill(
+        &mut self,
+        backfill: Option<&dyn SpawnBackfill>,
+        tip: Option<BlockNumber>,
+        from: BlockNumber,
+        next_id: usize,
+    ) {
+        let (Some(backfill), Some(tip)) = (backfill, tip) else {
+            warn!(exex_id = %self.id, %from, "Backfill is not supported, ignoring");
+            return
+        };
+        if from > tip {
+            debug!(exex_id = %self.id, %from, %tip, "Nothing to backfill");
+            return
+        }
+
+        debug!(exex_id = %self.id, %from, %tip, "Starting backfill");
+        self.backfill = Some(backfill.spawn_backfill(from..=tip));
+        // the backfill covers everything up to the tip, so only the notifications after it are
+        // delivered once the backfill is finished
+        self.next_notification_id = next_id;
+    }
+
+    /// delivers the backfilled batches to the `ExEx` as [`ExExNotification::ChainCommitted`]
+    /// notifications.
+    ///
+    /// returns [`Poll::Ready`] once there is no backfill in progress, meaning that the `ExEx` can
+    /// receive live notifications again.
+    fn poll_backfill(&mut self, cx: &mut Context<'_>) -> Poll<eyre::Result<()>> {
+        while self.backfill.is_some() {
+            // reserve the slot first, so that a received batch can always be sent right away
+            ready!(self.sender.poll_reserve(cx))?;
+
+            let backfill = self.backfill.as_mut().expect("checked above");
+            match ready!(backfill.poll_recv(cx)) {
+                Some(Ok(chain)) => {
+                    let range = chain.range();
+                    let notification = ExExNotification::ChainCommitted { new: Arc::new(chain) };
+                    let filtered = match &self.filter {
+                        Some(filter) => filter.filter_notification(&notification),
+                        None => Some(notification),
+                    };
+                    let Some(notification) = filtered else {
+                        debug!(exex_id = %self.id, ?range, "Skipping filtered backfilled notification");
+                        self.skip_filtered(*range.end());
+                        continue
+                    };
+
+                    debug!(exex_id = %self.id, ?range, "Sending backfilled notification");
+                    self.set_delivered_tip(notification_tip(&notification), Some(*range.end()));
+                    self.sender.send_item(notification)?;
+                    self.last_seen = Instant::now();
+                    self.metrics.notifications_sent_total.increment(1);
+                }
+                Some(Err(err)) => return Poll::Ready(Err(err)),
+                None => {
+                    debug!(exex_id = %self.id, "Backfill finished");
+                    self.backfill = None;
+                }
+            }
+        }
+
+        Poll::Ready(Ok(()))
+    }
+}
+
 /// metrics for the `ExEx` manager.
 #[derive(Metrics)]
 #[metrics(scope = "exex_manager")]
@@ -177,6 +444,8 @@ pub struct ExExManager {
     min_id: usize,
     /// Monotonically increasing ID for [`ExExNotification`]s.
     next_id: usize,
+    /// Notifications with an ID lower than this were replayed from the [`Wal`] on startup.
+    replay_end_id: usize,
     /// Internal buffer of [`ExExNotification`]s.
     ///
     /// The first element of the tuple is a monotonically increasing ID unique to the notification
@@ -195,6 +464,31 @@ pub struct ExExManager {
     /// The finished height of all `ExEx`'s.
     finished_height: watch::Sender<FinishedExExHeight>,
 
+    /// The write-ahead log that notifications are persisted to before being delivered, if any.
+    wal: Option<Wal>,
+    /// Channel to receive the IDs of `ExEx`'s to detach from the [`ExExManagerHandle`]s.
+    detach_rx: UnboundedReceiver<String>,
+    /// Channel to receive the IDs of failed `ExEx`'s to restart from the [`ExExManagerHandle`]s.
+    restart_rx: UnboundedReceiver<String>,
+    /// The duration after which an `ExEx` with pending notifications that hasn't emitted any
+    /// event is considered unresponsive.
+    heartbeat_timeout: Duration,
+    /// Interval to periodically check the health of `ExEx`'s.
+    ///
+    /// Created on first poll, since it requires a runtime.
+    health_check: Option<Interval>,
+
+    /// Spawner of backfill jobs for `ExEx`'s that start behind the node head, if any.
+    backfill: Option<Box<dyn SpawnBackfill>>,
+    /// The number of the canonical tip as of the last notification.
+    ///
+    /// Backfill jobs execute blocks up to and including this block.
+    tip: Option<BlockNumber>,
+    /// Channel to receive finalized block numbers from the [`ExExManagerHandle`]s.
+    finalized_rx: UnboundedReceiver<BlockNumber>,
+    /// The highest finalized block number received so far.
+    finalized_block: Option<BlockNumber>,
+
     /// A handle to the `ExEx` manager.
     handle: ExExManagerHandle,
     /// Metrics for the `ExEx` manager.
@@ -213,6 +507,9 @@ impl ExExManager {
         let num_exexs = handles.len();
 
         let (handle_tx, handle_rx) = mpsc::unbounded_channel();
+        let (finalized_tx, finalized_rx) = mpsc::unbounded_channel();
+        let (detach_tx, detach_rx) = mpsc::unbounded_channel();
+        let (restart_tx, restart_rx) = mpsc::unbounded_channel();
         let (is_ready_tx, is_ready_rx) = watch::channel(true);
         let (finished_height_tx, finished_height_rx) = watch::channel(if num_exexs == 0 {
             FinishedExExHeight::NoExExs
@@ -233,6 +530,7 @@ impl ExExManager {
 
             min_id: 0,
             next_id: 0,
+            replay_end_id: 0,
             buffer: VecDeque::with_capacity(max_capacity),
             max_capacity,
             current_capacity: Arc::clone(&current_capacity),
@@ -240,9 +538,23 @@ impl ExExManager {
             is_ready: is_ready_tx,
             finished_height: finished_height_tx,
 
+            detach_rx,
+            restart_rx,
+            heartbeat_timeout: DEFAULT_EXEX_HEARTBEAT_TIMEOUT,
+            health_check: None,
+
+            wal: None,
+            backfill: None,
+            tip: None,
+            finalized_rx,
+            finalized_block: None,
+
             handle: ExExManagerHandle {
                 exex_tx: handle_tx,
-                num_exexs,
+                finalized_tx,
+                detach_tx,
+                restart_tx,
+                num_exexs: Arc::new(AtomicUsize::new(num_exexs)),
                 is_ready_receiver: is_ready_rx.clone(),
                 is_ready: ReusableBoxFuture::new(make_wait_future(is_ready_rx)),
                 current_capacity,
@@ -252,6 +564,52 @@ impl ExExManager {
         }
     }
 
+    /// Sets the [`Wal`] of the manager and replays the notifications it contains.
+    ///
+    /// Every `ExEx` that has not emitted a `FinishedHeight` event yet starts from the finished
+    /// height persisted in the WAL, and only receives the replayed notifications above it.
+    /// All new notifications are written to the WAL before they are delivered.
+    pub fn with_wal(mut self, wal: Wal) -> eyre::Result<Self> {
+        for exex in &mut self.exex_handles {
+            exex.persisted_finished_height = wal.finished_height(&exex.id);
+            if exex.finished_height.is_none() {
+                exex.finished_height = exex.persisted_finished_height;
+            }
+        }
+
+        for notification in wal.iter_notifications() {
+            self.push_notification(notification?);
+        }
+        self.replay_end_id = self.next_id;
+        debug!(replayed = %self.replay_end_id, "Replayed notifications from WAL");
+
+        self.wal = Some(wal);
+        self.update_capacity();
+
+        Ok(self)
+    }
+
+    /// Sets the duration after which an `ExEx` with pending notifications that hasn't emitted any
+    /// event is considered [`ExExHealth::Unresponsive`].
+    ///
+    /// Defaults to [`DEFAULT_EXEX_HEARTBEAT_TIMEOUT`].
+    pub const fn with_heartbeat_timeout(mut self, heartbeat_timeout: Duration) -> Self {
+        self.heartbeat_timeout = heartbeat_timeout;
+        self
+    }
+
+    /// Sets the spawner of backfill jobs, and the current head of the node.
+    ///
+    /// `ExEx`'s that emit an [`ExExEvent::BackfillFrom`] event receive all blocks from the
+    /// requested block up to the node head as batches of [`ExExNotification::ChainCommitted`]
+    /// notifications, before switching to live notifications. Note that the blocks must not be
+    /// pruned from the node yet.
+    pub fn with_backfill(mut self, backfill: impl SpawnBackfill, head: BlockNumber) -> Self {
+        self.backfill = Some(Box::new(backfill));
+        self.tip = Some(head);
+        self
+    }
+
     /// Returns the handle to the manager.
     pub fn handle(&self) -> ExExManagerHandle {
         self.handle.clone()
@@ -273,10 +631,51 @@ impl ExExManager {
     /// Pushes a new notification into the managers internal buffer, assigning the notification a
     /// unique ID.
     fn push_notification(&mut self, notification: ExExNotification) {
+        self.tip = notification_tip(&notification);
+
         let next_id = self.next_id;
         self.buffer.push_back((next_id, notification));
         self.next_id += 1;
     }
+
+    /// Detaches the `ExEx` with the given ID, dropping its channels.
+    ///
+    /// Returns `true` if the `ExEx` was found.
+    fn detach(&mut self, exex_id: &str) -> bool {
+        let Some(idx) = self.exex_handles.iter().position(|exex| exex.id == exex_id) else {
+            return false
+        };
+
+        let mut exex = self.exex_handles.swap_remove(idx);
+        exex.set_health(ExExHealth::Detached);
+        warn!(exex_id = %exex.id, health = ?exex.health, "Detached ExEx");
+
+        self.handle.num_exexs.store(self.exex_handles.len(), Ordering::Relaxed);
+        self.metrics.num_exexs.set(self.exex_handles.len() as f64);
+        true
+    }
+
+    /// Restarts the failed `ExEx` with the given ID, or detaches it if it can't be restarted.
+    ///
+    /// Returns `true` if the `ExEx` was found.
+    fn restart(&mut self, exex_id: &str) -> bool {
+        let next_id = self.next_id;
+        let tip = self.tip;
+        let backfill = self.backfill.as_deref();
+        let Some(exex) = self.exex_handles.iter_mut().find(|exex| exex.id == exex_id) else {
+            return false
+        };
+
+        if !exex.health.is_failed() {
+            debug!(%exex_id, health = ?exex.health, "Not restarting ExEx that didn't fail");
+            return true
+        }
+        if !exex.restart(backfill, tip, next_id) {
+            warn!(%exex_id, "ExEx stopped receiving notifications and can't be restarted");
+            self.detach(exex_id);
+        }
+        true
+    }
 }
 
 impl Future for ExExManager {
@@ -292,6 +691,12 @@ impl Future for ExExManager {
                     reverted_tip = ?notification.reverted_chain().map(|chain| chain.tip().number),
                     "Received new notification"
                 );
+                // Persist the notification before it is delivered to any ExEx
+                if let Some(wal) = &mut self.wal {
+                    if let Err(err) = wal.commit(&notification) {
+                        return Poll::Ready(Err(err))
+                    }
+                }
                 // Add the new notification to the buffer
                 self.push_notification(notification);
                 continue
@@ -302,11 +707,49 @@ impl Future for ExExManager {
         // Update the buffer capacity after adding new notifications
         self.update_capacity();
 
+        // Detach the ExEx's the node has decided to detach
+        while let Poll::Ready(Some(exex_id)) = self.detach_rx.poll_recv(cx) {
+            if !self.detach(&exex_id) {
+                debug!(%exex_id, "Cannot detach unknown exex");
+            }
+        }
+
+        // Restart the failed ExEx's the node has decided to restart
+        while let Poll::Ready(Some(exex_id)) = self.restart_rx.poll_recv(cx) {
+            if !self.restart(&exex_id) {
+                debug!(%exex_id, "Cannot restart unknown exex");
+            }
+        }
+
         // Advance all poll senders for each ExEx handle
         let mut min_id = usize::MAX;
         for idx in (0..self.exex_handles.len()).rev() {
             let mut exex = self.exex_handles.swap_remove(idx);
 
+            // Failed ExEx's don't receive notifications anymore, and must not hold back the
+            // buffer until the node detaches them
+            if exex.health.is_failed() {
+                exex.next_notification_id = self.next_id;
+                self.exex_handles.push(exex);
+                continue
+            }
+
+            // Deliver backfilled blocks first, live notifications are held back in the buffer
+            // until the backfill is finished
+            match exex.poll_backfill(cx) {
+                Poll::Ready(Ok(())) => {}
+                Poll::Ready(Err(err)) => {
+                    exex.fail(format!("backfill failed: {err}"));
+                    self.exex_handles.push(exex);
+                    continue
+                }
+                Poll::Pending => {
+                    min_id = min_id.min(exex.next_notification_id);
+                    self.exex_handles.push(exex);
+                    continue
+                }
+            }
+
             // Calculate the notification index for this ExEx handle
             let notification_index = exex
                 .next_notification_id
@@ -314,9 +757,10 @@ impl Future for ExExManager {
                 .expect("exex expected notification ID outside the manager's range");
             if let Some(notification) = self.buffer.get(notification_index) {
                 // Attempt to send the notification
-                if let Poll::Ready(Err(err)) = exex.send(cx, notification) {
-                    // If the channel was closed, return an error
-                    return Poll::Ready(Err(err.into()))
+                let is_replay = notification.0 < self.replay_end_id;
+                if let Poll::Ready(Err(err)) = exex.send(cx, notification, is_replay) {
+                    // If the channel was closed, the ExEx is gone
+                    exex.fail(format!("failed to send notification: {err}"));
                 }
             }
             // Update the minimum notification ID seen so far
@@ -333,34 +777,132 @@ impl Future for ExExManager {
         self.update_capacity();
 
         // Handle incoming events from each ExEx handle
-        for exex in &mut self.exex_handles {
+        let this = &mut *self;
+        let now = Instant::now();
+        for exex in &mut this.exex_handles {
             while let Poll::Ready(Some(event)) = exex.receiver.poll_recv(cx) {
                 // Log the received event from the ExEx handle
                 debug!(exex_id = %exex.id, ?event, "Received event from exex");
                 // Increment the total events sent metric
                 exex.metrics.events_sent_total.increment(1);
+                exex.last_seen = now;
+
+                // Any event but a fatal error means that the ExEx is alive
+                if exex.health == ExExHealth::Unresponsive {
+                    exex.set_health(ExExHealth::Healthy);
+                }
+
+                let finished_height = match event {
+                    ExExEvent::FinishedHeight(height) => Some(height),
+                    ExExEvent::BackfillFrom(from) => {
+                        exex.start_backfill(this.backfill.as_deref(), this.tip, from, this.next_id);
+                        None
+                    }
+                    ExExEvent::Rewind(height) => {
+                        exex.metrics.rewinds_total.increment(1);
+                        if this.backfill.is_some() {
+                            exex.start_backfill(
+                                this.backfill.as_deref(),
+                                this.tip,
+                                height + 1,
+                                this.next_id,
+                            );
+                        } else {
+                            // the blocks above the height can't be delivered again
+                            exex.fail(format!(
+                                "cannot rewind to block {height} without a backfill spawner"
+                            ));
+                        }
+                        Some(height)
+                    }
+                    ExExEvent::Heartbeat => {
+                        exex.metrics.heartbeats_total.increment(1);
+                        None
+                    }
+                    ExExEvent::FatalError(reason) => {
+                        exex.fail(reason);
+                        None
+                    }
+                };
+
                 // Update the finished height if the event contains a new height
-                match event {
-                    ExExEvent::FinishedHeight(height) => exex.finished_height = Some(height),
+                if let Some(height) = finished_height {
+                    exex.set_finished_height(height);
                 }
             }
-        }
 
-        // Update the watch channel with the minimum finished height across all ExEx handles
-        let finished_height = self.exex_handles.iter_mut().try_fold(u64::MAX, |curr, exex| {
-            let height = match exex.finished_height {
-                None => return Err(()),
-                Some(height) => height,
-            };
+            // Persist the finished height so that the ExEx can resume from it, including a height
+            // advanced past the notifications skipped by the filter
+            if let (Some(wal), Some(height)) = (&mut this.wal, exex.finished_height) {
+                if exex.persisted_finished_height != Some(height) {
+                    if let Err(err) = wal.set_finished_height(&exex.id, height) {
+                        return Poll::Ready(Err(err))
+                    }
+                    exex.persisted_finished_height = Some(height);
+                }
+            }
 
-            if height < curr {
-                Ok(height)
-            } else {
-                Ok(curr)
+            // An ExEx without pending notifications is considered alive, as it is just waiting
+            let is_pending = exex.backfill.is_some() || exex.next_notification_id < this.next_id;
+            if !is_pending {
+                exex.last_seen = now;
+            } else if exex.health == ExExHealth::Healthy &&
+                now.duration_since(exex.last_seen) > this.heartbeat_timeout
+            {
+                exex.set_health(ExExHealth::Unresponsive);
             }
+        }
+
+        // Periodically wake up to check the health of ExEx's, even if there are no new events
+        let heartbeat_timeout = self.heartbeat_timeout;
+        let health_check =
+            self.health_check.get_or_insert_with(|| tokio::time::interval(heartbeat_timeout));
+        while health_check.poll_tick(cx).is_ready() {}
+
+        // Track the highest finalized block
+        while let Poll::Ready(Some(block_number)) = self.finalized_rx.poll_recv(cx) {
+            self.finalized_block = self.finalized_block.max(Some(block_number));
+        }
+
+        // Update the watch channel with the minimum finished height across all ExEx handles, and
+        // the ExEx's that failed
+        let finished_height = self
+            .exex_handles
+            .iter()
+            .try_fold(u64::MAX, |curr, exex| exex.finished_height.map(|height| height.min(curr)));
+        let failed = self
+            .exex_handles
+            .iter()
+            .filter_map(|exex| match &exex.health {
+                ExExHealth::Failed { reason } => Some((exex.id.clone(), reason.clone())),
+                _ => None,
+            })
+            .collect::<BTreeMap<_, _>>();
+        let status = if self.exex_handles.is_empty() {
+            // all ExEx's were detached
+            FinishedExExHeight::NoExExs
+        } else if !failed.is_empty() {
+            FinishedExExHeight::Failed { height: finished_height, failed }
+        } else if let Some(finished_height) = finished_height {
+            FinishedExExHeight::Height(finished_height)
+        } else {
+            FinishedExExHeight::NotReady
+        };
+        self.finished_height.send_if_modified(|current| {
+            let modified = *current != status;
+            *current = status;
+            modified
         });
-        if let Ok(finished_height) = finished_height {
-            let _ = self.finished_height.send(FinishedExExHeight::Height(finished_height));
+
+        // Prune the notifications that are finalized and processed by all ExEx's from the WAL
+        if let (Some(finished_height), Some(finalized_block)) =
+            (finished_height, self.finalized_block)
+        {
+            if let Some(wal) = &mut self.wal {
+                if let Err(err) = wal.finalize(finalized_block.min(finished_height)) {
+                    return Poll::Ready(Err(err))
+                }
+            }
         }
 
         // Indicate that the future is not yet complete and should be polled again
@@ -369,13 +911,28 @@ impl Future for ExExManager {
 }
 
 
+/// Returns the canonical tip after the notification is applied.
+///
+/// The tip is the new chain on commits and reorgs, and the parent of the old chain on reverts.
+fn notification_tip(notification: &ExExNotification) -> Option<BlockNumber> {
+    notification.committed_chain().map(|chain| chain.tip().number).or_else(|| {
+        notification.reverted_chain().map(|chain| chain.first().number.saturating_sub(1))
+    })
+}
+
 /// A handle to communicate with the [`ExExManager`].
 #[derive(Debug)]
 pub struct ExExManagerHandle {
     /// Channel to send notifications to the `ExEx` manager.
     exex_tx: UnboundedSender<ExExNotification>,
-    /// The number of `ExEx`'s running on the node.
-    num_exexs: usize,
+    /// Channel to send finalized block numbers to the `ExEx` manager.
+    finalized_tx: UnboundedSender<BlockNumber>,
+    /// Channel to send the IDs of `ExEx`'s to detach to the `ExEx` manager.
+    detach_tx: UnboundedSender<String>,
+    /// Channel to send the IDs of failed `ExEx`'s to restart to the `ExEx` manager.
+    restart_tx: UnboundedSender<String>,
+    /// The number of `ExEx`'s running on the node, decremented when an `ExEx` is detached.
+    num_exexs: Arc<AtomicUsize>,
     /// A watch channel denoting whether the manager is ready for new notifications or not.
     ///
     /// This is stored internally alongside a `ReusableBoxFuture` representation of the same value.
@@ -399,12 +956,18 @@ impl ExExManagerHandle {
     /// The handle will always be ready, and have a capacity of 0.
     pub fn empty() -> Self {
         let (exex_tx, _) = mpsc::unbounded_channel();
+        let (finalized_tx, _) = mpsc::unbounded_channel();
+        let (detach_tx, _) = mpsc::unbounded_channel();
+        let (restart_tx, _) = mpsc::unbounded_channel();
         let (_, is_ready_rx) = watch::channel(true);
         let (_, finished_height_rx) = watch::channel(FinishedExExHeight::NoExExs);
 
         Self {
             exex_tx,
-            num_exexs: 0,
+            finalized_tx,
+            detach_tx,
+            restart_tx,
+            num_exexs: Arc::new(AtomicUsize::new(0)),
             is_ready_receiver: is_ready_rx.clone(),
             is_ready: ReusableBoxFuture::new(make_wait_future(is_ready_rx)),
             current_capacity: Arc::new(AtomicUsize::new(0)),
@@ -431,6 +994,15 @@ impl ExExManagerHandle {
         self.exex_tx.send(notification)
     }
 
+    /// Notify the `ExEx` manager that the given block has been finalized.
+    ///
+    /// Notifications up to the finalized block are pruned from the [`Wal`] once all `ExEx`'s
+    /// have finished processing them.
+    pub fn update_finalized_block(&self, block_number: BlockNumber) {
+        // we can safely ignore if the channel is closed, since this means the manager is gone
+        let _ = self.finalized_tx.send(block_number);
+    }
+
     /// Get the current capacity of the `ExEx` manager's internal notification buffer.
     pub fn capacity(&self) -> usize {
         self.current_capacity.load(Ordering::Relaxed)
@@ -444,16 +1016,38 @@ impl ExExManagerHandle {
         self.current_capacity.load(Ordering::Relaxed) > 0
     }
 
-    /// Returns `true` if there are `ExEx`'s installed in the node.
-    pub const fn has_exexs(&self) -> bool {
-        self.num_exexs > 0
+    /// Returns `true` if there are `ExEx`'s installed in the node that were not detached.
+    pub fn has_exexs(&self) -> bool {
+        self.num_exexs.load(Ordering::Relaxed) > 0
     }
 
-    /// The finished height of all `ExEx`'s.
+    /// The finished height of all `ExEx`'s, and the `ExEx`'s that
+    /// [failed](FinishedExExHeight::Failed).
     pub fn finished_height(&self) -> watch::Receiver<FinishedExExHeight> {
         self.finished_height.clone()
     }
 
+    /// Detach the `ExEx` with the given ID from the node.
+    ///
+    /// The `ExEx` no longer receives notifications, and no longer holds back the
+    /// [`FinishedExExHeight`]. This is usually done after the `ExEx` has
+    /// [failed](ExExHealth::Failed).
+    pub fn detach(&self, exex_id: impl Into<String>) {
+        // we can safely ignore if the channel is closed, since this means the manager is gone
+        let _ = self.detach_tx.send(exex_id.into());
+    }
+
+    /// Restart the [failed](ExExHealth::Failed) `ExEx` with the given ID.
+    ///
+    /// The `ExEx` is considered healthy again, and receives all blocks above its finished height
+    /// again, through a backfill if the manager has one. This is meant for `ExEx`'s that reported
+    /// a fatal error, reset their state, and keep receiving notifications. An `ExEx` that stopped
+    /// receiving notifications can't be restarted, and is detached instead.
+    pub fn restart(&self, exex_id: impl Into<String>) {
+        // we can safely ignore if the channel is closed, since this means the manager is gone
+        let _ = self.restart_tx.send(exex_id.into());
+    }
+
     /// Wait until the manager is ready for new notifications.
     pub async fn ready(&mut self) {
         poll_fn(|cx| self.poll_ready(cx)).await
@@ -480,7 +1074,10 @@ impl Clone for ExExManagerHandle {
     ///
     /// This method creates a new instance of `ExExManagerHandle` with cloned fields:
     /// - `exex_tx`: Clones the unbounded sender for `ExExNotification`.
-    /// - `num_exexs`: Copies the number of `ExEx` instances.
+    /// - `finalized_tx`: Clones the unbounded sender for finalized block numbers.
+    /// - `detach_tx`: Clones the unbounded sender for `ExEx` IDs to detach.
+    /// - `restart_tx`: Clones the unbounded sender for `ExEx` IDs to restart.
+    /// - `num_exexs`: Clones the atomic integer tracking the number of `ExEx` instances.
     /// - `is_ready_receiver`: Clones the watch channel receiver indicating manager readiness.
     /// - `is_ready`: Initializes a new `ReusableBoxFuture` waiting on `is_ready_receiver`.
     /// - `current_capacity`: Clones the atomic integer tracking buffer capacity.
@@ -492,7 +1089,10 @@ impl Clone for ExExManagerHandle {
     fn clone(&self) -> Self {
         Self {
             exex_tx: self.exex_tx.clone(),
-            num_exexs: self.num_exexs,
+            finalized_tx: self.finalized_tx.clone(),
+            detach_tx: self.detach_tx.clone(),
+            restart_tx: self.restart_tx.clone(),
+            num_exexs: self.num_exexs.clone(),
             is_ready_receiver: self.is_ready_receiver.clone(),
             is_ready: ReusableBoxFuture::new(make_wait_future(self.is_ready_receiver.clone())),
             current_capacity: self.current_capacity.clone(),
@@ -503,6 +1103,255 @@ impl Clone for ExExManagerHandle {
 
 #[cfg(test)]
 mod tests {
+    use super::*;
+    use crate::Wal;
+    use reth_execution_types::ExecutionOutcome;
+    use reth_testing_utils::generators::{self, random_block};
+
+    fn chain(number: BlockNumber) -> Chain {
+        let mut rng = generators::rng();
+        let block = random_block(&mut rng, number, None, Some(0), None)
+            .seal_with_senders()
+            .expect("failed to recover senders");
+
+        Chain::new(vec![block], ExecutionOutcome::default(), None)
+    }
+
+    fn committed(number: BlockNumber) -> ExExNotification {
+        ExExNotification::ChainCommitted { new: Arc::new(chain(number)) }
+    }
+
+    /// A [`SpawnBackfill`] that delivers one chain per block right away.
+    #[derive(Debug)]
+    struct TestBackfill;
+
+    impl SpawnBackfill for TestBackfill {
+        fn spawn_backfill(
+            &self,
+            range: std::ops::RangeInclusive<BlockNumber>,
+        ) -> Receiver<eyre::Result<Chain>> {
+            let (tx, rx) = mpsc::channel(range.clone().count().max(1));
+            for number in range {
+                tx.try_send(Ok(chain(number))).expect("channel has capacity");
+            }
+            rx
+        }
+    }
+
+    /// Polls the manager once, returning an error if it failed.
+    async fn poll_manager(manager: &mut ExExManager) -> eyre::Result<()> {
+        poll_fn(|cx| match Pin::new(&mut *manager).poll(cx) {
+            Poll::Ready(result) => Poll::Ready(result),
+            Poll::Pending => Poll::Ready(Ok(())),
+        })
+        .await
+    }
+
+    /// Polls the manager until the `ExEx` receives a notification.
+    async fn next_notification(
+        manager: &mut ExExManager,
+        notifications: &mut Receiver<ExExNotification>,
+    ) -> eyre::Result<ExExNotification> {
+        for _ in 0..10 {
+            poll_manager(manager).await?;
+            if let Ok(notification) = notifications.try_recv() {
+                return Ok(notification)
+            }
+        }
+        eyre::bail!("no notification was delivered")
+    }
+
+    #[tokio::test]
+    async fn replays_wal_after_restart() -> eyre::Result<()> {
+        let temp_dir = tempfile::tempdir()?;
+        let notifications = [committed(1), committed(2), committed(3)];
+
+        // the ExEx only finishes the first notification before the node stops
+        {
+            let (exex, events_tx, mut notifications_rx) = ExExHandle::new("exex".to_string());
+            let mut manager = ExExManager::new(vec![exex], 10).with_wal(Wal::new(&temp_dir)?)?;
+            for notification in &notifications {
+                manager.handle().send(notification.clone())?;
+            }
+
+            let notification = next_notification(&mut manager, &mut notifications_rx).await?;
+            assert_eq!(notification, notifications[0]);
+            events_tx.send(ExExEvent::FinishedHeight(1))?;
+            poll_manager(&mut manager).await?;
+        }
+
+        // after the restart, only the notifications above the finished height are delivered
+        let (exex, _events_tx, mut notifications_rx) = ExExHandle::new("exex".to_string());
+        let mut manager = ExExManager::new(vec![exex], 10).with_wal(Wal::new(&temp_dir)?)?;
+        for expected in &notifications[1..] {
+            let notification = next_notification(&mut manager, &mut notifications_rx).await?;
+            assert_eq!(&notification, expected);
+        }
+        assert!(next_notification(&mut manager, &mut notifications_rx).await.is_err());
+
+        Ok(())
+    }
+
+    #[tokio::test]
+    async fn replays_wal_without_finished_height() -> eyre::Result<()> {
+        let temp_dir = tempfile::tempdir()?;
+        let notifications = [committed(1), committed(2)];
+
+        let mut wal = Wal::new(&temp_dir)?;
+        for notification in &notifications {
+            wal.commit(notification)?;
+        }
+        drop(wal);
+
+        // an ExEx that never reported a finished height receives all notifications again
+        let (exex, _events_tx, mut notifications_rx) = ExExHandle::new("exex".to_string());
+        let mut manager = ExExManager::new(vec![exex], 10).with_wal(Wal::new(&temp_dir)?)?;
+        for expected in &notifications {
+            let notification = next_notification(&mut manager, &mut notifications_rx).await?;
+            assert_eq!(&notification, expected);
+        }
+
+        // new notifications are delivered after the replayed ones
+        manager.handle().send(committed(3))?;
+        let notification = next_notification(&mut manager, &mut notifications_rx).await?;
+        assert_eq!(notification.committed_chain().map(|chain| chain.tip().number), Some(3));
+
+        Ok(())
+    }
+
+    #[tokio::test]
+    async fn persists_height_of_filtered_notifications() -> eyre::Result<()> {
+        let temp_dir = tempfile::tempdir()?;
+
+        {
+            let (exex, events_tx, mut notifications_rx) = ExExHandle::new("exex".to_string());
+            let exex = exex.with_filter(ExExNotificationFilter::new().block_range(1..=1));
+            let mut manager = ExExManager::new(vec![exex], 10).with_wal(Wal::new(&temp_dir)?)?;
+            manager.handle().send(committed(1))?;
+            next_notification(&mut manager, &mut notifications_rx).await?;
+            events_tx.send(ExExEvent::FinishedHeight(1))?;
+            poll_manager(&mut manager).await?;
+
+            // the second notification is outside the block range, so it is never delivered
+            manager.handle().send(committed(2))?;
+            assert!(next_notification(&mut manager, &mut notifications_rx).await.is_err());
+            assert_eq!(manager.exex_handles[0].finished_height, Some(2));
+        }
+
+        // the height advanced past the filtered notification survives a restart
+        assert_eq!(Wal::new(&temp_dir)?.finished_height("exex"), Some(2));
+
+        Ok(())
+    }
+
+    #[tokio::test(start_paused = true)]
+    async fn detects_stalled_exex() -> eyre::Result<()> {
+        let (exex, events_tx, _notifications_rx) = ExExHandle::new("exex".to_string());
+        let mut manager =
+            ExExManager::new(vec![exex], 10).with_heartbeat_timeout(Duration::from_secs(10));
+
+        // the ExEx never receives the first notification, so the second one stays pending
+        manager.handle().send(committed(1))?;
+        manager.handle().send(committed(2))?;
+        poll_manager(&mut manager).await?;
+        assert_eq!(manager.exex_handles[0].health, ExExHealth::Healthy);
+
+        tokio::time::advance(Duration::from_secs(5)).await;
+        poll_manager(&mut manager).await?;
+        assert_eq!(manager.exex_handles[0].health, ExExHealth::Healthy);
+
+        tokio::time::advance(Duration::from_secs(6)).await;
+        poll_manager(&mut manager).await?;
+        assert_eq!(manager.exex_handles[0].health, ExExHealth::Unresponsive);
+
+        // a slow but alive ExEx is healthy again as soon as it emits a heartbeat
+        events_tx.send(ExExEvent::Heartbeat)?;
+        poll_manager(&mut manager).await?;
+        assert_eq!(manager.exex_handles[0].health, ExExHealth::Healthy);
+
+        Ok(())
+    }
+
+    #[tokio::test]
+    async fn restarts_failed_exex() -> eyre::Result<()> {
+        let (exex, events_tx, mut notifications_rx) = ExExHandle::new("exex".to_string());
+        let mut manager = ExExManager::new(vec![exex], 10).with_backfill(TestBackfill, 0);
+        let handle = manager.handle();
+        let finished_height = handle.finished_height();
+
+        handle.send(committed(1))?;
+        next_notification(&mut manager, &mut notifications_rx).await?;
+        events_tx.send(ExExEvent::FinishedHeight(1))?;
+        handle.send(committed(2))?;
+        next_notification(&mut manager, &mut notifications_rx).await?;
+
+        events_tx.send(ExExEvent::FatalError("corrupted state".to_string()))?;
+        poll_manager(&mut manager).await?;
+        assert_eq!(
+            *finished_height.borrow(),
+            FinishedExExHeight::Failed {
+                height: Some(1),
+                failed: [("exex".to_string(), "corrupted state".to_string())].into(),
+            }
+        );
+
+        // failed ExEx's don't receive notifications
+        handle.send(committed(3))?;
+        assert!(next_notification(&mut manager, &mut notifications_rx).await.is_err());
+
+        // after the restart, all blocks above the finished height are delivered again
+        handle.restart("exex");
+        for number in 2..=3 {
+            let notification = next_notification(&mut manager, &mut notifications_rx).await?;
+            let tip = notification.committed_chain().map(|chain| chain.tip().number);
+            assert_eq!(tip, Some(number));
+        }
+        assert_eq!(manager.exex_handles[0].health, ExExHealth::Healthy);
+        assert_eq!(*finished_height.borrow(), FinishedExExHeight::Height(1));
+
+        // an ExEx that stopped receiving notifications is detached instead
+        events_tx.send(ExExEvent::FatalError("crashed".to_string()))?;
+        drop(notifications_rx);
+        poll_manager(&mut manager).await?;
+        handle.restart("exex");
+        poll_manager(&mut manager).await?;
+        assert!(manager.exex_handles.is_empty());
+        assert_eq!(*finished_height.borrow(), FinishedExExHeight::NoExExs);
+        assert!(!handle.has_exexs());
+
+        Ok(())
+    }
+
+    #[tokio::test]
+    async fn rewind_without_backfill_fails() -> eyre::Result<()> {
+        let (exex, events_tx, mut notifications_rx) = ExExHandle::new("exex".to_string());
+        let mut manager = ExExManager::new(vec![exex], 10);
+        let handle = manager.handle();
+        let finished_height = handle.finished_height();
+
+        handle.send(committed(1))?;
+        next_notification(&mut manager, &mut notifications_rx).await?;
+        events_tx.send(ExExEvent::FinishedHeight(1))?;
+        poll_manager(&mut manager).await?;
+        assert_eq!(*finished_height.borrow(), FinishedExExHeight::Height(1));
+
+        // the blocks above the height can't be delivered again, so the ExEx fails
+        events_tx.send(ExExEvent::Rewind(0))?;
+        poll_manager(&mut manager).await?;
+        assert!(manager.exex_handles[0].health.is_failed());
+        let status = finished_height.borrow().clone();
+        assert_eq!(status.height(), Some(0));
+        assert_eq!(status.failed().map(|(id, _)| id).collect::<Vec<_>>(), vec!["exex"]);
+
+        // the node detaches it
+        handle.detach("exex");
+        poll_manager(&mut manager).await?;
+        assert_eq!(*finished_height.borrow(), FinishedExExHeight::NoExExs);
+        assert!(!handle.has_exexs());
+
+        Ok(())
+    }
+
     // Define asynchronous tests using `tokio::test` attribute
 
     #[tokio::test]
@@ -1,9 +1,12 @@
 use std::sync::Arc;
 
-use reth_provider::{CanonStateNotification, Chain};
+use reth_execution_types::Chain;
+use reth_provider::CanonStateNotification;
 
 /// notifications sent to an `ExEx`
-#[derive(Debug, Clone, PartialEq, Eq)]
+///
+/// notifications are serializable so that they can be persisted in the [`Wal`](crate::Wal).
+#[derive(Debug, Clone, PartialEq, Eq, serde::Serialize, serde::Deserialize)]
 pub enum ExExNotification {
     /// chain got committed without a reorg, and only the new chain is returned
     ChainCommitted {
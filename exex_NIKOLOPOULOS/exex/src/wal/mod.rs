@@ -0,0 +1,200 @@
+//! The write-ahead log (WAL) of [`ExExNotification`]s.
+
+mod storage;
+
+use crate::ExExNotification;
+use reth_primitives::BlockNumber;
+use reth_tracing::tracing::debug;
+use std::{collections::BTreeMap, path::Path};
+use storage::Storage;
+
+/// The write-ahead log of [`ExExNotification`]s.
+///
+/// Every notification is written to the WAL before it is delivered to any `ExEx`, together with
+/// the last finished height reported by each `ExEx`. On startup, the [`ExExManager`] replays all
+/// notifications in the WAL above each `ExEx`'s finished height, so that `ExEx`'s don't lose
+/// notifications they had not processed yet when the node was shut down or crashed.
+///
+/// Notifications are pruned from the WAL once they are finalized and all `ExEx`'s have finished
+/// processing them.
+///
+/// [`ExExManager`]: crate::ExExManager
+#[derive(Debug)]
+pub struct Wal {
+    /// The underlying on-disk storage.
+    storage: Storage,
+    /// The highest block number of each notification in the WAL, keyed by file ID.
+    ///
+    /// Used to determine which notifications can be pruned without reading them from disk.
+    block_cache: BTreeMap<u64, BlockNumber>,
+    /// The file ID of the next notification that will be written to the WAL.
+    next_file_id: u64,
+    /// The last finished height reported by each `ExEx`, keyed by `ExEx` ID.
+    finished_heights: BTreeMap<String, BlockNumber>,
+}
+
+impl Wal {
+    /// Opens the WAL in the given directory, creating the directory if it doesn't exist.
+    pub fn new(directory: impl AsRef<Path>) -> eyre::Result<Self> {
+        let storage = Storage::new(directory)?;
+
+        let mut block_cache = BTreeMap::new();
+        let mut next_file_id = 0;
+        if let Some(files_range) = storage.files_range()? {
+            next_file_id = files_range.end() + 1;
+            for id in files_range {
+                if let Some(notification) = storage.read_notification(id)? {
+                    block_cache.insert(id, highest_block(&notification));
+                }
+            }
+        }
+
+        let finished_heights = storage.read_finished_heights()?;
+
+        debug!(
+            target: "exex::wal",
+            notifications = %block_cache.len(),
+            %next_file_id,
+            "Opened WAL"
+        );
+
+        Ok(Self { storage, block_cache, next_file_id, finished_heights })
+    }
+
+    /// Returns the number of notifications in the WAL.
+    pub fn len(&self) -> usize {
+        self.block_cache.len()
+    }
+
+    /// Returns `true` if there are no notifications in the WAL.
+    pub fn is_empty(&self) -> bool {
+        self.block_cache.is_empty()
+    }
+
+    /// Writes the notification to the WAL.
+    ///
+    /// The notification is durable once this returns.
+    pub fn commit(&mut self, notification: &ExExNotification) -> eyre::Result<()> {
+        let id = self.next_file_id;
+        self.storage.write_notification(id, notification)?;
+        self.block_cache.insert(id, highest_block(notification));
+        self.next_file_id += 1;
+
+        Ok(())
+    }
+
+    /// Removes all notifications that only touch blocks up to and including `to_block` from the
+    /// WAL.
+    ///
+    /// Returns the number of removed notifications.
+    pub fn finalize(&mut self, to_block: BlockNumber) -> eyre::Result<usize> {
+        let ids = self
+            .block_cache
+            .iter()
+            .filter(|(_, highest_block)| **highest_block <= to_block)
+            .map(|(id, _)| *id)
+            .collect::<Vec<_>>();
+        if ids.is_empty() {
+            return Ok(0)
+        }
+
+        self.storage.remove_notifications(ids.iter().copied())?;
+        for id in &ids {
+            self.block_cache.remove(id);
+        }
+
+        debug!(target: "exex::wal", %to_block, removed = %ids.len(), "Finalized WAL");
+        Ok(ids.len())
+    }
+
+    /// Returns an iterator over all notifications in the WAL, in the order they were committed.
+    pub fn iter_notifications(&self) -> impl Iterator<Item = eyre::Result<ExExNotification>> + '_ {
+        self.block_cache.keys().filter_map(|id| self.storage.read_notification(*id).transpose())
+    }
+
+    /// Returns the last finished height persisted for the `ExEx` with the given ID, if any.
+    pub fn finished_height(&self, exex_id: &str) -> Option<BlockNumber> {
+        self.finished_heights.get(exex_id).copied()
+    }
+
+    /// Persists the finished height of the `ExEx` with the given ID.
+    pub fn set_finished_height(&mut self, exex_id: &str, height: BlockNumber) -> eyre::Result<()> {
+        if self.finished_heights.get(exex_id) == Some(&height) {
+            return Ok(())
+        }
+
+        self.finished_heights.insert(exex_id.to_string(), height);
+        self.storage.write_finished_heights(&self.finished_heights)
+    }
+}
+
+/// Returns the highest block number touched by the notification, either committed or reverted.
+pub(crate) fn highest_block(notification: &ExExNotification) -> BlockNumber {
+    let committed = notification.committed_chain().map(|chain| chain.tip().number);
+    let reverted = notification.reverted_chain().map(|chain| chain.tip().number);
+    committed.max(reverted).unwrap_or_default()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use reth_execution_types::{Chain, ExecutionOutcome};
+    use reth_testing_utils::generators::{self, random_block};
+    use std::sync::Arc;
+
+    fn committed(number: BlockNumber) -> ExExNotification {
+        let mut rng = generators::rng();
+        let block = random_block(&mut rng, number, None, Some(0), None)
+            .seal_with_senders()
+            .expect("failed to recover senders");
+
+        ExExNotification::ChainCommitted {
+            new: Arc::new(Chain::new(vec![block], ExecutionOutcome::default(), None)),
+        }
+    }
+
+    #[test]
+    fn commit_and_reopen() -> eyre::Result<()> {
+        let temp_dir = tempfile::tempdir()?;
+
+        let notifications = vec![committed(1), committed(2), committed(3)];
+        let mut wal = Wal::new(&temp_dir)?;
+        for notification in &notifications {
+            wal.commit(notification)?;
+        }
+        wal.set_finished_height("exex", 1)?;
+        drop(wal);
+
+        let wal = Wal::new(&temp_dir)?;
+        assert_eq!(wal.len(), 3);
+        assert_eq!(wal.iter_notifications().collect::<eyre::Result<Vec<_>>>()?, notifications);
+        assert_eq!(wal.finished_height("exex"), Some(1));
+        assert_eq!(wal.finished_height("other"), None);
+
+        Ok(())
+    }
+
+    #[test]
+    fn finalize() -> eyre::Result<()> {
+        let temp_dir = tempfile::tempdir()?;
+
+        let mut wal = Wal::new(&temp_dir)?;
+        for number in 1..=3 {
+            wal.commit(&committed(number))?;
+        }
+
+        assert_eq!(wal.finalize(0)?, 0);
+        assert_eq!(wal.finalize(2)?, 2);
+        assert_eq!(wal.iter_notifications().collect::<eyre::Result<Vec<_>>>()?.len(), 1);
+
+        // new notifications keep increasing file IDs after reopening
+        drop(wal);
+        let mut wal = Wal::new(&temp_dir)?;
+        wal.commit(&committed(4))?;
+        assert_eq!(wal.len(), 2);
+        assert_eq!(wal.finalize(4)?, 2);
+        assert!(wal.is_empty());
+
+        Ok(())
+    }
+}
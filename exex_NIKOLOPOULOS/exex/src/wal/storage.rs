@@ -0,0 +1,160 @@
+use crate::ExExNotification;
+use reth_primitives::BlockNumber;
+use reth_tracing::tracing::debug;
+use std::{
+    collections::BTreeMap,
+    io::Write,
+    ops::RangeInclusive,
+    path::{Path, PathBuf},
+};
+
+/// The name of the file that stores the finished heights of all `ExEx`'s.
+const FINISHED_HEIGHTS_FILE_NAME: &str = "finished_heights";
+
+/// The file extension of a WAL notification file.
+const WAL_FILE_EXTENSION: &str = "wal";
+
+/// The underlying on-disk storage of the [`Wal`](super::Wal).
+///
+/// Each notification is stored in its own file named `{id}.wal`, where `id` is a monotonically
+/// increasing file ID. Files are written to a temporary path first and then renamed, so a crash
+/// during a write never leaves a partially written notification behind.
+#[derive(Debug)]
+pub(super) struct Storage {
+    /// The path to the WAL directory.
+    path: PathBuf,
+}
+
+impl Storage {
+    /// Creates a new instance of [`Storage`] backed by the directory at the given path.
+    ///
+    /// Creates the directory if it doesn't exist.
+    pub(super) fn new(path: impl AsRef<Path>) -> eyre::Result<Self> {
+        reth_fs_util::create_dir_all(&path)?;
+
+        Ok(Self { path: path.as_ref().to_path_buf() })
+    }
+
+    /// Returns the path of the notification file with the given ID.
+    fn file_path(&self, id: u64) -> PathBuf {
+        self.path.join(format!("{id}.{WAL_FILE_EXTENSION}"))
+    }
+
+    /// Parses the file ID out of a WAL file name, returning `None` if the file is not a WAL
+    /// notification file.
+    fn parse_file_name(file_name: &str) -> Option<u64> {
+        file_name.strip_suffix(WAL_FILE_EXTENSION)?.strip_suffix('.')?.parse().ok()
+    }
+
+    /// Returns the range of file IDs present in the storage, or `None` if the storage is empty.
+    pub(super) fn files_range(&self) -> eyre::Result<Option<RangeInclusive<u64>>> {
+        let mut min_id = None;
+        let mut max_id = None;
+
+        for entry in std::fs::read_dir(&self.path)? {
+            let entry = entry?;
+            let Some(id) = entry.file_name().to_str().and_then(Self::parse_file_name) else {
+                continue
+            };
+
+            min_id = Some(min_id.map_or(id, |min_id: u64| min_id.min(id)));
+            max_id = Some(max_id.map_or(id, |max_id: u64| max_id.max(id)));
+        }
+
+        Ok(min_id.zip(max_id).map(|(min_id, max_id)| min_id..=max_id))
+    }
+
+    /// Reads the notification with the given file ID.
+    ///
+    /// Returns `None` if the file doesn't exist.
+    pub(super) fn read_notification(&self, id: u64) -> eyre::Result<Option<ExExNotification>> {
+        let path = self.file_path(id);
+        if !path.exists() {
+            return Ok(None)
+        }
+
+        let data = reth_fs_util::read(&path)?;
+        Ok(Some(rmp_serde::decode::from_slice(&data)?))
+    }
+
+    /// Writes the notification to the file with the given ID.
+    pub(super) fn write_notification(
+        &self,
+        id: u64,
+        notification: &ExExNotification,
+    ) -> eyre::Result<()> {
+        let data = rmp_serde::encode::to_vec(notification)?;
+        write_atomic(&self.file_path(id), &data)?;
+
+        debug!(target: "exex::wal::storage", %id, "Wrote notification to WAL");
+        Ok(())
+    }
+
+    /// Removes the notification files with the given IDs.
+    ///
+    /// Returns the number of removed files.
+    pub(super) fn remove_notifications(
+        &self,
+        ids: impl IntoIterator<Item = u64>,
+    ) -> eyre::Result<usize> {
+        let mut removed = 0;
+        for id in ids {
+            let path = self.file_path(id);
+            if path.exists() {
+                reth_fs_util::remove_file(&path)?;
+                removed += 1;
+            }
+        }
+
+        if removed > 0 {
+            sync_dir(&self.path)?;
+        }
+
+        debug!(target: "exex::wal::storage", %removed, "Removed notifications from WAL");
+        Ok(removed)
+    }
+
+    /// Reads the persisted finished heights of all `ExEx`'s, keyed by `ExEx` ID.
+    pub(super) fn read_finished_heights(&self) -> eyre::Result<BTreeMap<String, BlockNumber>> {
+        let path = self.path.join(FINISHED_HEIGHTS_FILE_NAME);
+        if !path.exists() {
+            return Ok(BTreeMap::new())
+        }
+
+        let data = reth_fs_util::read(&path)?;
+        Ok(rmp_serde::decode::from_slice(&data)?)
+    }
+
+    /// Persists the finished heights of all `ExEx`'s, keyed by `ExEx` ID.
+    pub(super) fn write_finished_heights(
+        &self,
+        finished_heights: &BTreeMap<String, BlockNumber>,
+    ) -> eyre::Result<()> {
+        let data = rmp_serde::encode::to_vec(finished_heights)?;
+        write_atomic(&self.path.join(FINISHED_HEIGHTS_FILE_NAME), &data)
+    }
+}
+
+/// Writes the data to a temporary file next to `path` and renames it to `path`.
+///
+/// The temporary file is synced before the rename, and the parent directory after it, so that
+/// the file is durable with either its old or its new contents once this returns.
+fn write_atomic(path: &Path, data: &[u8]) -> eyre::Result<()> {
+    let tmp_path = path.with_extension("tmp");
+    let mut file = std::fs::File::create(&tmp_path)?;
+    file.write_all(data)?;
+    file.sync_all()?;
+    drop(file);
+
+    std::fs::rename(&tmp_path, path)?;
+    sync_dir(path.parent().unwrap_or_else(|| Path::new(".")))
+}
+
+/// Syncs the directory, persisting the creation, rename and removal of the files in it.
+fn sync_dir(path: &Path) -> eyre::Result<()> {
+    #[cfg(unix)]
+    std::fs::File::open(path)?.sync_all()?;
+    #[cfg(not(unix))]
+    let _ = path;
+    Ok(())
+}
@@ -9,11 +9,12 @@
 #![cfg_attr(docsrs, feature(doc_cfg, doc_auto_cfg))]
 
 use alloy_primitives::BlockNumber;
+use std::collections::BTreeMap;
 
 /// The finished height of all `ExEx`'s.
-#[derive(Debug, Clone, Copy)]
+#[derive(Debug, Clone, PartialEq, Eq)]
 pub enum FinishedExExHeight {
-    /// No `ExEx`'s are installed, so there is no finished height.
+    /// No `ExEx`'s are installed, or all of them were detached, so there is no finished height.
     NoExExs,
     /// Not all `ExExs` have emitted a `FinishedHeight` event yet.
     NotReady,
@@ -24,13 +25,85 @@ pub enum FinishedExExHeight {
     /// This block is used to (amongst other things) determine what blocks are safe to prune.
     ///
     /// The number is inclusive, i.e. all blocks `<= finished_height` are safe to prune.
+    ///
+    /// `ExEx`'s that were detached after reporting a failure are not taken into account.
     Height(BlockNumber),
+    /// Some `ExEx`'s [failed](ExExHealth::Failed), and hold back the finished height until the
+    /// node restarts or detaches them.
+    Failed {
+        /// The finished height of all `ExEx`'s, or `None` if not all `ExEx`'s have emitted a
+        /// `FinishedHeight` event yet.
+        height: Option<BlockNumber>,
+        /// The reasons of the failures, keyed by the ID of the failed `ExEx`.
+        failed: BTreeMap<String, String>,
+    },
 }
 
 impl FinishedExExHeight {
     /// Returns `true` if not all `ExExs` have emitted a `FinishedHeight` event yet.
     pub const fn is_not_ready(&self) -> bool {
-        matches!(self, Self::NotReady)
+        matches!(self, Self::NotReady | Self::Failed { height: None, .. })
+    }
+
+    /// Returns the finished height of all `ExEx`'s, if all of them emitted a `FinishedHeight`
+    /// event.
+    pub const fn height(&self) -> Option<BlockNumber> {
+        match self {
+            Self::Height(height) | Self::Failed { height: Some(height), .. } => Some(*height),
+            _ => None,
+        }
+    }
+
+    /// Returns the reasons of the failures of the failed `ExEx`'s, keyed by `ExEx` ID.
+    pub fn failed(&self) -> impl Iterator<Item = (&str, &str)> {
+        let failed = match self {
+            Self::Failed { failed, .. } => Some(failed),
+            _ => None,
+        };
+        failed.into_iter().flatten().map(|(id, reason)| (id.as_str(), reason.as_str()))
+    }
+}
+
+/// The health of an `ExEx`, as tracked by the `ExEx` manager.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum ExExHealth {
+    /// The `ExEx` is keeping up with notifications, or has recently emitted an event.
+    Healthy,
+    /// The `ExEx` has pending notifications, but has not emitted any event (including
+    /// heartbeats) for longer than the heartbeat timeout.
+    ///
+    /// The `ExEx` may just be slow, so it still holds back the [`FinishedExExHeight`].
+    Unresponsive,
+    /// The `ExEx` reported a fatal error, or stopped receiving notifications.
+    ///
+    /// The `ExEx` still holds back the [`FinishedExExHeight`] until it is detached, and is
+    /// reported in [`FinishedExExHeight::Failed`].
+    Failed {
+        /// The reason of the failure.
+        reason: String,
+    },
+    /// The `ExEx` was detached from the node and no longer receives notifications.
+    ///
+    /// Detached `ExEx`'s are not taken into account for the [`FinishedExExHeight`].
+    Detached,
+}
+
+impl ExExHealth {
+    /// Returns `true` if the `ExEx` reported a fatal error or stopped receiving notifications.
+    pub const fn is_failed(&self) -> bool {
+        matches!(self, Self::Failed { .. })
+    }
+
+    /// Returns the numeric representation of the health, as reported in metrics.
+    ///
+    /// `0` is healthy, `1` is unresponsive, `2` is failed and `3` is detached.
+    pub const fn as_metric(&self) -> u8 {
+        match self {
+            Self::Healthy => 0,
+            Self::Unresponsive => 1,
+            Self::Failed { .. } => 2,
+            Self::Detached => 3,
+        }
     }
 }
 
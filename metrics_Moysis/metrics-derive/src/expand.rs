@@ -17,17 +17,23 @@ use syn::{
     DeriveInput,            // Represents the entire input to a derive macro.
     Error,                  // Represents errors that can occur during parsing.
     Expr,                   // Represents Rust expressions.
+    ExprArray,              // Represents array expressions, e.g. histogram buckets.
     Field,                  // Represents a field in a struct or enum.
     Lit,                    // Represents literal values in Rust code.
     LitBool,                // Represents boolean literals.
     LitStr,                 // Represents string literals.
     Meta,                   // Represents meta items, such as attributes.
+    MetaList,               // Represents meta items in list form.
     MetaNameValue,          // Represents meta items in name-value form.
     Result,                 // A type alias for results specific to syn.
     Token,                  // Represents tokens in Rust code.
+    UnOp,                   // Represents unary operators, e.g. the sign of a bucket.
 };
 
-use crate::{metric::Metric, with_attrs::WithAttrs};
+use crate::{
+    metric::{is_histogram, Distribution, Metric, MetricOptions, SUPPORTED_UNITS},
+    with_attrs::WithAttrs,
+};
 
 /// Metric name regex according to Prometheus data model
 ///
@@ -35,6 +41,11 @@ use crate::{metric::Metric, with_attrs::WithAttrs};
 static METRIC_NAME_RE: Lazy<Regex> =
     Lazy::new(|| Regex::new(r"^[a-zA-Z_:.][a-zA-Z0-9_:.]*$").unwrap());
 
+/// Label name regex according to Prometheus data model
+///
+/// See <https://prometheus.io/docs/concepts/data_model/#metric-names-and-labels>
+static LABEL_NAME_RE: Lazy<Regex> = Lazy::new(|| Regex::new(r"^[a-zA-Z_][a-zA-Z0-9_]*$").unwrap());
+
 /// Supported metrics separators
 const SUPPORTED_SEPARATORS: &[&str] = &[".", "_", ":"];
 
@@ -93,7 +104,7 @@ pub(crate) fn derive(node: &DeriveInput) -> Result<proc_macro2::TokenStream> {
 
     // Parse metrics attributes and fields from the node.
     let metrics_attr = parse_metrics_attr(node)?;
-    let metric_fields = parse_metric_fields(node)?;
+    let metric_fields = parse_metric_fields(node, &metrics_attr.scope)?;
 
     // Documentation for the describe method.
     let describe_doc = quote! {
@@ -122,15 +133,18 @@ pub(crate) fn derive(node: &DeriveInput) -> Result<proc_macro2::TokenStream> {
                             let registrar = metric.register_stmt()?;
                             let describe = metric.describe_stmt()?;
                             let description = &metric.description;
+                            let default_labels = metric.labels_expr(false).map(|l| quote! { , #l });
+                            let labels = metric.labels_expr(true);
+                            let unit = metric.unit_expr().map(|unit| quote! { #unit, });
                             Ok((
                                 quote! {
-                                    #field_name: #registrar(#metric_name),
+                                    #field_name: #registrar(#metric_name #default_labels),
                                 },
                                 quote! {
-                                    #field_name: #registrar(#metric_name, labels.clone()),
+                                    #field_name: #registrar(#metric_name, #labels),
                                 },
                                 Some(quote! {
-                                    #describe(#metric_name, #description);
+                                    #describe(#metric_name, #unit #description);
                                 }),
                             ))
                         }
@@ -156,8 +170,21 @@ pub(crate) fn derive(node: &DeriveInput) -> Result<proc_macro2::TokenStream> {
                     acc
                 });
 
+            // Register the distributions of histograms for the metrics exporter.
+            let histogram_configs =
+                metric_fields.iter().enumerate().filter_map(|(index, metric)| {
+                    let MetricField::Included(metric) = metric else { return None };
+                    let metric_name =
+                        format!("{}{}{}", scope.value(), metrics_attr.separator(), metric.name());
+                    metric.histogram_config(index, &metric_name)
+                });
+
             // Generate implementations for the static scope.
             quote! {
+                const _: () = {
+                    #(#histogram_configs)*
+                };
+
                 impl Default for #ty {
                     fn default() -> Self {
                         #ty::describe();
@@ -200,16 +227,19 @@ pub(crate) fn derive(node: &DeriveInput) -> Result<proc_macro2::TokenStream> {
                             let registrar = metric.register_stmt()?;
                             let describe = metric.describe_stmt()?;
                             let description = &metric.description;
+                            let default_labels = metric.labels_expr(false).map(|l| quote! { , #l });
+                            let labels = metric.labels_expr(true);
+                            let unit = metric.unit_expr().map(|unit| quote! { #unit, });
 
                             Ok((
                                 quote! {
-                                    #field_name: #registrar(#metric_name),
+                                    #field_name: #registrar(#metric_name #default_labels),
                                 },
                                 quote! {
-                                    #field_name: #registrar(#metric_name, labels.clone()),
+                                    #field_name: #registrar(#metric_name, #labels),
                                 },
                                 Some(quote! {
-                                    #describe(#metric_name, #description);
+                                    #describe(#metric_name, #unit #description);
                                 }),
                             ))
                         }
@@ -422,6 +452,8 @@ fn parse_metrics_attr(node: &DeriveInput) -> Result<MetricsAttr> {
 ///
 /// # Arguments
 /// * `node` - A reference to the `DeriveInput` which represents the input to a derive macro.
+/// * `scope` - The scope of the metrics. Histograms with a dynamic scope can't have `buckets` or
+///   `quantiles`, since their names are only known at runtime.
 ///
 /// # Returns
 /// A `Result` containing a vector of `MetricField` or an error if the parsing fails.
@@ -429,7 +461,10 @@ fn parse_metrics_attr(node: &DeriveInput) -> Result<MetricsAttr> {
 /// # Errors
 /// This function will return an error if the input is not a struct, if the `metric` attributes are
 /// malformed, contain duplicate entries, unsupported values, or if required values are missing.
-fn parse_metric_fields(node: &DeriveInput) -> Result<Vec<MetricField<'_>>> {
+fn parse_metric_fields<'a>(
+    node: &'a DeriveInput,
+    scope: &MetricsScope,
+) -> Result<Vec<MetricField<'a>>> {
     // Ensure the input is a struct.
     let Data::Struct(ref data) = node.data else {
         return Err(Error::new_spanned(node, "Only structs are supported."))
@@ -441,6 +476,7 @@ fn parse_metric_fields(node: &DeriveInput) -> Result<Vec<MetricField<'_>>> {
     // Iterate over the fields of the struct.
     for field in &data.fields {
         let (mut describe, mut rename, mut skip) = (None, None, false);
+        let mut options = MetricOptions::default();
         
         // Parse the `metric` attribute if it exists.
         if let Some(metric_attr) = parse_single_attr(field, "metric")? {
@@ -451,6 +487,40 @@ fn parse_metric_fields(node: &DeriveInput) -> Result<Vec<MetricField<'_>>> {
             for meta in parsed {
                 match meta {
                     Meta::Path(path) if path.is_ident("skip") => skip = true,
+
+                    // Check for the static "labels" list.
+                    Meta::List(list) if list.path.is_ident("labels") => {
+                        if !options.labels.is_empty() {
+                            return Err(Error::new_spanned(list, "Duplicate `labels` provided."))
+                        }
+                        options.labels = parse_labels(&list)?;
+                    }
+
+                    // Check for the "buckets" and "quantiles" of a histogram.
+                    Meta::NameValue(kv)
+                        if kv.path.is_ident("buckets") || kv.path.is_ident("quantiles") =>
+                    {
+                        if options.distribution.is_some() {
+                            return Err(Error::new_spanned(
+                                kv,
+                                "Only one of `buckets` or `quantiles` can be provided.",
+                            ))
+                        }
+                        if !is_histogram(field) {
+                            return Err(Error::new_spanned(
+                                kv,
+                                "`buckets` and `quantiles` are only supported for histograms.",
+                            ))
+                        }
+                        if let MetricsScope::Dynamic = scope {
+                            return Err(Error::new_spanned(
+                                kv,
+                                "`buckets` and `quantiles` are not supported with a dynamic scope.",
+                            ))
+                        }
+                        options.distribution = Some(parse_distribution(&kv)?);
+                    }
+
                     Meta::NameValue(kv) => {
                         let lit = match kv.value {
                             Expr::Lit(ref expr) => &expr.lit,
@@ -478,6 +548,16 @@ fn parse_metric_fields(node: &DeriveInput) -> Result<Vec<MetricField<'_>>> {
                             let rename_lit = parse_str_lit(lit)?;
                             validate_metric_name(&rename_lit)?;
                             rename = Some(rename_lit)
+
+                        // Check for the "unit" key.
+                        } else if kv.path.is_ident("unit") {
+                            if options.unit.is_some() {
+                                return Err(Error::new_spanned(
+                                    kv,
+                                    "Duplicate `unit` value provided.",
+                                ))
+                            }
+                            options.unit = Some(parse_unit(lit)?);
                         
                         // Handle unsupported keys.
                         } else {
@@ -511,7 +591,7 @@ fn parse_metric_fields(node: &DeriveInput) -> Result<Vec<MetricField<'_>>> {
         };
 
         // Add the included metric to the vector.
-        metrics.push(MetricField::Included(Metric::new(field, description, rename)));
+        metrics.push(MetricField::Included(Metric::new(field, description, rename, options)));
     }
 
     // Return the parsed metric fields.
@@ -540,6 +620,152 @@ fn validate_metric_name(name: &LitStr) -> Result<()> {
     }
 }
 
+/// Parses the static labels of a metric, e.g. `labels(kind = "pending")`.
+///
+/// # Arguments
+/// * `list` - A reference to the `labels(..)` list of the `metric` attribute.
+///
+/// # Returns
+/// A `Result` containing the label keys and values.
+///
+/// # Errors
+/// This function returns an error if the labels are empty, a key is not a valid label name or is
+/// provided more than once, or a value is not a string literal.
+fn parse_labels(list: &MetaList) -> Result<Vec<(String, LitStr)>> {
+    let parsed = list.parse_args_with(Punctuated::<MetaNameValue, Token![,]>::parse_terminated)?;
+    if parsed.is_empty() {
+        return Err(Error::new_spanned(list, "At least one label must be provided."))
+    }
+
+    let mut labels: Vec<(String, LitStr)> = Vec::with_capacity(parsed.len());
+    for kv in parsed {
+        let key = kv
+            .path
+            .get_ident()
+            .map(ToString::to_string)
+            .ok_or_else(|| Error::new_spanned(&kv.path, "Label key must be an identifier."))?;
+        if !LABEL_NAME_RE.is_match(&key) || key.starts_with("__") {
+            return Err(Error::new_spanned(
+                &kv.path,
+                format!(
+                    "Label key must match regex {} and must not start with `__`.",
+                    LABEL_NAME_RE.as_str()
+                ),
+            ))
+        }
+        if labels.iter().any(|(existing, _)| *existing == key) {
+            return Err(Error::new_spanned(kv, format!("Duplicate `{key}` label provided.")))
+        }
+        let value = match &kv.value {
+            Expr::Lit(expr) => parse_str_lit(&expr.lit)?,
+            value => return Err(Error::new_spanned(value, "Value **must** be a string literal.")),
+        };
+        labels.push((key, value));
+    }
+    Ok(labels)
+}
+
+/// Parses the `unit` of a metric into the matching variant of `metrics::Unit`.
+///
+/// # Arguments
+/// * `lit` - A reference to the `Lit` value of the `unit` entry.
+///
+/// # Returns
+/// A `Result` containing the identifier of the `metrics::Unit` variant.
+///
+/// # Errors
+/// This function returns an error if the value is not a string literal or an unsupported unit.
+fn parse_unit(lit: &Lit) -> Result<proc_macro2::Ident> {
+    let unit = parse_str_lit(lit)?;
+    SUPPORTED_UNITS
+        .iter()
+        .find(|(name, _)| *name == unit.value())
+        .map(|(_, variant)| proc_macro2::Ident::new(variant, unit.span()))
+        .ok_or_else(|| {
+            Error::new_spanned(
+                &unit,
+                format!(
+                    "Unsupported `unit` value. Supported: {}.",
+                    SUPPORTED_UNITS
+                        .iter()
+                        .map(|(name, _)| format!("`{name}`"))
+                        .collect::<Vec<_>>()
+                        .join(", ")
+                ),
+            )
+        })
+}
+
+/// Parses the `buckets` or `quantiles` of a histogram, e.g. `buckets = [0.001, 0.01, 0.1]`.
+///
+/// # Arguments
+/// * `kv` - A reference to the `buckets = [..]` or `quantiles = [..]` entry.
+///
+/// # Returns
+/// A `Result` containing the parsed `Distribution`.
+///
+/// # Errors
+/// This function returns an error if the value is not a non-empty array of numeric literals in
+/// strictly increasing order, or if a quantile is not between 0 and 1.
+fn parse_distribution(kv: &MetaNameValue) -> Result<Distribution> {
+    let name = if kv.path.is_ident("buckets") { "buckets" } else { "quantiles" };
+    let Expr::Array(ExprArray { elems, .. }) = &kv.value else {
+        return Err(Error::new_spanned(
+            &kv.value,
+            format!("`{name}` must be an array of numbers, e.g. `[0.001, 0.01, 0.1]`."),
+        ))
+    };
+    if elems.is_empty() {
+        return Err(Error::new_spanned(&kv.value, format!("`{name}` must not be empty.")))
+    }
+
+    let mut values = Vec::with_capacity(elems.len());
+    for elem in elems {
+        let value = parse_number(elem)?;
+        if !value.is_finite() {
+            return Err(Error::new_spanned(elem, "Value must be a finite number."))
+        }
+        if name == "quantiles" && !(0.0..=1.0).contains(&value) {
+            return Err(Error::new_spanned(elem, "Quantiles must be between 0 and 1."))
+        }
+        if values.last().is_some_and(|last| *last >= value) {
+            return Err(Error::new_spanned(
+                elem,
+                format!("`{name}` must be in strictly increasing order."),
+            ))
+        }
+        values.push(value);
+    }
+
+    Ok(if name == "buckets" {
+        Distribution::Buckets(values)
+    } else {
+        Distribution::Quantiles(values)
+    })
+}
+
+/// Parses a number from an integer or float literal, optionally negated.
+///
+/// # Arguments
+/// * `expr` - A reference to the expression of the number.
+///
+/// # Returns
+/// A `Result` containing the number as `f64`.
+///
+/// # Errors
+/// This function returns an error if the expression is not a numeric literal.
+fn parse_number(expr: &Expr) -> Result<f64> {
+    match expr {
+        Expr::Lit(expr) => match &expr.lit {
+            Lit::Float(lit) => lit.base10_parse(),
+            Lit::Int(lit) => lit.base10_parse(),
+            lit => Err(Error::new_spanned(lit, "Value **must** be a numeric literal.")),
+        },
+        Expr::Unary(unary) if matches!(unary.op, UnOp::Neg(_)) => Ok(-parse_number(&unary.expr)?),
+        expr => Err(Error::new_spanned(expr, "Value **must** be a numeric literal.")),
+    }
+}
+
 /// Parses a single attribute with the specified identifier from the given token.
 ///
 /// This function searches for an attribute with the given identifier in the attributes
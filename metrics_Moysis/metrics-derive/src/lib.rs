@@ -29,6 +29,19 @@ mod with_attrs; // Module for working with attributes
 /// * `input` - The input token stream representing the struct for which the `Metrics` trait
 ///   should be derived.
 ///
+/// # Field attributes
+/// * `#[metric(skip)]` - Skips the field.
+/// * `#[metric(describe = "..")]` - Describes the metric, instead of the doc comment.
+/// * `#[metric(rename = "..")]` - Renames the metric.
+/// * `#[metric(unit = "seconds")]` - Describes the metric with a unit of `metrics::Unit`.
+/// * `#[metric(labels(kind = ".."))]` - Always registers the metric with these labels.
+/// * `#[metric(buckets = [0.0001, 0.001])]` - Counts the values of a histogram in buckets with
+///   these upper bounds.
+/// * `#[metric(quantiles = [0.5, 0.99])]` - Tracks these quantiles of the values of a histogram.
+///
+/// Buckets and quantiles are registered in `reth_metrics::histogram::HISTOGRAMS`, which requires
+/// the `reth_metrics` crate. They are only supported with a static scope.
+///
 /// # Returns
 /// A `TokenStream` containing the generated code for the `Metrics` implementation.
 #[proc_macro_derive(Metrics, attributes(metrics, metric))]
@@ -1,6 +1,7 @@
 // Moysis Moysis Volos, Greece 29/06/2024.
 
-use quote::quote;
+use proc_macro2::{Ident, TokenStream};
+use quote::{format_ident, quote};
 use syn::{Error, Field, LitStr, Result, Type};
 
 // Constants representing the names of different metric types.
@@ -8,6 +9,47 @@ const COUNTER_TY: &str = "Counter"; // Represents the Counter metric type.
 const HISTOGRAM_TY: &str = "Histogram"; // Represents the Histogram metric type.
 const GAUGE_TY: &str = "Gauge"; // Represents the Gauge metric type.
 
+/// Supported units, and the matching variants of `metrics::Unit`.
+pub(crate) const SUPPORTED_UNITS: &[(&str, &str)] = &[
+    ("count", "Count"),
+    ("percent", "Percent"),
+    ("seconds", "Seconds"),
+    ("milliseconds", "Milliseconds"),
+    ("microseconds", "Microseconds"),
+    ("nanoseconds", "Nanoseconds"),
+    ("tebibytes", "Tebibytes"),
+    ("gibibytes", "Gigibytes"),
+    ("mebibytes", "Mebibytes"),
+    ("kibibytes", "Kibibytes"),
+    ("bytes", "Bytes"),
+    ("terabits_per_second", "TerabitsPerSecond"),
+    ("gigabits_per_second", "GigabitsPerSecond"),
+    ("megabits_per_second", "MegabitsPerSecond"),
+    ("kilobits_per_second", "KilobitsPerSecond"),
+    ("bits_per_second", "BitsPerSecond"),
+    ("count_per_second", "CountPerSecond"),
+];
+
+/// The distribution of the values recorded by a histogram.
+pub(crate) enum Distribution {
+    /// Count the values in buckets with the given upper bounds, rendered as a histogram.
+    Buckets(Vec<f64>),
+
+    /// Track the given quantiles of the values, rendered as a summary.
+    Quantiles(Vec<f64>),
+}
+
+/// Optional settings of a metric.
+#[derive(Default)]
+pub(crate) struct MetricOptions {
+    /// The variant of `metrics::Unit` the metric is described with.
+    pub(crate) unit: Option<Ident>,
+    /// Static labels the metric is always registered with.
+    pub(crate) labels: Vec<(String, LitStr)>,
+    /// The distribution of the values recorded by a histogram.
+    pub(crate) distribution: Option<Distribution>,
+}
+
 /// Represents a metric with an associated field.
 ///
 /// This struct holds information about a metric, including the field it is associated with,
@@ -16,6 +58,7 @@ pub(crate) struct Metric<'a> {
     pub(crate) field: &'a Field, // The field associated with the metric.
     pub(crate) description: String, // The description of the metric.
     rename: Option<LitStr>, // An optional rename attribute for the metric.
+    options: MetricOptions, // Optional unit, static labels and distribution of the metric.
 }
 
 impl<'a> Metric<'a> {
@@ -25,11 +68,17 @@ impl<'a> Metric<'a> {
     /// * `field` - A reference to the field associated with the metric.
     /// * `description` - A description of the metric.
     /// * `rename` - An optional rename attribute for the metric.
+    /// * `options` - Optional unit, static labels and distribution of the metric.
     ///
     /// # Returns
     /// A new `Metric` instance.
-    pub(crate) const fn new(field: &'a Field, description: String, rename: Option<LitStr>) -> Self {
-        Self { field, description, rename }
+    pub(crate) const fn new(
+        field: &'a Field,
+        description: String,
+        rename: Option<LitStr>,
+        options: MetricOptions,
+    ) -> Self {
+        Self { field, description, rename, options }
     }
 
     /// Returns the name of the metric.
@@ -88,4 +137,73 @@ impl<'a> Metric<'a> {
 
         Err(Error::new_spanned(&self.field.ty, "Unsupported metric type"))
     }
+
+    /// Generates the labels the metric is registered with.
+    ///
+    /// The static labels of the metric are combined with the `labels` argument of the generated
+    /// constructor if `with_labels` is set.
+    ///
+    /// # Returns
+    /// A token stream with the labels, or `None` if the metric is registered without labels.
+    pub(crate) fn labels_expr(&self, with_labels: bool) -> Option<TokenStream> {
+        let static_labels = (!self.options.labels.is_empty()).then(|| {
+            let (keys, values): (Vec<_>, Vec<_>) =
+                self.options.labels.iter().map(|(key, value)| (key, value)).unzip();
+            quote! { &[#((#keys, #values)),*] }
+        });
+
+        match (static_labels, with_labels) {
+            (None, false) => None,
+            (None, true) => Some(quote! { labels.clone() }),
+            (Some(static_labels), false) => Some(static_labels),
+            (Some(static_labels), true) => Some(quote! {{
+                let mut all_labels = metrics::IntoLabels::into_labels(labels.clone());
+                all_labels.extend(metrics::IntoLabels::into_labels(#static_labels));
+                all_labels
+            }}),
+        }
+    }
+
+    /// Generates the unit the metric is described with, if any.
+    pub(crate) fn unit_expr(&self) -> Option<TokenStream> {
+        self.options.unit.as_ref().map(|unit| quote! { metrics::Unit::#unit })
+    }
+
+    /// Generates the registration of the distribution of a histogram, which is picked up by the
+    /// metrics exporter, e.g. to configure the buckets of the histogram.
+    ///
+    /// # Arguments
+    /// * `index` - The index of the field, used to name the generated item.
+    /// * `name` - The full name of the histogram, including the scope.
+    ///
+    /// # Returns
+    /// A token stream with the registration, or `None` if no distribution was configured.
+    pub(crate) fn histogram_config(&self, index: usize, name: &str) -> Option<TokenStream> {
+        let (buckets, quantiles) = match self.options.distribution.as_ref()? {
+            Distribution::Buckets(buckets) => (buckets.as_slice(), [].as_slice()),
+            Distribution::Quantiles(quantiles) => ([].as_slice(), quantiles.as_slice()),
+        };
+        let ident = format_ident!("__HISTOGRAM_{}", index);
+
+        Some(quote! {
+            #[reth_metrics::__private::linkme::distributed_slice(reth_metrics::histogram::HISTOGRAMS)]
+            #[linkme(crate = reth_metrics::__private::linkme)]
+            static #ident: reth_metrics::histogram::HistogramConfig =
+                reth_metrics::histogram::HistogramConfig {
+                    name: #name,
+                    buckets: &[#(#buckets),*],
+                    quantiles: &[#(#quantiles),*],
+                };
+        })
+    }
+}
+
+/// Returns `true` if the field is a `Histogram`.
+pub(crate) fn is_histogram(field: &Field) -> bool {
+    match &field.ty {
+        Type::Path(path_ty) => {
+            path_ty.path.segments.last().is_some_and(|last| last.ident == HISTOGRAM_TY)
+        }
+        _ => false,
+    }
 }
\ No newline at end of file
@@ -0,0 +1,113 @@
+// Moysis Moysis Volos, Greece 29/06/2024.
+
+extern crate metrics;
+extern crate reth_metrics_derive;
+
+use metrics::{Counter, Histogram};
+use reth_metrics_derive::Metrics;
+
+fn main() {}
+
+// Struct with buckets on a metric that is not a histogram.
+#[derive(Metrics)]
+#[metrics(scope = "some_scope")]
+struct CustomMetrics {
+    #[metric(describe = "", buckets = [0.1, 1.0])]
+    counter: Counter,
+}
+
+// Struct with both buckets and quantiles on a histogram.
+#[derive(Metrics)]
+#[metrics(scope = "some_scope")]
+struct CustomMetrics2 {
+    #[metric(describe = "", buckets = [0.1, 1.0], quantiles = [0.5])]
+    histogram: Histogram,
+}
+
+// Struct with buckets that are not an array.
+#[derive(Metrics)]
+#[metrics(scope = "some_scope")]
+struct CustomMetrics3 {
+    #[metric(describe = "", buckets = 0.1)]
+    histogram: Histogram,
+}
+
+// Struct with empty buckets.
+#[derive(Metrics)]
+#[metrics(scope = "some_scope")]
+struct CustomMetrics4 {
+    #[metric(describe = "", buckets = [])]
+    histogram: Histogram,
+}
+
+// Struct with buckets that are not in increasing order.
+#[derive(Metrics)]
+#[metrics(scope = "some_scope")]
+struct CustomMetrics5 {
+    #[metric(describe = "", buckets = [0.1, 0.01])]
+    histogram: Histogram,
+}
+
+// Struct with a bucket that is not a number.
+#[derive(Metrics)]
+#[metrics(scope = "some_scope")]
+struct CustomMetrics6 {
+    #[metric(describe = "", buckets = [0.1, "1"])]
+    histogram: Histogram,
+}
+
+// Struct with a quantile that is not between 0 and 1.
+#[derive(Metrics)]
+#[metrics(scope = "some_scope")]
+struct CustomMetrics7 {
+    #[metric(describe = "", quantiles = [0.5, 1.5])]
+    histogram: Histogram,
+}
+
+// Struct with an unsupported unit.
+#[derive(Metrics)]
+#[metrics(scope = "some_scope")]
+struct CustomMetrics8 {
+    #[metric(describe = "", unit = "hours")]
+    histogram: Histogram,
+}
+
+// Struct with a duplicate unit.
+#[derive(Metrics)]
+#[metrics(scope = "some_scope")]
+struct CustomMetrics9 {
+    #[metric(describe = "", unit = "seconds", unit = "seconds")]
+    histogram: Histogram,
+}
+
+// Struct with a label value that is not a string.
+#[derive(Metrics)]
+#[metrics(scope = "some_scope")]
+struct CustomMetrics10 {
+    #[metric(describe = "", labels(kind = 1))]
+    counter: Counter,
+}
+
+// Struct with a reserved label key.
+#[derive(Metrics)]
+#[metrics(scope = "some_scope")]
+struct CustomMetrics11 {
+    #[metric(describe = "", labels(__kind = "pending"))]
+    counter: Counter,
+}
+
+// Struct with a duplicate label key.
+#[derive(Metrics)]
+#[metrics(scope = "some_scope")]
+struct CustomMetrics12 {
+    #[metric(describe = "", labels(kind = "pending", kind = "queued"))]
+    counter: Counter,
+}
+
+// Struct with buckets on a histogram with a dynamic scope.
+#[derive(Metrics)]
+#[metrics(dynamic = true)]
+struct CustomMetrics13 {
+    #[metric(describe = "", buckets = [0.1, 1.0])]
+    histogram: Histogram,
+}
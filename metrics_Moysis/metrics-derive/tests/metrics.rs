@@ -87,6 +87,24 @@ struct DynamicScopeMetrics {
     skipped_field_e: u128, // This field is skipped and not included in the metrics.
 }
 
+#[allow(dead_code)]
+#[derive(Metrics)]
+#[metrics(scope = "metrics_histograms")]
+/// A struct representing metrics with units, static labels and histogram distributions.
+struct DistributionMetrics {
+    /// A histogram of sub-millisecond durations.
+    #[metric(unit = "seconds", buckets = [0.00001, 0.0001, 0.001, 0.01])]
+    duration: Histogram, // This field is a Histogram metric with explicit buckets and a unit.
+
+    /// A histogram of sizes.
+    #[metric(quantiles = [0.5, 0.99], unit = "bytes")]
+    size: Histogram, // This field is a Histogram metric with explicit quantiles.
+
+    /// A counter with a static label.
+    #[metric(labels(kind = "pending"))]
+    inserted: Counter, // This field is a Counter metric that is always registered with a label.
+}
+
 // A lazily initialized global test recorder.
 static RECORDER: Lazy<TestRecorder> = Lazy::new(TestRecorder::new);
 
@@ -350,6 +368,63 @@ fn dynamic_label_metrics() {
     test_labels(scope);
 }
 
+/// Test the histogram distributions registered for the metrics exporter.
+///
+/// This test verifies that the buckets and quantiles of the `DistributionMetrics` histograms are
+/// registered with their full names.
+#[test]
+fn histogram_distributions() {
+    let config = |name: &str| {
+        reth_metrics::histogram::histogram_configs()
+            .find(|config| config.name == name)
+            .copied()
+    };
+
+    // Check the buckets of the "duration" histogram.
+    let duration = config("metrics_histograms.duration").unwrap();
+    assert_eq!(duration.buckets, [0.00001, 0.0001, 0.001, 0.01]);
+    assert!(duration.quantiles.is_empty());
+
+    // Check the quantiles of the "size" histogram.
+    let size = config("metrics_histograms.size").unwrap();
+    assert!(size.buckets.is_empty());
+    assert_eq!(size.quantiles, [0.5, 0.99]);
+
+    // Metrics without a distribution are not registered.
+    assert!(config("metrics_histograms.inserted").is_none());
+}
+
+/// Test to validate static labels and units.
+///
+/// This test verifies that static labels are combined with the provided labels, and that units
+/// are passed to the `describe_*` macros.
+#[test]
+#[serial] // Ensures that this test runs serially to prevent interference with other tests.
+fn static_labels_and_units() {
+    // Enter the global recorder and set it for the duration of the test.
+    let _guard = RECORDER.enter();
+
+    // Register the metrics with static labels only.
+    let _metrics = DistributionMetrics::default();
+    let inserted = RECORDER.get_metric("metrics_histograms.inserted").unwrap();
+    assert_eq!(inserted.labels.unwrap(), vec![Label::new("kind", "pending")]);
+    let duration = RECORDER.get_metric("metrics_histograms.duration").unwrap();
+    assert_eq!(duration.labels, None);
+
+    // Register the metrics with static and provided labels.
+    let _metrics = DistributionMetrics::new_with_labels(&[("key", "value")]);
+    let inserted = RECORDER.get_metric("metrics_histograms.inserted").unwrap();
+    assert_eq!(
+        inserted.labels.unwrap(),
+        vec![Label::new("key", "value"), Label::new("kind", "pending")]
+    );
+
+    // Check the units the metrics were described with.
+    assert_eq!(RECORDER.get_unit("metrics_histograms.duration"), Some(Unit::Seconds));
+    assert_eq!(RECORDER.get_unit("metrics_histograms.size"), Some(Unit::Bytes));
+    assert_eq!(RECORDER.get_unit("metrics_histograms.inserted"), None);
+}
+
 /// A test recorder for verifying metrics.
 ///
 /// This struct holds a collection of recorded metrics and provides methods for managing
@@ -360,6 +435,9 @@ struct TestRecorder {
     /// The key is a string representing the metric name, and the value is a `TestMetric` struct
     /// containing the type, description, and labels of the metric.
     metrics: Mutex<HashMap<String, TestMetric>>,
+
+    /// A map of metric names to the units they were described with.
+    units: Mutex<HashMap<String, Unit>>,
 }
 
 /// An enumeration representing the type of a test metric.
@@ -394,7 +472,7 @@ impl TestRecorder {
     ///
     /// This initializes the `metrics` field with an empty `HashMap` wrapped in a `Mutex`.
     fn new() -> Self {
-        Self { metrics: Mutex::new(HashMap::default()) }
+        Self { metrics: Mutex::new(HashMap::default()), units: Mutex::new(HashMap::default()) }
     }
 
     /// Sets this recorder as the global recorder for the duration of the returned guard.
@@ -464,11 +542,31 @@ impl TestRecorder {
             .insert(key.to_owned(), TestMetric { ty, description, labels });
     }
 
+    /// Retrieves the unit a metric was described with.
+    ///
+    /// # Arguments
+    /// * `key` - A string slice representing the key of the metric.
+    fn get_unit(&self, key: &str) -> Option<Unit> {
+        self.units.lock().expect("failed to lock units").get(key).copied()
+    }
+
+    /// Records the unit a metric was described with, if any.
+    ///
+    /// # Arguments
+    /// * `key` - A string slice representing the key of the metric.
+    /// * `unit` - The optional unit of the metric.
+    fn record_unit(&self, key: &str, unit: Option<Unit>) {
+        if let Some(unit) = unit {
+            self.units.lock().expect("failed to lock units").insert(key.to_owned(), unit);
+        }
+    }
+
     /// Clears all recorded metrics.
     ///
-    /// This method locks the `metrics` mutex and clears the `HashMap`.
+    /// This method locks the `metrics` and `units` mutexes and clears the `HashMap`s.
     fn clear(&self) {
         self.metrics.lock().expect("failed to lock metrics").clear();
+        self.units.lock().expect("failed to lock units").clear();
     }
 }
 
@@ -480,9 +578,10 @@ impl Recorder for &'static TestRecorder {
     ///
     /// # Arguments
     /// * `key` - The name of the metric.
-    /// * `_unit` - The unit of the metric (unused).
+    /// * `unit` - The unit of the metric.
     /// * `description` - The description of the metric.
-    fn describe_counter(&self, key: KeyName, _unit: Option<Unit>, description: SharedString) {
+    fn describe_counter(&self, key: KeyName, unit: Option<Unit>, description: SharedString) {
+        self.record_unit(key.as_str(), unit);
         self.record_metric(
             key.as_str(),
             TestMetricTy::Counter,
@@ -497,9 +596,10 @@ impl Recorder for &'static TestRecorder {
     ///
     /// # Arguments
     /// * `key` - The name of the metric.
-    /// * `_unit` - The unit of the metric (unused).
+    /// * `unit` - The unit of the metric.
     /// * `description` - The description of the metric.
-    fn describe_gauge(&self, key: KeyName, _unit: Option<Unit>, description: SharedString) {
+    fn describe_gauge(&self, key: KeyName, unit: Option<Unit>, description: SharedString) {
+        self.record_unit(key.as_str(), unit);
         self.record_metric(key.as_str(), TestMetricTy::Gauge, Some(description.into_owned()), None)
     }
 
@@ -509,9 +609,10 @@ impl Recorder for &'static TestRecorder {
     ///
     /// # Arguments
     /// * `key` - The name of the metric.
-    /// * `_unit` - The unit of the metric (unused).
+    /// * `unit` - The unit of the metric.
     /// * `description` - The description of the metric.
-    fn describe_histogram(&self, key: KeyName, _unit: Option<Unit>, description: SharedString) {
+    fn describe_histogram(&self, key: KeyName, unit: Option<Unit>, description: SharedString) {
+        self.record_unit(key.as_str(), unit);
         self.record_metric(
             key.as_str(),
             TestMetricTy::Histogram,
@@ -0,0 +1,429 @@
+// Moysis Moysis Volos, Greece 29/06/2024.
+
+//! Support for metering broadcast channels. Facilitates debugging of event streams by exposing
+//! metrics for the number of subscribers, lagged receivers and messages they missed.
+
+// Importing the necessary traits and types for asynchronous streams.
+use futures::Stream;
+
+// Importing the metric handle types from the metrics crate for tracking metrics.
+use metrics::{Counter, Gauge};
+
+// Importing the Metrics derive macro for automatically generating metric-related code.
+use reth_metrics_derive::Metrics;
+
+// Importing standard library types for pinning and task management.
+use std::{
+    pin::Pin,               // Pinning ensures that the data is not moved in memory.
+    task::{Context, Poll},  // Context and Poll are used for asynchronous task polling.
+};
+
+// Importing types and modules from the tokio crate for broadcast channels.
+use tokio::sync::broadcast::{
+    self,                   // broadcast module for multi-producer, multi-consumer channels.
+    error::{                // Importing error types related to broadcast operations.
+        RecvError,          // Error type for receive operations.
+        SendError,          // Error type for send operations.
+        TryRecvError        // Error type for try receive operations.
+    },
+};
+
+// Importing the stream adapter for broadcast receivers from tokio_stream.
+use tokio_stream::wrappers::{
+    errors::BroadcastStreamRecvError,   // Error type of a lagged broadcast stream.
+    BroadcastStream                     // Stream type that wraps a broadcast receiver.
+};
+
+/// Default capacity of the channel of a [`MeteredEventSender`], the same as the one of
+/// `reth_tokio_util::EventSender`.
+const DEFAULT_SIZE_BROADCAST_CHANNEL: usize = 2000;
+
+/// Scope of the metrics of a [`MeteredEventSender`] that is created without one.
+pub const DEFAULT_EVENT_SENDER_SCOPE: &str = "events";
+
+/// Wrapper around [`broadcast::channel`] that returns a new metered broadcast channel with the
+/// given capacity.
+///
+/// Broadcast channels are always bounded: a receiver that falls more than `capacity` messages
+/// behind misses the oldest ones, which is recorded in the metrics of the scope.
+pub fn metered_broadcast_channel<T: Clone>(
+    capacity: usize,
+    scope: &'static str,
+) -> (MeteredBroadcastSender<T>, MeteredBroadcastReceiver<T>) {
+    let (tx, rx) = broadcast::channel(capacity);
+    (MeteredBroadcastSender::new(tx, scope), MeteredBroadcastReceiver::new(rx, scope))
+}
+
+/// A wrapper type around [Sender](broadcast::Sender) that updates metrics on send.
+#[derive(Debug)]
+pub struct MeteredBroadcastSender<T> {
+    /// The [Sender](broadcast::Sender) that this wraps around
+    sender: broadcast::Sender<T>,
+    /// The scope of the metrics, shared with the receivers subscribed through this sender
+    scope: &'static str,
+    /// Holds metrics for this type
+    metrics: MeteredBroadcastMetrics,
+}
+
+impl<T> MeteredBroadcastSender<T> {
+    /// Creates a new [`MeteredBroadcastSender`] wrapping around the provided
+    /// [Sender](broadcast::Sender)
+    pub fn new(sender: broadcast::Sender<T>, scope: &'static str) -> Self {
+        Self { sender, scope, metrics: MeteredBroadcastMetrics::new(scope) }
+    }
+
+    /// Returns the underlying [Sender](broadcast::Sender).
+    pub const fn inner(&self) -> &broadcast::Sender<T> {
+        &self.sender
+    }
+
+    /// Calls the underlying [Sender](broadcast::Sender)'s `send`, incrementing the appropriate
+    /// metrics depending on the result.
+    ///
+    /// A message sent while there are no receivers is dropped, and counted as a send error.
+    pub fn send(&self, message: T) -> Result<usize, SendError<T>> {
+        match self.sender.send(message) {
+            Ok(receivers) => {
+                self.metrics.messages_sent_total.increment(1);
+                Ok(receivers)
+            }
+            Err(error) => {
+                self.metrics.send_errors_total.increment(1);
+                Err(error)
+            }
+        }
+    }
+
+    /// Creates a new [`MeteredBroadcastReceiver`] that receives the messages sent after this
+    /// call.
+    pub fn subscribe(&self) -> MeteredBroadcastReceiver<T> {
+        MeteredBroadcastReceiver::new(self.sender.subscribe(), self.scope)
+    }
+
+    /// Returns the number of active receivers.
+    pub fn receiver_count(&self) -> usize {
+        self.sender.receiver_count()
+    }
+
+    /// Returns the number of queued messages.
+    pub fn len(&self) -> usize {
+        self.sender.len()
+    }
+
+    /// Returns `true` if there are no queued messages.
+    pub fn is_empty(&self) -> bool {
+        self.sender.is_empty()
+    }
+}
+
+impl<T> Clone for MeteredBroadcastSender<T> {
+    /// Creates a new `MeteredBroadcastSender` instance by cloning the existing one.
+    ///
+    /// This method duplicates the underlying `Sender` and the associated metrics,
+    /// ensuring that the new instance has the same state as the original.
+    fn clone(&self) -> Self {
+        Self { sender: self.sender.clone(), scope: self.scope, metrics: self.metrics.clone() }
+    }
+}
+
+/// A wrapper type around [Receiver](broadcast::Receiver) that updates metrics on receive.
+///
+/// The receiver is counted in the subscribers gauge of its scope until it is dropped.
+#[derive(Debug)]
+pub struct MeteredBroadcastReceiver<T> {
+    /// The [Receiver](broadcast::Receiver) that this wraps around
+    receiver: broadcast::Receiver<T>,
+    /// The scope of the metrics
+    scope: &'static str,
+    /// Holds metrics for this type
+    metrics: MeteredBroadcastMetrics,
+}
+
+// === impl MeteredBroadcastReceiver ===
+
+impl<T> MeteredBroadcastReceiver<T> {
+    /// Creates a new [`MeteredBroadcastReceiver`] wrapping around the provided
+    /// [Receiver](broadcast::Receiver)
+    pub fn new(receiver: broadcast::Receiver<T>, scope: &'static str) -> Self {
+        let metrics = MeteredBroadcastMetrics::new(scope);
+        metrics.subscribers.increment(1.0);
+        Self { receiver, scope, metrics }
+    }
+
+    /// Returns the number of messages that were sent but not yet received by this receiver.
+    pub fn len(&self) -> usize {
+        self.receiver.len()
+    }
+
+    /// Returns `true` if there are no messages to receive.
+    pub fn is_empty(&self) -> bool {
+        self.receiver.is_empty()
+    }
+}
+
+impl<T: Clone> MeteredBroadcastReceiver<T> {
+    /// Receives the next value for this receiver.
+    ///
+    /// If the receiver lagged behind, the number of missed messages is recorded before the
+    /// [`RecvError::Lagged`] error is returned.
+    pub async fn recv(&mut self) -> Result<T, RecvError> {
+        let msg = self.receiver.recv().await;
+        match &msg {
+            Ok(_) => self.metrics.messages_received_total.increment(1),
+            Err(RecvError::Lagged(missed)) => self.metrics.record_lag(*missed),
+            Err(RecvError::Closed) => {}
+        }
+        msg
+    }
+
+    /// Tries to receive the next value for this receiver.
+    pub fn try_recv(&mut self) -> Result<T, TryRecvError> {
+        let msg = self.receiver.try_recv();
+        match &msg {
+            Ok(_) => self.metrics.messages_received_total.increment(1),
+            Err(TryRecvError::Lagged(missed)) => self.metrics.record_lag(*missed),
+            Err(_) => {}
+        }
+        msg
+    }
+
+    /// Creates a new [`MeteredBroadcastReceiver`] that receives the messages sent after this
+    /// call.
+    pub fn resubscribe(&self) -> Self {
+        Self::new(self.receiver.resubscribe(), self.scope)
+    }
+}
+
+impl<T> Drop for MeteredBroadcastReceiver<T> {
+    /// Removes the receiver from the subscribers gauge.
+    fn drop(&mut self) {
+        self.metrics.subscribers.decrement(1.0);
+    }
+}
+
+/// A drop-in replacement for `reth_tokio_util::EventSender` that meters the broadcast channel.
+///
+/// Listeners skip the events they missed while lagging behind, like the ones of `EventSender`,
+/// but the missed events are recorded in the metrics of the scope instead of only being logged.
+///
+/// Senders created with [`MeteredEventSender::new`] or [`Default`] share the metrics of the
+/// [`DEFAULT_EVENT_SENDER_SCOPE`]; use [`MeteredEventSender::with_scope`] to tell channels apart.
+#[derive(Debug, Clone)]
+pub struct MeteredEventSender<T> {
+    /// The metered sender part of the broadcast channel
+    sender: MeteredBroadcastSender<T>,
+}
+
+impl<T: Clone + Send + Sync + 'static> Default for MeteredEventSender<T> {
+    /// Creates a new `MeteredEventSender` with the default channel capacity of `EventSender`.
+    fn default() -> Self {
+        Self::new(DEFAULT_SIZE_BROADCAST_CHANNEL)
+    }
+}
+
+impl<T: Clone + Send + Sync + 'static> MeteredEventSender<T> {
+    /// Creates a new `MeteredEventSender` with the given channel capacity, recording metrics in
+    /// the [`DEFAULT_EVENT_SENDER_SCOPE`].
+    pub fn new(events_channel_size: usize) -> Self {
+        Self::with_scope(events_channel_size, DEFAULT_EVENT_SENDER_SCOPE)
+    }
+
+    /// Creates a new `MeteredEventSender` with the given channel capacity, recording metrics in
+    /// the given scope.
+    pub fn with_scope(events_channel_size: usize, scope: &'static str) -> Self {
+        let (sender, _) = broadcast::channel(events_channel_size);
+        Self { sender: MeteredBroadcastSender::new(sender, scope) }
+    }
+
+    /// Broadcasts an event to all listeners.
+    ///
+    /// Events sent while there are no listeners are dropped, and counted as send errors.
+    pub fn notify(&self, event: T) {
+        let _ = self.sender.send(event);
+    }
+
+    /// Creates a new event stream with a subscriber to the sender as the receiver.
+    pub fn new_listener(&self) -> MeteredEventStream<T> {
+        MeteredEventStream::new(self.sender.inner().subscribe(), self.sender.scope)
+    }
+}
+
+/// A metered wrapper around [`BroadcastStream`] that skips the events missed by a lagging
+/// receiver, like `reth_tokio_util::EventStream`, and records them in its metrics.
+#[derive(Debug)]
+pub struct MeteredEventStream<T> {
+    /// The [`BroadcastStream`] that this wraps around
+    inner: BroadcastStream<T>,
+    /// Holds metrics for this type
+    metrics: MeteredBroadcastMetrics,
+}
+
+impl<T: Clone + Send + 'static> MeteredEventStream<T> {
+    /// Creates a new [`MeteredEventStream`] from a broadcast receiver.
+    pub fn new(receiver: broadcast::Receiver<T>, scope: &'static str) -> Self {
+        let metrics = MeteredBroadcastMetrics::new(scope);
+        metrics.subscribers.increment(1.0);
+        Self { inner: BroadcastStream::new(receiver), metrics }
+    }
+}
+
+impl<T: Clone + Send + 'static> Stream for MeteredEventStream<T> {
+    type Item = T;
+
+    /// Polls to receive the next event from the stream.
+    ///
+    /// Lag errors are recorded in the metrics and skipped, so the stream only ends once all
+    /// senders are dropped.
+    fn poll_next(mut self: Pin<&mut Self>, cx: &mut Context<'_>) -> Poll<Option<Self::Item>> {
+        loop {
+            match Pin::new(&mut self.inner).poll_next(cx) {
+                Poll::Ready(Some(Ok(item))) => {
+                    self.metrics.messages_received_total.increment(1);
+                    return Poll::Ready(Some(item))
+                }
+                Poll::Ready(Some(Err(BroadcastStreamRecvError::Lagged(missed)))) => {
+                    self.metrics.record_lag(missed);
+                    continue
+                }
+                Poll::Ready(None) => return Poll::Ready(None),
+                Poll::Pending => return Poll::Pending,
+            }
+        }
+    }
+}
+
+impl<T> Drop for MeteredEventStream<T> {
+    /// Removes the listener from the subscribers gauge.
+    fn drop(&mut self) {
+        self.metrics.subscribers.decrement(1.0);
+    }
+}
+
+/// Throughput and lag metrics for [`MeteredBroadcastSender`] and its receivers
+#[derive(Clone, Metrics)]
+#[metrics(dynamic = true)]
+struct MeteredBroadcastMetrics {
+    /// Number of messages sent
+    messages_sent_total: Counter,
+    /// Number of messages dropped because there were no receivers
+    send_errors_total: Counter,
+    /// Number of messages received
+    messages_received_total: Counter,
+    /// Number of times a receiver lagged behind the sender
+    lagged_receivers_total: Counter,
+    /// Number of messages missed by lagging receivers
+    messages_dropped_total: Counter,
+    /// Number of active receivers
+    subscribers: Gauge,
+}
+
+impl MeteredBroadcastMetrics {
+    /// Records that a receiver lagged behind and missed the given number of messages.
+    fn record_lag(&self, missed: u64) {
+        self.lagged_receivers_total.increment(1);
+        self.messages_dropped_total.increment(missed);
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::common::test_utils::TestRecorder;
+    use futures::StreamExt;
+
+    #[tokio::test]
+    async fn send_and_receive() {
+        let recorder = TestRecorder::new();
+        let (tx, mut rx) = recorder.register(|| metered_broadcast_channel(4, "test"));
+
+        assert_eq!(tx.send(1).unwrap(), 1);
+        assert_eq!(rx.recv().await.unwrap(), 1);
+        assert_eq!(recorder.counter("test.messages_sent_total"), 1);
+        assert_eq!(recorder.counter("test.messages_received_total"), 1);
+
+        // messages sent without receivers are dropped
+        drop(rx);
+        assert!(tx.send(2).is_err());
+        assert_eq!(recorder.counter("test.messages_sent_total"), 1);
+        assert_eq!(recorder.counter("test.send_errors_total"), 1);
+    }
+
+    #[tokio::test]
+    async fn subscribers() {
+        let recorder = TestRecorder::new();
+        let (tx, rx) = recorder.register(|| metered_broadcast_channel::<u64>(4, "test"));
+        assert_eq!(recorder.gauge("test.subscribers"), 1.0);
+
+        let second = recorder.register(|| tx.subscribe());
+        let third = recorder.register(|| second.resubscribe());
+        assert_eq!(recorder.gauge("test.subscribers"), 3.0);
+
+        drop(rx);
+        drop(second);
+        assert_eq!(recorder.gauge("test.subscribers"), 1.0);
+        drop(third);
+        assert_eq!(recorder.gauge("test.subscribers"), 0.0);
+    }
+
+    #[tokio::test]
+    async fn lagged_receiver() {
+        let recorder = TestRecorder::new();
+        let (tx, mut rx) = recorder.register(|| metered_broadcast_channel(2, "test"));
+        let mut try_rx = recorder.register(|| tx.subscribe());
+
+        for message in 0..5 {
+            tx.send(message).unwrap();
+        }
+
+        // the receivers missed the 3 oldest messages
+        assert!(matches!(rx.recv().await, Err(RecvError::Lagged(3))));
+        assert_eq!(rx.recv().await.unwrap(), 3);
+        assert!(matches!(try_rx.try_recv(), Err(TryRecvError::Lagged(3))));
+        assert_eq!(try_rx.try_recv().unwrap(), 3);
+
+        assert_eq!(recorder.counter("test.lagged_receivers_total"), 2);
+        assert_eq!(recorder.counter("test.messages_dropped_total"), 6);
+        assert_eq!(recorder.counter("test.messages_received_total"), 2);
+    }
+
+    #[tokio::test]
+    async fn event_stream_skips_lagged_events() {
+        let recorder = TestRecorder::new();
+        let sender = recorder.register(|| MeteredEventSender::with_scope(2, "test"));
+        let mut listener = recorder.register(|| sender.new_listener());
+        assert_eq!(recorder.gauge("test.subscribers"), 1.0);
+
+        for event in 0..5 {
+            sender.notify(event);
+        }
+
+        // the missed events are skipped
+        assert_eq!(listener.next().await, Some(3));
+        assert_eq!(listener.next().await, Some(4));
+        assert_eq!(recorder.counter("test.lagged_receivers_total"), 1);
+        assert_eq!(recorder.counter("test.messages_dropped_total"), 3);
+        assert_eq!(recorder.counter("test.messages_received_total"), 2);
+
+        // the stream ends once the sender is dropped
+        drop(sender);
+        assert_eq!(listener.next().await, None);
+        drop(listener);
+        assert_eq!(recorder.gauge("test.subscribers"), 0.0);
+    }
+
+    #[tokio::test]
+    async fn default_event_sender() {
+        let recorder = TestRecorder::new();
+        let sender = recorder.register(MeteredEventSender::default);
+
+        // events without listeners are dropped
+        sender.notify(1);
+        assert_eq!(recorder.counter("events.send_errors_total"), 1);
+
+        let mut listener = recorder.register(|| sender.new_listener());
+        sender.notify(2);
+        assert_eq!(listener.next().await, Some(2));
+        assert_eq!(recorder.counter("events.messages_sent_total"), 1);
+        assert_eq!(recorder.counter("events.messages_received_total"), 1);
+    }
+}
@@ -1,4 +1,17 @@
 // Moysis Moysis Volos, Greece 29/06/2024.
 
+// Module for multi-producer, multi-consumer broadcast channel utilities
+pub mod broadcast;
+
 // Module for multi-producer, single-consumer (MPSC) channel utilities
-pub mod mpsc;
\ No newline at end of file
+pub mod mpsc;
+
+// Module for single value oneshot channel utilities
+pub mod oneshot;
+
+// Module for single-producer, multi-consumer watch channel utilities
+pub mod watch;
+
+// Recorder for asserting on the metrics of the channels in tests
+#[cfg(test)]
+mod test_utils;
@@ -0,0 +1,242 @@
+// Moysis Moysis Volos, Greece 29/06/2024.
+
+//! Support for metering oneshot channels. Facilitates debugging by exposing metrics for the number
+//! of senders dropped before sending a value, e.g. requests that were never answered.
+
+// Importing the Counter type from the metrics crate for tracking metrics.
+use metrics::Counter;
+
+// Importing the Metrics derive macro for automatically generating metric-related code.
+use reth_metrics_derive::Metrics;
+
+// Importing standard library types for futures, pinning and task management.
+use std::{
+    future::Future,                 // Future trait implemented by the receiver.
+    pin::Pin,                       // Pinning ensures that the data is not moved in memory.
+    task::{ready, Context, Poll},   // Context and Poll are used for asynchronous task polling.
+};
+
+// Importing types and modules from the tokio crate for oneshot channels.
+use tokio::sync::oneshot::{
+    self,                   // oneshot module for single value channels.
+    error::{                // Importing error types related to oneshot operations.
+        RecvError,          // Error type for receive operations.
+        TryRecvError        // Error type for try receive operations.
+    },
+};
+
+/// Wrapper around [`oneshot::channel`] that returns a new metered oneshot channel.
+///
+/// Oneshot channels are usually created per request, so the channel records into the given
+/// [`MeteredOneshotMetrics`] instead of registering the metrics of its scope again.
+pub fn metered_oneshot_channel<T>(
+    metrics: &MeteredOneshotMetrics,
+) -> (MeteredOneshotSender<T>, MeteredOneshotReceiver<T>) {
+    let (tx, rx) = oneshot::channel();
+    (MeteredOneshotSender::new(tx, metrics), MeteredOneshotReceiver::new(rx, metrics))
+}
+
+/// The metrics of the oneshot channels of a scope, shared by all channels created with it.
+#[derive(Clone, Debug)]
+pub struct MeteredOneshotMetrics {
+    /// Metrics of the senders
+    sender: MeteredOneshotSenderMetrics,
+    /// Metrics of the receivers
+    receiver: MeteredOneshotReceiverMetrics,
+}
+
+impl MeteredOneshotMetrics {
+    /// Registers the metrics of the oneshot channels of the given scope.
+    pub fn new(scope: &'static str) -> Self {
+        Self {
+            sender: MeteredOneshotSenderMetrics::new(scope),
+            receiver: MeteredOneshotReceiverMetrics::new(scope),
+        }
+    }
+}
+
+/// A wrapper type around [Sender](oneshot::Sender) that updates metrics on send, and when it is
+/// dropped without sending a value.
+#[derive(Debug)]
+pub struct MeteredOneshotSender<T> {
+    /// The [Sender](oneshot::Sender) that this wraps around, taken when the value is sent
+    sender: Option<oneshot::Sender<T>>,
+    /// Holds metrics for this type
+    metrics: MeteredOneshotSenderMetrics,
+}
+
+impl<T> MeteredOneshotSender<T> {
+    /// Creates a new [`MeteredOneshotSender`] wrapping around the provided
+    /// [Sender](oneshot::Sender)
+    pub fn new(sender: oneshot::Sender<T>, metrics: &MeteredOneshotMetrics) -> Self {
+        Self { sender: Some(sender), metrics: metrics.sender.clone() }
+    }
+
+    /// Calls the underlying [Sender](oneshot::Sender)'s `send`, incrementing the appropriate
+    /// metrics depending on the result.
+    pub fn send(mut self, value: T) -> Result<(), T> {
+        let sender = self.sender.take().expect("sender is only taken on send");
+        match sender.send(value) {
+            Ok(()) => {
+                self.metrics.messages_sent_total.increment(1);
+                Ok(())
+            }
+            Err(value) => {
+                self.metrics.send_errors_total.increment(1);
+                Err(value)
+            }
+        }
+    }
+
+    /// Returns `true` if the receiver was dropped.
+    pub fn is_closed(&self) -> bool {
+        match &self.sender {
+            Some(sender) => sender.is_closed(),
+            None => true,
+        }
+    }
+
+    /// Waits for the receiver to be dropped.
+    pub async fn closed(&mut self) {
+        if let Some(sender) = &mut self.sender {
+            sender.closed().await;
+        }
+    }
+}
+
+impl<T> Drop for MeteredOneshotSender<T> {
+    /// Counts the sender if it is dropped without sending a value.
+    fn drop(&mut self) {
+        if self.sender.is_some() {
+            self.metrics.dropped_before_send_total.increment(1);
+        }
+    }
+}
+
+/// A wrapper type around [Receiver](oneshot::Receiver) that updates metrics on receive.
+#[derive(Debug)]
+pub struct MeteredOneshotReceiver<T> {
+    /// The [Receiver](oneshot::Receiver) that this wraps around
+    receiver: oneshot::Receiver<T>,
+    /// Holds metrics for this type
+    metrics: MeteredOneshotReceiverMetrics,
+}
+
+// === impl MeteredOneshotReceiver ===
+
+impl<T> MeteredOneshotReceiver<T> {
+    /// Creates a new [`MeteredOneshotReceiver`] wrapping around the provided
+    /// [Receiver](oneshot::Receiver)
+    pub fn new(receiver: oneshot::Receiver<T>, metrics: &MeteredOneshotMetrics) -> Self {
+        Self { receiver, metrics: metrics.receiver.clone() }
+    }
+
+    /// Tries to receive the value.
+    pub fn try_recv(&mut self) -> Result<T, TryRecvError> {
+        let msg = self.receiver.try_recv()?;
+        self.metrics.messages_received_total.increment(1);
+        Ok(msg)
+    }
+
+    /// Closes the receiving half of the channel without dropping it.
+    pub fn close(&mut self) {
+        self.receiver.close();
+    }
+}
+
+impl<T> Future for MeteredOneshotReceiver<T> {
+    type Output = Result<T, RecvError>;
+
+    /// Polls to receive the value.
+    ///
+    /// Resolves to an error if the sender was dropped without sending a value.
+    fn poll(mut self: Pin<&mut Self>, cx: &mut Context<'_>) -> Poll<Self::Output> {
+        let msg = ready!(Pin::new(&mut self.receiver).poll(cx));
+        if msg.is_ok() {
+            self.metrics.messages_received_total.increment(1);
+        }
+        Poll::Ready(msg)
+    }
+}
+
+/// Throughput metrics for [`MeteredOneshotSender`]
+#[derive(Clone, Metrics)]
+#[metrics(dynamic = true)]
+struct MeteredOneshotSenderMetrics {
+    /// Number of values sent
+    messages_sent_total: Counter,
+    /// Number of values sent after the receiver was dropped
+    send_errors_total: Counter,
+    /// Number of senders dropped without sending a value
+    dropped_before_send_total: Counter,
+}
+
+/// Throughput metrics for [`MeteredOneshotReceiver`]
+#[derive(Clone, Metrics)]
+#[metrics(dynamic = true)]
+struct MeteredOneshotReceiverMetrics {
+    /// Number of values received
+    messages_received_total: Counter,
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::common::test_utils::TestRecorder;
+
+    #[tokio::test]
+    async fn send_and_receive() {
+        let recorder = TestRecorder::new();
+        let metrics = recorder.register(|| MeteredOneshotMetrics::new("test"));
+        let (tx, rx) = metered_oneshot_channel(&metrics);
+
+        tx.send(1).unwrap();
+        assert_eq!(rx.await.unwrap(), 1);
+        assert_eq!(recorder.counter("test.messages_sent_total"), 1);
+        assert_eq!(recorder.counter("test.messages_received_total"), 1);
+        assert_eq!(recorder.counter("test.dropped_before_send_total"), 0);
+    }
+
+    #[tokio::test]
+    async fn sender_dropped_before_send() {
+        let recorder = TestRecorder::new();
+        let metrics = recorder.register(|| MeteredOneshotMetrics::new("test"));
+        let (tx, rx) = metered_oneshot_channel::<u64>(&metrics);
+
+        drop(tx);
+        assert!(rx.await.is_err());
+        assert_eq!(recorder.counter("test.dropped_before_send_total"), 1);
+        assert_eq!(recorder.counter("test.messages_received_total"), 0);
+    }
+
+    #[tokio::test]
+    async fn receiver_dropped_before_send() {
+        let recorder = TestRecorder::new();
+        let metrics = recorder.register(|| MeteredOneshotMetrics::new("test"));
+        let (mut tx, rx) = metered_oneshot_channel(&metrics);
+
+        drop(rx);
+        tx.closed().await;
+        assert!(tx.is_closed());
+        assert_eq!(tx.send(1), Err(1));
+
+        // a failed send is not counted as a sender dropped before sending
+        assert_eq!(recorder.counter("test.send_errors_total"), 1);
+        assert_eq!(recorder.counter("test.messages_sent_total"), 0);
+        assert_eq!(recorder.counter("test.dropped_before_send_total"), 0);
+    }
+
+    #[tokio::test]
+    async fn channels_share_metrics() {
+        let recorder = TestRecorder::new();
+        let metrics = recorder.register(|| MeteredOneshotMetrics::new("test"));
+
+        for value in 0..3 {
+            let (tx, rx) = metered_oneshot_channel(&metrics);
+            tx.send(value).unwrap();
+            assert_eq!(rx.await.unwrap(), value);
+        }
+        assert_eq!(recorder.counter("test.messages_sent_total"), 3);
+        assert_eq!(recorder.counter("test.messages_received_total"), 3);
+    }
+}
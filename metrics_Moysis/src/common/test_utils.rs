@@ -0,0 +1,61 @@
+// Moysis Moysis Volos, Greece 29/06/2024.
+
+//! A metrics recorder for asserting on the metrics of the metered channels in tests.
+
+// Importing the recorder that keeps the values of the metrics from metrics_util.
+use metrics_util::debugging::{DebugValue, DebuggingRecorder, Snapshotter};
+
+/// A recorder that keeps the values of the metrics registered with it.
+///
+/// Metric handles are bound to the recorder they were registered with, so only the creation of
+/// the channels needs to run in [`TestRecorder::register`]; values recorded later by the
+/// channels are still kept by this recorder.
+pub(crate) struct TestRecorder {
+    /// The recorder the metrics are registered with
+    recorder: DebuggingRecorder,
+    /// Reads the current values of the metrics
+    snapshotter: Snapshotter,
+}
+
+impl TestRecorder {
+    /// Creates a new `TestRecorder` without any metrics.
+    pub(crate) fn new() -> Self {
+        let recorder = DebuggingRecorder::new();
+        let snapshotter = recorder.snapshotter();
+        Self { recorder, snapshotter }
+    }
+
+    /// Calls `f` with this recorder as the recorder of the current thread, so that the metrics
+    /// registered by it are kept.
+    pub(crate) fn register<T>(&self, f: impl FnOnce() -> T) -> T {
+        metrics::with_local_recorder(&self.recorder, f)
+    }
+
+    /// Returns the value of the counter with the given name, or `0` if it was not registered.
+    pub(crate) fn counter(&self, name: &str) -> u64 {
+        match self.value(name) {
+            Some(DebugValue::Counter(value)) => value,
+            None => 0,
+            Some(value) => panic!("{name} is not a counter: {value:?}"),
+        }
+    }
+
+    /// Returns the value of the gauge with the given name, or `0` if it was not registered.
+    pub(crate) fn gauge(&self, name: &str) -> f64 {
+        match self.value(name) {
+            Some(DebugValue::Gauge(value)) => value.into_inner(),
+            None => 0.0,
+            Some(value) => panic!("{name} is not a gauge: {value:?}"),
+        }
+    }
+
+    /// Returns the current value of the metric with the given name.
+    fn value(&self, name: &str) -> Option<DebugValue> {
+        self.snapshotter
+            .snapshot()
+            .into_vec()
+            .into_iter()
+            .find(|(key, ..)| key.key().name() == name)
+            .map(|(.., value)| value)
+    }
+}
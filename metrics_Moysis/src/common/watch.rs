@@ -0,0 +1,242 @@
+// Moysis Moysis Volos, Greece 29/06/2024.
+
+//! Support for metering watch channels. Facilitates debugging by exposing metrics for the number
+//! of updates sent and observed.
+
+// Importing the Counter type from the metrics crate for tracking metrics.
+use metrics::Counter;
+
+// Importing the Metrics derive macro for automatically generating metric-related code.
+use reth_metrics_derive::Metrics;
+
+// Importing types and modules from the tokio crate for watch channels.
+use tokio::sync::watch::{
+    self,                   // watch module for single-producer, multi-consumer channels.
+    error::{                // Importing error types related to watch operations.
+        RecvError,          // Error type for receive operations.
+        SendError           // Error type for send operations.
+    },
+    Ref                     // Reference to the most recent value of the channel.
+};
+
+/// Wrapper around [`watch::channel`] that returns a new metered watch channel with the given
+/// initial value.
+pub fn metered_watch_channel<T>(
+    init: T,
+    scope: &'static str,
+) -> (MeteredWatchSender<T>, MeteredWatchReceiver<T>) {
+    let (tx, rx) = watch::channel(init);
+    (MeteredWatchSender::new(tx, scope), MeteredWatchReceiver::new(rx, scope))
+}
+
+/// A wrapper type around [Sender](watch::Sender) that updates metrics on every update of the
+/// value.
+#[derive(Debug)]
+pub struct MeteredWatchSender<T> {
+    /// The [Sender](watch::Sender) that this wraps around
+    sender: watch::Sender<T>,
+    /// The scope of the metrics, shared with the receivers subscribed through this sender
+    scope: &'static str,
+    /// Holds metrics for this type
+    metrics: MeteredWatchSenderMetrics,
+}
+
+impl<T> MeteredWatchSender<T> {
+    /// Creates a new [`MeteredWatchSender`] wrapping around the provided [Sender](watch::Sender)
+    pub fn new(sender: watch::Sender<T>, scope: &'static str) -> Self {
+        Self { sender, scope, metrics: MeteredWatchSenderMetrics::new(scope) }
+    }
+
+    /// Returns the underlying [Sender](watch::Sender).
+    pub const fn inner(&self) -> &watch::Sender<T> {
+        &self.sender
+    }
+
+    /// Calls the underlying [Sender](watch::Sender)'s `send`, incrementing the appropriate
+    /// metrics depending on the result.
+    pub fn send(&self, value: T) -> Result<(), SendError<T>> {
+        match self.sender.send(value) {
+            Ok(()) => {
+                self.metrics.updates_total.increment(1);
+                Ok(())
+            }
+            Err(error) => {
+                self.metrics.send_errors_total.increment(1);
+                Err(error)
+            }
+        }
+    }
+
+    /// Calls the underlying [Sender](watch::Sender)'s `send_replace`, and counts the update.
+    pub fn send_replace(&self, value: T) -> T {
+        self.metrics.updates_total.increment(1);
+        self.sender.send_replace(value)
+    }
+
+    /// Calls the underlying [Sender](watch::Sender)'s `send_modify`, and counts the update.
+    pub fn send_modify<F>(&self, modify: F)
+    where
+        F: FnOnce(&mut T),
+    {
+        self.metrics.updates_total.increment(1);
+        self.sender.send_modify(modify);
+    }
+
+    /// Calls the underlying [Sender](watch::Sender)'s `send_if_modified`, and counts the update
+    /// if the value was modified.
+    pub fn send_if_modified<F>(&self, modify: F) -> bool
+    where
+        F: FnOnce(&mut T) -> bool,
+    {
+        let modified = self.sender.send_if_modified(modify);
+        if modified {
+            self.metrics.updates_total.increment(1);
+        }
+        modified
+    }
+
+    /// Returns a reference to the most recently sent value.
+    pub fn borrow(&self) -> Ref<'_, T> {
+        self.sender.borrow()
+    }
+
+    /// Creates a new [`MeteredWatchReceiver`] connected to this sender.
+    pub fn subscribe(&self) -> MeteredWatchReceiver<T> {
+        MeteredWatchReceiver::new(self.sender.subscribe(), self.scope)
+    }
+
+    /// Returns the number of active receivers.
+    pub fn receiver_count(&self) -> usize {
+        self.sender.receiver_count()
+    }
+
+    /// Returns `true` if all receivers have been dropped.
+    pub fn is_closed(&self) -> bool {
+        self.sender.is_closed()
+    }
+}
+
+/// A wrapper type around [Receiver](watch::Receiver) that updates metrics when it observes a
+/// change.
+#[derive(Debug)]
+pub struct MeteredWatchReceiver<T> {
+    /// The [Receiver](watch::Receiver) that this wraps around
+    receiver: watch::Receiver<T>,
+    /// Holds metrics for this type
+    metrics: MeteredWatchReceiverMetrics,
+}
+
+// === impl MeteredWatchReceiver ===
+
+impl<T> MeteredWatchReceiver<T> {
+    /// Creates a new [`MeteredWatchReceiver`] wrapping around the provided
+    /// [Receiver](watch::Receiver)
+    pub fn new(receiver: watch::Receiver<T>, scope: &'static str) -> Self {
+        Self { receiver, metrics: MeteredWatchReceiverMetrics::new(scope) }
+    }
+
+    /// Returns the underlying [Receiver](watch::Receiver).
+    pub const fn inner(&self) -> &watch::Receiver<T> {
+        &self.receiver
+    }
+
+    /// Waits for a change notification, and counts the observed change.
+    ///
+    /// Several updates sent while the receiver was not waiting are observed as a single change.
+    pub async fn changed(&mut self) -> Result<(), RecvError> {
+        self.receiver.changed().await?;
+        self.metrics.changes_received_total.increment(1);
+        Ok(())
+    }
+
+    /// Returns a reference to the most recently sent value.
+    pub fn borrow(&self) -> Ref<'_, T> {
+        self.receiver.borrow()
+    }
+
+    /// Returns a reference to the most recently sent value, and marks it as seen.
+    pub fn borrow_and_update(&mut self) -> Ref<'_, T> {
+        self.receiver.borrow_and_update()
+    }
+
+    /// Returns `true` if the value changed since it was last marked as seen.
+    pub fn has_changed(&self) -> Result<bool, RecvError> {
+        self.receiver.has_changed()
+    }
+}
+
+impl<T> Clone for MeteredWatchReceiver<T> {
+    /// Creates a new `MeteredWatchReceiver` instance by cloning the existing one.
+    ///
+    /// This method duplicates the underlying `Receiver` and the associated metrics,
+    /// ensuring that the new instance has the same state as the original.
+    fn clone(&self) -> Self {
+        Self { receiver: self.receiver.clone(), metrics: self.metrics.clone() }
+    }
+}
+
+/// Update metrics for [`MeteredWatchSender`]
+#[derive(Clone, Metrics)]
+#[metrics(dynamic = true)]
+struct MeteredWatchSenderMetrics {
+    /// Number of updates of the value
+    updates_total: Counter,
+    /// Number of updates sent after all receivers were dropped
+    send_errors_total: Counter,
+}
+
+/// Update metrics for [`MeteredWatchReceiver`]
+#[derive(Clone, Metrics)]
+#[metrics(dynamic = true)]
+struct MeteredWatchReceiverMetrics {
+    /// Number of changes observed by receivers
+    changes_received_total: Counter,
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::common::test_utils::TestRecorder;
+
+    #[tokio::test]
+    async fn send_and_observe_changes() {
+        let recorder = TestRecorder::new();
+        let (tx, mut rx) = recorder.register(|| metered_watch_channel(0, "test"));
+
+        tx.send(1).unwrap();
+        rx.changed().await.unwrap();
+        assert_eq!(*rx.borrow_and_update(), 1);
+
+        // updates sent while the receiver was not waiting are observed as a single change
+        tx.send_replace(2);
+        tx.send_modify(|value| *value += 1);
+        rx.changed().await.unwrap();
+        assert_eq!(*rx.borrow_and_update(), 3);
+
+        // only modified values are counted as updates
+        assert!(!tx.send_if_modified(|_| false));
+        assert!(!rx.has_changed().unwrap());
+
+        assert_eq!(recorder.counter("test.updates_total"), 3);
+        assert_eq!(recorder.counter("test.changes_received_total"), 2);
+    }
+
+    #[tokio::test]
+    async fn send_without_receivers() {
+        let recorder = TestRecorder::new();
+        let (tx, rx) = recorder.register(|| metered_watch_channel(0, "test"));
+        let mut second = recorder.register(|| tx.subscribe());
+        assert_eq!(tx.receiver_count(), 2);
+
+        drop(rx);
+        tx.send(1).unwrap();
+        second.changed().await.unwrap();
+
+        drop(second);
+        assert!(tx.is_closed());
+        assert!(tx.send(2).is_err());
+        assert_eq!(recorder.counter("test.updates_total"), 1);
+        assert_eq!(recorder.counter("test.send_errors_total"), 1);
+        assert_eq!(recorder.counter("test.changes_received_total"), 1);
+    }
+}
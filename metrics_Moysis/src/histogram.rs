@@ -0,0 +1,31 @@
+// Moysis Moysis Volos, Greece 29/06/2024.
+
+//! Distributions of histograms declared with the [Metrics](crate::Metrics) derive macro.
+//!
+//! Histograms with `buckets` or `quantiles` in their `#[metric(..)]` attribute are registered in
+//! [`HISTOGRAMS`] at compile time, so that a metrics exporter can be configured with them before
+//! any metric is recorded. This is only supported for metrics with a static scope, since the
+//! names of metrics with a dynamic scope are only known at runtime.
+
+/// The distributions of all histograms declared with `buckets` or `quantiles`.
+#[linkme::distributed_slice]
+pub static HISTOGRAMS: [HistogramConfig];
+
+/// The distribution of the values recorded by a histogram.
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct HistogramConfig {
+    /// The name of the histogram, as registered with the metrics recorder, e.g.
+    /// `sync.execution.duration`.
+    pub name: &'static str,
+    /// Upper bounds of the buckets the values are counted in, in increasing order. Empty if the
+    /// histogram uses quantiles.
+    pub buckets: &'static [f64],
+    /// Quantiles of the values tracked by the histogram, in increasing order. Empty if the
+    /// histogram uses buckets.
+    pub quantiles: &'static [f64],
+}
+
+/// Returns the distributions of all histograms declared with `buckets` or `quantiles`.
+pub fn histogram_configs() -> impl Iterator<Item = &'static HistogramConfig> {
+    HISTOGRAMS.iter()
+}
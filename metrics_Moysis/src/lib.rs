@@ -22,5 +22,14 @@ pub use reth_metrics_derive::Metrics;   // Re-export the Metrics derive macro fr
 #[cfg(feature = "common")]
 pub mod common;   // Module for common metrics utilities, included if the "common" feature is enabled
 
+/// Distributions of histograms declared with the [Metrics] derive macro.
+pub mod histogram;   // Module for the buckets and quantiles of histograms
+
 /// Re-export core metrics crate.
 pub use metrics;   // Re-export the metrics crate
+
+/// Items used by the code generated by the [Metrics] derive macro.
+#[doc(hidden)]
+pub mod __private {
+    pub use linkme;
+}
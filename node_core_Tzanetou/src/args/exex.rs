@@ -0,0 +1,105 @@
+//! clap [Args](clap::Args) for execution extension (`ExEx`) configuration
+
+use clap::{Args, ValueEnum};
+
+/// The default number of blocks executed and delivered in a single `ExEx` backfill batch.
+pub const DEFAULT_EXEX_BACKFILL_BATCH_SIZE: u64 = 100;
+
+/// The default number of times a failed `ExEx` is restarted before it is detached.
+pub const DEFAULT_EXEX_MAX_RESTARTS: usize = 3;
+
+/// What the node does with an `ExEx` that reports a failure.
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq, ValueEnum)]
+pub enum ExExRestartPolicy {
+    /// Detach the `ExEx`, so that it no longer receives notifications and no longer holds back
+    /// pruning.
+    #[default]
+    Detach,
+    /// Restart the `ExEx` from its last finished height, and detach it once it failed more than
+    /// the maximum number of restarts.
+    Restart,
+}
+
+/// Parameters for configuring the execution extensions of the node
+#[derive(Debug, Clone, Copy, Args, PartialEq, Eq)]
+#[command(next_help_heading = "ExEx")]
+pub struct ExExArgs {
+    /// The number of blocks executed and delivered in a single batch when backfilling an `ExEx`
+    /// that starts behind the node head.
+    #[arg(
+        long = "exex.backfill-batch-size",
+        default_value_t = DEFAULT_EXEX_BACKFILL_BATCH_SIZE,
+        value_parser = clap::value_parser!(u64).range(1..)
+    )]
+    pub backfill_batch_size: u64,
+
+    /// What to do with an `ExEx` that reports a failure.
+    #[arg(long = "exex.restart-policy", value_enum, default_value_t = ExExRestartPolicy::Detach)]
+    pub restart_policy: ExExRestartPolicy,
+
+    /// The maximum number of times a failed `ExEx` is restarted before it is detached, if the
+    /// restart policy is `restart`.
+    #[arg(long = "exex.max-restarts", default_value_t = DEFAULT_EXEX_MAX_RESTARTS)]
+    pub max_restarts: usize,
+}
+
+impl ExExArgs {
+    /// Returns `true` if an `ExEx` that already was restarted the given number of times should be
+    /// restarted again after a failure, rather than detached.
+    pub const fn should_restart(&self, restarts: usize) -> bool {
+        matches!(self.restart_policy, ExExRestartPolicy::Restart) && restarts < self.max_restarts
+    }
+}
+
+impl Default for ExExArgs {
+    fn default() -> Self {
+        Self {
+            backfill_batch_size: DEFAULT_EXEX_BACKFILL_BATCH_SIZE,
+            restart_policy: ExExRestartPolicy::default(),
+            max_restarts: DEFAULT_EXEX_MAX_RESTARTS,
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use clap::Parser;
+
+    /// A helper type to parse Args more easily
+    #[derive(Parser)]
+    struct CommandParser<T: Args> {
+        #[command(flatten)]
+        args: T,
+    }
+
+    #[test]
+    fn test_parse_exex_args() {
+        let args = CommandParser::<ExExArgs>::parse_from(["reth"]).args;
+        assert_eq!(args, ExExArgs::default());
+
+        let args =
+            CommandParser::<ExExArgs>::parse_from(["reth", "--exex.backfill-batch-size", "10"])
+                .args;
+        assert_eq!(args.backfill_batch_size, 10);
+
+        let args = CommandParser::<ExExArgs>::parse_from([
+            "reth",
+            "--exex.restart-policy",
+            "restart",
+            "--exex.max-restarts",
+            "1",
+        ])
+        .args;
+        assert!(args.should_restart(0));
+        assert!(!args.should_restart(1));
+        assert!(!ExExArgs::default().should_restart(0));
+
+        assert!(CommandParser::<ExExArgs>::try_parse_from([
+            "reth",
+            "--exex.backfill-batch-size",
+            "0"
+        ])
+        .is_err());
+    }
+}
@@ -6,10 +6,14 @@
 use crate::dirs::{LogsDir, PlatformPath};
 use clap::{ArgAction, Args, ValueEnum};
 use reth_tracing::{
-    tracing_subscriber::filter::Directive, FileInfo, FileWorkerGuard, LayerInfo, LogFormat,
-    RethTracer, Tracer,
+    tracing_subscriber::filter::Directive, FileInfo, FileWorkerGuard, LayerInfo, LogCompression,
+    LogFormat, LogRotation, RethTracer, Tracer,
 };
-use std::{fmt, fmt::Display};
+#[cfg(feature = "otlp")]
+use reth_tracing::{OtlpConfig, OtlpProtocol, OtlpSampler};
+use humantime::parse_duration;
+use reth_chainspec::Chain;
+use std::{fmt, fmt::Display, path::PathBuf, time::Duration};
 use tracing::{level_filters::LevelFilter, Level};
 /// Constant to convert megabytes to bytes
 const MB_TO_BYTES: u64 = 1024 * 1024;
@@ -50,6 +54,23 @@ pub struct LogArgs {
     #[arg(long = "log.file.max-files", value_name = "COUNT", global = true, default_value_t = 5)]
     pub log_file_max_files: usize,
 
+    /// When log files are rotated, in addition to rotating them by size.
+    #[arg(long = "log.file.rotation", value_name = "ROTATION", global = true, default_value_t = LogRotation::Never)]
+    pub log_file_rotation: LogRotation,
+
+    /// The compression of rotated log files.
+    #[arg(long = "log.file.compression", value_name = "COMPRESSION", global = true, default_value_t = LogCompression::None)]
+    pub log_file_compression: LogCompression,
+
+    /// The maximum total size (in MB) of the rotated log files. The oldest files are removed
+    /// first.
+    #[arg(long = "log.file.max-total-size", value_name = "SIZE", global = true)]
+    pub log_file_max_total_size: Option<u64>,
+
+    /// The maximum age of rotated log files, e.g. `7d`. Older files are removed.
+    #[arg(long = "log.file.max-age", value_name = "DURATION", global = true, value_parser = parse_duration)]
+    pub log_file_max_age: Option<Duration>,
+
     /// Write logs to journald.
     #[arg(long = "log.journald", global = true)]
     pub journald: bool,
@@ -72,6 +93,53 @@ pub struct LogArgs {
         default_value_t = ColorMode::Always
     )]
     pub color: ColorMode,
+
+    /// A file with additional filter directives for all log outputs, in the same format as
+    /// `RUST_LOG`.
+    ///
+    /// The file is re-read when the node receives `SIGHUP`.
+    #[arg(long = "log.filter-file", value_name = "PATH", global = true)]
+    pub log_filter_file: Option<PathBuf>,
+
+    /// Export spans to an OpenTelemetry collector at the given endpoint.
+    ///
+    /// If no endpoint is given, the default endpoint of the protocol is used.
+    #[cfg(feature = "otlp")]
+    #[arg(long = "log.otlp", value_name = "URL", global = true, num_args = 0..=1, default_missing_value = "")]
+    pub otlp: Option<String>,
+
+    /// The protocol to use for exporting spans.
+    #[cfg(feature = "otlp")]
+    #[arg(long = "log.otlp.protocol", value_name = "PROTOCOL", global = true, default_value_t = OtlpProtocol::Grpc)]
+    pub otlp_protocol: OtlpProtocol,
+
+    /// The filter to use for spans exported to the collector.
+    #[cfg(feature = "otlp")]
+    #[arg(long = "log.otlp.filter", value_name = "FILTER", global = true, default_value = "info")]
+    pub otlp_filter: String,
+
+    /// The sampler that decides which traces are exported: `always_on`, `always_off`, or a
+    /// ratio between 0 and 1.
+    #[cfg(feature = "otlp")]
+    #[arg(long = "log.otlp.sampler", value_name = "SAMPLER", global = true, default_value_t = OtlpSampler::AlwaysOn)]
+    pub otlp_sampler: OtlpSampler,
+
+    /// The name of this instance, used to tell apart the spans of multiple nodes.
+    #[cfg(feature = "otlp")]
+    #[arg(long = "log.otlp.instance", value_name = "NAME", global = true)]
+    pub otlp_instance: Option<String>,
+
+    /// Additional resource attributes of the exported spans, as comma separated KEY=VALUE pairs.
+    #[cfg(feature = "otlp")]
+    #[arg(
+        long = "log.otlp.resource",
+        value_name = "KEY=VALUE",
+        global = true,
+        value_delimiter = ',',
+        value_parser = parse_resource_attribute
+    )]
+    pub otlp_resource: Vec<(String, String)>,
+
     /// The verbosity settings for the tracer.
     #[command(flatten)]
     pub verbosity: Verbosity,
@@ -88,19 +156,77 @@ impl LogArgs {
         )
     }
 
-    /// File info from the current log options.
-    fn file_info(&self) -> FileInfo {
-        FileInfo::new(
+    /// File info from the current log options, with the chain the logs are written for.
+    fn file_info(&self, chain: Option<Chain>) -> FileInfo {
+        let mut info = FileInfo::new(
             self.log_file_directory.clone().into(),
             self.log_file_max_size * MB_TO_BYTES,
             self.log_file_max_files,
         )
+        .with_rotation(self.log_file_rotation)
+        .with_compression(self.log_file_compression);
+        if let Some(max_total_size) = self.log_file_max_total_size {
+            info = info.with_max_total_bytes(max_total_size * MB_TO_BYTES);
+        }
+        if let Some(max_age) = self.log_file_max_age {
+            info = info.with_max_age(max_age);
+        }
+        if let Some(chain) = chain {
+            info = info.with_chain(chain.to_string());
+        }
+        info
+    }
+
+    /// OTLP exporter config from the current log options, if span export is enabled.
+    ///
+    /// The exported spans are tagged with the version of the client, the chain, the instance
+    /// name and the given resource attributes.
+    #[cfg(feature = "otlp")]
+    fn otlp_config(
+        &self,
+        chain: Option<Chain>,
+        resource: &[(String, String)],
+    ) -> Option<OtlpConfig> {
+        let endpoint = self.otlp.as_ref()?;
+
+        let mut config = OtlpConfig::new(self.otlp_protocol)
+            .with_sampler(self.otlp_sampler)
+            .with_resource_attribute("service.version", crate::version::SHORT_VERSION);
+        if !endpoint.is_empty() {
+            config = config.with_endpoint(endpoint.clone());
+        }
+        if let Some(chain) = chain {
+            config = config.with_resource_attribute("chain", chain.to_string());
+        }
+        if let Some(instance) = &self.otlp_instance {
+            config = config.with_resource_attribute("service.instance.id", instance.clone());
+        }
+        for (key, value) in resource.iter().chain(&self.otlp_resource) {
+            config = config.with_resource_attribute(key.clone(), value.clone());
+        }
+
+        Some(config)
     }
 
     /// Initializes tracing with the configured options from cli args.
     ///
+    /// The chain the command runs, if any, is included in the names of rotated log files and in
+    /// the resource attributes of exported spans.
+    ///
     /// Returns the file worker guard, and the file name, if a file worker was configured.
-    pub fn init_tracing(&self) -> eyre::Result<Option<FileWorkerGuard>> {
+    pub fn init_tracing(&self, chain: Option<Chain>) -> eyre::Result<Option<FileWorkerGuard>> {
+        self.init_tracing_with_resource(chain, &[])
+    }
+
+    /// Initializes tracing with the configured options from cli args, and tags exported spans
+    /// with the given additional resource attributes.
+    ///
+    /// Returns the file worker guard, and the file name, if a file worker was configured.
+    pub fn init_tracing_with_resource(
+        &self,
+        chain: Option<Chain>,
+        #[cfg_attr(not(feature = "otlp"), allow(unused_variables))] resource: &[(String, String)],
+    ) -> eyre::Result<Option<FileWorkerGuard>> {
         let mut tracer = RethTracer::new();
 
         let stdout = self.layer(self.log_stdout_format, self.log_stdout_filter.clone(), true);
@@ -111,16 +237,35 @@ impl LogArgs {
         }
 
         if self.log_file_max_files > 0 {
-            let info = self.file_info();
+            let info = self.file_info(chain);
             let file = self.layer(self.log_file_format, self.log_file_filter.clone(), false);
             tracer = tracer.with_file(file, info);
         }
 
+        #[cfg(feature = "otlp")]
+        if let Some(otlp) = self.otlp_config(chain, resource) {
+            let layer = self.layer(self.log_stdout_format, self.otlp_filter.clone(), false);
+            tracer = tracer.with_otlp(layer, otlp);
+        }
+
+        if let Some(path) = &self.log_filter_file {
+            tracer = tracer.with_filter_file(path.clone());
+        }
+
         let guard = tracer.init()?;
         Ok(guard)
     }
 }
 
+/// Parses a resource attribute of the exported spans from a `KEY=VALUE` pair.
+#[cfg(feature = "otlp")]
+fn parse_resource_attribute(s: &str) -> eyre::Result<(String, String)> {
+    let (key, value) = s
+        .split_once('=')
+        .ok_or_else(|| eyre::eyre!("invalid resource attribute {s:?}, expected KEY=VALUE"))?;
+    Ok((key.trim().to_string(), value.trim().to_string()))
+}
+
 /// The color mode for the cli.
 #[derive(Debug, Copy, Clone, ValueEnum, Eq, PartialEq)]
 pub enum ColorMode {
@@ -180,3 +325,35 @@ impl Verbosity {
         }
     }
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use clap::Parser;
+
+    /// A helper type to parse Args more easily
+    #[derive(Parser)]
+    struct CommandParser<T: Args> {
+        #[command(flatten)]
+        args: T,
+    }
+
+    #[cfg(feature = "otlp")]
+    #[test]
+    fn otlp_resource_includes_chain() {
+        let args = CommandParser::<LogArgs>::parse_from(["reth"]).args;
+        assert!(args.otlp_config(Some(Chain::holesky()), &[]).is_none());
+
+        let args = CommandParser::<LogArgs>::parse_from([
+            "reth",
+            "--log.otlp",
+            "--log.otlp.resource",
+            "region=eu",
+        ])
+        .args;
+        let config = args.otlp_config(Some(Chain::holesky()), &[]).unwrap();
+        let resource = config.resource_attributes();
+        assert!(resource.contains(&("chain".to_string(), "holesky".to_string())));
+        assert!(resource.contains(&("region".to_string(), "eu".to_string())));
+    }
+}
@@ -47,6 +47,20 @@ pub use txpool::TxPoolArgs;
 mod dev;
 pub use dev::DevArgs;
 
+/// ExExArgs for configuring the execution extensions
+mod exex;
+pub use exex::{
+    ExExArgs, ExExRestartPolicy, DEFAULT_EXEX_BACKFILL_BATCH_SIZE, DEFAULT_EXEX_MAX_RESTARTS,
+};
+
+/// TreeArgs for configuring the blockchain tree
+mod tree;
+pub use tree::TreeArgs;
+
+/// StaticFilesArgs for configuring the static files
+mod static_files;
+pub use static_files::StaticFilesArgs;
+
 /// PruneArgs for configuring the pruning and full node
 mod pruning;
 pub use pruning::PruningArgs;
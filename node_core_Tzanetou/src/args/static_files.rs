@@ -0,0 +1,152 @@
+//! clap [Args](clap::Args) for static files configuration
+
+use clap::{Args, ValueEnum};
+use reth_static_file_types::{Compression, StaticFileCompressionConfig, StaticFileSegment};
+
+/// Parameters for configuring the static files of the node
+#[derive(Debug, Clone, Default, Args, PartialEq, Eq)]
+#[command(next_help_heading = "Static Files")]
+pub struct StaticFilesArgs {
+    /// Compression of the static files of a segment created by the node, e.g.
+    /// `senders=zstd`. Can be repeated for several segments.
+    ///
+    /// The compression of the headers, transactions and receipts segments can't be changed.
+    /// Existing static files keep their compression.
+    #[arg(
+        long = "static-files.compression",
+        value_name = "SEGMENT=COMPRESSION",
+        value_parser = parse_segment_compression
+    )]
+    pub compression: Vec<(StaticFileSegment, Compression)>,
+
+    /// Move finalized blocks to static files in chunks, so that the static file producer doesn't
+    /// compete with the live sync for disk I/O.
+    #[arg(long = "static-files.throttle")]
+    pub throttle: bool,
+    /// Max number of blocks moved to static files per segment at once, when throttled.
+    #[arg(
+        long = "static-files.chunk-size",
+        value_parser = clap::value_parser!(u64).range(1..),
+        requires = "throttle"
+    )]
+    pub chunk_size: Option<u64>,
+    /// Max number of bytes written to static files per second, when throttled. Unlimited by
+    /// default.
+    #[arg(long = "static-files.io-budget", requires = "throttle")]
+    pub io_budget: Option<u64>,
+}
+
+impl StaticFilesArgs {
+    /// Returns the compression of the static files, per segment.
+    pub fn compression_config(&self) -> StaticFileCompressionConfig {
+        self.compression
+            .iter()
+            .fold(StaticFileCompressionConfig::default(), |config, &(segment, compression)| {
+                config.with_segment(segment, compression)
+            })
+    }
+}
+
+/// Parses a `SEGMENT=COMPRESSION` pair.
+fn parse_segment_compression(value: &str) -> Result<(StaticFileSegment, Compression), String> {
+    let (segment, compression) = value
+        .split_once('=')
+        .ok_or_else(|| format!("expected SEGMENT=COMPRESSION, got {value:?}"))?;
+    let segment = segment
+        .parse::<StaticFileSegment>()
+        .map_err(|_| format!("unknown static file segment {segment:?}"))?;
+    if matches!(
+        segment,
+        StaticFileSegment::Headers | StaticFileSegment::Transactions | StaticFileSegment::Receipts
+    ) {
+        return Err(format!(
+            "compression of the {} static files can't be configured",
+            segment.as_str()
+        ))
+    }
+    let compression = Compression::from_str(compression, true)?;
+    Ok((segment, compression))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use clap::Parser;
+
+    /// A helper type to parse Args more easily
+    #[derive(Parser)]
+    struct CommandParser<T: Args> {
+        #[command(flatten)]
+        args: T,
+    }
+
+    #[test]
+    fn test_parse_static_files_args() {
+        let args = CommandParser::<StaticFilesArgs>::parse_from(["reth"]).args;
+        assert_eq!(args, StaticFilesArgs::default());
+        assert_eq!(args.compression_config(), StaticFileCompressionConfig::default());
+
+        let args = CommandParser::<StaticFilesArgs>::parse_from([
+            "reth",
+            "--static-files.compression",
+            "senders=zstd",
+            "--static-files.compression",
+            "block-bodies=lz4",
+            "--static-files.compression",
+            "account-changesets=zstd-dict",
+        ])
+        .args;
+        let config = args.compression_config();
+        assert_eq!(config.compression(StaticFileSegment::Senders), Compression::Zstd);
+        assert_eq!(config.compression(StaticFileSegment::BlockBodies), Compression::Lz4);
+        assert_eq!(
+            config.compression(StaticFileSegment::AccountChangeSets),
+            Compression::ZstdWithDictionary
+        );
+        assert_eq!(
+            config.compression(StaticFileSegment::Headers),
+            StaticFileSegment::Headers.config().compression
+        );
+
+        for invalid in [
+            "senders",
+            "unknown=zstd",
+            "senders=unknown",
+            "senders=zstd-with-dictionary",
+            "headers=zstd",
+            "transactions=lz4",
+            "receipts=uncompressed",
+        ] {
+            assert!(CommandParser::<StaticFilesArgs>::try_parse_from([
+                "reth",
+                "--static-files.compression",
+                invalid,
+            ])
+            .is_err());
+        }
+    }
+
+    #[test]
+    fn test_parse_static_files_throttle_args() {
+        let args = CommandParser::<StaticFilesArgs>::parse_from([
+            "reth",
+            "--static-files.throttle",
+            "--static-files.chunk-size",
+            "1000",
+            "--static-files.io-budget",
+            "1000000",
+        ])
+        .args;
+        assert!(args.throttle);
+        assert_eq!(args.chunk_size, Some(1000));
+        assert_eq!(args.io_budget, Some(1_000_000));
+
+        for invalid in [
+            ["reth", "--static-files.chunk-size", "1000"],
+            ["reth", "--static-files.io-budget", "1000000"],
+            ["reth", "--static-files.throttle", "--static-files.chunk-size=0"],
+        ] {
+            assert!(CommandParser::<StaticFilesArgs>::try_parse_from(invalid).is_err());
+        }
+    }
+}
@@ -0,0 +1,35 @@
+//! clap [Args](clap::Args) for blockchain tree configuration
+
+use clap::Args;
+
+/// Parameters for configuring the blockchain tree of the node
+#[derive(Debug, Clone, Copy, Default, Args, PartialEq, Eq)]
+#[command(next_help_heading = "Blockchain tree")]
+pub struct TreeArgs {
+    /// Persist the sidechains and buffered blocks of the blockchain tree to a journal in the data
+    /// directory, so that they are restored after a restart or a crash.
+    #[arg(long = "tree.journal")]
+    pub journal: bool,
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use clap::Parser;
+
+    /// A helper type to parse Args more easily
+    #[derive(Parser)]
+    struct CommandParser<T: Args> {
+        #[command(flatten)]
+        args: T,
+    }
+
+    #[test]
+    fn test_parse_tree_args() {
+        let args = CommandParser::<TreeArgs>::parse_from(["reth"]).args;
+        assert_eq!(args, TreeArgs::default());
+
+        let args = CommandParser::<TreeArgs>::parse_from(["reth", "--tree.journal"]).args;
+        assert!(args.journal);
+    }
+}
@@ -9,14 +9,19 @@
 //! transaction pool module (`reth_transaction_pool`). This allows configuring sub-pool
 //! limits, blob limits, local transaction handling, and price bump configurations.
 use crate::cli::config::RethTransactionPoolConfig;
-use clap::Args;
+use clap::{Args, ValueEnum};
+use humantime::parse_duration;
 use reth_primitives::Address;
 use reth_transaction_pool::{
-    blobstore::disk::DEFAULT_MAX_CACHED_BLOBS, validate::DEFAULT_MAX_TX_INPUT_BYTES,
-    LocalTransactionConfig, PoolConfig, PriceBumpConfig, SubPoolLimit, DEFAULT_PRICE_BUMP,
-    REPLACE_BLOB_PRICE_BUMP, TXPOOL_MAX_ACCOUNT_SLOTS_PER_SENDER,
+    admission::{AdmissionPolicies, SenderRateLimit},
+    blobstore::disk::DEFAULT_MAX_CACHED_BLOBS,
+    validate::DEFAULT_MAX_TX_INPUT_BYTES,
+    LocalTransactionConfig, PoolConfig, PriceBumpConfig, SubPoolLimit, TransactionOrderingKind,
+    DEFAULT_LOCAL_BOOST, DEFAULT_PRICE_BUMP, REPLACE_BLOB_PRICE_BUMP,
+    TXPOOL_AUDIT_LOG_MAX_ENTRIES_DEFAULT, TXPOOL_MAX_ACCOUNT_SLOTS_PER_SENDER,
     TXPOOL_SUBPOOL_MAX_SIZE_MB_DEFAULT, TXPOOL_SUBPOOL_MAX_TXS_DEFAULT,
 };
+use std::time::Duration;
 /// Parameters for debugging purposes
 #[derive(Debug, Clone, Args, PartialEq, Eq)]
 #[command(next_help_heading = "TxPool")]
@@ -71,6 +76,80 @@ pub struct TxPoolArgs {
     /// Flag to toggle local transaction propagation.
     #[arg(long = "txpool.no-local-transactions-propagation")]
     pub no_local_transactions_propagation: bool,
+
+    /// Persists the transactions of all origins and sub-pools across restarts, instead of only
+    /// the local transactions.
+    #[arg(long = "txpool.snapshot")]
+    pub snapshot: bool,
+    /// Interval at which the transaction pool snapshot is written while the node is running, in
+    /// addition to shutdown.
+    ///
+    /// Parses strings using [`humantime::parse_duration`]
+    /// --txpool.snapshot-interval 30s
+    #[arg(
+        long = "txpool.snapshot-interval",
+        requires = "snapshot",
+        value_parser = parse_duration,
+        verbatim_doc_comment
+    )]
+    pub snapshot_interval: Option<Duration>,
+
+    /// The ordering of the pending transactions.
+    #[arg(long = "txpool.ordering", value_enum, default_value_t = TxPoolOrdering::CoinbaseTip)]
+    pub ordering: TxPoolOrdering,
+    /// Boost (in %) of the tip of local transactions with the `local-boost` ordering.
+    #[arg(long = "txpool.local-boost", default_value_t = DEFAULT_LOCAL_BOOST)]
+    pub local_boost: u64,
+
+    /// Max number of removed transactions kept in the audit log, `0` disables the log.
+    #[arg(long = "txpool.audit-log-max-entries", default_value_t = TXPOOL_AUDIT_LOG_MAX_ENTRIES_DEFAULT)]
+    pub audit_log_max_entries: u32,
+
+    /// Max number of transactions per second each sender can add to the pool.
+    ///
+    /// Local transactions are not limited.
+    #[arg(long = "txpool.sender-rate-limit", value_parser = clap::value_parser!(u32).range(1..))]
+    pub sender_rate_limit: Option<u32>,
+    /// Max number of transactions a sender can add to the pool at once, before it is limited to
+    /// `--txpool.sender-rate-limit`. Defaults to the rate limit.
+    #[arg(long = "txpool.sender-rate-burst", requires = "sender_rate_limit")]
+    pub sender_rate_burst: Option<u32>,
+}
+
+impl TxPoolArgs {
+    /// Returns the configured admission policies of the pool.
+    pub fn admission_policies(&self) -> AdmissionPolicies {
+        let mut policies = AdmissionPolicies::new();
+        if let Some(rate_limit) = self.sender_rate_limit {
+            let burst = self.sender_rate_burst.unwrap_or(rate_limit);
+            policies = policies.with_policy(SenderRateLimit::new(rate_limit as f64, burst));
+        }
+        policies
+    }
+
+    /// Returns the configured ordering of the pending transactions.
+    pub const fn ordering_kind(&self) -> TransactionOrderingKind {
+        match self.ordering {
+            TxPoolOrdering::CoinbaseTip => TransactionOrderingKind::CoinbaseTip,
+            TxPoolOrdering::Fifo => TransactionOrderingKind::Fifo,
+            TxPoolOrdering::TipThenArrival => TransactionOrderingKind::TipThenArrival,
+            TxPoolOrdering::LocalBoost => TransactionOrderingKind::LocalBoost(self.local_boost),
+        }
+    }
+}
+
+/// The ordering of the pending transactions of the pool.
+#[derive(Debug, Copy, Clone, Default, ValueEnum, Eq, PartialEq)]
+pub enum TxPoolOrdering {
+    /// Order by coinbase tip
+    #[default]
+    CoinbaseTip,
+    /// Order by the time the transactions were added to the pool
+    Fifo,
+    /// Order by coinbase tip, and transactions with the same tip by the time they were added
+    TipThenArrival,
+    /// Order by coinbase tip, boosting the tip of local transactions
+    LocalBoost,
 }
 
 impl Default for TxPoolArgs {
@@ -90,6 +169,13 @@ impl Default for TxPoolArgs {
             no_locals: false,
             locals: Default::default(),
             no_local_transactions_propagation: false,
+            snapshot: false,
+            snapshot_interval: None,
+            ordering: TxPoolOrdering::default(),
+            local_boost: DEFAULT_LOCAL_BOOST,
+            audit_log_max_entries: TXPOOL_AUDIT_LOG_MAX_ENTRIES_DEFAULT,
+            sender_rate_limit: None,
+            sender_rate_burst: None,
         }
     }
 }
@@ -124,6 +210,8 @@ impl RethTransactionPoolConfig for TxPoolArgs {
                 default_price_bump: self.price_bump,
                 replace_blob_tx_price_bump: self.blob_transaction_price_bump,
             },
+            admission_policies: self.admission_policies(),
+            audit_log_max_entries: self.audit_log_max_entries,
         }
     }
 }
@@ -146,4 +234,72 @@ mod tests {
         let args = CommandParser::<TxPoolArgs>::parse_from(["reth"]).args;
         assert_eq!(args, default_args);
     }
+
+    #[test]
+    fn txpool_args_snapshot() {
+        let args = CommandParser::<TxPoolArgs>::parse_from([
+            "reth",
+            "--txpool.snapshot",
+            "--txpool.snapshot-interval",
+            "30s",
+        ])
+        .args;
+        assert!(args.snapshot);
+        assert_eq!(args.snapshot_interval, Some(Duration::from_secs(30)));
+
+        // the interval requires snapshots to be enabled
+        assert!(CommandParser::<TxPoolArgs>::try_parse_from([
+            "reth",
+            "--txpool.snapshot-interval",
+            "30s"
+        ])
+        .is_err());
+    }
+
+    #[test]
+    fn txpool_args_ordering() {
+        let args =
+            CommandParser::<TxPoolArgs>::parse_from(["reth", "--txpool.ordering", "fifo"]).args;
+        assert_eq!(args.ordering_kind(), TransactionOrderingKind::Fifo);
+
+        let args = CommandParser::<TxPoolArgs>::parse_from([
+            "reth",
+            "--txpool.ordering",
+            "local-boost",
+            "--txpool.local-boost",
+            "50",
+        ])
+        .args;
+        assert_eq!(args.ordering_kind(), TransactionOrderingKind::LocalBoost(50));
+    }
+    #[test]
+    fn txpool_args_sender_rate_limit() {
+        let args = CommandParser::<TxPoolArgs>::parse_from([
+            "reth",
+            "--txpool.sender-rate-limit",
+            "10",
+            "--txpool.sender-rate-burst",
+            "20",
+        ])
+        .args;
+        assert_eq!(args.sender_rate_limit, Some(10));
+        assert_eq!(args.sender_rate_burst, Some(20));
+        assert!(!args.admission_policies().is_empty());
+        assert!(TxPoolArgs::default().admission_policies().is_empty());
+
+        // the rate must be positive
+        assert!(CommandParser::<TxPoolArgs>::try_parse_from([
+            "reth",
+            "--txpool.sender-rate-limit",
+            "0"
+        ])
+        .is_err());
+        // the burst requires a rate limit
+        assert!(CommandParser::<TxPoolArgs>::try_parse_from([
+            "reth",
+            "--txpool.sender-rate-burst",
+            "20"
+        ])
+        .is_err());
+    }
 }
@@ -327,6 +327,22 @@ impl<D> ChainPath<D> {
         self.data_dir().join("blobstore")
     }
 
+    /// Returns the path to the write-ahead log directory of the execution extensions for this
+    /// chain.
+    ///
+    /// `<DIR>/<CHAIN_ID>/exex/wal`
+    pub fn exex_wal(&self) -> PathBuf {
+        self.data_dir().join("exex").join("wal")
+    }
+
+    /// Returns the path to the journal of the sidechains and buffered blocks of the blockchain
+    /// tree for this chain.
+    ///
+    /// `<DIR>/<CHAIN_ID>/blockchain-tree-journal`
+    pub fn blockchain_tree_journal(&self) -> PathBuf {
+        self.data_dir().join("blockchain-tree-journal")
+    }
+
     /// Returns the path to the local transactions backup file
     ///
     /// `<DIR>/<CHAIN_ID>/txpool-transactions-backup.rlp`
@@ -334,6 +350,13 @@ impl<D> ChainPath<D> {
         self.data_dir().join("txpool-transactions-backup.rlp")
     }
 
+    /// Returns the path to the transaction pool snapshot file
+    ///
+    /// `<DIR>/<CHAIN_ID>/txpool-snapshot.bin`
+    pub fn txpool_snapshot(&self) -> PathBuf {
+        self.data_dir().join("txpool-snapshot.bin")
+    }
+
     /// Returns the path to the config file for this chain.
     ///
     /// `<DIR>/<CHAIN_ID>/reth.toml`
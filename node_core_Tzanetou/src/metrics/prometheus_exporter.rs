@@ -9,10 +9,13 @@ use eyre::WrapErr;
 use futures::{future::FusedFuture, FutureExt};
 use http::Response;
 use metrics::describe_gauge;
-use metrics_exporter_prometheus::{PrometheusBuilder, PrometheusHandle};
+use metrics_exporter_prometheus::{Matcher, PrometheusBuilder, PrometheusHandle};
 use metrics_util::layers::{PrefixLayer, Stack};
 use reth_db_api::database_metrics::DatabaseMetrics;
-use reth_metrics::metrics::Unit;
+use reth_metrics::{
+    histogram::{histogram_configs, HistogramConfig},
+    metrics::Unit,
+};
 use reth_provider::providers::StaticFileProvider;
 use reth_tasks::TaskExecutor;
 use std::{convert::Infallible, net::SocketAddr, sync::Arc};
@@ -20,20 +23,60 @@ use std::{convert::Infallible, net::SocketAddr, sync::Arc};
 pub(crate) trait Hook: Fn() + Send + Sync {}
 impl<T: Fn() + Send + Sync> Hook for T {}
 
+/// Prefix of the names of all metrics.
+const METRICS_PREFIX: &str = "reth";
+
+/// Default quantiles of histograms that are rendered as summaries.
+const DEFAULT_QUANTILES: [f64; 7] = [0.0, 0.5, 0.9, 0.95, 0.99, 0.999, 1.0];
+
 /// Installs Prometheus as the metrics recorder.
+///
+/// Histograms declared with `buckets` are rendered as Prometheus histograms with these buckets.
+/// All other histograms are rendered as summaries, see [`prometheus_builder`].
 pub fn install_recorder() -> eyre::Result<PrometheusHandle> {
-    let recorder = PrometheusBuilder::new().build_recorder();
+    let recorder = prometheus_builder(histogram_configs())?.build_recorder();
     let handle = recorder.handle();
 
     // Build metrics stack
     Stack::new(recorder)
-        .push(PrefixLayer::new("reth"))
+        .push(PrefixLayer::new(METRICS_PREFIX))
         .install()
         .wrap_err("Couldn't set metrics recorder.")?;
 
     Ok(handle)
 }
 
+/// Creates a [`PrometheusBuilder`] with the distributions of the given histograms.
+///
+/// The exporter only supports quantiles for all summaries at once, so summaries track the default
+/// quantiles and the quantiles declared by any histogram.
+fn prometheus_builder<'a>(
+    histograms: impl IntoIterator<Item = &'a HistogramConfig>,
+) -> eyre::Result<PrometheusBuilder> {
+    let mut builder = PrometheusBuilder::new();
+    let mut quantiles = DEFAULT_QUANTILES.to_vec();
+    for histogram in histograms {
+        if !histogram.buckets.is_empty() {
+            let matcher =
+                Matcher::Full(prometheus_name(&format!("{METRICS_PREFIX}.{}", histogram.name)));
+            builder = builder.set_buckets_for_metric(matcher, histogram.buckets)?;
+        }
+        quantiles.extend_from_slice(histogram.quantiles);
+    }
+
+    quantiles.sort_by(f64::total_cmp);
+    quantiles.dedup();
+    Ok(builder.set_quantiles(&quantiles)?)
+}
+
+/// Returns the name of a metric as rendered by the exporter, which replaces all characters that
+/// are not allowed in Prometheus metric names with `_`. Histograms are matched by this name.
+fn prometheus_name(name: &str) -> String {
+    name.chars()
+        .map(|c| if c.is_ascii_alphanumeric() || c == '_' || c == ':' { c } else { '_' })
+        .collect()
+}
+
 /// Serves Prometheus metrics over HTTP with hooks.
 ///
 /// The hooks are called every time the metrics are requested at the given endpoint, and can be used
@@ -296,7 +339,9 @@ const fn describe_io_stats() {}
 
 #[cfg(test)]
 mod tests {
+    use super::*;
     use crate::node_config::PROMETHEUS_RECORDER_HANDLE;
+    use metrics_util::layers::Layer;
 
     // Dependencies using different version of the `metrics` crate (to be exact, 0.21 vs 0.22)
     // may not be able to communicate with each other through the global recorder.
@@ -315,4 +360,31 @@ mod tests {
         let metrics = PROMETHEUS_RECORDER_HANDLE.render();
         assert!(metrics.contains("process_cpu_seconds_total"), "{metrics:?}");
     }
+
+    #[test]
+    fn histogram_buckets() {
+        let histograms = [
+            HistogramConfig {
+                name: "sync.execution.duration",
+                buckets: &[0.0001, 0.001],
+                quantiles: &[],
+            },
+            HistogramConfig { name: "txpool.size", buckets: &[], quantiles: &[0.75] },
+        ];
+        let recorder = prometheus_builder(&histograms).unwrap().build_recorder();
+        let handle = recorder.handle();
+        let recorder = PrefixLayer::new(METRICS_PREFIX).layer(recorder);
+
+        metrics::with_local_recorder(&recorder, || {
+            metrics::histogram!("sync.execution.duration").record(0.00005);
+            metrics::histogram!("txpool.size").record(10.0);
+        });
+
+        let metrics = handle.render();
+        assert!(
+            metrics.contains("reth_sync_execution_duration_bucket{le=\"0.0001\"} 1"),
+            "{metrics:?}"
+        );
+        assert!(metrics.contains("reth_txpool_size{quantile=\"0.75\"}"), "{metrics:?}");
+    }
 }
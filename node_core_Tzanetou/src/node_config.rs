@@ -27,8 +27,8 @@
 
 use crate::{
     args::{
-        DatabaseArgs, DatadirArgs, DebugArgs, DevArgs, NetworkArgs, PayloadBuilderArgs,
-        PruningArgs, RpcServerArgs, TxPoolArgs,
+        DatabaseArgs, DatadirArgs, DebugArgs, DevArgs, ExExArgs, NetworkArgs, PayloadBuilderArgs,
+        PruningArgs, RpcServerArgs, StaticFilesArgs, TreeArgs, TxPoolArgs,
     },
     dirs::{ChainPath, DataDirPath},
     metrics::prometheus_exporter,
@@ -170,6 +170,15 @@ pub struct NodeConfig {
 
     /// All pruning related arguments
     pub pruning: PruningArgs,
+
+    /// All `ExEx` related arguments with --exex prefix
+    pub exex: ExExArgs,
+
+    /// All blockchain tree related arguments with --tree prefix
+    pub tree: TreeArgs,
+
+    /// All static files related arguments with --static-files prefix
+    pub static_files: StaticFilesArgs,
 }
 
 impl NodeConfig {
@@ -277,6 +286,24 @@ impl NodeConfig {
         self
     }
 
+    /// Set the `ExEx` args for the node
+    pub const fn with_exex(mut self, exex: ExExArgs) -> Self {
+        self.exex = exex;
+        self
+    }
+
+    /// Set the blockchain tree args for the node
+    pub const fn with_tree(mut self, tree: TreeArgs) -> Self {
+        self.tree = tree;
+        self
+    }
+
+    /// Set the static files args for the node
+    pub fn with_static_files(mut self, static_files: StaticFilesArgs) -> Self {
+        self.static_files = static_files;
+        self
+    }
+
     /// Returns pruning configuration.
     pub fn prune_config(&self) -> Option<PruneConfig> {
         self.pruning.prune_config(&self.chain)
@@ -460,6 +487,9 @@ impl Default for NodeConfig {
             db: DatabaseArgs::default(),
             dev: DevArgs::default(),
             pruning: PruningArgs::default(),
+            exex: ExExArgs::default(),
+            tree: TreeArgs::default(),
+            static_files: StaticFilesArgs::default(),
             datadir: DatadirArgs::default(),
         }
     }
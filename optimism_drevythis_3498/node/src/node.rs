@@ -22,8 +22,8 @@ use reth_payload_builder::{PayloadBuilderHandle, PayloadBuilderService};
 use reth_provider::CanonStateSubscriptions;
 use reth_tracing::tracing::{debug, info};
 use reth_transaction_pool::{
-    blobstore::DiskFileBlobStore, CoinbaseTipOrdering, TransactionPool,
-    TransactionValidationTaskExecutor,
+    blobstore::{OpenSegmentBlobStore, SegmentBlobStore, SegmentBlobStoreConfig},
+    ConfiguredOrdering, TransactionPool, TransactionValidationTaskExecutor,
 };
 use std::sync::Arc;
 
@@ -134,11 +134,17 @@ impl<Node> PoolBuilder<Node> for OptimismPoolBuilder
 where
     Node: FullNodeTypes,
 {
-    type Pool = OpTransactionPool<Node::Provider, DiskFileBlobStore>;
+    type Pool = OpTransactionPool<Node::Provider, SegmentBlobStore>;
 
     async fn build_pool(self, ctx: &BuilderContext<Node>) -> eyre::Result<Self::Pool> {
         let data_dir = ctx.config().datadir();
-        let blob_store = DiskFileBlobStore::open(data_dir.blobstore(), Default::default())?;
+        let blob_store = SegmentBlobStore::open(
+            data_dir.blobstore(),
+            SegmentBlobStoreConfig::default()
+                .with_max_cached_entries(ctx.config().txpool.max_cached_entries)
+                // keep the blobs across restarts, and import the ones of the previous blob store
+                .with_open(OpenSegmentBlobStore::Migrate),
+        )?;
         
         // Configure transaction validation tasks for the transaction pool
         let validator = TransactionValidationTaskExecutor::eth_builder(ctx.chain_spec())
@@ -155,7 +161,7 @@ where
         // Create the transaction pool with configured validator and settings
         let transaction_pool = reth_transaction_pool::Pool::new(
             validator,
-            CoinbaseTipOrdering::default(),
+            ConfiguredOrdering::new(ctx.config().txpool.ordering_kind()),
             blob_store,
             ctx.pool_config(),
         );
@@ -170,20 +176,44 @@ where
             let pool = transaction_pool.clone();
             let chain_events = ctx.provider().canonical_state_stream();
             let client = ctx.provider().clone();
-            let transactions_backup_config =
-                reth_transaction_pool::maintain::LocalTransactionBackupConfig::with_local_txs_backup(transactions_path);
+            let txpool_args = &ctx.config().txpool;
 
             // Spawn tasks for maintaining transaction pool integrity
-            ctx.task_executor().spawn_critical_with_graceful_shutdown_signal(
-                "local transactions backup task",
-                |shutdown| {
-                    reth_transaction_pool::maintain::backup_local_transactions_task(
-                        shutdown,
-                        pool.clone(),
-                        transactions_backup_config,
+            if txpool_args.snapshot {
+                let mut snapshot_config =
+                    reth_transaction_pool::maintain::TransactionPoolSnapshotConfig::new(
+                        data_dir.txpool_snapshot(),
                     )
-                },
-            );
+                    .with_legacy_transactions_path(transactions_path);
+                if let Some(interval) = txpool_args.snapshot_interval {
+                    snapshot_config = snapshot_config.with_interval(interval);
+                }
+
+                ctx.task_executor().spawn_critical_with_graceful_shutdown_signal(
+                    "transaction pool snapshot task",
+                    |shutdown| {
+                        reth_transaction_pool::maintain::pool_snapshot_task(
+                            shutdown,
+                            pool.clone(),
+                            snapshot_config,
+                        )
+                    },
+                );
+            } else {
+                let transactions_backup_config =
+                    reth_transaction_pool::maintain::LocalTransactionBackupConfig::with_local_txs_backup(transactions_path);
+
+                ctx.task_executor().spawn_critical_with_graceful_shutdown_signal(
+                    "local transactions backup task",
+                    |shutdown| {
+                        reth_transaction_pool::maintain::backup_local_transactions_task(
+                            shutdown,
+                            pool.clone(),
+                            transactions_backup_config,
+                        )
+                    },
+                );
+            }
 
             // spawn the maintenance task
             ctx.task_executor().spawn_critical(
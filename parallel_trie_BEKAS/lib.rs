@@ -13,14 +13,26 @@ pub use storage_root_targets::StorageRootTargets;
 /// Parallel trie calculation stats.
 pub mod stats;
 
+/// In-memory cache of the trie nodes of blocks that are not persisted yet.
+pub mod sparse_cache;
+pub use sparse_cache::SparseTrieCache;
+
 /// Implementation of async state root computation.
 #[cfg(feature = "async")]
 pub mod async_root;
 
+/// Implementation of streaming state root computation.
+#[cfg(feature = "async")]
+pub mod streaming_root;
+
 /// Implementation of parallel state root computation.
 #[cfg(feature = "parallel")]
 pub mod parallel_root;
 
+/// Implementation of parallel proof generation.
+#[cfg(feature = "parallel")]
+pub mod parallel_proof;
+
 /// Parallel state root metrics.
 #[cfg(feature = "metrics")]
 pub mod metrics;
\ No newline at end of file
@@ -37,8 +37,17 @@ impl ParallelStateRootMetrics {
         self.state_trie.record(stats.trie_stats());
         // Record the number of precomputed storage roots
         self.parallel.precomputed_storage_roots.record(stats.precomputed_storage_roots() as f64);
+        // Record the number of storage roots computed again by the streaming calculator
+        self.parallel.recomputed_storage_roots.record(stats.recomputed_storage_roots() as f64);
         // Record the number of missed leaves
         self.parallel.missed_leaves.record(stats.missed_leaves() as f64);
+        // Record the sparse trie cache lookups, only if a cache was used
+        if let Some(cache_hits) = stats.cache_hits() {
+            self.parallel.cache_hits.record(cache_hits as f64);
+        }
+        if let Some(cache_misses) = stats.cache_misses() {
+            self.parallel.cache_misses.record(cache_misses as f64);
+        }
     }
 }
 
@@ -48,6 +57,12 @@ impl ParallelStateRootMetrics {
 pub struct ParallelTrieMetrics {
     /// Histogram for the number of storage roots computed in parallel.
     pub precomputed_storage_roots: Histogram,
+    /// Histogram for the number of storage roots computed again after further storage changes.
+    pub recomputed_storage_roots: Histogram,
     /// Histogram for the number of leaves that missed pre-computation of storage roots.
     pub missed_leaves: Histogram,
+    /// Histogram for the number of trie node lookups served from the sparse trie cache.
+    pub cache_hits: Histogram,
+    /// Histogram for the number of trie node lookups that missed the sparse trie cache.
+    pub cache_misses: Histogram,
 }
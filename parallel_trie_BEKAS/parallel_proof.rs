@@ -0,0 +1,418 @@
+use crate::{
+    parallel_root::ParallelStateRootError, stats::ParallelTrieTracker,
+    storage_root_targets::StorageRootTargets,
+};
+use alloy_rlp::{BufMut, Encodable};
+use rayon::prelude::*;
+use reth_db_api::database::Database;
+use reth_execution_errors::StorageRootError;
+use reth_primitives::{constants::EMPTY_ROOT_HASH, keccak256, Address, B256};
+use reth_provider::{providers::ConsistentDbView, DatabaseProviderFactory, ProviderError};
+use reth_trie::{
+    hashed_cursor::{HashedCursorFactory, HashedPostStateCursorFactory, HashedStorageCursor},
+    node_iter::{TrieElement, TrieNodeIter},
+    prefix_set::PrefixSet,
+    trie_cursor::TrieCursorFactory,
+    walker::TrieWalker,
+    HashBuilder, HashedPostState, Nibbles, StorageRoot, TrieAccount,
+};
+use reth_trie_common::{proof::ProofRetainer, AccountProof, StorageProof};
+use std::collections::HashMap;
+use tracing::*;
+
+#[cfg(feature = "metrics")]
+use crate::metrics::ParallelStateRootMetrics;
+
+/// Parallel proof generator.
+///
+/// The generator starts off by computing the storage roots and storage proofs of the target
+/// accounts, as well as the storage roots of accounts changed by the overlay, in parallel.
+/// Once that's done, it walks the state trie once, retaining the proof nodes of all target
+/// accounts.
+///
+/// Internally, the generator uses [`ConsistentDbView`] since
+/// it needs to rely on database state saying the same until
+/// the last transaction is open.
+/// See docs of using [`ConsistentDbView`] for caveats.
+#[derive(Debug)]
+pub struct ParallelProof<DB, Provider> {
+    /// Consistent view of the database.
+    view: ConsistentDbView<DB, Provider>,
+    /// Hashed state of non-persisted blocks to generate the proofs on top of.
+    hashed_state: HashedPostState,
+    /// Parallel state root metrics.
+    #[cfg(feature = "metrics")]
+    metrics: ParallelStateRootMetrics,
+}
+
+impl<DB, Provider> ParallelProof<DB, Provider> {
+    /// Create new parallel proof generator for the state of the database.
+    pub fn new(view: ConsistentDbView<DB, Provider>) -> Self {
+        Self {
+            view,
+            hashed_state: HashedPostState::default(),
+            #[cfg(feature = "metrics")]
+            metrics: ParallelStateRootMetrics::default(),
+        }
+    }
+
+    /// Set the hashed state of non-persisted blocks the proofs are generated on top of.
+    pub fn with_hashed_state(mut self, hashed_state: HashedPostState) -> Self {
+        self.hashed_state = hashed_state;
+        self
+    }
+}
+
+impl<DB, Provider> ParallelProof<DB, Provider>
+where
+    DB: Database,
+    Provider: DatabaseProviderFactory<DB> + Send + Sync,
+{
+    /// Generate the account and storage proofs of the given accounts and storage slots in
+    /// parallel.
+    ///
+    /// Accounts that do not exist have no account info, but still contain proofs of their
+    /// requested storage slots.
+    pub fn multiproof(
+        self,
+        targets: HashMap<Address, Vec<B256>>,
+    ) -> Result<HashMap<Address, AccountProof>, ParallelStateRootError> {
+        let mut tracker = ParallelTrieTracker::default();
+        let targets = targets
+            .into_iter()
+            .map(|(address, slots)| (keccak256(address), (address, slots)))
+            .collect::<HashMap<_, _>>();
+
+        // Extend the prefix sets with the targets, so that their paths are walked
+        let mut prefix_sets = self.hashed_state.construct_prefix_sets();
+        for (hashed_address, (_, slots)) in &targets {
+            prefix_sets.account_prefix_set.insert(Nibbles::unpack(hashed_address));
+            let storage_prefix_set =
+                prefix_sets.storage_prefix_sets.entry(*hashed_address).or_default();
+            for slot in slots {
+                storage_prefix_set.insert(Nibbles::unpack(keccak256(slot)));
+            }
+        }
+        let prefix_sets = prefix_sets.freeze();
+
+        let storage_root_targets = StorageRootTargets::new(
+            self.hashed_state.accounts.keys().chain(targets.keys()).copied(),
+            prefix_sets.storage_prefix_sets,
+        );
+        let hashed_state_sorted = self.hashed_state.into_sorted();
+
+        tracker.set_precomputed_storage_roots(storage_root_targets.len() as u64);
+        debug!(target: "trie::parallel_proof", len = storage_root_targets.len(), "pre-calculating storage roots and proofs");
+
+        let mut storage_roots = storage_root_targets
+            .into_par_iter()
+            .map(|(hashed_address, prefix_set)| {
+                let provider_ro = self.view.provider_ro()?;
+                let trie_cursor_factory = provider_ro.tx_ref();
+                let hashed_cursor_factory =
+                    HashedPostStateCursorFactory::new(provider_ro.tx_ref(), &hashed_state_sorted);
+                let result = match targets.get(&hashed_address) {
+                    Some((_, slots)) => storage_root_with_proofs(
+                        trie_cursor_factory,
+                        hashed_cursor_factory,
+                        hashed_address,
+                        prefix_set,
+                        slots,
+                    )?,
+                    None => {
+                        let (storage_root, _, _) = StorageRoot::new_hashed(
+                            trie_cursor_factory,
+                            hashed_cursor_factory,
+                            hashed_address,
+                            #[cfg(feature = "metrics")]
+                            self.metrics.storage_trie.clone(),
+                        )
+                        .with_prefix_set(prefix_set)
+                        .calculate(false)?;
+                        (storage_root, Vec::new())
+                    }
+                };
+                Ok((hashed_address, result))
+            })
+            .collect::<Result<HashMap<_, _>, ParallelStateRootError>>()?;
+
+        trace!(target: "trie::parallel_proof", "generating account proofs");
+        let provider_ro = self.view.provider_ro()?;
+        let hashed_cursor_factory =
+            HashedPostStateCursorFactory::new(provider_ro.tx_ref(), &hashed_state_sorted);
+        let trie_cursor_factory = provider_ro.tx_ref();
+
+        let walker = TrieWalker::new(
+            trie_cursor_factory.account_trie_cursor().map_err(ProviderError::Database)?,
+            prefix_sets.account_prefix_set,
+        );
+        let mut account_node_iter = TrieNodeIter::new(
+            walker,
+            hashed_cursor_factory.hashed_account_cursor().map_err(ProviderError::Database)?,
+        );
+
+        let retainer = ProofRetainer::from_iter(targets.keys().map(Nibbles::unpack));
+        let mut hash_builder = HashBuilder::default().with_proof_retainer(retainer);
+        let mut account_proofs = targets
+            .iter()
+            .map(|(hashed_address, (address, _))| (*hashed_address, AccountProof::new(*address)))
+            .collect::<HashMap<_, _>>();
+        let mut account_rlp = Vec::with_capacity(128);
+
+        while let Some(node) = account_node_iter.try_next().map_err(ProviderError::Database)? {
+            match node {
+                TrieElement::Branch(node) => {
+                    hash_builder.add_branch(node.key, node.value, node.children_are_in_trie);
+                }
+                TrieElement::Leaf(hashed_address, account) => {
+                    let (storage_root, storage_proofs) = match storage_roots.remove(&hashed_address)
+                    {
+                        Some(result) => result,
+                        None => {
+                            tracker.inc_missed_leaves();
+                            let (storage_root, _, _) = StorageRoot::new_hashed(
+                                trie_cursor_factory,
+                                hashed_cursor_factory.clone(),
+                                hashed_address,
+                                #[cfg(feature = "metrics")]
+                                self.metrics.storage_trie.clone(),
+                            )
+                            .calculate(false)?;
+                            (storage_root, Vec::new())
+                        }
+                    };
+
+                    if let Some(account_proof) = account_proofs.get_mut(&hashed_address) {
+                        account_proof.set_account(account, storage_root, storage_proofs);
+                    }
+
+                    account_rlp.clear();
+                    let account = TrieAccount::from((account, storage_root));
+                    account.encode(&mut account_rlp as &mut dyn BufMut);
+                    hash_builder.add_leaf(Nibbles::unpack(hashed_address), &account_rlp);
+                }
+            }
+        }
+
+        let root = hash_builder.root();
+        let proof_nodes = hash_builder.take_proofs();
+
+        let account_proofs = account_proofs
+            .into_iter()
+            .map(|(hashed_address, mut account_proof)| {
+                // The storage proofs of accounts without a leaf in the trie were never taken
+                if account_proof.info.is_none() {
+                    if let Some((_, storage_proofs)) = storage_roots.remove(&hashed_address) {
+                        account_proof.storage_proofs = storage_proofs;
+                    }
+                }
+                let nibbles = Nibbles::unpack(hashed_address);
+                account_proof.set_proof(
+                    proof_nodes
+                        .iter()
+                        .filter(|(path, _)| nibbles.starts_with(path))
+                        .map(|(_, node)| node.clone())
+                        .collect(),
+                );
+                (account_proof.address, account_proof)
+            })
+            .collect();
+
+        let stats = tracker.finish();
+
+        #[cfg(feature = "metrics")]
+        self.metrics.record_state_trie(stats);
+
+        trace!(
+            target: "trie::parallel_proof",
+            %root,
+            duration = ?stats.duration(),
+            branches_added = stats.branches_added(),
+            leaves_added = stats.leaves_added(),
+            missed_leaves = stats.missed_leaves(),
+            precomputed_storage_roots = stats.precomputed_storage_roots(),
+            "generated proofs"
+        );
+
+        Ok(account_proofs)
+    }
+}
+
+/// Computes the storage root of the given account, retaining the proofs of the given slots.
+///
+/// The prefix set must contain the paths of all slots.
+fn storage_root_with_proofs<T, H>(
+    trie_cursor_factory: T,
+    hashed_cursor_factory: H,
+    hashed_address: B256,
+    prefix_set: PrefixSet,
+    slots: &[B256],
+) -> Result<(B256, Vec<StorageProof>), StorageRootError>
+where
+    T: TrieCursorFactory,
+    H: HashedCursorFactory,
+{
+    let mut proofs = slots.iter().copied().map(StorageProof::new).collect::<Vec<_>>();
+
+    let mut hashed_storage_cursor = hashed_cursor_factory.hashed_storage_cursor(hashed_address)?;
+    // short circuit on empty storage
+    if hashed_storage_cursor.is_storage_empty()? {
+        return Ok((EMPTY_ROOT_HASH, proofs))
+    }
+
+    let walker =
+        TrieWalker::new(trie_cursor_factory.storage_trie_cursor(hashed_address)?, prefix_set);
+    let retainer = ProofRetainer::from_iter(proofs.iter().map(|proof| proof.nibbles.clone()));
+    let mut hash_builder = HashBuilder::default().with_proof_retainer(retainer);
+    let mut storage_node_iter = TrieNodeIter::new(walker, hashed_storage_cursor);
+
+    while let Some(node) = storage_node_iter.try_next()? {
+        match node {
+            TrieElement::Branch(node) => {
+                hash_builder.add_branch(node.key, node.value, node.children_are_in_trie);
+            }
+            TrieElement::Leaf(hashed_slot, value) => {
+                let nibbles = Nibbles::unpack(hashed_slot);
+                for proof in proofs.iter_mut().filter(|proof| proof.nibbles == nibbles) {
+                    proof.set_value(value);
+                }
+                hash_builder.add_leaf(nibbles, alloy_rlp::encode_fixed_size(&value).as_ref());
+            }
+        }
+    }
+
+    let root = hash_builder.root();
+    let proof_nodes = hash_builder.take_proofs();
+    for proof in &mut proofs {
+        proof.set_proof(
+            proof_nodes
+                .iter()
+                .filter(|(path, _)| proof.nibbles.starts_with(path))
+                .map(|(_, node)| node.clone())
+                .collect(),
+        );
+    }
+
+    Ok((root, proofs))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::parallel_root::ParallelStateRoot;
+    use rand::Rng;
+    use reth_primitives::{Account, StorageEntry, U256};
+    use reth_provider::{
+        bundle_state::HashedStateChanges, test_utils::create_test_provider_factory, HashingWriter,
+    };
+    use reth_trie::{proof::Proof, HashedStorage, StateRoot};
+
+    #[test]
+    fn multiproof_matches_serial_proofs() {
+        let factory = create_test_provider_factory();
+        let mut rng = rand::thread_rng();
+        let state = (0..100)
+            .map(|_| {
+                let address = Address::random();
+                let account =
+                    Account { balance: U256::from(rng.gen::<u64>()), ..Default::default() };
+                let storage = (0..20)
+                    .map(|_| {
+                        (B256::from(U256::from(rng.gen::<u8>())), U256::from(rng.gen::<u64>()))
+                    })
+                    .collect::<HashMap<_, _>>();
+                (address, (account, storage))
+            })
+            .collect::<HashMap<_, _>>();
+
+        {
+            let provider_rw = factory.provider_rw().unwrap();
+            provider_rw
+                .insert_account_for_hashing(
+                    state.iter().map(|(address, (account, _))| (*address, Some(*account))),
+                )
+                .unwrap();
+            provider_rw
+                .insert_storage_for_hashing(state.iter().map(|(address, (_, storage))| {
+                    (
+                        *address,
+                        storage
+                            .iter()
+                            .map(|(slot, value)| StorageEntry { key: *slot, value: *value }),
+                    )
+                }))
+                .unwrap();
+            let (_, updates) =
+                StateRoot::from_tx(provider_rw.tx_ref()).root_with_updates().unwrap();
+            updates.write_to_database(provider_rw.tx_ref()).unwrap();
+            provider_rw.commit().unwrap();
+        }
+        let consistent_view = ConsistentDbView::new(factory.clone(), None);
+
+        // Request existing and missing slots, as well as a missing account
+        let missing_address = Address::random();
+        let targets = state
+            .iter()
+            .take(10)
+            .map(|(address, (_, storage))| {
+                let mut slots = storage.keys().take(3).copied().collect::<Vec<_>>();
+                slots.push(B256::random());
+                (*address, slots)
+            })
+            .chain([(missing_address, vec![B256::random()])])
+            .collect::<HashMap<_, _>>();
+
+        let assert_serial_proofs = |proofs: &HashMap<Address, AccountProof>| {
+            let provider = factory.provider().unwrap();
+            for (address, slots) in &targets {
+                let expected = Proof::new(provider.tx_ref(), provider.tx_ref())
+                    .account_proof(*address, slots)
+                    .unwrap();
+                let proof = &proofs[address];
+                assert_eq!(proof.info, expected.info);
+                assert_eq!(proof.storage_root, expected.storage_root);
+                assert_eq!(proof.proof, expected.proof);
+                if *address == missing_address {
+                    assert_eq!(proof.storage_proofs.len(), 1);
+                } else {
+                    assert_eq!(proof.storage_proofs, expected.storage_proofs);
+                }
+            }
+        };
+
+        let proofs =
+            ParallelProof::new(consistent_view.clone()).multiproof(targets.clone()).unwrap();
+        assert_eq!(proofs.len(), targets.len());
+        assert_serial_proofs(&proofs);
+
+        // Proofs on top of an overlay match the serial proofs once the overlay is persisted
+        let mut overlay = HashedPostState::default();
+        for (address, slots) in targets.iter().take(5) {
+            let hashed_address = keccak256(address);
+            let account = Account { balance: U256::from(rng.gen::<u64>()), ..Default::default() };
+            overlay.accounts.insert(hashed_address, Some(account));
+            overlay.storages.insert(
+                hashed_address,
+                HashedStorage::from_iter(
+                    false,
+                    slots.iter().map(|slot| (keccak256(slot), U256::from(rng.gen::<u64>()))),
+                ),
+            );
+        }
+        let proofs = ParallelProof::new(consistent_view.clone())
+            .with_hashed_state(overlay.clone())
+            .multiproof(targets.clone())
+            .unwrap();
+
+        {
+            let (_, updates) = ParallelStateRoot::new(consistent_view, overlay.clone())
+                .incremental_root_with_updates()
+                .unwrap();
+            let provider_rw = factory.provider_rw().unwrap();
+            HashedStateChanges(overlay).write_to_db(provider_rw.tx_ref()).unwrap();
+            updates.write_to_database(provider_rw.tx_ref()).unwrap();
+            provider_rw.commit().unwrap();
+        }
+        assert_serial_proofs(&proofs);
+    }
+}
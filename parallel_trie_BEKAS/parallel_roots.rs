@@ -1,5 +1,9 @@
 // Import necessary crates and modules
-use crate::{stats::ParallelTrieTracker, storage_root_targets::StorageRootTargets};
+use crate::{
+    sparse_cache::{CachedTrieCursorFactory, SparseTrieCache, TrieCacheCounters},
+    stats::ParallelTrieTracker,
+    storage_root_targets::StorageRootTargets,
+};
 use alloy_rlp::{BufMut, Encodable};
 use rayon::prelude::*;
 use reth_db_api::database::Database;
@@ -64,20 +68,36 @@ where
 {
     /// Calculate incremental state root in parallel.
     pub fn incremental_root(self) -> Result<B256, ParallelStateRootError> {
-        self.calculate(false).map(|(root, _)| root)
+        self.calculate(false, None).map(|(root, _)| root)
     }
 
     /// Calculate incremental state root with updates in parallel.
     pub fn incremental_root_with_updates(
         self,
     ) -> Result<(B256, TrieUpdates), ParallelStateRootError> {
-        self.calculate(true)
+        self.calculate(true, None)
+    }
+
+    /// Calculate the state root of a block on top of the cached blocks, with updates in parallel.
+    ///
+    /// The hashed state must only contain the changes of the block, whose parent has to be the
+    /// [tip](SparseTrieCache::tip) of the cache. Trie nodes of the cache take precedence over the
+    /// ones in the database, so only the paths touched by the block are re-hashed.
+    ///
+    /// Once the block is known to be valid, the returned updates can be inserted into the cache
+    /// with [`SparseTrieCache::insert_block`].
+    pub fn incremental_root_with_cache(
+        self,
+        cache: &SparseTrieCache,
+    ) -> Result<(B256, TrieUpdates), ParallelStateRootError> {
+        self.calculate(true, Some(cache))
     }
 
     /// Internal method to perform state root calculation.
     fn calculate(
         self,
         retain_updates: bool,
+        cache: Option<&SparseTrieCache>,
     ) -> Result<(B256, TrieUpdates), ParallelStateRootError> {
         // Initialize a tracker to collect statistics during computation
         let mut tracker = ParallelTrieTracker::default();
@@ -88,8 +108,27 @@ where
             self.hashed_state.accounts.keys().copied(),
             prefix_sets.storage_prefix_sets,
         );
-        // Convert hashed state to a sorted form
-        let hashed_state_sorted = self.hashed_state.into_sorted();
+        // Convert hashed state to a sorted form, on top of the state of the cached blocks
+        let hashed_state_sorted = match cache {
+            Some(cache) => {
+                let mut hashed_state = cache.hashed_state().clone();
+                hashed_state.extend(self.hashed_state);
+                hashed_state.into_sorted()
+            }
+            None => self.hashed_state.into_sorted(),
+        };
+
+        // Without a cache, an empty one is used and all trie nodes are read from the database
+        let use_cache = cache.is_some();
+        let empty_cache;
+        let cache = match cache {
+            Some(cache) => cache,
+            None => {
+                empty_cache = SparseTrieCache::new(Default::default());
+                &empty_cache
+            }
+        };
+        let cache_counters = TrieCacheCounters::default();
 
         // Set the number of precomputed storage roots in the tracker
         tracker.set_precomputed_storage_roots(storage_root_targets.len() as u64);
@@ -102,7 +141,7 @@ where
                 let provider_ro = self.view.provider_ro()?;
                 // Calculate storage root for each account
                 let storage_root_result = StorageRoot::new_hashed(
-                    provider_ro.tx_ref(),
+                    CachedTrieCursorFactory::new(provider_ro.tx_ref(), cache, &cache_counters),
                     HashedPostStateCursorFactory::new(provider_ro.tx_ref(), &hashed_state_sorted),
                     hashed_address,
                     #[cfg(feature = "metrics")]
@@ -123,8 +162,9 @@ where
         // Create hashed cursor factory for the sorted hashed state
         let hashed_cursor_factory =
             HashedPostStateCursorFactory::new(provider_ro.tx_ref(), &hashed_state_sorted);
-        // Obtain trie cursor factory for database operations
-        let trie_cursor_factory = provider_ro.tx_ref();
+        // Obtain trie cursor factory for database operations, reading cached nodes first
+        let trie_cursor_factory =
+            CachedTrieCursorFactory::new(provider_ro.tx_ref(), cache, &cache_counters);
 
         // Create a trie walker with account trie cursor and account prefix set
         let walker = TrieWalker::new(
@@ -195,6 +235,9 @@ where
         );
 
         // Finish tracking statistics
+        if use_cache {
+            tracker.set_cache_lookups(cache_counters.hits(), cache_counters.misses());
+        }
         let stats = tracker.finish();
 
         // Record metrics if feature is enabled
@@ -210,6 +253,8 @@ where
             leaves_added = stats.leaves_added(),
             missed_leaves = stats.missed_leaves(),
             precomputed_storage_roots = stats.precomputed_storage_roots(),
+            cache_hits = stats.cache_hits(),
+            cache_misses = stats.cache_misses(),
             "calculated state root"
         );
 
@@ -244,9 +289,11 @@ impl From<ParallelStateRootError> for ProviderError {
 mod tests {
     use super::*;
     use rand::Rng;
-    use reth_primitives::{keccak256, Account, Address, StorageEntry, U256};
-    use reth_provider::{test_utils::create_test_provider_factory, HashingWriter};
-    use reth_trie::{test_utils, HashedStorage};
+    use reth_primitives::{keccak256, Account, Address, BlockNumHash, StorageEntry, U256};
+    use reth_provider::{
+        bundle_state::HashedStateChanges, test_utils::create_test_provider_factory, HashingWriter,
+    };
+    use reth_trie::{test_utils, HashedStorage, StateRoot};
 
     #[tokio::test]
     async fn random_parallel_root() {
@@ -315,4 +362,67 @@ mod tests {
             )
         );
     }
+
+    fn random_hashed_state(rng: &mut impl Rng, hashed_addresses: &[B256]) -> HashedPostState {
+        HashedPostState::default()
+            .with_accounts(hashed_addresses.iter().map(|hashed_address| {
+                let account =
+                    Account { balance: U256::from(rng.gen::<u64>()), ..Default::default() };
+                (*hashed_address, Some(account))
+            }))
+            .with_storages(hashed_addresses.iter().map(|hashed_address| {
+                let storage = (0..10).map(|_| {
+                    (B256::from(U256::from(rng.gen::<u8>())), U256::from(rng.gen::<u64>()))
+                });
+                (*hashed_address, HashedStorage::from_iter(false, storage))
+            }))
+    }
+
+    #[test]
+    fn cached_root_matches_root_of_combined_state() {
+        let factory = create_test_provider_factory();
+        let mut rng = rand::thread_rng();
+        let hashed_addresses = (0..50).map(|_| B256::random()).collect::<Vec<_>>();
+
+        {
+            // Persist the initial state together with its trie
+            let provider_rw = factory.provider_rw().unwrap();
+            HashedStateChanges(random_hashed_state(&mut rng, &hashed_addresses))
+                .write_to_db(provider_rw.tx_ref())
+                .unwrap();
+            let (_, updates) =
+                StateRoot::from_tx(provider_rw.tx_ref()).root_with_updates().unwrap();
+            updates.write_to_database(provider_rw.tx_ref()).unwrap();
+            provider_rw.commit().unwrap();
+        }
+
+        let consistent_view = ConsistentDbView::new(factory, None);
+        let mut cache = SparseTrieCache::new(BlockNumHash::default());
+        let mut combined_state = HashedPostState::default();
+
+        for number in 1..=3 {
+            let mut block_state = random_hashed_state(&mut rng, &hashed_addresses[..20]);
+            if number == 2 {
+                // Destroy an account, which wipes its storage
+                block_state.accounts.insert(hashed_addresses[30], None);
+                block_state.storages.insert(hashed_addresses[30], HashedStorage::new(true));
+            }
+            combined_state.extend(block_state.clone());
+
+            // The root of the block on top of the cache matches the root of all changes since
+            // the persisted state
+            let expected = ParallelStateRoot::new(consistent_view.clone(), combined_state.clone())
+                .incremental_root()
+                .unwrap();
+            let (root, updates) =
+                ParallelStateRoot::new(consistent_view.clone(), block_state.clone())
+                    .incremental_root_with_cache(&cache)
+                    .unwrap();
+            assert_eq!(root, expected);
+
+            let parent_hash = cache.tip().hash;
+            let block = BlockNumHash::new(number, B256::random());
+            assert!(cache.insert_block(block, parent_hash, block_state, &updates));
+        }
+    }
 }
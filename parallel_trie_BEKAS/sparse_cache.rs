@@ -0,0 +1,498 @@
+//! In-memory cache of the trie nodes of blocks that are not persisted yet.
+//!
+//! When blocks are validated back-to-back at the tip, the database lags behind the block that is
+//! being validated. Without a cache, the state root of every block has to be computed from the
+//! combined state changes of all blocks since the persisted tip. The [`SparseTrieCache`] instead
+//! keeps the trie nodes and hashed state of those blocks keyed by path, so that the root of the
+//! next block only re-hashes the paths touched by that block.
+
+use reth_db_api::DatabaseError;
+use reth_primitives::{BlockNumHash, B256};
+use reth_trie::{
+    trie_cursor::{TrieCursor, TrieCursorFactory},
+    updates::TrieUpdates,
+    BranchNodeCompact, HashedPostState, Nibbles,
+};
+use std::{
+    collections::{BTreeMap, HashMap, VecDeque},
+    sync::atomic::{AtomicU64, Ordering},
+};
+
+/// Cached trie nodes keyed by path, `None` if the node was removed.
+type CachedNodes = BTreeMap<Nibbles, Option<BranchNodeCompact>>;
+
+/// In-memory cache of the trie nodes and hashed state of a chain of blocks on top of the persisted
+/// tip.
+///
+/// The cache is updated with the [`TrieUpdates`] of each block via
+/// [`SparseTrieCache::insert_block`], and only accepts blocks that extend its
+/// [tip](SparseTrieCache::tip). After a reorg, the cache has to be [reset](SparseTrieCache::reset)
+/// to the persisted tip.
+#[derive(Clone, Debug)]
+pub struct SparseTrieCache {
+    /// The persisted block the cached blocks are on top of.
+    anchor: BlockNumHash,
+    /// The cached blocks, in ascending order.
+    blocks: VecDeque<BlockNumHash>,
+    /// The combined hashed state of the cached blocks.
+    hashed_state: HashedPostState,
+    /// The account trie nodes of the cached blocks.
+    account_nodes: CachedNodes,
+    /// The storage trie nodes of the cached blocks.
+    storage_tries: HashMap<B256, CachedStorageTrie>,
+}
+
+impl SparseTrieCache {
+    /// Creates an empty cache on top of the given persisted block.
+    pub fn new(anchor: BlockNumHash) -> Self {
+        Self {
+            anchor,
+            blocks: VecDeque::new(),
+            hashed_state: HashedPostState::default(),
+            account_nodes: CachedNodes::new(),
+            storage_tries: HashMap::new(),
+        }
+    }
+
+    /// Returns the persisted block the cached blocks are on top of.
+    pub const fn anchor(&self) -> BlockNumHash {
+        self.anchor
+    }
+
+    /// Returns the block whose state the cache reflects.
+    ///
+    /// This is the most recently inserted block, or the anchor if the cache is empty.
+    pub fn tip(&self) -> BlockNumHash {
+        self.blocks.back().copied().unwrap_or(self.anchor)
+    }
+
+    /// Returns the combined hashed state of the cached blocks.
+    pub const fn hashed_state(&self) -> &HashedPostState {
+        &self.hashed_state
+    }
+
+    /// Returns `true` if no blocks are cached.
+    pub fn is_empty(&self) -> bool {
+        self.blocks.is_empty()
+    }
+
+    /// Returns the number of cached trie nodes, including removed ones.
+    pub fn nodes_len(&self) -> usize {
+        self.account_nodes.len() +
+            self.storage_tries.values().map(|trie| trie.nodes.len()).sum::<usize>()
+    }
+
+    /// Inserts the hashed state and trie updates of the given block into the cache.
+    ///
+    /// The trie updates must have been computed on top of the current [tip](Self::tip), e.g. with
+    /// [`ParallelStateRoot::incremental_root_with_cache`](crate::parallel_root::ParallelStateRoot::incremental_root_with_cache).
+    ///
+    /// Returns `false` and leaves the cache untouched if the block does not extend the tip, e.g.
+    /// after a reorg.
+    pub fn insert_block(
+        &mut self,
+        block: BlockNumHash,
+        parent_hash: B256,
+        hashed_state: HashedPostState,
+        updates: &TrieUpdates,
+    ) -> bool {
+        if parent_hash != self.tip().hash {
+            return false
+        }
+
+        self.hashed_state.extend(hashed_state);
+
+        // removed nodes first, so that nodes that were updated again take precedence
+        apply_updates(
+            &mut self.account_nodes,
+            updates.removed_nodes_ref(),
+            updates.account_nodes_ref(),
+        );
+        for (hashed_address, storage_updates) in updates.storage_tries_ref() {
+            let trie = self.storage_tries.entry(*hashed_address).or_default();
+            if storage_updates.is_deleted() {
+                trie.wiped = true;
+                trie.nodes.clear();
+            }
+            apply_updates(
+                &mut trie.nodes,
+                storage_updates.removed_nodes_ref(),
+                storage_updates.storage_nodes_ref(),
+            );
+        }
+
+        self.blocks.push_back(block);
+        true
+    }
+
+    /// Notifies the cache that all blocks up to and including the given block were persisted.
+    ///
+    /// If the cache reflects the given block, it is cleared. Otherwise the cached nodes stay valid,
+    /// because they are at least as recent as the persisted ones.
+    pub fn on_persisted(&mut self, block: BlockNumHash) {
+        if block == self.tip() {
+            self.reset(block);
+            return
+        }
+
+        if let Some(position) = self.blocks.iter().position(|cached| *cached == block) {
+            self.blocks.drain(..=position);
+            self.anchor = block;
+        }
+    }
+
+    /// Clears the cache and anchors it on top of the given persisted block.
+    ///
+    /// This needs to be called after a reorg, since the blocks of the cache can't be unwound.
+    pub fn reset(&mut self, anchor: BlockNumHash) {
+        *self = Self::new(anchor);
+    }
+}
+
+/// The cached nodes of a single storage trie.
+#[derive(Clone, Debug, Default)]
+struct CachedStorageTrie {
+    /// Whether the storage trie was wiped by one of the cached blocks, in which case the nodes of
+    /// the database are outdated.
+    wiped: bool,
+    /// The cached nodes.
+    nodes: CachedNodes,
+}
+
+/// Applies the given removed and updated nodes to the cached nodes.
+fn apply_updates<'a>(
+    nodes: &mut CachedNodes,
+    removed: impl IntoIterator<Item = &'a Nibbles>,
+    updated: impl IntoIterator<Item = (&'a Nibbles, &'a BranchNodeCompact)>,
+) {
+    // the root node is never persisted, so it is not cached either
+    for path in removed.into_iter().filter(|path| !path.is_empty()) {
+        nodes.insert(path.clone(), None);
+    }
+    for (path, node) in updated.into_iter().filter(|(path, _)| !path.is_empty()) {
+        nodes.insert(path.clone(), Some(node.clone()));
+    }
+}
+
+/// Counters of the lookups of the trie cursors created by a [`CachedTrieCursorFactory`].
+#[derive(Debug, Default)]
+pub struct TrieCacheCounters {
+    hits: AtomicU64,
+    misses: AtomicU64,
+}
+
+impl TrieCacheCounters {
+    /// Returns the number of lookups that were served from the cache.
+    pub fn hits(&self) -> u64 {
+        self.hits.load(Ordering::Relaxed)
+    }
+
+    /// Returns the number of lookups that had to read from the database.
+    pub fn misses(&self) -> u64 {
+        self.misses.load(Ordering::Relaxed)
+    }
+
+    fn inc_hits(&self) {
+        self.hits.fetch_add(1, Ordering::Relaxed);
+    }
+
+    fn inc_misses(&self) {
+        self.misses.fetch_add(1, Ordering::Relaxed);
+    }
+}
+
+/// Trie cursor factory that overlays the nodes of a [`SparseTrieCache`] on top of the trie cursors
+/// of the inner factory.
+#[derive(Clone, Copy, Debug)]
+pub struct CachedTrieCursorFactory<'a, CF> {
+    /// The inner cursor factory, usually reading from the database.
+    cursor_factory: CF,
+    /// The cache whose nodes take precedence.
+    cache: &'a SparseTrieCache,
+    /// Counters of the cache lookups.
+    counters: &'a TrieCacheCounters,
+}
+
+impl<'a, CF> CachedTrieCursorFactory<'a, CF> {
+    /// Creates a new cursor factory that counts lookups in the given counters.
+    pub const fn new(
+        cursor_factory: CF,
+        cache: &'a SparseTrieCache,
+        counters: &'a TrieCacheCounters,
+    ) -> Self {
+        Self { cursor_factory, cache, counters }
+    }
+}
+
+impl<'a, CF: TrieCursorFactory> TrieCursorFactory for CachedTrieCursorFactory<'a, CF> {
+    type AccountTrieCursor = CachedTrieCursor<'a, CF::AccountTrieCursor>;
+    type StorageTrieCursor = CachedTrieCursor<'a, CF::StorageTrieCursor>;
+
+    fn account_trie_cursor(&self) -> Result<Self::AccountTrieCursor, DatabaseError> {
+        Ok(CachedTrieCursor::new(
+            self.cursor_factory.account_trie_cursor()?,
+            Some(&self.cache.account_nodes),
+            false,
+            self.counters,
+        ))
+    }
+
+    fn storage_trie_cursor(
+        &self,
+        hashed_address: B256,
+    ) -> Result<Self::StorageTrieCursor, DatabaseError> {
+        let trie = self.cache.storage_tries.get(&hashed_address);
+        Ok(CachedTrieCursor::new(
+            self.cursor_factory.storage_trie_cursor(hashed_address)?,
+            trie.map(|trie| &trie.nodes),
+            trie.map_or(false, |trie| trie.wiped),
+            self.counters,
+        ))
+    }
+}
+
+/// Trie cursor that overlays cached nodes on top of an inner cursor.
+#[derive(Debug)]
+pub struct CachedTrieCursor<'a, C> {
+    /// The inner cursor.
+    cursor: C,
+    /// The cached nodes of the trie, if any.
+    nodes: Option<&'a CachedNodes>,
+    /// Whether the trie was wiped, in which case the inner cursor is not consulted.
+    wiped: bool,
+    /// Counters of the cache lookups.
+    counters: &'a TrieCacheCounters,
+    /// The key of the last node that was returned.
+    last_key: Option<Nibbles>,
+}
+
+impl<'a, C: TrieCursor> CachedTrieCursor<'a, C> {
+    const fn new(
+        cursor: C,
+        nodes: Option<&'a CachedNodes>,
+        wiped: bool,
+        counters: &'a TrieCacheCounters,
+    ) -> Self {
+        Self { cursor, nodes, wiped, counters, last_key: None }
+    }
+
+    /// Returns the cached entry for the given key, `Some(None)` if the node was removed.
+    fn cached(&self, key: &Nibbles) -> Option<Option<&'a BranchNodeCompact>> {
+        self.nodes.and_then(|nodes| nodes.get(key)).map(Option::as_ref)
+    }
+
+    /// Seeks the inner cursor to the first node at or after the given key that was not removed in
+    /// the cache.
+    fn seek_inner(
+        &mut self,
+        mut key: Nibbles,
+    ) -> Result<Option<(Nibbles, BranchNodeCompact)>, DatabaseError> {
+        if self.wiped {
+            return Ok(None)
+        }
+
+        loop {
+            match self.cursor.seek(key)? {
+                Some((found, _)) if matches!(self.cached(&found), Some(None)) => {
+                    // the smallest key that is greater than the removed one
+                    key = found;
+                    key.push(0);
+                }
+                entry => return Ok(entry),
+            }
+        }
+    }
+}
+
+impl<'a, C: TrieCursor> TrieCursor for CachedTrieCursor<'a, C> {
+    fn seek_exact(
+        &mut self,
+        key: Nibbles,
+    ) -> Result<Option<(Nibbles, BranchNodeCompact)>, DatabaseError> {
+        let entry = if let Some(cached) = self.cached(&key) {
+            self.counters.inc_hits();
+            cached.map(|node| (key, node.clone()))
+        } else {
+            self.counters.inc_misses();
+            if self.wiped {
+                None
+            } else {
+                self.cursor.seek_exact(key)?
+            }
+        };
+
+        self.last_key = entry.as_ref().map(|(key, _)| key.clone());
+        Ok(entry)
+    }
+
+    fn seek(
+        &mut self,
+        key: Nibbles,
+    ) -> Result<Option<(Nibbles, BranchNodeCompact)>, DatabaseError> {
+        // an exact match is the smallest possible result, so the inner cursor can be skipped
+        if let Some(Some(node)) = self.cached(&key) {
+            self.counters.inc_hits();
+            self.last_key = Some(key.clone());
+            return Ok(Some((key, node.clone())))
+        }
+        self.counters.inc_misses();
+
+        let cached = self.nodes.and_then(|nodes| {
+            nodes
+                .range(key.clone()..)
+                .find_map(|(key, node)| node.as_ref().map(|node| (key.clone(), node.clone())))
+        });
+        let entry = match (cached, self.seek_inner(key)?) {
+            (Some(cached), Some(inner)) => Some(if inner.0 < cached.0 { inner } else { cached }),
+            (cached, inner) => cached.or(inner),
+        };
+
+        self.last_key = entry.as_ref().map(|(key, _)| key.clone());
+        Ok(entry)
+    }
+
+    fn current(&mut self) -> Result<Option<Nibbles>, DatabaseError> {
+        match &self.last_key {
+            Some(key) => Ok(Some(key.clone())),
+            None => self.cursor.current(),
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use reth_trie::{updates::StorageTrieUpdates, TrieMask};
+
+    fn node(hash: u8) -> BranchNodeCompact {
+        BranchNodeCompact::new(
+            TrieMask::new(0b11),
+            TrieMask::new(0),
+            TrieMask::new(0b1),
+            vec![B256::with_last_byte(hash)],
+            None,
+        )
+    }
+
+    fn block(number: u64) -> BlockNumHash {
+        BlockNumHash::new(number, B256::with_last_byte(number as u8))
+    }
+
+    #[derive(Default)]
+    struct MockTrieCursor {
+        nodes: BTreeMap<Nibbles, BranchNodeCompact>,
+        current: Option<Nibbles>,
+    }
+
+    impl TrieCursor for MockTrieCursor {
+        fn seek_exact(
+            &mut self,
+            key: Nibbles,
+        ) -> Result<Option<(Nibbles, BranchNodeCompact)>, DatabaseError> {
+            let entry = self.nodes.get(&key).map(|node| (key, node.clone()));
+            self.current = entry.as_ref().map(|(key, _)| key.clone());
+            Ok(entry)
+        }
+
+        fn seek(
+            &mut self,
+            key: Nibbles,
+        ) -> Result<Option<(Nibbles, BranchNodeCompact)>, DatabaseError> {
+            let entry =
+                self.nodes.range(key..).next().map(|(key, node)| (key.clone(), node.clone()));
+            self.current = entry.as_ref().map(|(key, _)| key.clone());
+            Ok(entry)
+        }
+
+        fn current(&mut self) -> Result<Option<Nibbles>, DatabaseError> {
+            Ok(self.current.clone())
+        }
+    }
+
+    #[test]
+    fn overlays_cached_nodes() {
+        let persisted = MockTrieCursor {
+            nodes: BTreeMap::from([
+                (Nibbles::from_nibbles([0x1]), node(1)),
+                (Nibbles::from_nibbles([0x2]), node(2)),
+                (Nibbles::from_nibbles([0x3]), node(3)),
+            ]),
+            current: None,
+        };
+        let cached = CachedNodes::from([
+            (Nibbles::from_nibbles([0x2]), None),
+            (Nibbles::from_nibbles([0x2, 0x1]), Some(node(21))),
+            (Nibbles::from_nibbles([0x3]), Some(node(33))),
+        ]);
+        let counters = TrieCacheCounters::default();
+        let mut cursor = CachedTrieCursor::new(persisted, Some(&cached), false, &counters);
+
+        // removed node
+        assert_eq!(cursor.seek_exact(Nibbles::from_nibbles([0x2])).unwrap(), None);
+        // persisted node
+        assert_eq!(
+            cursor.seek(Nibbles::from_nibbles([0x1])).unwrap(),
+            Some((Nibbles::from_nibbles([0x1]), node(1)))
+        );
+        // skips the removed node
+        assert_eq!(
+            cursor.seek(Nibbles::from_nibbles([0x1, 0x1])).unwrap(),
+            Some((Nibbles::from_nibbles([0x2, 0x1]), node(21)))
+        );
+        assert_eq!(cursor.current().unwrap(), Some(Nibbles::from_nibbles([0x2, 0x1])));
+        // updated node
+        assert_eq!(
+            cursor.seek(Nibbles::from_nibbles([0x3])).unwrap(),
+            Some((Nibbles::from_nibbles([0x3]), node(33)))
+        );
+        assert_eq!(cursor.seek(Nibbles::from_nibbles([0x4])).unwrap(), None);
+
+        assert_eq!(counters.hits(), 2);
+        assert_eq!(counters.misses(), 3);
+    }
+
+    #[test]
+    fn wiped_trie_ignores_persisted_nodes() {
+        let persisted = MockTrieCursor {
+            nodes: BTreeMap::from([(Nibbles::from_nibbles([0x1]), node(1))]),
+            current: None,
+        };
+        let cached = CachedNodes::from([(Nibbles::from_nibbles([0x2]), Some(node(2)))]);
+        let counters = TrieCacheCounters::default();
+        let mut cursor = CachedTrieCursor::new(persisted, Some(&cached), true, &counters);
+
+        assert_eq!(cursor.seek_exact(Nibbles::from_nibbles([0x1])).unwrap(), None);
+        assert_eq!(
+            cursor.seek(Nibbles::default()).unwrap(),
+            Some((Nibbles::from_nibbles([0x2]), node(2)))
+        );
+    }
+
+    #[test]
+    fn insert_blocks() {
+        let mut cache = SparseTrieCache::new(block(1));
+
+        let mut updates = TrieUpdates::default();
+        updates.insert_storage_updates(B256::with_last_byte(1), StorageTrieUpdates::deleted());
+        assert!(cache.insert_block(block(2), block(1).hash, HashedPostState::default(), &updates));
+        assert_eq!(cache.tip(), block(2));
+        assert!(cache.storage_tries[&B256::with_last_byte(1)].wiped);
+
+        // the block does not extend the tip
+        assert!(!cache.insert_block(block(4), block(3).hash, HashedPostState::default(), &updates));
+        assert!(!cache.insert_block(block(3), block(1).hash, HashedPostState::default(), &updates));
+        assert_eq!(cache.tip(), block(2));
+
+        assert!(cache.insert_block(block(3), block(2).hash, HashedPostState::default(), &updates));
+
+        cache.on_persisted(block(2));
+        assert_eq!(cache.anchor(), block(2));
+        assert_eq!(cache.tip(), block(3));
+        assert!(!cache.storage_tries.is_empty());
+
+        cache.on_persisted(block(3));
+        assert!(cache.is_empty());
+        assert_eq!(cache.anchor(), block(3));
+        assert!(cache.storage_tries.is_empty());
+    }
+}
@@ -7,7 +7,10 @@ pub struct ParallelTrieStats {
     #[deref]
     trie: TrieStats,
     precomputed_storage_roots: u64,
+    recomputed_storage_roots: u64,
     missed_leaves: u64,
+    cache_hits: Option<u64>,
+    cache_misses: Option<u64>,
 }
 
 impl ParallelTrieStats {
@@ -21,10 +24,28 @@ impl ParallelTrieStats {
         self.precomputed_storage_roots
     }
 
+    /// The number of storage roots that were computed again because the storage changed after
+    /// the first computation had been started.
+    pub const fn recomputed_storage_roots(&self) -> u64 {
+        self.recomputed_storage_roots
+    }
+
     /// The number of added leaf nodes for which we did not precompute the storage root.
     pub const fn missed_leaves(&self) -> u64 {
         self.missed_leaves
     }
+
+    /// The number of trie node lookups that were served from the sparse trie cache, or `None` if
+    /// the calculation did not use a cache.
+    pub const fn cache_hits(&self) -> Option<u64> {
+        self.cache_hits
+    }
+
+    /// The number of trie node lookups that were not served from the sparse trie cache, or `None`
+    /// if the calculation did not use a cache.
+    pub const fn cache_misses(&self) -> Option<u64> {
+        self.cache_misses
+    }
 }
 
 /// Trie metrics tracker.
@@ -33,7 +54,10 @@ pub struct ParallelTrieTracker {
     #[deref]
     trie: TrieTracker,
     precomputed_storage_roots: u64,
+    recomputed_storage_roots: u64,
     missed_leaves: u64,
+    cache_hits: Option<u64>,
+    cache_misses: Option<u64>,
 }
 
 impl ParallelTrieTracker {
@@ -42,6 +66,11 @@ impl ParallelTrieTracker {
         self.precomputed_storage_roots = count;
     }
 
+    /// Increment the number of storage roots that were computed again.
+    pub fn inc_recomputed_storage_roots(&mut self) {
+        self.recomputed_storage_roots += 1;
+    }
+
     /// Increment the number of branches added to the hash builder during the calculation.
     pub fn inc_branch(&mut self) {
         self.trie.inc_branch();
@@ -57,12 +86,21 @@ impl ParallelTrieTracker {
         self.missed_leaves += 1;
     }
 
+    /// Set the number of trie node lookups that were served from and missed the sparse trie cache.
+    pub fn set_cache_lookups(&mut self, hits: u64, misses: u64) {
+        self.cache_hits = Some(hits);
+        self.cache_misses = Some(misses);
+    }
+
     /// Called when root calculation is finished to return trie statistics.
     pub fn finish(self) -> ParallelTrieStats {
         ParallelTrieStats {
             trie: self.trie.finish(),
             precomputed_storage_roots: self.precomputed_storage_roots,
+            recomputed_storage_roots: self.recomputed_storage_roots,
             missed_leaves: self.missed_leaves,
+            cache_hits: self.cache_hits,
+            cache_misses: self.cache_misses,
         }
     }
 }
\ No newline at end of file
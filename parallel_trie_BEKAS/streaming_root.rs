@@ -0,0 +1,426 @@
+use crate::{
+    async_root::AsyncStateRootError, stats::ParallelTrieTracker,
+    storage_root_targets::StorageRootTargets,
+};
+use alloy_rlp::{BufMut, Encodable};
+use reth_db_api::database::Database;
+use reth_primitives::B256;
+use reth_provider::{providers::ConsistentDbView, DatabaseProviderFactory, ProviderError};
+use reth_tasks::pool::{BlockingTaskHandle, BlockingTaskPool};
+use reth_trie::{
+    hashed_cursor::{HashedCursorFactory, HashedPostStateCursorFactory},
+    node_iter::{TrieElement, TrieNodeIter},
+    trie_cursor::TrieCursorFactory,
+    updates::{StorageTrieUpdates, TrieUpdates},
+    walker::TrieWalker,
+    HashBuilder, HashedPostState, Nibbles, StorageRoot, TrieAccount,
+};
+use std::{
+    collections::{hash_map::Entry, HashMap, HashSet},
+    sync::{
+        atomic::{AtomicBool, Ordering},
+        Arc,
+    },
+};
+use tokio::sync::mpsc::UnboundedReceiver;
+use tracing::*;
+
+#[cfg(feature = "metrics")]
+use crate::metrics::ParallelStateRootMetrics;
+
+/// The result of a storage root task.
+type StorageRootResult = Result<(B256, usize, StorageTrieUpdates), AsyncStateRootError>;
+
+/// A storage root computation of an account on the blocking pool.
+#[derive(Debug)]
+struct StorageRootTask {
+    /// Handle of the blocking task.
+    handle: BlockingTaskHandle<StorageRootResult>,
+    /// Set once the computation finished.
+    finished: Arc<AtomicBool>,
+}
+
+impl StorageRootTask {
+    /// Returns `true` if the computation finished.
+    fn is_finished(&self) -> bool {
+        self.finished.load(Ordering::Acquire)
+    }
+}
+
+/// Streaming state root calculator.
+///
+/// Unlike `AsyncStateRoot`, the calculator does not wait for the final state of the block.
+/// Instead, it receives the state changes of the block, e.g. of each transaction, over a channel
+/// while the block is still being executed.
+///
+/// As soon as an account is touched, a task computing its storage root is launched. If the
+/// storage of the account changes again, the task is relaunched with the updated storage once the
+/// running computation finished, so that there is at most one computation per account at a time.
+/// Accounts whose storage is untouched by later changes are not recomputed.
+///
+/// Once the sender side of the channel is closed, the changes are considered complete and the
+/// calculator walks the state trie the same way `AsyncStateRoot` does, polling the storage root
+/// tasks for the encountered leaves.
+///
+/// Internally, the calculator uses [`ConsistentDbView`] since
+/// it needs to rely on database state saying the same until
+/// the last transaction is open.
+/// See docs of using [`ConsistentDbView`] for caveats.
+#[derive(Debug)]
+pub struct StreamingStateRoot<DB, Provider> {
+    /// Consistent view of the database.
+    view: ConsistentDbView<DB, Provider>,
+    /// Blocking task pool.
+    blocking_pool: BlockingTaskPool,
+    /// Receiver of the hashed state changes, in the order they were applied.
+    updates: UnboundedReceiver<HashedPostState>,
+    /// Parallel state root metrics.
+    #[cfg(feature = "metrics")]
+    metrics: ParallelStateRootMetrics,
+}
+
+impl<DB, Provider> StreamingStateRoot<DB, Provider> {
+    /// Create new streaming state root calculator.
+    pub fn new(
+        view: ConsistentDbView<DB, Provider>,
+        blocking_pool: BlockingTaskPool,
+        updates: UnboundedReceiver<HashedPostState>,
+    ) -> Self {
+        Self {
+            view,
+            blocking_pool,
+            updates,
+            #[cfg(feature = "metrics")]
+            metrics: ParallelStateRootMetrics::default(),
+        }
+    }
+}
+
+impl<DB, Provider> StreamingStateRoot<DB, Provider>
+where
+    DB: Database + Clone + 'static,
+    Provider: DatabaseProviderFactory<DB> + Clone + Send + Sync + 'static,
+{
+    /// Calculate incremental state root once all state changes have been received.
+    pub async fn incremental_root(self) -> Result<B256, AsyncStateRootError> {
+        self.calculate(false).await.map(|(root, _)| root)
+    }
+
+    /// Calculate incremental state root with updates once all state changes have been received.
+    pub async fn incremental_root_with_updates(
+        self,
+    ) -> Result<(B256, TrieUpdates), AsyncStateRootError> {
+        self.calculate(true).await
+    }
+
+    async fn calculate(
+        mut self,
+        retain_updates: bool,
+    ) -> Result<(B256, TrieUpdates), AsyncStateRootError> {
+        let mut tracker = ParallelTrieTracker::default();
+        let mut hashed_state = HashedPostState::default();
+        let mut storage_roots = HashMap::<B256, StorageRootTask>::default();
+        // accounts whose storage changed after their storage root task was launched
+        let mut outdated = HashSet::<B256>::default();
+
+        while let Some(diff) = self.updates.recv().await {
+            let changed_storages = diff.storages.keys().copied().collect::<HashSet<_>>();
+            let diff_prefix_sets = diff.construct_prefix_sets().freeze();
+            let targets = StorageRootTargets::new(
+                diff.accounts.keys().copied(),
+                diff_prefix_sets.storage_prefix_sets,
+            );
+            hashed_state.extend(diff);
+
+            for (hashed_address, _) in targets {
+                match storage_roots.entry(hashed_address) {
+                    Entry::Occupied(_) => {
+                        // The storage root only has to be computed again if the storage changed.
+                        // Dropping the handle would not stop the running computation, so the task
+                        // is only relaunched once the running computation finished.
+                        if changed_storages.contains(&hashed_address) {
+                            outdated.insert(hashed_address);
+                        }
+                    }
+                    Entry::Vacant(entry) => {
+                        entry.insert(self.spawn_storage_root(
+                            &hashed_state,
+                            hashed_address,
+                            retain_updates,
+                        ));
+                    }
+                }
+            }
+
+            // relaunch the outdated tasks whose computation finished in the meantime
+            outdated.retain(|hashed_address| {
+                let task = storage_roots.get_mut(hashed_address).expect("task exists");
+                if !task.is_finished() {
+                    return true
+                }
+                tracker.inc_recomputed_storage_roots();
+                *task = self.spawn_storage_root(&hashed_state, *hashed_address, retain_updates);
+                false
+            });
+        }
+
+        // the remaining outdated tasks are relaunched with the final storage once they finished
+        for hashed_address in outdated {
+            let task = storage_roots.get_mut(&hashed_address).expect("task exists");
+            let _ = (&mut task.handle).await;
+            tracker.inc_recomputed_storage_roots();
+            *task = self.spawn_storage_root(&hashed_state, hashed_address, retain_updates);
+        }
+
+        tracker.set_precomputed_storage_roots(storage_roots.len() as u64);
+        debug!(target: "trie::streaming_state_root", len = storage_roots.len(), "received all state changes");
+
+        trace!(target: "trie::streaming_state_root", "calculating state root");
+        let prefix_sets = hashed_state.construct_prefix_sets().freeze();
+        let hashed_state_sorted = hashed_state.into_sorted();
+        let mut trie_updates = TrieUpdates::default();
+
+        let provider_ro = self.view.provider_ro()?;
+        let tx = provider_ro.tx_ref();
+        let hashed_cursor_factory = HashedPostStateCursorFactory::new(tx, &hashed_state_sorted);
+        let trie_cursor_factory = tx;
+
+        let walker = TrieWalker::new(
+            trie_cursor_factory.account_trie_cursor().map_err(ProviderError::Database)?,
+            prefix_sets.account_prefix_set,
+        )
+        .with_deletions_retained(retain_updates);
+        let mut account_node_iter = TrieNodeIter::new(
+            walker,
+            hashed_cursor_factory.hashed_account_cursor().map_err(ProviderError::Database)?,
+        );
+
+        let mut hash_builder = HashBuilder::default().with_updates(retain_updates);
+        let mut account_rlp = Vec::with_capacity(128);
+        while let Some(node) = account_node_iter.try_next().map_err(ProviderError::Database)? {
+            match node {
+                TrieElement::Branch(node) => {
+                    hash_builder.add_branch(node.key, node.value, node.children_are_in_trie);
+                }
+                TrieElement::Leaf(hashed_address, account) => {
+                    let (storage_root, _, updates) = match storage_roots.remove(&hashed_address) {
+                        Some(task) => task.handle.await.map_err(|_| {
+                            AsyncStateRootError::StorageRootChannelClosed { hashed_address }
+                        })??,
+                        None => {
+                            tracker.inc_missed_leaves();
+                            StorageRoot::new_hashed(
+                                trie_cursor_factory,
+                                hashed_cursor_factory.clone(),
+                                hashed_address,
+                                #[cfg(feature = "metrics")]
+                                self.metrics.storage_trie.clone(),
+                            )
+                            .calculate(retain_updates)?
+                        }
+                    };
+
+                    if retain_updates {
+                        trie_updates.insert_storage_updates(hashed_address, updates);
+                    }
+
+                    account_rlp.clear();
+                    let account = TrieAccount::from((account, storage_root));
+                    account.encode(&mut account_rlp as &mut dyn BufMut);
+                    hash_builder.add_leaf(Nibbles::unpack(hashed_address), &account_rlp);
+                }
+            }
+        }
+
+        let root = hash_builder.root();
+
+        trie_updates.finalize(
+            account_node_iter.walker,
+            hash_builder,
+            prefix_sets.destroyed_accounts,
+        );
+
+        let stats = tracker.finish();
+
+        #[cfg(feature = "metrics")]
+        self.metrics.record_state_trie(stats);
+
+        trace!(
+            target: "trie::streaming_state_root",
+            %root,
+            duration = ?stats.duration(),
+            branches_added = stats.branches_added(),
+            leaves_added = stats.leaves_added(),
+            missed_leaves = stats.missed_leaves(),
+            precomputed_storage_roots = stats.precomputed_storage_roots(),
+            recomputed_storage_roots = stats.recomputed_storage_roots(),
+            "calculated state root"
+        );
+
+        Ok((root, trie_updates))
+    }
+
+    /// Spawns a task computing the storage root of the given account on top of the storage
+    /// changes received so far.
+    fn spawn_storage_root(
+        &self,
+        hashed_state: &HashedPostState,
+        hashed_address: B256,
+        retain_updates: bool,
+    ) -> StorageRootTask {
+        // Only the storage of the account itself is relevant for its storage root
+        let mut storage_state = HashedPostState::default();
+        if let Some(storage) = hashed_state.storages.get(&hashed_address) {
+            storage_state.storages.insert(hashed_address, storage.clone());
+        }
+        let prefix_set = storage_state
+            .construct_prefix_sets()
+            .freeze()
+            .storage_prefix_sets
+            .remove(&hashed_address)
+            .unwrap_or_default();
+        let storage_state_sorted = Arc::new(storage_state.into_sorted());
+
+        let view = self.view.clone();
+        #[cfg(feature = "metrics")]
+        let metrics = self.metrics.storage_trie.clone();
+        let finished = Arc::new(AtomicBool::new(false));
+        let task_finished = finished.clone();
+        let handle = self.blocking_pool.spawn_fifo(move || -> StorageRootResult {
+            let calculate = || -> StorageRootResult {
+                let provider = view.provider_ro()?;
+                Ok(StorageRoot::new_hashed(
+                    provider.tx_ref(),
+                    HashedPostStateCursorFactory::new(provider.tx_ref(), &storage_state_sorted),
+                    hashed_address,
+                    #[cfg(feature = "metrics")]
+                    metrics,
+                )
+                .with_prefix_set(prefix_set)
+                .calculate(retain_updates)?)
+            };
+            let result = calculate();
+            task_finished.store(true, Ordering::Release);
+            result
+        });
+        StorageRootTask { handle, finished }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::async_root::AsyncStateRoot;
+    use rand::Rng;
+    use rayon::ThreadPoolBuilder;
+    use reth_primitives::{keccak256, Account, Address, StorageEntry, U256};
+    use reth_provider::{test_utils::create_test_provider_factory, HashingWriter};
+    use reth_trie::{test_utils, HashedStorage};
+    use tokio::sync::mpsc::unbounded_channel;
+
+    #[tokio::test]
+    async fn random_streaming_root() {
+        let blocking_pool = BlockingTaskPool::new(ThreadPoolBuilder::default().build().unwrap());
+
+        let factory = create_test_provider_factory();
+        let consistent_view = ConsistentDbView::new(factory.clone(), None);
+
+        let mut rng = rand::thread_rng();
+        let mut state = (0..100)
+            .map(|_| {
+                let address = Address::random();
+                let account =
+                    Account { balance: U256::from(rng.gen::<u64>()), ..Default::default() };
+                let mut storage = HashMap::<B256, U256>::default();
+                if rng.gen_bool(0.7) {
+                    for _ in 0..100 {
+                        storage.insert(
+                            B256::from(U256::from(rng.gen::<u64>())),
+                            U256::from(rng.gen::<u64>()),
+                        );
+                    }
+                }
+                (address, (account, storage))
+            })
+            .collect::<HashMap<_, _>>();
+
+        {
+            let provider_rw = factory.provider_rw().unwrap();
+            provider_rw
+                .insert_account_for_hashing(
+                    state.iter().map(|(address, (account, _))| (*address, Some(*account))),
+                )
+                .unwrap();
+            provider_rw
+                .insert_storage_for_hashing(state.iter().map(|(address, (_, storage))| {
+                    (
+                        *address,
+                        storage
+                            .iter()
+                            .map(|(slot, value)| StorageEntry { key: *slot, value: *value }),
+                    )
+                }))
+                .unwrap();
+            provider_rw.commit().unwrap();
+        }
+
+        // Split the changes into several "transactions" touching overlapping accounts
+        let (tx, rx) = unbounded_channel();
+        let streaming_root =
+            StreamingStateRoot::new(consistent_view.clone(), blocking_pool.clone(), rx);
+        let mut hashed_state = HashedPostState::default();
+        for _ in 0..5 {
+            let mut diff = HashedPostState::default();
+            for (address, (account, storage)) in &mut state {
+                let hashed_address = keccak256(address);
+
+                if rng.gen_bool(0.3) {
+                    *account = Account { balance: U256::from(rng.gen::<u64>()), ..*account };
+                    diff.accounts.insert(hashed_address, Some(*account));
+                }
+
+                if rng.gen_bool(0.2) {
+                    for (slot, value) in storage.iter_mut().filter(|_| rng.gen_bool(0.5)) {
+                        *value = U256::from(rng.gen::<u64>());
+                        diff.storages
+                            .entry(hashed_address)
+                            .or_insert_with(|| HashedStorage::new(false))
+                            .storage
+                            .insert(keccak256(slot), *value);
+                    }
+                }
+            }
+            hashed_state.extend(diff.clone());
+            tx.send(diff).unwrap();
+        }
+        drop(tx);
+
+        let (root, updates) = streaming_root.incremental_root_with_updates().await.unwrap();
+        assert_eq!(root, test_utils::state_root(state));
+
+        let (expected_root, expected_updates) =
+            AsyncStateRoot::new(consistent_view, blocking_pool, hashed_state)
+                .incremental_root_with_updates()
+                .await
+                .unwrap();
+        assert_eq!(root, expected_root);
+        assert_eq!(updates, expected_updates);
+    }
+
+    #[tokio::test]
+    async fn streaming_root_without_changes() {
+        let blocking_pool = BlockingTaskPool::new(ThreadPoolBuilder::default().build().unwrap());
+        let factory = create_test_provider_factory();
+        let consistent_view = ConsistentDbView::new(factory, None);
+
+        let (tx, rx) = unbounded_channel();
+        drop(tx);
+        assert_eq!(
+            StreamingStateRoot::new(consistent_view, blocking_pool, rx)
+                .incremental_root()
+                .await
+                .unwrap(),
+            test_utils::state_root(HashMap::<Address, (Account, HashMap<B256, U256>)>::default())
+        );
+    }
+}
@@ -4,6 +4,7 @@
 
 use crate::{
     components::NodeComponentsBuilder,
+    fork_schedule::ForkScheduleNotifications,
     node::FullNode,
     rpc::{RethRpcServerHandles, RpcContext},
     DefaultNodeLauncher, Node, NodeHandle,
@@ -15,7 +16,7 @@ use reth_db::{
     test_utils::{create_test_rw_db, TempDatabase},
     DatabaseEnv,
 };
-use reth_exex::ExExContext;
+use reth_exex::{ExExContext, ExExNotificationFilter};
 use reth_network::{NetworkBuilder, NetworkConfig, NetworkHandle};
 use reth_node_api::{FullNodeTypes, FullNodeTypesAdapter, NodeTypes};
 use reth_node_core::{
@@ -398,6 +399,32 @@ where
         }
     }
 
+    /// Installs an ExEx (Execution Extension) in the node, that only receives the notifications
+    /// matching the given filter.
+    ///
+    /// # Note
+    ///
+    /// The ExEx ID must be unique.
+    pub fn install_filtered_exex<F, R, E>(
+        self,
+        exex_id: impl Into<String>,
+        filter: ExExNotificationFilter,
+        exex: F,
+    ) -> Self
+    where
+        F: FnOnce(ExExContext<NodeAdapter<RethFullAdapter<DB, T>, CB::Components>>) -> R
+            + Send
+            + 'static,
+        R: Future<Output = eyre::Result<E>> + Send,
+        E: Future<Output = eyre::Result<()>> + Send,
+    {
+        Self {
+            builder: self.builder.install_filtered_exex(exex_id, filter, exex),
+            task_executor: self.task_executor,
+            data_dir: self.data_dir,
+        }
+    }
+
     /// Launches the node and returns a handle to it.
     pub async fn launch(
         self,
@@ -430,6 +457,8 @@ pub struct BuilderContext<Node: FullNodeTypes> {
     pub(crate) config: NodeConfig,
     /// loaded config.
     pub(crate) reth_config: reth_config::Config,
+    /// Notifications about hardfork transitions.
+    pub(crate) fork_schedule: ForkScheduleNotifications,
 }
 
 impl<Node: FullNodeTypes> BuilderContext<Node> {
@@ -442,7 +471,15 @@ impl<Node: FullNodeTypes> BuilderContext<Node> {
         config: NodeConfig,
         reth_config: reth_config::Config,
     ) -> Self {
-        Self { head, provider, executor, data_dir, config, reth_config }
+        Self {
+            head,
+            provider,
+            executor,
+            data_dir,
+            config,
+            reth_config,
+            fork_schedule: ForkScheduleNotifications::default(),
+        }
     }
 
     /// Returns the configured provider to interact with the blockchain.
@@ -455,6 +492,13 @@ impl<Node: FullNodeTypes> BuilderContext<Node> {
         self.head
     }
 
+    /// Returns the handle to subscribe to hardfork transitions.
+    ///
+    /// Components can use this to prepare fork specific state ahead of a hardfork activation.
+    pub fn fork_schedule(&self) -> &ForkScheduleNotifications {
+        &self.fork_schedule
+    }
+
     /// Returns the config of the node.
     pub fn config(&self) -> &NodeConfig {
         &self.config
@@ -561,6 +605,7 @@ impl<Node: FullNodeTypes> std::fmt::Debug for BuilderContext<Node> {
             .field("executor", &self.executor)
             .field("data_dir", &self.data_dir)
             .field("config", &self.config)
+            .field("fork_schedule", &self.fork_schedule)
             .finish()
     }
 }
\ No newline at end of file
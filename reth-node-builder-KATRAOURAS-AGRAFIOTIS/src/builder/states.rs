@@ -13,7 +13,7 @@ use crate::{
     rpc::{RethRpcServerHandles, RpcContext, RpcHooks},
     FullNode,
 };
-use reth_exex::ExExContext;
+use reth_exex::{ExExContext, ExExNotificationFilter};
 use reth_network::NetworkHandle;
 use reth_node_api::{FullNodeComponents, FullNodeTypes, NodeTypes};
 use reth_node_core::node_config::NodeConfig;
@@ -208,7 +208,28 @@ impl<T: FullNodeTypes, CB: NodeComponentsBuilder<T>> NodeBuilderWithComponents<T
         R: Future<Output = eyre::Result<E>> + Send,
         E: Future<Output = eyre::Result<()>> + Send,
     {
-        self.add_ons.exexs.push((exex_id.into(), Box::new(exex)));
+        self.add_ons.exexs.push((exex_id.into(), None, Box::new(exex)));
+        self
+    }
+
+    /// Installs an ExEx (Execution Extension) in the node, that only receives the notifications
+    /// matching the given filter.
+    ///
+    /// # Note
+    ///
+    /// The ExEx ID must be unique.
+    pub fn install_filtered_exex<F, R, E>(
+        mut self,
+        exex_id: impl Into<String>,
+        filter: ExExNotificationFilter,
+        exex: F,
+    ) -> Self
+    where
+        F: FnOnce(ExExContext<NodeAdapter<T, CB::Components>>) -> R + Send + 'static,
+        R: Future<Output = eyre::Result<E>> + Send,
+        E: Future<Output = eyre::Result<()>> + Send,
+    {
+        self.add_ons.exexs.push((exex_id.into(), Some(filter), Box::new(exex)));
         self
     }
 
@@ -234,6 +255,6 @@ pub(crate) struct NodeAddOns<Node: FullNodeComponents> {
     pub(crate) hooks: NodeHooks<Node>,
     /// Additional RPC hooks.
     pub(crate) rpc: RpcHooks<Node>,
-    /// The ExExs (execution extensions) of the node.
-    pub(crate) exexs: Vec<(String, Box<dyn BoxedLaunchExEx<Node>>)>,
+    /// The ExExs (execution extensions) of the node, with their optional notification filters.
+    pub(crate) exexs: Vec<(String, Option<ExExNotificationFilter>, Box<dyn BoxedLaunchExEx<Node>>)>,
 }
\ No newline at end of file
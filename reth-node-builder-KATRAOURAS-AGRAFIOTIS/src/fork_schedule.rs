@@ -0,0 +1,151 @@
+use futures::{Stream, StreamExt};
+use reth_ethereum_forks::{ChainHardforks, ForkScheduleEvent, ForkScheduler, Hardfork, Head};
+use reth_provider::{CanonStateSubscriptions, HeaderProvider};
+use reth_tracing::tracing::{debug, trace, warn};
+use tokio::sync::broadcast::{self, error::RecvError};
+use tokio_stream::wrappers::BroadcastStream;
+
+/// Size of the broadcast channel used to notify subscribers about hardfork transitions.
+const FORK_SCHEDULE_CHANNEL_SIZE: usize = 64;
+
+/// A handle to subscribe to hardfork transitions of the node.
+///
+/// Events are emitted ahead of a hardfork activation, at the activation block, and at the block
+/// after it, see [`ForkScheduleEventKind`](reth_ethereum_forks::ForkScheduleEventKind). This gives
+/// subsystems like the transaction pool or the payload builder a chance to prepare fork specific
+/// state before the first block of the fork has to be validated or built.
+#[derive(Debug, Clone)]
+pub struct ForkScheduleNotifications {
+    sender: broadcast::Sender<ForkScheduleEvent>,
+}
+
+impl ForkScheduleNotifications {
+    /// Creates a new handle without any subscribers.
+    pub fn new() -> Self {
+        let (sender, _) = broadcast::channel(FORK_SCHEDULE_CHANNEL_SIZE);
+        Self { sender }
+    }
+
+    /// Subscribes to the events of all hardforks.
+    pub fn subscribe(&self) -> broadcast::Receiver<ForkScheduleEvent> {
+        self.sender.subscribe()
+    }
+
+    /// Returns a stream of the events of the given hardfork.
+    ///
+    /// Events that were missed because the subscriber lagged behind are skipped.
+    pub fn subscribe_to<H: Hardfork>(
+        &self,
+        fork: H,
+    ) -> impl Stream<Item = ForkScheduleEvent> + Send + Unpin + 'static {
+        let name = fork.name();
+        BroadcastStream::new(self.subscribe()).filter_map(move |event| {
+            futures::future::ready(event.ok().filter(|event| event.fork.name() == name))
+        })
+    }
+
+    /// Sends the event to all current subscribers.
+    fn notify(&self, event: ForkScheduleEvent) {
+        // an error only means there are no subscribers
+        let _ = self.sender.send(event);
+    }
+}
+
+impl Default for ForkScheduleNotifications {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+/// Advances a [`ForkScheduler`] with every new canonical head, and forwards its events to the
+/// subscribers of the [`ForkScheduleNotifications`].
+///
+/// Runs until the canonical state notification channel is closed.
+pub(crate) async fn run_fork_schedule<P>(
+    provider: P,
+    hardforks: ChainHardforks,
+    head: Head,
+    notifications: ForkScheduleNotifications,
+) where
+    P: CanonStateSubscriptions + HeaderProvider,
+{
+    let mut scheduler = ForkScheduler::new(&hardforks, head);
+    let mut canon_state_notifications = provider.subscribe_to_canonical_state();
+
+    loop {
+        let notification = match canon_state_notifications.recv().await {
+            Ok(notification) => notification,
+            Err(RecvError::Lagged(skipped)) => {
+                warn!(
+                    target: "reth::cli",
+                    skipped,
+                    "Fork schedule lagged behind canonical state notifications"
+                );
+                continue
+            }
+            Err(RecvError::Closed) => break,
+        };
+
+        let tip = notification.tip();
+        let total_difficulty = match provider.header_td_by_number(tip.number) {
+            Ok(total_difficulty) => total_difficulty.unwrap_or_default(),
+            Err(err) => {
+                warn!(
+                    target: "reth::cli",
+                    %err,
+                    number = tip.number,
+                    "Failed to fetch total difficulty of the canonical tip"
+                );
+                continue
+            }
+        };
+        let head = Head {
+            number: tip.number,
+            hash: tip.hash(),
+            difficulty: tip.difficulty,
+            total_difficulty,
+            timestamp: tip.timestamp,
+        };
+        trace!(target: "reth::cli", number = head.number, "Advancing fork schedule");
+
+        for event in scheduler.on_new_head(head) {
+            debug!(
+                target: "reth::cli",
+                fork = event.fork.name(),
+                kind = ?event.kind,
+                number = head.number,
+                "Hardfork transition"
+            );
+            notifications.notify(event);
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use reth_ethereum_forks::{EthereumHardfork, ForkCondition, ForkScheduleEventKind};
+
+    #[tokio::test]
+    async fn subscribe_to_fork() {
+        let notifications = ForkScheduleNotifications::default();
+        let mut all = notifications.subscribe();
+        let mut cancun = notifications.subscribe_to(EthereumHardfork::Cancun);
+
+        let hardforks = ChainHardforks::new(vec![
+            (EthereumHardfork::Shanghai.boxed(), ForkCondition::Block(1)),
+            (EthereumHardfork::Cancun.boxed(), ForkCondition::Block(2)),
+        ]);
+        let mut scheduler = ForkScheduler::new(&hardforks, Head::default());
+        for event in scheduler.on_new_head(Head { number: 2, ..Default::default() }) {
+            notifications.notify(event);
+        }
+
+        assert!(all.recv().await.unwrap().is_for(EthereumHardfork::Shanghai));
+        assert!(all.recv().await.unwrap().is_for(EthereumHardfork::Cancun));
+
+        let event = cancun.next().await.unwrap();
+        assert!(event.is_for(EthereumHardfork::Cancun));
+        assert_eq!(event.kind, ForkScheduleEventKind::Activated);
+    }
+}
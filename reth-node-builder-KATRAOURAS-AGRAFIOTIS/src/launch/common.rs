@@ -20,7 +20,7 @@ use reth_primitives::{BlockNumber, Chain, ChainSpec, Head, PruneModes, B256};
 use reth_provider::{providers::StaticFileProvider, ProviderFactory, StaticFileProviderFactory};
 use reth_prune::PrunerBuilder;
 use reth_rpc_layer::JwtSecret;
-use reth_static_file::StaticFileProducer;
+use reth_static_file::{recover_recompressions, StaticFileProducer, StaticFileThrottle};
 use reth_tasks::TaskExecutor;
 use reth_tracing::tracing::{error, info, warn};
 
@@ -486,6 +486,13 @@ where
     ///
     /// A result containing the `ProviderFactory`.
     pub fn create_provider_factory(&self) -> eyre::Result<ProviderFactory<DB>> {
+        // Static files whose recompression was interrupted have to be recovered before they're
+        // opened
+        let recovered = recover_recompressions(&self.data_dir().static_files())?;
+        if recovered > 0 {
+            info!(target: "reth::cli", recovered, "Finished interrupted static file recompressions");
+        }
+
         let factory = ProviderFactory::new(
             self.right().clone(),
             self.chain_spec(),
@@ -551,11 +558,22 @@ where
     ///
     /// A `StaticFileProducer` instance.
     pub fn static_file_producer(&self) -> StaticFileProducer<DB> {
+        let static_files = &self.node_config().static_files;
+        let throttle = static_files.throttle.then(|| {
+            let default = StaticFileThrottle::default();
+            StaticFileThrottle {
+                chunk_size: static_files.chunk_size.unwrap_or(default.chunk_size),
+                io_budget: static_files.io_budget,
+            }
+        });
+
         StaticFileProducer::new(
             self.provider_factory().clone(),
             self.static_file_provider(),
             self.prune_modes().unwrap_or_default(),
         )
+        .with_compression(static_files.compression_config())
+        .with_throttle(throttle)
     }
 
     /// Convenience function to [Self::init_genesis].
@@ -3,6 +3,7 @@
 use crate::{
     builder::{NodeAdapter, NodeAddOns, NodeTypesAdapter},
     components::{NodeComponents, NodeComponentsBuilder},
+    fork_schedule::run_fork_schedule,
     hooks::NodeHooks,
     node::FullNode,
     BuilderContext, NodeBuilderWithComponents, NodeHandle,
@@ -18,7 +19,10 @@ use reth_blockchain_tree::{
     TreeExternals,
 };
 use reth_consensus::Consensus;
-use reth_exex::{ExExContext, ExExHandle, ExExManager, ExExManagerHandle};
+use reth_exex::{
+    BackfillJobFactory, BackfillJobSpawner, ExExContext, ExExHandle, ExExManager,
+    ExExManagerHandle, Wal,
+};
 use reth_network::NetworkEvents;
 use reth_node_api::{FullNodeComponents, FullNodeTypes};
 use reth_node_core::{
@@ -29,13 +33,13 @@ use reth_node_core::{
 };
 use reth_node_events::{cl::ConsensusLayerHealthEvents, node};
 use reth_primitives::format_ether;
-use reth_provider::{providers::BlockchainProvider, CanonStateSubscriptions};
+use reth_provider::{providers::BlockchainProvider, BlockIdReader, CanonStateSubscriptions};
 use reth_rpc_engine_api::EngineApi;
 use reth_rpc_types::engine::ClientVersionV1;
 use reth_tasks::TaskExecutor;
-use reth_tracing::tracing::{debug, info};
+use reth_tracing::tracing::{debug, error, info, warn};
 use reth_transaction_pool::TransactionPool;
-use std::{future::Future, sync::Arc};
+use std::{collections::HashMap, future::Future, sync::Arc};
 use tokio::sync::{mpsc::unbounded_channel, oneshot};
 use tokio_stream::wrappers::UnboundedReceiverStream;
 
@@ -141,6 +145,34 @@ where
         let sync_metrics_listener = reth_stages::MetricsListener::new(sync_metrics_rx);
         ctx.task_executor().spawn_critical("stages metrics listener task", sync_metrics_listener);
 
+        // Re-read the log configuration on SIGHUP
+        #[cfg(unix)]
+        if let Some(filters) = reth_tracing::log_filter_handle() {
+            let mut hangup =
+                tokio::signal::unix::signal(tokio::signal::unix::SignalKind::hangup())?;
+            ctx.task_executor().spawn(Box::pin(async move {
+                while hangup.recv().await.is_some() {
+                    match filters.reload() {
+                        Ok(()) => info!(target: "reth::cli", "Reloaded log filters"),
+                        Err(err) => {
+                            warn!(target: "reth::cli", %err, "Failed to reload log filters")
+                        }
+                    }
+                }
+            }));
+        }
+
+        // Export the last batch of spans before the node exits
+        #[cfg(feature = "otlp")]
+        ctx.task_executor().spawn_critical_with_graceful_shutdown_signal(
+            "otlp shutdown",
+            |shutdown| async move {
+                let _guard = shutdown.await;
+                // flushing the exporter blocks until the spans are exported
+                let _ = tokio::task::spawn_blocking(reth_tracing::shutdown_otlp).await;
+            },
+        );
+
         // Fetch the head block from the database
         let head = ctx.lookup_head()?;
 
@@ -175,7 +207,12 @@ where
             consensus.clone(),
             components.block_executor().clone(),
         );
-        let tree = BlockchainTree::new(tree_externals, tree_config, ctx.prune_modes())?
+        let mut tree = BlockchainTree::new(tree_externals, tree_config, ctx.prune_modes())?;
+        if ctx.node_config().tree.journal {
+            // restores the sidechains and buffered blocks of the previous run
+            tree = tree.with_journal(ctx.data_dir().blockchain_tree_journal());
+        }
+        let tree = tree
             .with_sync_metrics_tx(sync_metrics_tx.clone())
             // Note: This is required because we need to ensure that both the components and the
             // tree are using the same channel for canon state notifications. This will be removed
@@ -190,6 +227,18 @@ where
 
         debug!(target: "reth::cli", "Configured blockchain tree");
 
+        // Notify subscribers about hardfork transitions of the canonical chain
+        let fork_schedule = builder_ctx.fork_schedule().clone();
+        ctx.task_executor().spawn_critical(
+            "fork schedule",
+            run_fork_schedule(
+                blockchain_db.clone(),
+                ctx.chain_spec().hardforks.clone(),
+                head,
+                fork_schedule.clone(),
+            ),
+        );
+
         let NodeHooks { on_component_initialized, on_node_started, .. } = hooks;
 
         let node_adapter = NodeAdapter {
@@ -204,9 +253,12 @@ where
         // Spawn ExExs
         let mut exex_handles = Vec::with_capacity(installed_exex.len());
         let mut exexs = Vec::with_capacity(installed_exex.len());
-        for (id, exex) in installed_exex {
+        for (id, filter, exex) in installed_exex {
             // Create a new ExEx handle
-            let (handle, events, notifications) = ExExHandle::new(id.clone());
+            let (mut handle, events, notifications) = ExExHandle::new(id.clone());
+            if let Some(filter) = filter {
+                handle = handle.with_filter(filter);
+            }
             exex_handles.push(handle);
 
             // Create the launch context for the ExEx
@@ -245,8 +297,20 @@ where
         // Spawn ExEx manager
         let exex_manager_handle = if !exex_handles.is_empty() {
             debug!(target: "reth::cli", "Spawning ExEx manager");
+            let exex_wal = Wal::new(ctx.data_dir().exex_wal())?;
+            let exex_backfill = BackfillJobSpawner::new(
+                BackfillJobFactory::new(
+                    node_adapter.components.block_executor().clone(),
+                    blockchain_db.clone(),
+                )
+                .with_prune_modes(ctx.prune_modes().unwrap_or_default())
+                .with_batch_size(ctx.node_config().exex.backfill_batch_size),
+                ctx.task_executor().clone(),
+            );
             // TODO: Remove magic number
-            let exex_manager = ExExManager::new(exex_handles, 1024);
+            let exex_manager = ExExManager::new(exex_handles, 1024)
+                .with_wal(exex_wal)?
+                .with_backfill(exex_backfill, head.number);
             let exex_manager_handle = exex_manager.handle();
             ctx.task_executor().spawn_critical("exex manager", async move {
                 exex_manager.await.expect("ExEx manager crashed");
@@ -255,6 +319,7 @@ where
             // Send notifications from the blockchain tree to ExEx manager
             let mut canon_state_notifications = blockchain_db.subscribe_to_canonical_state();
             let mut handle = exex_manager_handle.clone();
+            let provider = blockchain_db.clone();
             ctx.task_executor().spawn_critical(
                 "exex manager blockchain tree notifications",
                 async move {
@@ -262,10 +327,48 @@ where
                         handle.send_async(notification.into()).await.expect(
                             "Blockchain tree notification could not be sent to ExEx manager",
                         );
+
+                        // Let the manager prune its WAL up to the finalized block
+                        if let Ok(Some(finalized)) = provider.finalized_block_number() {
+                            handle.update_finalized_block(finalized);
+                        }
                     }
                 },
             );
 
+            // Restart or detach ExEx's that report a failure, so that they don't hold back
+            // pruning
+            let mut finished_exex_height = exex_manager_handle.finished_height();
+            let handle = exex_manager_handle.clone();
+            let exex_args = ctx.node_config().exex;
+            ctx.task_executor().spawn(Box::pin(async move {
+                let mut restarts = HashMap::<String, usize>::new();
+                while finished_exex_height.changed().await.is_ok() {
+                    let failed = finished_exex_height
+                        .borrow_and_update()
+                        .failed()
+                        .map(|(id, reason)| (id.to_string(), reason.to_string()))
+                        .collect::<Vec<_>>();
+                    for (id, reason) in failed {
+                        let restarts = restarts.entry(id.clone()).or_default();
+                        if exex_args.should_restart(*restarts) {
+                            *restarts += 1;
+                            warn!(
+                                target: "reth::cli",
+                                id,
+                                %reason,
+                                restarts = *restarts,
+                                "ExEx failed, restarting"
+                            );
+                            handle.restart(id);
+                        } else {
+                            error!(target: "reth::cli", id, %reason, "ExEx failed, detaching");
+                            handle.detach(id);
+                        }
+                    }
+                }
+            }));
+
             info!(target: "reth::cli", "ExEx Manager started");
 
             Some(exex_manager_handle)
@@ -478,6 +581,7 @@ where
             rpc_registry,
             config: ctx.node_config().clone(),
             data_dir: ctx.data_dir().clone(),
+            fork_schedule,
         };
         // Notify on node started
         on_node_started.on_event(full_node.clone())?;
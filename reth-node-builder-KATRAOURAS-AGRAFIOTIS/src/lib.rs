@@ -42,6 +42,13 @@ pub use launch::*;
 mod handle;
 pub use handle::NodeHandle;
 
+/// Notifications about upcoming and activated hardforks.
+///
+/// This module provides a handle that components can use to subscribe
+/// to hardfork transitions of the node's chain.
+pub mod fork_schedule;
+pub use fork_schedule::ForkScheduleNotifications;
+
 /// RPC module.
 ///
 /// This module provides support for configuring and managing
@@ -1,4 +1,7 @@
-use crate::rpc::{RethRpcServerHandles, RpcRegistry};
+use crate::{
+    fork_schedule::ForkScheduleNotifications,
+    rpc::{RethRpcServerHandles, RpcRegistry},
+};
 use reth_network::NetworkHandle;
 use reth_node_api::FullNodeComponents;
 use reth_node_core::{
@@ -65,6 +68,8 @@ pub struct FullNode<Node: FullNodeComponents> {
     pub config: NodeConfig,
     /// The data directory of the node.
     pub data_dir: ChainPath<DataDirPath>,
+    /// Notifications about hardfork transitions of the node.
+    pub fork_schedule: ForkScheduleNotifications,
 }
 
 impl<Node: FullNodeComponents> FullNode<Node> {
@@ -161,6 +166,7 @@ impl<Node: FullNodeComponents> Clone for FullNode<Node> {
             rpc_registry: self.rpc_registry.clone(),
             config: self.config.clone(),
             data_dir: self.data_dir.clone(),
+            fork_schedule: self.fork_schedule.clone(),
         }
     }
 }
\ No newline at end of file
@@ -0,0 +1,674 @@
+//! Trained zstd dictionaries and offline recompression of static files.
+
+use crate::verifier::segment_files;
+use reth_fs_util::FsPathError;
+use reth_nippy_jar::{NippyJar, NippyJarCursor, NippyJarError};
+use reth_provider::ProviderError;
+use reth_static_file_types::{Compression, SegmentHeader, StaticFileSegment};
+use serde::{Deserialize, Serialize};
+use std::{
+    fs::File,
+    path::{Path, PathBuf},
+};
+use tracing::{debug, info, warn};
+
+/// Maximum size of a trained dictionary, per column.
+pub const MAX_DICTIONARY_SIZE: usize = 5_000_000;
+
+/// Maximum number of rows sampled to train dictionaries.
+pub const DICTIONARY_SAMPLES: usize = 1000;
+
+/// Extension of the file next to a static file that records the version of the dictionaries it
+/// was compressed with.
+const DICTIONARY_VERSION_EXTENSION: &str = "dict";
+
+/// Prefix of the temporary directories static files are recompressed in.
+const RECOMPRESSION_PREFIX: &str = "recompress_";
+
+/// Marker written to a temporary recompression directory once all of its files are synced.
+const RECOMPRESSION_COMPLETE: &str = "complete";
+
+/// Errors of static file compression.
+#[derive(Debug, thiserror::Error)]
+pub enum StaticFileCompressionError {
+    /// Dictionary samples or versions could not be encoded or decoded.
+    #[error("invalid dictionaries file {path:?}: {source}")]
+    Encoding {
+        /// Path of the dictionaries file.
+        path: PathBuf,
+        /// The encoding error.
+        source: bincode::Error,
+    },
+    /// [`Compression::ZstdWithDictionary`] was requested without dictionary samples.
+    #[error("no trained dictionaries for {0} static files")]
+    MissingDictionaries(StaticFileSegment),
+    /// A recompressed static file could not be synced to disk.
+    #[error("failed to sync {path:?}: {source}")]
+    Sync {
+        /// Path of the file or directory.
+        path: PathBuf,
+        /// The I/O error.
+        source: std::io::Error,
+    },
+    /// Nippy jar error.
+    #[error(transparent)]
+    NippyJar(#[from] NippyJarError),
+    /// Provider error.
+    #[error(transparent)]
+    Provider(#[from] ProviderError),
+}
+
+impl From<StaticFileCompressionError> for ProviderError {
+    fn from(err: StaticFileCompressionError) -> Self {
+        match err {
+            StaticFileCompressionError::Provider(err) => err,
+            err => Self::NippyJar(err.to_string()),
+        }
+    }
+}
+
+impl From<FsPathError> for StaticFileCompressionError {
+    fn from(err: FsPathError) -> Self {
+        Self::Provider(err.into())
+    }
+}
+
+/// Rows sampled from a static file segment to train zstd dictionaries from, one set of samples
+/// per column.
+///
+/// `reth-nippy-jar` trains the dictionaries of a jar itself from the samples it's prepared with,
+/// and stores them in the configuration of the jar. The samples are persisted in the static files
+/// directory next to the jars of the segment, so that all jars compressed with the same version
+/// of the samples train the same dictionaries. The version a jar was compressed with is recorded
+/// next to it, see [`dictionary_version`]. Sampling again creates a new version, so that jars
+/// compressed with older dictionaries can be told apart until they're recompressed.
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub struct SegmentDictionaries {
+    /// Segment the samples were taken from.
+    pub segment: StaticFileSegment,
+    /// Version of the dictionaries.
+    pub version: u32,
+    /// Sampled values of every column.
+    pub samples: Vec<Vec<Vec<u8>>>,
+}
+
+impl SegmentDictionaries {
+    /// Creates a new version of dictionaries from sampled rows, one column of samples per
+    /// dictionary.
+    pub const fn new(segment: StaticFileSegment, version: u32, samples: Vec<Vec<Vec<u8>>>) -> Self {
+        Self { segment, version, samples }
+    }
+
+    /// Creates a new version of dictionaries from rows sampled evenly across all static files of
+    /// the segment in `directory`.
+    pub fn from_static_files(
+        directory: &Path,
+        segment: StaticFileSegment,
+        samples: usize,
+    ) -> Result<Self, StaticFileCompressionError> {
+        let jars = segment_files(directory, segment)?
+            .into_iter()
+            .map(|(path, _)| NippyJar::<SegmentHeader>::load(&path))
+            .collect::<Result<Vec<_>, _>>()?;
+
+        let total_rows = jars.iter().map(|jar| jar.rows()).sum::<usize>();
+        let step = (total_rows / samples.max(1)).max(1);
+
+        let mut columns = vec![Vec::new(); segment.columns()];
+        let mut rows_before = 0;
+        for jar in &jars {
+            let mut cursor = NippyJarCursor::new(jar)?;
+            // Continue the sampling where the previous jar left off
+            let first_row = (step - rows_before % step) % step;
+            for row in (first_row..jar.rows()).step_by(step) {
+                let Some(values) = cursor.row_by_number(row)? else { break };
+                for (column, value) in columns.iter_mut().zip(values) {
+                    column.push(value.to_vec());
+                }
+            }
+            rows_before += jar.rows();
+        }
+
+        let version = Self::latest_version(directory, segment)?.map_or(1, |version| version + 1);
+        debug!(target: "static_file::compression", %segment, version, samples = ?columns.iter().map(Vec::len).collect::<Vec<_>>(), "Sampled dictionaries");
+        Ok(Self::new(segment, version, columns))
+    }
+
+    /// Returns the file name of the dictionaries of the segment with the given version.
+    pub fn filename(segment: StaticFileSegment, version: u32) -> String {
+        format!("static_file_{}_dictionaries_v{version}", segment.as_ref())
+    }
+
+    /// Returns the highest version of dictionaries of the segment stored in `directory`.
+    pub fn latest_version(
+        directory: &Path,
+        segment: StaticFileSegment,
+    ) -> Result<Option<u32>, StaticFileCompressionError> {
+        let prefix = format!("static_file_{}_dictionaries_v", segment.as_ref());
+
+        let mut latest_version = None;
+        for entry in reth_fs_util::read_dir(directory)? {
+            let path = entry.map_err(|err| FsPathError::read_dir(err, directory))?.path();
+            let version = path
+                .file_name()
+                .and_then(|name| name.to_str()?.strip_prefix(&prefix)?.parse::<u32>().ok());
+            latest_version = latest_version.max(version);
+        }
+
+        Ok(latest_version)
+    }
+
+    /// Loads the dictionaries of the segment with the given version from `directory`.
+    pub fn load(
+        directory: &Path,
+        segment: StaticFileSegment,
+        version: u32,
+    ) -> Result<Self, StaticFileCompressionError> {
+        let path = directory.join(Self::filename(segment, version));
+        let bytes = reth_fs_util::read(&path)?;
+        bincode::deserialize(&bytes)
+            .map_err(|source| StaticFileCompressionError::Encoding { path, source })
+    }
+
+    /// Loads the latest version of dictionaries of the segment from `directory`, if any.
+    pub fn load_latest(
+        directory: &Path,
+        segment: StaticFileSegment,
+    ) -> Result<Option<Self>, StaticFileCompressionError> {
+        Self::latest_version(directory, segment)?
+            .map(|version| Self::load(directory, segment, version))
+            .transpose()
+    }
+
+    /// Persists the dictionaries in `directory`, and returns the path they were written to.
+    pub fn save(&self, directory: &Path) -> Result<PathBuf, StaticFileCompressionError> {
+        let path = directory.join(Self::filename(self.segment, self.version));
+        let bytes = bincode::serialize(self).map_err(|source| {
+            StaticFileCompressionError::Encoding { path: path.clone(), source }
+        })?;
+        reth_fs_util::write(&path, bytes)?;
+        Ok(path)
+    }
+}
+
+/// Returns the version of the dictionaries the static file at `path` was compressed with, if it
+/// was compressed with [`Compression::ZstdWithDictionary`].
+pub fn dictionary_version(path: &Path) -> Result<Option<u32>, StaticFileCompressionError> {
+    let version_path = dictionary_version_path(path);
+    if !version_path.exists() {
+        return Ok(None)
+    }
+
+    let bytes = reth_fs_util::read(&version_path)?;
+    bincode::deserialize(&bytes)
+        .map_err(|source| StaticFileCompressionError::Encoding { path: version_path, source })
+}
+
+/// Records the version of the dictionaries the static file at `path` was compressed with.
+///
+/// The version is kept out of the [`SegmentHeader`], whose layout is part of the configuration of
+/// existing static files.
+pub(crate) fn write_dictionary_version(
+    path: &Path,
+    version: Option<u32>,
+) -> Result<(), StaticFileCompressionError> {
+    let version_path = dictionary_version_path(path);
+    let bytes = bincode::serialize(&version).map_err(|source| {
+        StaticFileCompressionError::Encoding { path: version_path.clone(), source }
+    })?;
+    reth_fs_util::write(&version_path, bytes)?;
+    Ok(())
+}
+
+/// Returns the path of the file next to the static file at `path` that records the version of
+/// the dictionaries it was compressed with.
+fn dictionary_version_path(path: &Path) -> PathBuf {
+    path.with_extension(DICTIONARY_VERSION_EXTENSION)
+}
+
+/// Configures the compression of a [`NippyJar`].
+///
+/// [`Compression::ZstdWithDictionary`] requires `dictionaries`, whose version is expected to be
+/// recorded next to the jar with [`write_dictionary_version`].
+pub(crate) fn with_compression(
+    jar: NippyJar<SegmentHeader>,
+    compression: Compression,
+    dictionaries: Option<&SegmentDictionaries>,
+) -> Result<NippyJar<SegmentHeader>, StaticFileCompressionError> {
+    Ok(match compression {
+        Compression::Lz4 => jar.with_lz4(),
+        Compression::Zstd => jar.with_zstd(false, 0),
+        Compression::ZstdWithDictionary => {
+            let dictionaries = dictionaries.ok_or_else(|| {
+                StaticFileCompressionError::MissingDictionaries(jar.user_header().segment())
+            })?;
+            let mut jar = jar.with_zstd(true, MAX_DICTIONARY_SIZE);
+            jar.prepare_compression(dictionaries.samples.clone())?;
+            jar
+        }
+        Compression::Uncompressed => jar,
+    })
+}
+
+/// Takes up to [`DICTIONARY_SAMPLES`] values evenly spaced over the column, starting with the
+/// most recent one.
+pub(crate) fn sample_column(column: &[Vec<u8>]) -> Vec<Vec<u8>> {
+    let step = (column.len() / DICTIONARY_SAMPLES).max(1);
+    column.iter().rev().step_by(step).take(DICTIONARY_SAMPLES).cloned().collect()
+}
+
+/// Recompresses the static file at `path` with the given compression, and returns its header.
+///
+/// The data, offsets and configuration files of the recompressed static file, and its dictionary
+/// version, are written to a temporary directory and synced to disk. A marker is then written to
+/// the directory, before the files are moved over the original ones. An interrupted recompression
+/// is finished by [`recover_recompressions`] if the marker was written, and discarded otherwise,
+/// so that a static file never ends up with files of both compressions. Inclusion filters and
+/// perfect hashing functions are not carried over.
+///
+/// NOTE: static files have to be recompressed offline, while no
+/// [`StaticFileProvider`](reth_provider::providers::StaticFileProvider) has them open.
+pub fn recompress_static_file(
+    path: &Path,
+    compression: Compression,
+    dictionaries: Option<&SegmentDictionaries>,
+) -> Result<SegmentHeader, StaticFileCompressionError> {
+    let (temp_directory, header) = prepare_recompression(path, compression, dictionaries)?;
+    finish_recompression(&temp_directory)?;
+
+    debug!(target: "static_file::compression", ?path, compression = %compression.as_ref(), "Recompressed static file");
+    Ok(header)
+}
+
+/// Writes the recompressed static file at `path` to a temporary directory, and marks it as
+/// complete once all of its files are synced.
+///
+/// Returns the temporary directory and the header of the static file.
+fn prepare_recompression(
+    path: &Path,
+    compression: Compression,
+    dictionaries: Option<&SegmentDictionaries>,
+) -> Result<(PathBuf, SegmentHeader), StaticFileCompressionError> {
+    let jar = NippyJar::<SegmentHeader>::load(path)?;
+    let (rows, columns) = (jar.rows(), jar.columns());
+    let header = jar.user_header().clone();
+
+    let version = match (compression, dictionaries) {
+        (Compression::ZstdWithDictionary, Some(dictionaries)) => Some(dictionaries.version),
+        (Compression::ZstdWithDictionary, None) => {
+            return Err(StaticFileCompressionError::MissingDictionaries(header.segment()))
+        }
+        _ => None,
+    };
+
+    let mut values = vec![Vec::with_capacity(rows); columns];
+    let mut cursor = NippyJarCursor::new(&jar)?;
+    while let Some(row) = cursor.next_row()? {
+        for (column, value) in values.iter_mut().zip(row) {
+            column.push(value.to_vec());
+        }
+    }
+    drop(cursor);
+
+    let file_name = path.file_name().expect("static file path has a file name");
+    let temp_directory =
+        path.with_file_name(format!("{RECOMPRESSION_PREFIX}{}", file_name.to_string_lossy()));
+    // Leftover of a previous recompression that didn't complete
+    if temp_directory.exists() {
+        reth_fs_util::remove_dir_all(&temp_directory)?;
+    }
+    reth_fs_util::create_dir_all(&temp_directory)?;
+
+    let temp_path = temp_directory.join(file_name);
+    let recompressed = with_compression(
+        NippyJar::new(columns, &temp_path, header.clone()),
+        compression,
+        dictionaries,
+    )?
+    .freeze(
+        values
+            .into_iter()
+            .map(|column| column.into_iter().map(Ok::<_, Box<dyn std::error::Error + Send + Sync>>))
+            .collect(),
+        rows as u64,
+    )?;
+    write_dictionary_version(&temp_path, version)?;
+
+    for file in [
+        recompressed.data_path().to_path_buf(),
+        recompressed.offsets_path(),
+        recompressed.config_path(),
+        dictionary_version_path(&temp_path),
+    ] {
+        sync_file(&file)?;
+    }
+    let marker = temp_directory.join(RECOMPRESSION_COMPLETE);
+    reth_fs_util::write(&marker, [])?;
+    sync_file(&marker)?;
+    sync_dir(&temp_directory)?;
+
+    Ok((temp_directory, header))
+}
+
+/// Moves the files of a complete recompression over the ones of the original static file, and
+/// removes the temporary directory.
+///
+/// Files that were already moved by an interrupted call are not in the temporary directory
+/// anymore, so that the recompression can be finished again by [`recover_recompressions`].
+fn finish_recompression(temp_directory: &Path) -> Result<(), StaticFileCompressionError> {
+    let directory = temp_directory.parent().expect("temporary directory has a parent");
+
+    let entries = reth_fs_util::read_dir(temp_directory)?
+        .map(|entry| entry.map(|entry| entry.path()))
+        .collect::<Result<Vec<_>, _>>()
+        .map_err(|err| FsPathError::read_dir(err, temp_directory))?;
+    for from in entries {
+        let file_name = from.file_name().expect("directory entry has a file name");
+        if file_name != RECOMPRESSION_COMPLETE {
+            reth_fs_util::rename(&from, directory.join(file_name))?;
+        }
+    }
+    sync_dir(directory)?;
+
+    reth_fs_util::remove_dir_all(temp_directory)?;
+    Ok(())
+}
+
+/// Finishes or discards the recompressions of static files in `directory` that were interrupted,
+/// e.g. by a crash, and returns the number of finished ones.
+///
+/// Recompressions whose files were all synced are finished by moving them over the files of the
+/// original static files. The other ones are discarded, which leaves the original static files
+/// untouched. Has to be called before the static files are opened.
+pub fn recover_recompressions(directory: &Path) -> Result<usize, StaticFileCompressionError> {
+    if !directory.exists() {
+        return Ok(0)
+    }
+
+    let entries = reth_fs_util::read_dir(directory)?
+        .map(|entry| entry.map(|entry| entry.path()))
+        .collect::<Result<Vec<_>, _>>()
+        .map_err(|err| FsPathError::read_dir(err, directory))?;
+
+    let mut finished = 0;
+    for path in entries {
+        let is_recompression = path.is_dir() &&
+            path.file_name()
+                .and_then(|name| name.to_str())
+                .is_some_and(|name| name.starts_with(RECOMPRESSION_PREFIX));
+        if !is_recompression {
+            continue
+        }
+
+        if path.join(RECOMPRESSION_COMPLETE).exists() {
+            warn!(target: "static_file::compression", ?path, "Finishing interrupted recompression");
+            finish_recompression(&path)?;
+            finished += 1;
+        } else {
+            warn!(target: "static_file::compression", ?path, "Discarding incomplete recompression");
+            reth_fs_util::remove_dir_all(&path)?;
+        }
+    }
+
+    Ok(finished)
+}
+
+/// Recompresses all static files of the segment in `directory` with the given compression.
+///
+/// For [`Compression::ZstdWithDictionary`], a new version of dictionaries is sampled across the
+/// static files of the segment, and persisted next to them.
+///
+/// Interrupted recompressions are recovered first, see [`recover_recompressions`]. Returns the
+/// headers of the recompressed static files. See [`recompress_static_file`].
+pub fn recompress_segment(
+    directory: &Path,
+    segment: StaticFileSegment,
+    compression: Compression,
+) -> Result<Vec<SegmentHeader>, StaticFileCompressionError> {
+    recover_recompressions(directory)?;
+
+    let dictionaries = if compression == Compression::ZstdWithDictionary {
+        let dictionaries =
+            SegmentDictionaries::from_static_files(directory, segment, DICTIONARY_SAMPLES)?;
+        dictionaries.save(directory)?;
+        Some(dictionaries)
+    } else {
+        None
+    };
+
+    let headers = segment_files(directory, segment)?
+        .into_iter()
+        .map(|(path, _)| recompress_static_file(&path, compression, dictionaries.as_ref()))
+        .collect::<Result<Vec<_>, _>>()?;
+
+    info!(target: "static_file::compression", %segment, compression = %compression.as_ref(), files = headers.len(), "Recompressed static files");
+    Ok(headers)
+}
+
+/// Syncs the file at the given path to disk.
+fn sync_file(path: &Path) -> Result<(), StaticFileCompressionError> {
+    File::open(path)
+        .and_then(|file| file.sync_all())
+        .map_err(|source| StaticFileCompressionError::Sync { path: path.to_path_buf(), source })
+}
+
+/// Syncs the directory at the given path to disk.
+#[cfg(unix)]
+fn sync_dir(path: &Path) -> Result<(), StaticFileCompressionError> {
+    sync_file(path)
+}
+
+/// Directories can't be synced on this platform.
+#[cfg(not(unix))]
+fn sync_dir(_path: &Path) -> Result<(), StaticFileCompressionError> {
+    Ok(())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use reth_static_file_types::{find_fixed_range, SegmentRangeInclusive};
+
+    /// Creates a receipts static file with `rows` rows of similar values.
+    fn create_static_file(directory: &Path, rows: u64) -> (PathBuf, Vec<Vec<u8>>) {
+        let values = (0..rows)
+            .map(|row| format!("receipt {row} status=1 gas_used={}", row * 21_000).into_bytes())
+            .collect::<Vec<_>>();
+
+        let range = find_fixed_range(0);
+        let path = directory.join(StaticFileSegment::Receipts.filename(&range));
+        let jar = NippyJar::new(
+            1,
+            &path,
+            SegmentHeader::new(
+                range,
+                Some(SegmentRangeInclusive::new(0, 0)),
+                Some(SegmentRangeInclusive::new(0, rows - 1)),
+                StaticFileSegment::Receipts,
+            ),
+        )
+        .with_lz4();
+        jar.freeze(
+            vec![values.clone().into_iter().map(Ok::<_, Box<dyn std::error::Error + Send + Sync>>)],
+            rows,
+        )
+        .unwrap();
+
+        (path, values)
+    }
+
+    fn read_column(path: &Path) -> Vec<Vec<u8>> {
+        let jar = NippyJar::<SegmentHeader>::load(path).unwrap();
+        let mut cursor = NippyJarCursor::new(&jar).unwrap();
+        let mut values = Vec::new();
+        while let Some(row) = cursor.next_row().unwrap() {
+            values.push(row[0].to_vec());
+        }
+        values
+    }
+
+    /// Returns the temporary recompression directories in `directory`.
+    fn recompression_directories(directory: &Path) -> Vec<PathBuf> {
+        std::fs::read_dir(directory)
+            .unwrap()
+            .map(|entry| entry.unwrap().path())
+            .filter(|path| path.is_dir())
+            .collect()
+    }
+
+    #[test]
+    fn sample_column_spreads_samples() {
+        let column = (0..DICTIONARY_SAMPLES as u32 * 3)
+            .map(|value| value.to_be_bytes().to_vec())
+            .collect::<Vec<_>>();
+
+        let samples = sample_column(&column);
+        assert_eq!(samples.len(), DICTIONARY_SAMPLES);
+        assert_eq!(samples.first(), column.last());
+        assert_eq!(samples.last(), Some(&column[2]));
+
+        assert_eq!(sample_column(&column[..10]).len(), 10);
+    }
+
+    #[test]
+    fn dictionaries_versions() {
+        let directory = tempfile::tempdir().unwrap();
+        let (_, values) = create_static_file(directory.path(), 2_000);
+        let segment = StaticFileSegment::Receipts;
+        assert_eq!(SegmentDictionaries::load_latest(directory.path(), segment).unwrap(), None);
+
+        let dictionaries = SegmentDictionaries::new(segment, 1, vec![values]);
+        dictionaries.save(directory.path()).unwrap();
+        assert_eq!(
+            SegmentDictionaries::load_latest(directory.path(), segment).unwrap().as_ref(),
+            Some(&dictionaries)
+        );
+
+        // Sampling again creates a new version, and keeps the previous one
+        let resampled =
+            SegmentDictionaries::from_static_files(directory.path(), segment, DICTIONARY_SAMPLES)
+                .unwrap();
+        assert_eq!(resampled.version, 2);
+        assert_eq!(resampled.samples[0].len(), DICTIONARY_SAMPLES);
+        resampled.save(directory.path()).unwrap();
+        assert_eq!(
+            SegmentDictionaries::latest_version(directory.path(), segment).unwrap(),
+            Some(2)
+        );
+        assert_eq!(SegmentDictionaries::load(directory.path(), segment, 1).unwrap(), dictionaries);
+
+        // Other segments have no dictionaries
+        assert_eq!(
+            SegmentDictionaries::latest_version(directory.path(), StaticFileSegment::Headers)
+                .unwrap(),
+            None
+        );
+    }
+
+    #[test]
+    fn recompress() {
+        let directory = tempfile::tempdir().unwrap();
+        let (path, values) = create_static_file(directory.path(), 2_000);
+        assert_eq!(dictionary_version(&path).unwrap(), None);
+
+        for compression in [
+            Compression::Zstd,
+            Compression::ZstdWithDictionary,
+            Compression::Uncompressed,
+            Compression::Lz4,
+        ] {
+            let headers =
+                recompress_segment(directory.path(), StaticFileSegment::Receipts, compression)
+                    .unwrap();
+            assert_eq!(headers.len(), 1);
+            assert_eq!(
+                dictionary_version(&path).unwrap(),
+                (compression == Compression::ZstdWithDictionary).then_some(1)
+            );
+            assert_eq!(NippyJar::<SegmentHeader>::load(&path).unwrap().user_header(), &headers[0]);
+            assert_eq!(read_column(&path), values);
+        }
+
+        assert!(recompression_directories(directory.path()).is_empty());
+        assert_matches::assert_matches!(
+            recompress_static_file(&path, Compression::ZstdWithDictionary, None),
+            Err(StaticFileCompressionError::MissingDictionaries(StaticFileSegment::Receipts))
+        );
+    }
+
+    #[test]
+    fn recover_interrupted_recompressions() {
+        let directory = tempfile::tempdir().unwrap();
+        let (path, values) = create_static_file(directory.path(), 2_000);
+        let dictionaries =
+            SegmentDictionaries::new(StaticFileSegment::Receipts, 1, vec![sample_column(&values)]);
+
+        // A recompression interrupted before it completed is discarded
+        let (temp_directory, _) =
+            prepare_recompression(&path, Compression::ZstdWithDictionary, Some(&dictionaries))
+                .unwrap();
+        std::fs::remove_file(temp_directory.join(RECOMPRESSION_COMPLETE)).unwrap();
+        assert_eq!(recover_recompressions(directory.path()).unwrap(), 0);
+        assert!(recompression_directories(directory.path()).is_empty());
+        assert_eq!(dictionary_version(&path).unwrap(), None);
+        assert_eq!(read_column(&path), values);
+
+        // A recompression interrupted while its files were moved is finished
+        let (temp_directory, _) =
+            prepare_recompression(&path, Compression::ZstdWithDictionary, Some(&dictionaries))
+                .unwrap();
+        let file_name = path.file_name().unwrap();
+        std::fs::rename(temp_directory.join(file_name), &path).unwrap();
+        assert_eq!(recover_recompressions(directory.path()).unwrap(), 1);
+        assert!(recompression_directories(directory.path()).is_empty());
+        assert_eq!(dictionary_version(&path).unwrap(), Some(1));
+        assert_eq!(read_column(&path), values);
+    }
+
+    /// The layout of [`SegmentHeader`] in the configuration of static files written before
+    /// dictionaries were introduced.
+    #[derive(Debug, Serialize, Deserialize)]
+    struct PreviousSegmentHeader {
+        expected_block_range: SegmentRangeInclusive,
+        block_range: Option<SegmentRangeInclusive>,
+        tx_range: Option<SegmentRangeInclusive>,
+        segment: StaticFileSegment,
+    }
+
+    #[test]
+    fn decode_previous_static_file_config() {
+        let directory = tempfile::tempdir().unwrap();
+        let range = find_fixed_range(0);
+        let path = directory.path().join(StaticFileSegment::Receipts.filename(&range));
+        let values = vec![b"receipt 0".to_vec(), b"receipt 1".to_vec()];
+        NippyJar::new(
+            1,
+            &path,
+            PreviousSegmentHeader {
+                expected_block_range: range,
+                block_range: Some(SegmentRangeInclusive::new(0, 0)),
+                tx_range: Some(SegmentRangeInclusive::new(0, 1)),
+                segment: StaticFileSegment::Receipts,
+            },
+        )
+        .with_lz4()
+        .freeze(
+            vec![values.clone().into_iter().map(Ok::<_, Box<dyn std::error::Error + Send + Sync>>)],
+            values.len() as u64,
+        )
+        .unwrap();
+
+        let jar = NippyJar::<SegmentHeader>::load(&path).unwrap();
+        assert_eq!(
+            jar.user_header(),
+            &SegmentHeader::new(
+                range,
+                Some(SegmentRangeInclusive::new(0, 0)),
+                Some(SegmentRangeInclusive::new(0, 1)),
+                StaticFileSegment::Receipts,
+            )
+        );
+        assert_eq!(read_column(&path), values);
+        assert_eq!(dictionary_version(&path).unwrap(), None);
+    }
+}
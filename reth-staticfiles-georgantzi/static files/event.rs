@@ -1,5 +1,7 @@
 use crate::StaticFileTargets;
-use std::time::Duration;
+use alloy_primitives::BlockNumber;
+use reth_static_file_types::StaticFileSegment;
+use std::{ops::RangeInclusive, time::Duration};
 
 /// An event emitted by a [`StaticFileProducer`][crate::StaticFileProducer].
 #[derive(Debug, PartialEq, Eq, Clone)]
@@ -9,6 +11,10 @@ pub enum StaticFileProducerEvent {
         /// Targets that will be moved to static files.
         targets: StaticFileTargets,
     },
+    /// Emitted when a segment finished copying a block range to static files.
+    ///
+    /// In throttled mode, it's emitted once for every chunk of the segment targets.
+    SegmentProgress(StaticFileSegmentProgress),
     /// Emitted when static file producer finished running.
     Finished {
         /// Targets that were moved to static files.
@@ -17,3 +23,30 @@ pub enum StaticFileProducerEvent {
         elapsed: Duration,
     },
 }
+
+/// Progress of a single segment of the static file producer.
+#[derive(Debug, PartialEq, Eq, Clone)]
+pub struct StaticFileSegmentProgress {
+    /// Segment that was copied to static files.
+    pub segment: StaticFileSegment,
+    /// Block range that was copied to static files.
+    pub block_range: RangeInclusive<BlockNumber>,
+    /// Number of rows written to static files.
+    pub rows: u64,
+    /// Number of bytes written to static files data files.
+    pub bytes: u64,
+    /// Time it took to copy the block range.
+    pub elapsed: Duration,
+}
+
+impl StaticFileSegmentProgress {
+    /// Returns the number of bytes written per second.
+    pub fn bytes_per_second(&self) -> f64 {
+        self.bytes as f64 / self.elapsed.as_secs_f64().max(f64::EPSILON)
+    }
+
+    /// Returns the number of rows written per second.
+    pub fn rows_per_second(&self) -> f64 {
+        self.rows as f64 / self.elapsed.as_secs_f64().max(f64::EPSILON)
+    }
+}
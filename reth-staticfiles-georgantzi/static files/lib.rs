@@ -10,12 +10,23 @@
 )]
 #![cfg_attr(docsrs, feature(doc_cfg, doc_auto_cfg))]
 
+mod compression;
 mod event;
+mod reader;
 pub mod segments;
 mod static_file_producer;
+mod verifier;
+mod writer;
+
+pub use compression::{
+    dictionary_version, recompress_segment, recompress_static_file, recover_recompressions,
+    SegmentDictionaries, StaticFileCompressionError, DICTIONARY_SAMPLES, MAX_DICTIONARY_SIZE,
+};
+
+pub use reader::StaticFileSegmentReader;
 
 // Re-exports the `StaticFileProducerEvent` from the `event` module.
-pub use event::StaticFileProducerEvent;
+pub use event::{StaticFileProducerEvent, StaticFileSegmentProgress};
 
 // Re-exports several items from the `static_file_producer` module.
 pub use static_file_producer::{
@@ -24,7 +35,12 @@ pub use static_file_producer::{
     StaticFileProducerResult,    // Result type for the producer's operations.
     StaticFileProducerWithResult,// Wrapper struct for the producer with result handling.
     StaticFileTargets,           // Configuration for target static files.
+    StaticFileThrottle,          // Configuration for the throttled mode of the producer.
+};
+pub use verifier::{
+    StaticFileIssue, StaticFileRepairError, StaticFileReport, StaticFileVerifier,
 };
+pub use writer::StaticFileSegmentWriter;
 
 // Re-export all items from the `reth_static_file_types` crate for convenience.
 pub use reth_static_file_types::*;
@@ -0,0 +1,143 @@
+//! Reader of the static file segments written by
+//! [`StaticFileSegmentWriter`](crate::StaticFileSegmentWriter).
+
+use crate::verifier::segment_files;
+use alloy_primitives::{Address, BlockNumber, TxNumber};
+use reth_codecs::Compact;
+use reth_db_api::{
+    models::{AccountBeforeTx, StoredBlockBodyIndices, StoredBlockOmmers, StoredBlockWithdrawals},
+    table::Decompress,
+};
+use reth_nippy_jar::{NippyJar, NippyJarCursor};
+use reth_primitives::StorageEntry;
+use reth_static_file_types::{find_fixed_range, SegmentHeader, StaticFileSegment};
+use reth_storage_errors::provider::{ProviderError, ProviderResult};
+use std::path::{Path, PathBuf};
+
+/// Reads rows of the [`StaticFileSegment::Senders`], [`StaticFileSegment::BlockBodies`],
+/// [`StaticFileSegment::AccountChangeSets`] and [`StaticFileSegment::StorageChangeSets`]
+/// segments from static files.
+///
+/// Returns [None] for rows that are not in static files (yet).
+#[derive(Debug, Clone)]
+pub struct StaticFileSegmentReader {
+    /// Directory of the static files.
+    directory: PathBuf,
+}
+
+impl StaticFileSegmentReader {
+    /// Creates a new [`StaticFileSegmentReader`] of the static files in `directory`.
+    pub fn new(directory: impl AsRef<Path>) -> Self {
+        Self { directory: directory.as_ref().to_path_buf() }
+    }
+
+    /// Returns the sender of the transaction `tx_num`.
+    pub fn transaction_sender(&self, tx_num: TxNumber) -> ProviderResult<Option<Address>> {
+        let Some([sender]) = self.tx_row::<1>(StaticFileSegment::Senders, tx_num)? else {
+            return Ok(None)
+        };
+        Ok(Some(Address::decompress(sender)?))
+    }
+
+    /// Returns the body indices, ommers and withdrawals of `block`.
+    pub fn block_body(
+        &self,
+        block: BlockNumber,
+    ) -> ProviderResult<Option<(StoredBlockBodyIndices, StoredBlockOmmers, StoredBlockWithdrawals)>>
+    {
+        let Some([indices, ommers, withdrawals]) =
+            self.block_row::<3>(StaticFileSegment::BlockBodies, block)?
+        else {
+            return Ok(None)
+        };
+        Ok(Some((
+            StoredBlockBodyIndices::decompress(indices)?,
+            StoredBlockOmmers::decompress(ommers)?,
+            StoredBlockWithdrawals::decompress(withdrawals)?,
+        )))
+    }
+
+    /// Returns all account changes of `block`.
+    pub fn account_changeset(
+        &self,
+        block: BlockNumber,
+    ) -> ProviderResult<Option<Vec<AccountBeforeTx>>> {
+        let Some([changeset]) = self.block_row::<1>(StaticFileSegment::AccountChangeSets, block)?
+        else {
+            return Ok(None)
+        };
+        Ok(Some(Vec::from_compact(&changeset, changeset.len()).0))
+    }
+
+    /// Returns all storage changes of `block`, ordered by address and storage key.
+    pub fn storage_changeset(
+        &self,
+        block: BlockNumber,
+    ) -> ProviderResult<Option<Vec<(Address, StorageEntry)>>> {
+        let Some([addresses, entries]) =
+            self.block_row::<2>(StaticFileSegment::StorageChangeSets, block)?
+        else {
+            return Ok(None)
+        };
+        let addresses = Vec::<Address>::from_compact(&addresses, addresses.len()).0;
+        let entries = Vec::<StorageEntry>::from_compact(&entries, entries.len()).0;
+        Ok(Some(addresses.into_iter().zip(entries).collect()))
+    }
+
+    /// Returns the row of `block` of a block based segment.
+    fn block_row<const COLUMNS: usize>(
+        &self,
+        segment: StaticFileSegment,
+        block: BlockNumber,
+    ) -> ProviderResult<Option<[Vec<u8>; COLUMNS]>> {
+        let path = self.directory.join(segment.filename(&find_fixed_range(block)));
+        if !path.exists() {
+            return Ok(None)
+        }
+
+        let jar = load(&path)?;
+        match jar.user_header().block_start() {
+            Some(start) if start <= block && jar.user_header().block_end() >= Some(block) => {
+                row(&jar, block - start)
+            }
+            _ => Ok(None),
+        }
+    }
+
+    /// Returns the row of the transaction `tx_num` of a transaction based segment.
+    fn tx_row<const COLUMNS: usize>(
+        &self,
+        segment: StaticFileSegment,
+        tx_num: TxNumber,
+    ) -> ProviderResult<Option<[Vec<u8>; COLUMNS]>> {
+        for (path, _) in segment_files(&self.directory, segment)? {
+            let jar = load(&path)?;
+            if let Some(start) = jar.user_header().tx_start() {
+                if start <= tx_num && jar.user_header().tx_end() >= Some(tx_num) {
+                    return row(&jar, tx_num - start)
+                }
+            }
+        }
+        Ok(None)
+    }
+}
+
+/// Loads the static file at `path`.
+fn load(path: &Path) -> ProviderResult<NippyJar<SegmentHeader>> {
+    NippyJar::load(path).map_err(|e| ProviderError::NippyJar(e.to_string()))
+}
+
+/// Reads the row with the given number of the static file.
+fn row<const COLUMNS: usize>(
+    jar: &NippyJar<SegmentHeader>,
+    row: u64,
+) -> ProviderResult<Option<[Vec<u8>; COLUMNS]>> {
+    let mut cursor =
+        NippyJarCursor::new(jar).map_err(|e| ProviderError::NippyJar(e.to_string()))?;
+    let Some(values) =
+        cursor.row_by_number(row as usize).map_err(|e| ProviderError::NippyJar(e.to_string()))?
+    else {
+        return Ok(None)
+    };
+    Ok(values.into_iter().map(<[u8]>::to_vec).collect::<Vec<_>>().try_into().ok())
+}
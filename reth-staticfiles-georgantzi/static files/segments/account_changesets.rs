@@ -0,0 +1,91 @@
+use crate::{
+    segments::{create_static_file_by_block, Segment},
+    StaticFileSegmentWriter,
+};
+use alloy_primitives::BlockNumber;
+use reth_codecs::Compact;
+use reth_db::tables;
+use reth_db_api::{
+    cursor::DbDupCursorRO, database::Database, models::AccountBeforeTx, transaction::DbTx,
+};
+use reth_provider::{providers::StaticFileProvider, DatabaseProviderRO};
+use reth_static_file_types::{SegmentConfig, StaticFileSegment};
+use reth_storage_errors::provider::ProviderResult;
+use std::{ops::RangeInclusive, path::Path};
+
+/// Static File segment responsible for [`StaticFileSegment::AccountChangeSets`] part of data.
+#[derive(Debug, Default)]
+pub struct AccountChangeSets;
+
+impl AccountChangeSets {
+    /// Reads all account changes of the given block.
+    fn changeset<DB: Database>(
+        provider: &DatabaseProviderRO<DB>,
+        block: BlockNumber,
+    ) -> ProviderResult<Vec<AccountBeforeTx>> {
+        let mut cursor = provider.tx_ref().cursor_dup_read::<tables::AccountChangeSets>()?;
+        Ok(cursor
+            .walk_dup(Some(block), None)?
+            .map(|entry| entry.map(|(_, account_before)| account_before))
+            .collect::<Result<Vec<_>, _>>()?)
+    }
+
+    /// Encodes the account changes of a block as a static file row.
+    pub(crate) fn row(changeset: Vec<AccountBeforeTx>) -> [Vec<u8>; 1] {
+        let mut buf = Vec::new();
+        changeset.to_compact(&mut buf);
+        [buf]
+    }
+}
+
+impl<DB: Database> Segment<DB> for AccountChangeSets {
+    fn segment(&self) -> StaticFileSegment {
+        StaticFileSegment::AccountChangeSets
+    }
+
+    /// Copy account changesets from the database table [`tables::AccountChangeSets`] to static
+    /// files with segment [`StaticFileSegment::AccountChangeSets`] for the provided block range.
+    ///
+    /// Every block is appended, including the ones without any account changes, so that rows
+    /// can be looked up by block number.
+    fn copy_to_static_files(
+        &self,
+        provider: DatabaseProviderRO<DB>,
+        static_file_provider: StaticFileProvider,
+        config: SegmentConfig,
+        block_range: RangeInclusive<BlockNumber>,
+    ) -> ProviderResult<()> {
+        let mut static_file_writer = StaticFileSegmentWriter::new(
+            static_file_provider.directory(),
+            StaticFileSegment::AccountChangeSets,
+            config.compression,
+            *block_range.start(),
+        )?;
+
+        for block in block_range {
+            let changeset = Self::changeset(&provider, block)?;
+            let _static_file_block =
+                static_file_writer.append_account_changeset(block, changeset)?;
+            debug_assert_eq!(_static_file_block, block);
+        }
+
+        static_file_writer.commit()
+    }
+
+    fn create_static_file_file(
+        &self,
+        provider: &DatabaseProviderRO<DB>,
+        directory: &Path,
+        config: SegmentConfig,
+        block_range: RangeInclusive<BlockNumber>,
+    ) -> ProviderResult<()> {
+        create_static_file_by_block::<DB, 1>(
+            provider,
+            directory,
+            StaticFileSegment::AccountChangeSets,
+            config,
+            block_range,
+            |block| Ok(Self::row(Self::changeset(provider, block)?)),
+        )
+    }
+}
@@ -0,0 +1,101 @@
+use crate::{
+    segments::{create_static_file_by_block, Segment},
+    StaticFileSegmentWriter,
+};
+use alloy_primitives::BlockNumber;
+use reth_db::tables;
+use reth_db_api::{
+    database::Database,
+    models::{StoredBlockBodyIndices, StoredBlockOmmers, StoredBlockWithdrawals},
+    table::Compress,
+    transaction::DbTx,
+};
+use reth_provider::{providers::StaticFileProvider, DatabaseProviderRO};
+use reth_static_file_types::{SegmentConfig, StaticFileSegment};
+use reth_storage_errors::provider::{ProviderError, ProviderResult};
+use std::{ops::RangeInclusive, path::Path};
+
+/// Static File segment responsible for [`StaticFileSegment::BlockBodies`] part of data.
+#[derive(Debug, Default)]
+pub struct BlockBodies;
+
+impl BlockBodies {
+    /// Reads the body indices, ommers and withdrawals of the given block.
+    ///
+    /// Ommers and withdrawals are only stored for blocks that have them, so missing ones are
+    /// returned empty.
+    fn block_body<DB: Database>(
+        provider: &DatabaseProviderRO<DB>,
+        block: BlockNumber,
+    ) -> ProviderResult<(StoredBlockBodyIndices, StoredBlockOmmers, StoredBlockWithdrawals)> {
+        let tx = provider.tx_ref();
+        let indices = tx
+            .get::<tables::BlockBodyIndices>(block)?
+            .ok_or(ProviderError::BlockBodyIndicesNotFound(block))?;
+        let ommers = tx.get::<tables::BlockOmmers>(block)?.unwrap_or_default();
+        let withdrawals = tx.get::<tables::BlockWithdrawals>(block)?.unwrap_or_default();
+        Ok((indices, ommers, withdrawals))
+    }
+
+    /// Encodes the body of a block as a static file row.
+    pub(crate) fn row(
+        indices: StoredBlockBodyIndices,
+        ommers: StoredBlockOmmers,
+        withdrawals: StoredBlockWithdrawals,
+    ) -> [Vec<u8>; 3] {
+        [indices.compress().into(), ommers.compress().into(), withdrawals.compress().into()]
+    }
+}
+
+impl<DB: Database> Segment<DB> for BlockBodies {
+    fn segment(&self) -> StaticFileSegment {
+        StaticFileSegment::BlockBodies
+    }
+
+    /// Copy block bodies from the database tables [`tables::BlockBodyIndices`],
+    /// [`tables::BlockOmmers`] and [`tables::BlockWithdrawals`] to static files with segment
+    /// [`StaticFileSegment::BlockBodies`] for the provided block range.
+    fn copy_to_static_files(
+        &self,
+        provider: DatabaseProviderRO<DB>,
+        static_file_provider: StaticFileProvider,
+        config: SegmentConfig,
+        block_range: RangeInclusive<BlockNumber>,
+    ) -> ProviderResult<()> {
+        let mut static_file_writer = StaticFileSegmentWriter::new(
+            static_file_provider.directory(),
+            StaticFileSegment::BlockBodies,
+            config.compression,
+            *block_range.start(),
+        )?;
+
+        for block in block_range {
+            let (indices, ommers, withdrawals) = Self::block_body(&provider, block)?;
+            let _static_file_block =
+                static_file_writer.append_block_body(block, indices, ommers, withdrawals)?;
+            debug_assert_eq!(_static_file_block, block);
+        }
+
+        static_file_writer.commit()
+    }
+
+    fn create_static_file_file(
+        &self,
+        provider: &DatabaseProviderRO<DB>,
+        directory: &Path,
+        config: SegmentConfig,
+        block_range: RangeInclusive<BlockNumber>,
+    ) -> ProviderResult<()> {
+        create_static_file_by_block::<DB, 3>(
+            provider,
+            directory,
+            StaticFileSegment::BlockBodies,
+            config,
+            block_range,
+            |block| {
+                let (indices, ommers, withdrawals) = Self::block_body(provider, block)?;
+                Ok(Self::row(indices, ommers, withdrawals))
+            },
+        )
+    }
+}
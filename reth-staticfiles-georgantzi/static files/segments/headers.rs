@@ -25,6 +25,7 @@ impl<DB: Database> Segment<DB> for Headers {
         &self,
         provider: DatabaseProviderRO<DB>,
         static_file_provider: StaticFileProvider,
+        _config: SegmentConfig,
         block_range: RangeInclusive<BlockNumber>,
     ) -> ProviderResult<()> {
         // Retrieve a writer for the static file segment within the specified block range
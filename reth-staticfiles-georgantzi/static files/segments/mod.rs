@@ -10,7 +10,23 @@ pub use headers::Headers; // Export `Headers` module
 mod receipts;
 pub use receipts::Receipts; // Export `Receipts` module
 
+mod senders;
+pub use senders::Senders;
+
+mod block_bodies;
+pub use block_bodies::BlockBodies;
+
+mod account_changesets;
+pub use account_changesets::AccountChangeSets;
+
+mod storage_changesets;
+pub use storage_changesets::StorageChangeSets;
+
 // Standard library and external crate imports
+use crate::compression::{
+    sample_column, with_compression, write_dictionary_version, SegmentDictionaries,
+    DICTIONARY_SAMPLES,
+};
 use alloy_primitives::BlockNumber;
 use reth_db::{RawKey, RawTable}; // Database related imports
 use reth_db_api::{cursor::DbCursorRO, database::Database, table::Table, transaction::DbTx}; // Database API imports
@@ -34,10 +50,17 @@ pub trait Segment<DB: Database>: Send + Sync {
     fn segment(&self) -> StaticFileSegment;
 
     /// Copies data to static files for the provided block range.
+    ///
+    /// The compression of `config` applies to the static files created by the copy. The
+    /// [`StaticFileSegment::Headers`], [`StaticFileSegment::Transactions`] and
+    /// [`StaticFileSegment::Receipts`] segments are written by the [`StaticFileProvider`], which
+    /// configures the compression of their static files itself. They can still be recompressed
+    /// offline with [`recompress_segment`](crate::recompress_segment).
     fn copy_to_static_files(
         &self,
         provider: DatabaseProviderRO<DB>,
         static_file_provider: StaticFileProvider,
+        config: SegmentConfig,
         block_range: RangeInclusive<BlockNumber>,
     ) -> ProviderResult<()>;
 
@@ -63,33 +86,46 @@ pub(crate) fn prepare_jar<DB: Database, const COLUMNS: usize>(
     prepare_compression: impl Fn() -> ProviderResult<Rows<COLUMNS>>,
 ) -> ProviderResult<NippyJar<SegmentHeader>> {
     // Determine transaction range based on the segment type
-    let tx_range = match segment {
-        StaticFileSegment::Headers => None,
-        StaticFileSegment::Receipts | StaticFileSegment::Transactions => {
-            Some(provider.transaction_range_by_block_range(block_range.clone())?.into())
-        }
+    let tx_range = if segment.is_tx_based() {
+        Some(provider.transaction_range_by_block_range(block_range.clone())?.into())
+    } else {
+        None
+    };
+
+    let path = directory.as_ref().join(segment.filename(&find_fixed_range(*block_range.end())));
+    let header =
+        SegmentHeader::new(block_range.clone().into(), Some(block_range.into()), tx_range, segment);
+
+    // Dictionary samples are taken once per segment, and reused by the following static files
+    // until they're resampled
+    let dictionaries = if segment_config.compression == Compression::ZstdWithDictionary {
+        let dictionaries = match SegmentDictionaries::load_latest(directory.as_ref(), segment)? {
+            Some(dictionaries) => dictionaries,
+            None => {
+                let dictionaries =
+                    SegmentDictionaries::new(segment, 1, prepare_compression()?.to_vec());
+                dictionaries.save(directory.as_ref())?;
+                dictionaries
+            }
+        };
+        Some(dictionaries)
+    } else {
+        None
     };
 
+    // The dictionary version is recorded next to the static file, since the layout of the header
+    // is part of its configuration
+    write_dictionary_version(
+        &path,
+        dictionaries.as_ref().map(|dictionaries| dictionaries.version),
+    )?;
+
     // Initialize a `NippyJar` instance
-    let mut nippy_jar = NippyJar::new(
-        COLUMNS,
-        &directory.as_ref().join(segment.filename(&find_fixed_range(*block_range.end())).as_str()),
-        SegmentHeader::new(block_range.clone().into(), Some(block_range.into()), tx_range, segment),
-    );
+    let nippy_jar = NippyJar::new(COLUMNS, &path, header);
 
     // Handle compression based on segment configuration
-    nippy_jar = match segment_config.compression {
-        Compression::Lz4 => nippy_jar.with_lz4(),
-        Compression::Zstd => nippy_jar.with_zstd(false, 0),
-        Compression::ZstdWithDictionary => {
-            let dataset = prepare_compression()?;
-            nippy_jar = nippy_jar.with_zstd(true, 5_000_000);
-            nippy_jar.prepare_compression(dataset.to_vec())
-                .map_err(|e| ProviderError::NippyJar(e.to_string()))?;
-            nippy_jar
-        }
-        Compression::Uncompressed => nippy_jar,
-    };
+    let mut nippy_jar =
+        with_compression(nippy_jar, segment_config.compression, dictionaries.as_ref())?;
 
     // Handle inclusion filters and perfect hashing functions
     if let Filters::WithFilters(inclusion_filter, phf) = segment_config.filters {
@@ -105,15 +141,62 @@ pub(crate) fn prepare_jar<DB: Database, const COLUMNS: usize>(
     Ok(nippy_jar)
 }
 
-/// Generates the dataset for compression using the most recent rows.
+/// Generates the dataset for compression using rows sampled evenly over the range, starting with
+/// the most recent one.
 pub(crate) fn dataset_for_compression<DB: Database, T: Table<Key = u64>>(
     provider: &DatabaseProviderRO<DB>,
     range: &RangeInclusive<u64>,
     range_len: usize,
 ) -> ProviderResult<Vec<Vec<u8>>> {
     let mut cursor = provider.tx_ref().cursor_read::<RawTable<T>>()?;
-    Ok(cursor.walk_back(Some(RawKey::from(*range.end())))?
-        .take(range_len.min(1000))
-        .map(|row| row.map(|(_key, value)| value.into_value()).expect("should exist"))
-        .collect::<Vec<_>>())
+    let step = (range_len / DICTIONARY_SAMPLES).max(1);
+
+    let mut dataset = Vec::with_capacity(range_len.min(DICTIONARY_SAMPLES));
+    for key in range.clone().rev().step_by(step).take(DICTIONARY_SAMPLES) {
+        if let Some((_key, value)) = cursor.seek_exact(RawKey::from(key))? {
+            dataset.push(value.into_value());
+        }
+    }
+
+    Ok(dataset)
+}
+
+/// Creates a static file for a block based segment whose rows are built per block, rather than
+/// copied from a single table entry.
+pub(crate) fn create_static_file_by_block<DB: Database, const COLUMNS: usize>(
+    provider: &DatabaseProviderRO<DB>,
+    directory: &Path,
+    segment: StaticFileSegment,
+    config: SegmentConfig,
+    block_range: RangeInclusive<BlockNumber>,
+    row: impl Fn(BlockNumber) -> ProviderResult<[Vec<u8>; COLUMNS]>,
+) -> ProviderResult<()> {
+    let range_len = block_range.clone().count();
+    let mut rows: Rows<COLUMNS> = std::array::from_fn(|_| Vec::with_capacity(range_len));
+    for block in block_range.clone() {
+        for (column, value) in rows.iter_mut().zip(row(block)?) {
+            column.push(value);
+        }
+    }
+
+    let jar = prepare_jar::<DB, COLUMNS>(
+        provider,
+        directory,
+        segment,
+        config,
+        block_range,
+        range_len,
+        // Same as for table based segments, rows are sampled evenly over the block range
+        || Ok(rows.each_ref().map(|column| sample_column(column))),
+    )?;
+
+    jar.freeze(
+        rows.into_iter()
+            .map(|column| column.into_iter().map(Ok::<_, Box<dyn std::error::Error + Send + Sync>>))
+            .collect(),
+        range_len as u64,
+    )
+    .map_err(|e| ProviderError::NippyJar(e.to_string()))?;
+
+    Ok(())
 }
@@ -26,6 +26,7 @@ impl<DB: Database> Segment<DB> for Receipts {
         &self,
         provider: DatabaseProviderRO<DB>,
         static_file_provider: StaticFileProvider,
+        _config: SegmentConfig,
         block_range: RangeInclusive<BlockNumber>,
     ) -> ProviderResult<()> {
         // Get a writer for the static file segment based on the starting block number
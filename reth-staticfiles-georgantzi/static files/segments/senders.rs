@@ -0,0 +1,112 @@
+use crate::{
+    segments::{dataset_for_compression, prepare_jar, Segment},
+    StaticFileSegmentWriter,
+};
+use alloy_primitives::{BlockNumber, TxNumber};
+use reth_db::{static_file::create_static_file_T1, tables};
+use reth_db_api::{cursor::DbCursorRO, database::Database, transaction::DbTx};
+use reth_provider::{
+    providers::StaticFileProvider, BlockReader, DatabaseProviderRO, TransactionsProviderExt,
+};
+use reth_static_file_types::{SegmentConfig, SegmentHeader, StaticFileSegment};
+use reth_storage_errors::provider::{ProviderError, ProviderResult};
+use std::{ops::RangeInclusive, path::Path};
+
+/// Static File segment responsible for [`StaticFileSegment::Senders`] part of data.
+#[derive(Debug, Default)]
+pub struct Senders;
+
+impl<DB: Database> Segment<DB> for Senders {
+    fn segment(&self) -> StaticFileSegment {
+        StaticFileSegment::Senders
+    }
+
+    /// Copy recovered senders from the database table [`tables::TransactionSenders`] to static
+    /// files with segment [`StaticFileSegment::Senders`] for the provided block range.
+    fn copy_to_static_files(
+        &self,
+        provider: DatabaseProviderRO<DB>,
+        static_file_provider: StaticFileProvider,
+        config: SegmentConfig,
+        block_range: RangeInclusive<BlockNumber>,
+    ) -> ProviderResult<()> {
+        let mut static_file_writer = StaticFileSegmentWriter::new(
+            static_file_provider.directory(),
+            StaticFileSegment::Senders,
+            config.compression,
+            *block_range.start(),
+        )?;
+
+        for block in block_range {
+            let _static_file_block = static_file_writer.increment_block(block)?;
+            debug_assert_eq!(_static_file_block, block);
+
+            let block_body_indices = provider
+                .block_body_indices(block)?
+                .ok_or(ProviderError::BlockBodyIndicesNotFound(block))?;
+
+            let mut senders_cursor =
+                provider.tx_ref().cursor_read::<tables::TransactionSenders>()?;
+            let senders_walker = senders_cursor.walk_range(block_body_indices.tx_num_range())?;
+
+            for entry in senders_walker {
+                let (tx_number, sender) = entry?;
+                static_file_writer.append_transaction_sender(tx_number, sender)?;
+            }
+        }
+
+        static_file_writer.commit()
+    }
+
+    fn create_static_file_file(
+        &self,
+        provider: &DatabaseProviderRO<DB>,
+        directory: &Path,
+        config: SegmentConfig,
+        block_range: RangeInclusive<BlockNumber>,
+    ) -> ProviderResult<()> {
+        let tx_range = provider.transaction_range_by_block_range(block_range.clone())?;
+        let tx_range_len = tx_range.clone().count();
+
+        let jar = prepare_jar::<DB, 1>(
+            provider,
+            directory,
+            StaticFileSegment::Senders,
+            config,
+            block_range,
+            tx_range_len,
+            || {
+                Ok([dataset_for_compression::<DB, tables::TransactionSenders>(
+                    provider,
+                    &tx_range,
+                    tx_range_len,
+                )?])
+            },
+        )?;
+
+        // Generate list of transaction hashes for filters & PHF, if configured
+        let hashes = if config.filters.has_filters() {
+            Some(
+                provider
+                    .transaction_hashes_by_range(*tx_range.start()..(*tx_range.end() + 1))?
+                    .into_iter()
+                    .map(|(tx, _)| Ok(tx)),
+            )
+        } else {
+            None
+        };
+
+        create_static_file_T1::<tables::TransactionSenders, TxNumber, SegmentHeader>(
+            provider.tx_ref(),
+            tx_range,
+            None,
+            // We already prepared the dictionary beforehand
+            None::<Vec<std::vec::IntoIter<Vec<u8>>>>,
+            hashes,
+            tx_range_len,
+            jar,
+        )?;
+
+        Ok(())
+    }
+}
@@ -0,0 +1,97 @@
+use crate::{
+    segments::{create_static_file_by_block, Segment},
+    StaticFileSegmentWriter,
+};
+use alloy_primitives::{Address, BlockNumber};
+use reth_codecs::Compact;
+use reth_db::tables;
+use reth_db_api::{
+    cursor::DbCursorRO, database::Database, models::BlockNumberAddress, transaction::DbTx,
+};
+use reth_primitives::StorageEntry;
+use reth_provider::{providers::StaticFileProvider, DatabaseProviderRO};
+use reth_static_file_types::{SegmentConfig, StaticFileSegment};
+use reth_storage_errors::provider::ProviderResult;
+use std::{ops::RangeInclusive, path::Path};
+
+/// Static File segment responsible for [`StaticFileSegment::StorageChangeSets`] part of data.
+#[derive(Debug, Default)]
+pub struct StorageChangeSets;
+
+impl StorageChangeSets {
+    /// Reads all storage changes of the given block, ordered by address and storage key.
+    fn changeset<DB: Database>(
+        provider: &DatabaseProviderRO<DB>,
+        block: BlockNumber,
+    ) -> ProviderResult<Vec<(Address, StorageEntry)>> {
+        let mut cursor = provider.tx_ref().cursor_read::<tables::StorageChangeSets>()?;
+        Ok(cursor
+            .walk_range(BlockNumberAddress::range(block..=block))?
+            .map(|entry| entry.map(|(key, storage_before)| (key.address(), storage_before)))
+            .collect::<Result<Vec<_>, _>>()?)
+    }
+
+    /// Encodes the storage changes of a block as a static file row, with the addresses and the
+    /// storage entries in separate columns.
+    pub(crate) fn row(changeset: Vec<(Address, StorageEntry)>) -> [Vec<u8>; 2] {
+        let (addresses, entries): (Vec<_>, Vec<_>) = changeset.into_iter().unzip();
+        let (mut addresses_buf, mut entries_buf) = (Vec::new(), Vec::new());
+        addresses.to_compact(&mut addresses_buf);
+        entries.to_compact(&mut entries_buf);
+        [addresses_buf, entries_buf]
+    }
+}
+
+impl<DB: Database> Segment<DB> for StorageChangeSets {
+    fn segment(&self) -> StaticFileSegment {
+        StaticFileSegment::StorageChangeSets
+    }
+
+    /// Copy storage changesets from the database table [`tables::StorageChangeSets`] to static
+    /// files with segment [`StaticFileSegment::StorageChangeSets`] for the provided block range.
+    ///
+    /// Every block is appended, including the ones without any storage changes, so that rows
+    /// can be looked up by block number.
+    fn copy_to_static_files(
+        &self,
+        provider: DatabaseProviderRO<DB>,
+        static_file_provider: StaticFileProvider,
+        config: SegmentConfig,
+        block_range: RangeInclusive<BlockNumber>,
+    ) -> ProviderResult<()> {
+        let mut static_file_writer = StaticFileSegmentWriter::new(
+            static_file_provider.directory(),
+            StaticFileSegment::StorageChangeSets,
+            config.compression,
+            *block_range.start(),
+        )?;
+
+        for block in block_range {
+            let changeset = Self::changeset(&provider, block)?;
+            let _static_file_block =
+                static_file_writer.append_storage_changeset(block, changeset)?;
+            debug_assert_eq!(_static_file_block, block);
+        }
+
+        static_file_writer.commit()
+    }
+
+    /// Creates a static file with the addresses and the storage entries of every block's
+    /// changeset in separate columns, see [`StorageChangeSets::row`].
+    fn create_static_file_file(
+        &self,
+        provider: &DatabaseProviderRO<DB>,
+        directory: &Path,
+        config: SegmentConfig,
+        block_range: RangeInclusive<BlockNumber>,
+    ) -> ProviderResult<()> {
+        create_static_file_by_block::<DB, 2>(
+            provider,
+            directory,
+            StaticFileSegment::StorageChangeSets,
+            config,
+            block_range,
+            |block| Ok(Self::row(Self::changeset(provider, block)?)),
+        )
+    }
+}
@@ -27,6 +27,7 @@ impl<DB: Database> Segment<DB> for Transactions {
         &self,
         provider: DatabaseProviderRO<DB>, // Database provider read-only reference
         static_file_provider: StaticFileProvider, // Static file provider
+        _config: SegmentConfig, // Compression of the static files is configured by the provider
         block_range: RangeInclusive<BlockNumber>, // Range of blocks to process
     ) -> ProviderResult<()> {
         // Get a writer for the static file segment based on the starting block number
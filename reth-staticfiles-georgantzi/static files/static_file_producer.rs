@@ -1,23 +1,26 @@
 //! Support for producing static files.
 
-use crate::{segments, segments::Segment, StaticFileProducerEvent};
+use crate::{
+    segments, segments::Segment, verifier::segment_files, StaticFileCompressionConfig,
+    StaticFileProducerEvent, StaticFileSegmentProgress,
+};
 use alloy_primitives::BlockNumber;
 use parking_lot::Mutex;
 use rayon::prelude::*;
 use reth_db_api::database::Database;
 use reth_provider::{
-    providers::StaticFileWriter, ProviderFactory, StageCheckpointReader as _,
-    StaticFileProviderFactory,
+    providers::{StaticFileProvider, StaticFileWriter},
+    ProviderFactory, StageCheckpointReader as _, StaticFileProviderFactory,
 };
 use reth_prune_types::PruneModes;
 use reth_stages_types::StageId;
-use reth_static_file_types::HighestStaticFiles;
+use reth_static_file_types::{HighestStaticFiles, StaticFileSegment};
 use reth_storage_errors::provider::ProviderResult;
 use reth_tokio_util::{EventSender, EventStream};
 use std::{
     ops::{Deref, RangeInclusive},
     sync::Arc,
-    time::Instant,
+    time::{Duration, Instant},
 };
 use tracing::{debug, trace};
 
@@ -37,6 +40,113 @@ impl<DB: Database> StaticFileProducer<DB> {
     pub fn new(provider_factory: ProviderFactory<DB>, prune_modes: PruneModes) -> Self {
         Self(Arc::new(Mutex::new(StaticFileProducerInner::new(provider_factory, prune_modes))))
     }
+
+    /// Sets the compression of the static files created by the producer, per segment.
+    pub fn with_compression(self, compression: StaticFileCompressionConfig) -> Self {
+        self.lock().compression = compression;
+        self
+    }
+
+    /// Sets whether the senders, block bodies and changesets are also copied to static files.
+    ///
+    /// Disabled by default. The data of these segments is kept in the database either way, since
+    /// the providers don't read it from static files yet.
+    pub fn with_extended_segments(self, extended_segments: bool) -> Self {
+        self.lock().extended_segments = extended_segments;
+        self
+    }
+
+    /// Sets the throttle of the producer, used by [`StaticFileProducer::run`]. Unthrottled if
+    /// [`None`], which is the default.
+    pub fn with_throttle(self, throttle: Option<StaticFileThrottle>) -> Self {
+        self.lock().throttle = throttle;
+        self
+    }
+
+    /// Run the `static_file_producer` with the configured throttle.
+    ///
+    /// Same as [`StaticFileProducer::run_throttled`] if the producer is throttled, see
+    /// [`StaticFileProducer::with_throttle`], and as [`StaticFileProducerInner::run`] otherwise.
+    pub fn run(&self, targets: StaticFileTargets) -> StaticFileProducerResult {
+        let throttle = self.lock().throttle;
+        match throttle {
+            Some(throttle) => self.run_throttled(targets, throttle),
+            None => self.lock().run(targets),
+        }
+    }
+
+    /// Run the `static_file_producer` in throttled mode.
+    ///
+    /// Same as [`StaticFileProducerInner::run`], but the targets are moved to static files in
+    /// chunks of at most [`StaticFileThrottle::chunk_size`] blocks per segment. The lock is only
+    /// held while a chunk is produced, and released between chunks for at least as long as needed
+    /// to stay within [`StaticFileThrottle::io_budget`], so that the producer doesn't compete with
+    /// the live sync for disk I/O.
+    pub fn run_throttled(
+        &self,
+        targets: StaticFileTargets,
+        throttle: StaticFileThrottle,
+    ) -> StaticFileProducerResult {
+        if !targets.any() {
+            return Ok(targets)
+        }
+
+        let start = Instant::now();
+        self.lock().notify_started(&targets);
+
+        for chunk in targets.chunks(throttle.chunk_size) {
+            let chunk_start = Instant::now();
+            let bytes = {
+                let producer = self.lock();
+                // Another run could have moved some of the blocks while the lock was released
+                let chunk = chunk.without_produced(
+                    producer.provider_factory.static_file_provider().get_highest_static_files(),
+                );
+                if !chunk.any() {
+                    continue
+                }
+                producer.produce(&chunk)?
+            };
+
+            let pause = throttle.pause(bytes, chunk_start.elapsed());
+            trace!(target: "static_file", ?chunk, bytes, ?pause, "StaticFileProducer chunk finished");
+            if pause.is_zero() {
+                std::thread::yield_now();
+            } else {
+                std::thread::sleep(pause);
+            }
+        }
+
+        self.lock().notify_finished(&targets, start.elapsed());
+
+        Ok(targets)
+    }
+}
+
+/// Configuration of the throttled mode of the static file producer. See
+/// [`StaticFileProducer::run_throttled`].
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct StaticFileThrottle {
+    /// Maximum number of blocks moved to static files per segment at once.
+    pub chunk_size: u64,
+    /// Maximum number of bytes written to static files per second. Unlimited if [`None`].
+    pub io_budget: Option<u64>,
+}
+
+impl Default for StaticFileThrottle {
+    fn default() -> Self {
+        Self { chunk_size: 10_000, io_budget: None }
+    }
+}
+
+impl StaticFileThrottle {
+    /// Returns the time to pause for after writing `bytes` in `elapsed` time, so that the write
+    /// rate stays within the I/O budget.
+    pub fn pause(&self, bytes: u64, elapsed: Duration) -> Duration {
+        self.io_budget.map_or(Duration::ZERO, |io_budget| {
+            Duration::from_secs_f64(bytes as f64 / io_budget.max(1) as f64).saturating_sub(elapsed)
+        })
+    }
 }
 
 impl<DB> Deref for StaticFileProducer<DB> {
@@ -57,12 +167,19 @@ pub struct StaticFileProducerInner<DB> {
     /// needed in [`StaticFileProducerInner`] to prevent attempting to move prunable data to static
     /// files. See [`StaticFileProducerInner::get_static_file_targets`].
     prune_modes: PruneModes,
+    /// Compression of the static files, per segment.
+    compression: StaticFileCompressionConfig,
+    /// Whether the senders, block bodies and changesets segments are copied to static files. See
+    /// [`StaticFileProducer::with_extended_segments`].
+    extended_segments: bool,
+    /// Throttle of the producer. See [`StaticFileProducer::with_throttle`].
+    throttle: Option<StaticFileThrottle>,
     /// Event sender to notify about the progress and state of the static file production
     event_sender: EventSender<StaticFileProducerEvent>,
 }
 
 /// Static File targets, per data segment, measured in [`BlockNumber`].
-#[derive(Debug, Clone, Eq, PartialEq)]
+#[derive(Debug, Clone, Default, Eq, PartialEq)]
 pub struct StaticFileTargets {
     /// Block range for headers segment
     headers: Option<RangeInclusive<BlockNumber>>,
@@ -70,12 +187,26 @@ pub struct StaticFileTargets {
     receipts: Option<RangeInclusive<BlockNumber>>,
     /// Block range for transactions segment
     transactions: Option<RangeInclusive<BlockNumber>>,
+    /// Block range for transaction senders segment
+    senders: Option<RangeInclusive<BlockNumber>>,
+    /// Block range for block bodies segment
+    block_bodies: Option<RangeInclusive<BlockNumber>>,
+    /// Block range for account changesets segment
+    account_changesets: Option<RangeInclusive<BlockNumber>>,
+    /// Block range for storage changesets segment
+    storage_changesets: Option<RangeInclusive<BlockNumber>>,
 }
 
 impl StaticFileTargets {
     /// Returns `true` if any of the targets are [Some].
     pub const fn any(&self) -> bool {
-        self.headers.is_some() || self.receipts.is_some() || self.transactions.is_some()
+        self.headers.is_some() ||
+            self.receipts.is_some() ||
+            self.transactions.is_some() ||
+            self.senders.is_some() ||
+            self.block_bodies.is_some() ||
+            self.account_changesets.is_some() ||
+            self.storage_changesets.is_some()
     }
 
     // Returns `true` if all targets are either [`None`] or has beginning of the range equal to the
@@ -85,6 +216,10 @@ impl StaticFileTargets {
             (self.headers.as_ref(), static_files.headers),
             (self.receipts.as_ref(), static_files.receipts),
             (self.transactions.as_ref(), static_files.transactions),
+            (self.senders.as_ref(), static_files.senders),
+            (self.block_bodies.as_ref(), static_files.block_bodies),
+            (self.account_changesets.as_ref(), static_files.account_changesets),
+            (self.storage_changesets.as_ref(), static_files.storage_changesets),
         ]
         .iter()
         .all(|(target_block_range, highest_static_fileted_block)| {
@@ -96,12 +231,72 @@ impl StaticFileTargets {
             })
         })
     }
+
+    /// Splits the targets into chunks of at most `chunk_size` blocks per segment.
+    fn chunks(&self, chunk_size: u64) -> impl Iterator<Item = Self> + '_ {
+        let chunk_size = chunk_size.max(1);
+        let chunk = move |target: &Option<RangeInclusive<BlockNumber>>, index: u64| {
+            target.as_ref().and_then(|target| {
+                let start = target.start().checked_add(index.checked_mul(chunk_size)?)?;
+                (start <= *target.end())
+                    .then(|| start..=start.saturating_add(chunk_size - 1).min(*target.end()))
+            })
+        };
+
+        (0..)
+            .map(move |index| Self {
+                headers: chunk(&self.headers, index),
+                receipts: chunk(&self.receipts, index),
+                transactions: chunk(&self.transactions, index),
+                senders: chunk(&self.senders, index),
+                block_bodies: chunk(&self.block_bodies, index),
+                account_changesets: chunk(&self.account_changesets, index),
+                storage_changesets: chunk(&self.storage_changesets, index),
+            })
+            .take_while(Self::any)
+    }
+
+    /// Returns the targets without the blocks that are already in static files.
+    fn without_produced(&self, static_files: HighestStaticFiles) -> Self {
+        let remaining = |target: &Option<RangeInclusive<BlockNumber>>,
+                         highest_static_file: Option<BlockNumber>| {
+            target.as_ref().and_then(|target| {
+                let start = highest_static_file
+                    .map_or(*target.start(), |block| (*target.start()).max(block + 1));
+                let range = start..=*target.end();
+                (!range.is_empty()).then_some(range)
+            })
+        };
+
+        Self {
+            headers: remaining(&self.headers, static_files.headers),
+            receipts: remaining(&self.receipts, static_files.receipts),
+            transactions: remaining(&self.transactions, static_files.transactions),
+            senders: remaining(&self.senders, static_files.senders),
+            block_bodies: remaining(&self.block_bodies, static_files.block_bodies),
+            account_changesets: remaining(
+                &self.account_changesets,
+                static_files.account_changesets,
+            ),
+            storage_changesets: remaining(
+                &self.storage_changesets,
+                static_files.storage_changesets,
+            ),
+        }
+    }
 }
 
 impl<DB: Database> StaticFileProducerInner<DB> {
     /// Creates a new instance of [`StaticFileProducerInner`].
     fn new(provider_factory: ProviderFactory<DB>, prune_modes: PruneModes) -> Self {
-        Self { provider_factory, prune_modes, event_sender: Default::default() }
+        Self {
+            provider_factory,
+            prune_modes,
+            compression: Default::default(),
+            extended_segments: false,
+            throttle: None,
+            event_sender: Default::default(),
+        }
     }
 
     /// Listen for events on the `static_file_producer`.
@@ -129,11 +324,41 @@ impl<DB: Database> StaticFileProducerInner<DB> {
             self.provider_factory.static_file_provider().get_highest_static_files()
         ));
 
+        self.notify_started(&targets);
+        let start = Instant::now();
+
+        self.produce(&targets)?;
+
+        self.notify_finished(&targets, start.elapsed());
+
+        Ok(targets)
+    }
+
+    /// Notifies the listeners and logs that the `static_file_producer` started.
+    fn notify_started(&self, targets: &StaticFileTargets) {
         self.event_sender.notify(StaticFileProducerEvent::Started { targets: targets.clone() });
         // Log debug information indicating that the StaticFileProducer has started,
         // including the targets.
         debug!(target: "static_file", ?targets, "StaticFileProducer started");
-        let start = Instant::now();
+    }
+
+    /// Notifies the listeners and logs that the `static_file_producer` finished.
+    fn notify_finished(&self, targets: &StaticFileTargets, elapsed: Duration) {
+        // TODO(alexey): track in metrics
+        debug!(target: "static_file", ?targets, ?elapsed, "StaticFileProducer finished");
+        // Notify event listeners that the StaticFileProducer has finished processing,
+        // including the targets and the elapsed time.
+        self.event_sender
+            .notify(StaticFileProducerEvent::Finished { targets: targets.clone(), elapsed });
+    }
+
+    /// Moves the targets to static files, and notifies the listeners about the progress of every
+    /// segment with [`StaticFileProducerEvent::SegmentProgress`].
+    ///
+    /// Returns the number of bytes written to static files.
+    fn produce(&self, targets: &StaticFileTargets) -> ProviderResult<u64> {
+        let static_file_provider = self.provider_factory.static_file_provider();
+
         /// Initialize a vector to hold segments and their corresponding block ranges.
         let mut segments = Vec::<(Box<dyn Segment<DB>>, RangeInclusive<BlockNumber>)>::new();
         // If there is a range of blocks to process for transactions, add it to the segments vector.
@@ -148,39 +373,65 @@ impl<DB: Database> StaticFileProducerInner<DB> {
         if let Some(block_range) = targets.receipts.clone() {
             segments.push((Box::new(segments::Receipts), block_range));
         }
+        if let Some(block_range) = targets.senders.clone() {
+            segments.push((Box::new(segments::Senders), block_range));
+        }
+        if let Some(block_range) = targets.block_bodies.clone() {
+            segments.push((Box::new(segments::BlockBodies), block_range));
+        }
+        if let Some(block_range) = targets.account_changesets.clone() {
+            segments.push((Box::new(segments::AccountChangeSets), block_range));
+        }
+        if let Some(block_range) = targets.storage_changesets.clone() {
+            segments.push((Box::new(segments::StorageChangeSets), block_range));
+        }
+
+        let stats_before = segments
+            .iter()
+            .map(|(segment, _)| segment_stats(&static_file_provider, segment.segment()))
+            .collect::<ProviderResult<Vec<_>>>()?;
 
-        segments.par_iter().try_for_each(|(segment, block_range)| -> ProviderResult<()> {
+        let elapsed = segments.par_iter().map(|(segment, block_range)| -> ProviderResult<Duration> {
             debug!(target: "static_file", segment = %segment.segment(), ?block_range, "StaticFileProducer segment");
             let start = Instant::now();
 
             // Create a new database transaction on every segment to prevent long-lived read-only
             // transactions
             let provider = self.provider_factory.provider()?.disable_long_read_transaction_safety();
-            segment.copy_to_static_files(provider, self.provider_factory.static_file_provider(), block_range.clone())?;
+            let config = self.compression.segment_config(segment.segment());
+            segment.copy_to_static_files(provider, self.provider_factory.static_file_provider(), config, block_range.clone())?;
 
             let elapsed = start.elapsed(); // TODO(alexey): track in metrics
             debug!(target: "static_file", segment = %segment.segment(), ?block_range, ?elapsed, "Finished StaticFileProducer segment");
 
-            Ok(())
-        })?;
+            Ok(elapsed)
+        }).collect::<ProviderResult<Vec<_>>>()?;
         /// Commit the current state of the static file provider.
-        self.provider_factory.static_file_provider().commit()?;
-        /// Iterate over each segment and its corresponding block range
-        for (segment, block_range) in segments {
-            // Update the index of the static file provider for each segment with the end of the block range
-            self.provider_factory
-                .static_file_provider()
-                .update_index(segment.segment(), Some(*block_range.end()))?;
+        static_file_provider.commit()?;
+
+        let mut bytes = 0;
+        for (((segment, block_range), (rows_before, bytes_before)), elapsed) in
+            segments.into_iter().zip(stats_before).zip(elapsed)
+        {
+            let segment = segment.segment();
+            // Update the index of the static file provider for each segment with the end of the
+            // block range
+            static_file_provider.update_index(segment, Some(*block_range.end()))?;
+
+            let (rows_after, bytes_after) = segment_stats(&static_file_provider, segment)?;
+            let progress = StaticFileSegmentProgress {
+                segment,
+                block_range,
+                rows: rows_after.saturating_sub(rows_before),
+                bytes: bytes_after.saturating_sub(bytes_before),
+                elapsed,
+            };
+            trace!(target: "static_file", ?progress, "StaticFileProducer segment progress");
+            bytes += progress.bytes;
+            self.event_sender.notify(StaticFileProducerEvent::SegmentProgress(progress));
         }
-        /// Measure the elapsed time since the start of the operation.
-        let elapsed = start.elapsed(); // TODO(alexey): track in metrics
-        debug!(target: "static_file", ?targets, ?elapsed, "StaticFileProducer finished");
-        /// Notify event listeners that the StaticFileProducer has finished processing,
-        /// including the targets and the elapsed time.
-        self.event_sender
-            .notify(StaticFileProducerEvent::Finished { targets: targets.clone(), elapsed });
 
-        Ok(targets)
+        Ok(bytes)
     }
 
     /// Copies data from database to static files according to
@@ -189,15 +440,22 @@ impl<DB: Database> StaticFileProducerInner<DB> {
     /// Returns highest block numbers for all static file segments.
     pub fn copy_to_static_files(&self) -> ProviderResult<HighestStaticFiles> {
         let provider = self.provider_factory.provider()?;
-        let stages_checkpoints = [StageId::Headers, StageId::Execution, StageId::Bodies]
-            .into_iter()
-            .map(|stage| provider.get_stage_checkpoint(stage).map(|c| c.map(|c| c.block_number)))
-            .collect::<Result<Vec<_>, _>>()?;
+        let stages_checkpoints =
+            [StageId::Headers, StageId::Execution, StageId::Bodies, StageId::SenderRecovery]
+                .into_iter()
+                .map(|stage| {
+                    provider.get_stage_checkpoint(stage).map(|c| c.map(|c| c.block_number))
+                })
+                .collect::<Result<Vec<_>, _>>()?;
 
         let highest_static_files = HighestStaticFiles {
             headers: stages_checkpoints[0],
             receipts: stages_checkpoints[1],
             transactions: stages_checkpoints[2],
+            senders: stages_checkpoints[3],
+            block_bodies: stages_checkpoints[2],
+            account_changesets: stages_checkpoints[1],
+            storage_changesets: stages_checkpoints[1],
         };
         let targets = self.get_static_file_targets(highest_static_files)?;
         self.run(targets)?;
@@ -239,6 +497,52 @@ impl<DB: Database> StaticFileProducerInner<DB> {
                     finalized_block_number, // The current finalized block number.
                 )
             }),
+            // Senders, block bodies, account and storage changesets are only moved if enabled, and
+            // if they're not pruned according to the user configuration
+            senders: if self.extended_segments && self.prune_modes.sender_recovery.is_none() {
+                finalized_block_numbers.senders.and_then(|finalized_block_number| {
+                    self.get_static_file_target(
+                        highest_static_files.senders,
+                        finalized_block_number,
+                    )
+                })
+            } else {
+                None
+            },
+            block_bodies: if self.extended_segments {
+                finalized_block_numbers.block_bodies.and_then(|finalized_block_number| {
+                    self.get_static_file_target(
+                        highest_static_files.block_bodies,
+                        finalized_block_number,
+                    )
+                })
+            } else {
+                None
+            },
+            account_changesets: if self.extended_segments &&
+                self.prune_modes.account_history.is_none()
+            {
+                finalized_block_numbers.account_changesets.and_then(|finalized_block_number| {
+                    self.get_static_file_target(
+                        highest_static_files.account_changesets,
+                        finalized_block_number,
+                    )
+                })
+            } else {
+                None
+            },
+            storage_changesets: if self.extended_segments &&
+                self.prune_modes.storage_history.is_none()
+            {
+                finalized_block_numbers.storage_changesets.and_then(|finalized_block_number| {
+                    self.get_static_file_target(
+                        highest_static_files.storage_changesets,
+                        finalized_block_number,
+                    )
+                })
+            } else {
+                None
+            },
         };
 
         trace!(
@@ -270,19 +574,48 @@ impl<DB: Database> StaticFileProducerInner<DB> {
     }
 }
 
+/// Returns the number of rows and the size of data files of the segment in static files.
+fn segment_stats(
+    static_file_provider: &StaticFileProvider,
+    segment: StaticFileSegment,
+) -> ProviderResult<(u64, u64)> {
+    let rows = if segment.is_tx_based() {
+        static_file_provider.get_highest_static_file_tx(segment)
+    } else {
+        static_file_provider.get_highest_static_file_block(segment)
+    }
+    .map_or(0, |highest| highest + 1);
+
+    let mut bytes = 0;
+    for (path, _) in segment_files(static_file_provider.directory(), segment)? {
+        bytes += reth_fs_util::metadata(&path)?.len();
+    }
+
+    Ok((rows, bytes))
+}
+
 #[cfg(test)]
 mod tests {
-    use crate::static_file_producer::{
-        StaticFileProducer, StaticFileProducerInner, StaticFileTargets,
+    use crate::{
+        segments::{self, Segment},
+        static_file_producer::{
+            StaticFileProducer, StaticFileProducerInner, StaticFileTargets, StaticFileThrottle,
+        },
+        StaticFileProducerEvent, StaticFileSegmentReader,
     };
-    use alloy_primitives::{B256, U256};
+    use alloy_primitives::{Address, B256, U256};
     use assert_matches::assert_matches;
-    use reth_db::{test_utils::TempDatabase, DatabaseEnv};
-    use reth_db_api::{database::Database, transaction::DbTx};
+    use reth_db::{tables, test_utils::TempDatabase, DatabaseEnv};
+    use reth_db_api::{
+        database::Database,
+        models::{AccountBeforeTx, BlockNumberAddress, StoredBlockOmmers},
+        transaction::{DbTx, DbTxMut},
+    };
+    use reth_primitives::{Header, StorageEntry};
     use reth_provider::{
         providers::StaticFileWriter, ProviderError, ProviderFactory, StaticFileProviderFactory,
     };
-    use reth_prune_types::PruneModes;
+    use reth_prune_types::{PruneMode, PruneModes};
     use reth_stages::test_utils::{StorageKind, TestStageDB};
     use reth_static_file_types::{HighestStaticFiles, StaticFileSegment};
     use reth_testing_utils::{
@@ -294,6 +627,7 @@ mod tests {
         time::Duration,
     };
     use tempfile::TempDir;
+    use tokio_stream::StreamExt;
     /// Sets up the testing environment.
     
     /// Returns a tuple containing the provider factory and a temporary directory.
@@ -348,6 +682,7 @@ mod tests {
                 headers: Some(1),
                 receipts: Some(1),
                 transactions: Some(1),
+                ..Default::default()
             })
             .expect("get static file targets");
         assert_eq!(
@@ -355,14 +690,20 @@ mod tests {
             StaticFileTargets {
                 headers: Some(0..=1),
                 receipts: Some(0..=1),
-                transactions: Some(0..=1)
+                transactions: Some(0..=1),
+                ..Default::default()
             }
         );
         // Run the static file producer and check the result.
         assert_matches!(static_file_producer.run(targets), Ok(_));
         assert_eq!(
             provider_factory.static_file_provider().get_highest_static_files(),
-            HighestStaticFiles { headers: Some(1), receipts: Some(1), transactions: Some(1) }
+            HighestStaticFiles {
+                headers: Some(1),
+                receipts: Some(1),
+                transactions: Some(1),
+                ..Default::default()
+            }
         );
  
         // Repeat the process with different highest static files.
@@ -371,6 +712,7 @@ mod tests {
                 headers: Some(3),
                 receipts: Some(3),
                 transactions: Some(3),
+                ..Default::default()
             })
             .expect("get static file targets");
         assert_eq!(
@@ -378,13 +720,19 @@ mod tests {
             StaticFileTargets {
                 headers: Some(2..=3),
                 receipts: Some(2..=3),
-                transactions: Some(2..=3)
+                transactions: Some(2..=3),
+                ..Default::default()
             }
         );
         assert_matches!(static_file_producer.run(targets), Ok(_));
         assert_eq!(
             provider_factory.static_file_provider().get_highest_static_files(),
-            HighestStaticFiles { headers: Some(3), receipts: Some(3), transactions: Some(3) }
+            HighestStaticFiles {
+                headers: Some(3),
+                receipts: Some(3),
+                transactions: Some(3),
+                ..Default::default()
+            }
         );
  
         // Test error handling when the block body indices are not found.
@@ -393,6 +741,7 @@ mod tests {
                 headers: Some(4),
                 receipts: Some(4),
                 transactions: Some(4),
+                ..Default::default()
             })
             .expect("get static file targets");
         assert_eq!(
@@ -400,7 +749,8 @@ mod tests {
             StaticFileTargets {
                 headers: Some(4..=4),
                 receipts: Some(4..=4),
-                transactions: Some(4..=4)
+                transactions: Some(4..=4),
+                ..Default::default()
             }
         );
         assert_matches!(
@@ -409,10 +759,313 @@ mod tests {
         );
         assert_eq!(
             provider_factory.static_file_provider().get_highest_static_files(),
-            HighestStaticFiles { headers: Some(3), receipts: Some(3), transactions: Some(3) }
+            HighestStaticFiles {
+                headers: Some(3),
+                receipts: Some(3),
+                transactions: Some(3),
+                ..Default::default()
+            }
+        );
+    }
+
+    /// Tests that the senders, block bodies and changesets segments are only moved to static files
+    /// if enabled, and segments which are pruned according to the prune modes are not moved at
+    /// all.
+    #[test]
+    fn targets_respect_prune_modes() {
+        let (provider_factory, _temp_static_files_dir) = setup();
+        let finalized_block_numbers = HighestStaticFiles {
+            headers: Some(1),
+            receipts: Some(1),
+            transactions: Some(1),
+            senders: Some(1),
+            block_bodies: Some(1),
+            account_changesets: Some(1),
+            storage_changesets: Some(1),
+        };
+
+        let mut static_file_producer =
+            StaticFileProducerInner::new(provider_factory.clone(), PruneModes::default());
+        let targets = static_file_producer
+            .get_static_file_targets(finalized_block_numbers)
+            .expect("get static file targets");
+        assert_eq!(
+            targets,
+            StaticFileTargets {
+                headers: Some(0..=1),
+                receipts: Some(0..=1),
+                transactions: Some(0..=1),
+                ..Default::default()
+            }
+        );
+
+        static_file_producer.extended_segments = true;
+        let targets = static_file_producer
+            .get_static_file_targets(finalized_block_numbers)
+            .expect("get static file targets");
+        assert_eq!(
+            targets,
+            StaticFileTargets {
+                headers: Some(0..=1),
+                receipts: Some(0..=1),
+                transactions: Some(0..=1),
+                senders: Some(0..=1),
+                block_bodies: Some(0..=1),
+                account_changesets: Some(0..=1),
+                storage_changesets: Some(0..=1),
+            }
+        );
+
+        let prune_modes = PruneModes {
+            sender_recovery: Some(PruneMode::Full),
+            account_history: Some(PruneMode::Distance(10_064)),
+            storage_history: Some(PruneMode::Distance(10_064)),
+            ..Default::default()
+        };
+        let mut static_file_producer = StaticFileProducerInner::new(provider_factory, prune_modes);
+        static_file_producer.extended_segments = true;
+        let targets = static_file_producer
+            .get_static_file_targets(finalized_block_numbers)
+            .expect("get static file targets");
+        assert_eq!(
+            targets,
+            StaticFileTargets {
+                headers: Some(0..=1),
+                receipts: Some(0..=1),
+                transactions: Some(0..=1),
+                block_bodies: Some(0..=1),
+                ..Default::default()
+            }
         );
     }
+
+    /// Tests that the senders, block bodies and changesets segments are copied to static files
+    /// over several runs, and read back from them.
+    #[test]
+    fn copy_to_static_files_new_segments() {
+        let (provider_factory, _temp_static_files_dir) = setup();
+
+        let tx = provider_factory.db_ref().tx_mut().expect("init tx");
+        let tx_count = tx.entries::<tables::Transactions>().unwrap() as u64;
+        for tx_num in 0..tx_count {
+            tx.put::<tables::TransactionSenders>(tx_num, Address::with_last_byte(tx_num as u8))
+                .unwrap();
+        }
+        tx.put::<tables::BlockOmmers>(1, StoredBlockOmmers { ommers: vec![Header::default()] })
+            .unwrap();
+        // Block 3 has no account or storage changes
+        let mut account_changesets = vec![Vec::new(); 4];
+        let mut storage_changesets = vec![Vec::new(); 4];
+        for block in 0..3 {
+            let address = Address::with_last_byte(block as u8);
+            let account_before = AccountBeforeTx { address, info: None };
+            let storage_before =
+                StorageEntry { key: B256::with_last_byte(1), value: U256::from(block) };
+            tx.put::<tables::AccountChangeSets>(block, account_before.clone()).unwrap();
+            tx.put::<tables::StorageChangeSets>(
+                BlockNumberAddress((block, address)),
+                storage_before,
+            )
+            .unwrap();
+            account_changesets[block as usize].push(account_before);
+            storage_changesets[block as usize].push((address, storage_before));
+        }
+        let block_bodies = (0..4)
+            .map(|block| {
+                (
+                    tx.get::<tables::BlockBodyIndices>(block).unwrap().expect("body indices"),
+                    tx.get::<tables::BlockOmmers>(block).unwrap().unwrap_or_default(),
+                    tx.get::<tables::BlockWithdrawals>(block).unwrap().unwrap_or_default(),
+                )
+            })
+            .collect::<Vec<_>>();
+        tx.commit().expect("commit tx");
+
+        let static_file_provider = provider_factory.static_file_provider();
+        let new_segments: [Box<dyn Segment<_>>; 4] = [
+            Box::new(segments::Senders),
+            Box::new(segments::BlockBodies),
+            Box::new(segments::AccountChangeSets),
+            Box::new(segments::StorageChangeSets),
+        ];
+        // The second run appends to the static files created by the first one
+        for block_range in [0..=1, 2..=3] {
+            for segment in &new_segments {
+                segment
+                    .copy_to_static_files(
+                        provider_factory.provider().unwrap(),
+                        static_file_provider.clone(),
+                        segment.segment().config(),
+                        block_range.clone(),
+                    )
+                    .expect("copy to static files");
+            }
+        }
+        assert_matches!(
+            new_segments[2].copy_to_static_files(
+                provider_factory.provider().unwrap(),
+                static_file_provider.clone(),
+                StaticFileSegment::AccountChangeSets.config(),
+                5..=5
+            ),
+            Err(ProviderError::UnexpectedStaticFileBlockNumber(
+                StaticFileSegment::AccountChangeSets,
+                5,
+                4
+            ))
+        );
+
+        let reader = StaticFileSegmentReader::new(static_file_provider.directory());
+        let assert_static_files = || {
+            for tx_num in 0..tx_count {
+                assert_eq!(
+                    reader.transaction_sender(tx_num).unwrap(),
+                    Some(Address::with_last_byte(tx_num as u8))
+                );
+            }
+            assert_eq!(reader.transaction_sender(tx_count).unwrap(), None);
+
+            for block in 0..4 {
+                assert_eq!(
+                    reader.block_body(block).unwrap().as_ref(),
+                    block_bodies.get(block as usize)
+                );
+                assert_eq!(
+                    reader.account_changeset(block).unwrap().as_ref(),
+                    account_changesets.get(block as usize)
+                );
+                assert_eq!(
+                    reader.storage_changeset(block).unwrap().as_ref(),
+                    storage_changesets.get(block as usize)
+                );
+            }
+            assert_eq!(reader.block_body(4).unwrap(), None);
+            assert_eq!(reader.account_changeset(4).unwrap(), None);
+            assert_eq!(reader.storage_changeset(4).unwrap(), None);
+        };
+        assert_static_files();
+
+        // The data is still read from the database, so it's never deleted from there
+        let tx = provider_factory.db_ref().tx().expect("init tx");
+        assert_eq!(tx.entries::<tables::TransactionSenders>().unwrap(), tx_count as usize);
+        assert_eq!(tx.entries::<tables::BlockOmmers>().unwrap(), 1);
+        assert_eq!(tx.entries::<tables::AccountChangeSets>().unwrap(), 3);
+        assert_eq!(tx.entries::<tables::StorageChangeSets>().unwrap(), 3);
+    }
         
+    #[test]
+    fn chunks() {
+        let targets = StaticFileTargets {
+            headers: Some(0..=4),
+            transactions: Some(2..=3),
+            ..Default::default()
+        };
+        assert_eq!(
+            targets.chunks(2).collect::<Vec<_>>(),
+            vec![
+                StaticFileTargets {
+                    headers: Some(0..=1),
+                    transactions: Some(2..=3),
+                    ..Default::default()
+                },
+                StaticFileTargets { headers: Some(2..=3), ..Default::default() },
+                StaticFileTargets { headers: Some(4..=4), ..Default::default() },
+            ]
+        );
+        assert_eq!(targets.chunks(u64::MAX).collect::<Vec<_>>(), vec![targets.clone()]);
+
+        assert_eq!(
+            targets.without_produced(HighestStaticFiles {
+                headers: Some(2),
+                transactions: Some(3),
+                ..Default::default()
+            }),
+            StaticFileTargets { headers: Some(3..=4), ..Default::default() }
+        );
+    }
+
+    #[test]
+    fn throttle_pause() {
+        let throttle = StaticFileThrottle::default();
+        assert_eq!(throttle.pause(u64::MAX, Duration::ZERO), Duration::ZERO);
+
+        let throttle = StaticFileThrottle { io_budget: Some(1_000), ..Default::default() };
+        assert_eq!(throttle.pause(500, Duration::from_millis(100)), Duration::from_millis(400));
+        assert_eq!(throttle.pause(500, Duration::from_secs(1)), Duration::ZERO);
+    }
+
+    /// Tests that the throttled mode moves the same data to static files, and notifies about the
+    /// progress of every chunk.
+    #[tokio::test]
+    async fn run_throttled() {
+        let (provider_factory, _temp_static_files_dir) = setup();
+
+        let throttle = StaticFileThrottle { chunk_size: 1, io_budget: Some(u64::MAX) };
+        let static_file_producer =
+            StaticFileProducer::new(provider_factory.clone(), PruneModes::default())
+                .with_throttle(Some(throttle));
+        let mut events = static_file_producer.lock().events();
+
+        let targets = static_file_producer
+            .lock()
+            .get_static_file_targets(HighestStaticFiles {
+                headers: Some(3),
+                receipts: Some(3),
+                transactions: Some(3),
+                ..Default::default()
+            })
+            .expect("get static file targets");
+        assert_matches!(static_file_producer.run(targets), Ok(_));
+        assert_eq!(
+            provider_factory.static_file_provider().get_highest_static_files(),
+            HighestStaticFiles {
+                headers: Some(3),
+                receipts: Some(3),
+                transactions: Some(3),
+                ..Default::default()
+            }
+        );
+
+        let mut progress = Vec::new();
+        assert_matches!(events.next().await, Some(StaticFileProducerEvent::Started { .. }));
+        loop {
+            match events.next().await {
+                Some(StaticFileProducerEvent::SegmentProgress(segment_progress)) => {
+                    progress.push(segment_progress)
+                }
+                event => {
+                    assert_matches!(event, Some(StaticFileProducerEvent::Finished { .. }));
+                    break
+                }
+            }
+        }
+        for segment in [
+            StaticFileSegment::Headers,
+            StaticFileSegment::Receipts,
+            StaticFileSegment::Transactions,
+        ] {
+            let segment_progress =
+                progress.iter().filter(|progress| progress.segment == segment).collect::<Vec<_>>();
+            assert_eq!(
+                segment_progress
+                    .iter()
+                    .map(|progress| progress.block_range.clone())
+                    .collect::<Vec<_>>(),
+                vec![0..=0, 1..=1, 2..=2, 3..=3]
+            );
+            assert!(segment_progress
+                .iter()
+                .all(|progress| progress.rows > 0 && progress.bytes > 0));
+        }
+        assert_eq!(progress.len(), 12);
+
+        // Already produced chunks are skipped
+        let targets = StaticFileTargets { headers: Some(0..=3), ..Default::default() };
+        assert_matches!(static_file_producer.run_throttled(targets, throttle), Ok(_));
+        assert_matches!(events.next().await, Some(StaticFileProducerEvent::Started { .. }));
+        assert_matches!(events.next().await, Some(StaticFileProducerEvent::Finished { .. }));
+    }
+
     /// Tests that a cloneable [`StaticFileProducer`] type is not susceptible to any race condition.
     #[test]
     fn only_one() {
@@ -439,6 +1092,7 @@ mod tests {
                         headers: Some(1),
                         receipts: Some(1),
                         transactions: Some(1),
+                        ..Default::default()
                     })
                     .expect("get static file targets");
                 assert_matches!(locked_producer.run(targets.clone()), Ok(_)); // Run the static file producer and check the result.
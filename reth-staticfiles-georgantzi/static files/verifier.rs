@@ -0,0 +1,749 @@
+//! Verification and repair of static files.
+
+use crate::{
+    segments::{self, Segment},
+    StaticFileCompressionConfig,
+};
+use alloy_primitives::{BlockNumber, TxNumber, B256};
+use reth_db::tables;
+use reth_db_api::{database::Database, table::Decompress, transaction::DbTx};
+use reth_fs_util::FsPathError;
+use reth_nippy_jar::{DataReader, InclusionFilter, NippyJar, NippyJarCursor, NippyJarError};
+use reth_primitives::{Header, TransactionSignedNoHash};
+use reth_provider::{
+    providers::{StaticFileProvider, StaticFileWriter},
+    BlockReader, DatabaseProviderRO, ProviderError, ProviderFactory, StaticFileProviderFactory,
+    TransactionsProvider,
+};
+use reth_static_file_types::{
+    find_fixed_range, SegmentConfig, SegmentHeader, SegmentRangeInclusive, StaticFileSegment,
+};
+use reth_storage_errors::provider::ProviderResult;
+use std::{
+    fmt,
+    ops::RangeInclusive,
+    path::{Path, PathBuf},
+};
+use strum::IntoEnumIterator;
+use tracing::{debug, info, warn};
+
+/// An inconsistency found in a static file.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum StaticFileIssue {
+    /// The static file or its offsets could not be opened.
+    Unreadable(String),
+    /// The expected block range of the header does not match the file name.
+    ExpectedRangeMismatch {
+        /// The expected block range derived from the file name.
+        expected: SegmentRangeInclusive,
+        /// The expected block range of the header.
+        found: SegmentRangeInclusive,
+    },
+    /// The file does not start where the previous file of the segment ended.
+    NotContiguous {
+        /// The block or transaction the file was expected to start at.
+        expected: u64,
+        /// The block or transaction the file starts at.
+        found: u64,
+    },
+    /// The number of rows does not match the range of the header.
+    RowCountMismatch {
+        /// The number of rows according to the header.
+        expected: u64,
+        /// The number of rows in the file.
+        found: u64,
+    },
+    /// The offsets of the file are inconsistent with its data.
+    InvalidOffsets {
+        /// The first row with an invalid offset.
+        row: u64,
+    },
+    /// A row could not be read or decompressed.
+    UnreadableRow {
+        /// The row that could not be read.
+        row: u64,
+        /// The error encountered while reading the row.
+        error: String,
+    },
+    /// The hash stored in or recomputed from a row does not match the canonical chain.
+    HashMismatch {
+        /// The row with the mismatching hash.
+        row: u64,
+        /// The hash expected by the canonical chain.
+        expected: B256,
+        /// The hash found in the row.
+        found: B256,
+    },
+    /// The block number of a header does not match its row.
+    BlockNumberMismatch {
+        /// The row of the header.
+        row: u64,
+        /// The block number expected for the row.
+        expected: BlockNumber,
+        /// The block number of the header.
+        found: BlockNumber,
+    },
+    /// A transaction is indexed with a different transaction number by the database.
+    TransactionNumberMismatch {
+        /// The row of the transaction.
+        row: u64,
+        /// The transaction number according to the database.
+        expected: TxNumber,
+        /// The transaction number of the row.
+        found: TxNumber,
+    },
+    /// The inclusion filter does not contain the hash of a row.
+    FilterMiss {
+        /// The row whose hash is missing from the filter.
+        row: u64,
+    },
+}
+
+impl StaticFileIssue {
+    /// Returns the first row affected by the issue, if it's limited to the tail of the file.
+    const fn row(&self) -> Option<u64> {
+        match self {
+            Self::InvalidOffsets { row } |
+            Self::UnreadableRow { row, .. } |
+            Self::HashMismatch { row, .. } |
+            Self::BlockNumberMismatch { row, .. } |
+            Self::TransactionNumberMismatch { row, .. } |
+            Self::FilterMiss { row } => Some(*row),
+            Self::Unreadable(_) |
+            Self::ExpectedRangeMismatch { .. } |
+            Self::NotContiguous { .. } |
+            Self::RowCountMismatch { .. } => None,
+        }
+    }
+}
+
+impl fmt::Display for StaticFileIssue {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            Self::Unreadable(error) => write!(f, "unreadable: {error}"),
+            Self::ExpectedRangeMismatch { expected, found } => {
+                write!(f, "expected range {found:?} does not match file name range {expected:?}")
+            }
+            Self::NotContiguous { expected, found } => {
+                write!(f, "starts at {found}, expected {expected}")
+            }
+            Self::RowCountMismatch { expected, found } => {
+                write!(f, "has {found} rows, expected {expected}")
+            }
+            Self::InvalidOffsets { row } => write!(f, "invalid offsets from row {row}"),
+            Self::UnreadableRow { row, error } => write!(f, "unreadable row {row}: {error}"),
+            Self::HashMismatch { row, expected, found } => {
+                write!(f, "row {row} has hash {found}, expected {expected}")
+            }
+            Self::BlockNumberMismatch { row, expected, found } => {
+                write!(f, "row {row} has block {found}, expected {expected}")
+            }
+            Self::TransactionNumberMismatch { row, expected, found } => {
+                write!(f, "row {row} has transaction {found}, indexed as {expected}")
+            }
+            Self::FilterMiss { row } => write!(f, "filter does not contain row {row}"),
+        }
+    }
+}
+
+/// The result of verifying a single static file.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct StaticFileReport {
+    /// Path of the static file.
+    pub path: PathBuf,
+    /// Segment of the static file.
+    pub segment: StaticFileSegment,
+    /// The header of the static file, if it could be read.
+    pub header: Option<SegmentHeader>,
+    /// The number of rows that were read successfully.
+    pub rows: u64,
+    /// The inconsistencies found in the static file.
+    pub issues: Vec<StaticFileIssue>,
+}
+
+impl StaticFileReport {
+    /// Returns `true` if no inconsistencies were found.
+    pub fn is_ok(&self) -> bool {
+        self.issues.is_empty()
+    }
+
+    /// Returns the first row of the corrupted tail of the file, if all issues are limited to it.
+    ///
+    /// Returns `None` if the file is consistent, or if it's corrupted beyond its tail.
+    pub fn corrupted_tail(&self) -> Option<u64> {
+        let mut first_row = None;
+        for issue in &self.issues {
+            let row = match issue {
+                // Missing rows are a corrupted tail starting at the first missing row
+                StaticFileIssue::RowCountMismatch { expected, found } if found < expected => *found,
+                issue => issue.row()?,
+            };
+            first_row = Some(first_row.map_or(row, |first: u64| first.min(row)));
+        }
+        first_row
+    }
+}
+
+/// Errors of [`StaticFileVerifier::repair`].
+#[derive(Debug, thiserror::Error)]
+pub enum StaticFileRepairError {
+    /// The corruption is not limited to the tail of the latest static file of the segment.
+    #[error("static file {0:?} is corrupted beyond its tail")]
+    NotRepairable(PathBuf),
+    /// The segment can't be truncated by the static file writer.
+    #[error("repairing {0} static files is not supported")]
+    UnsupportedSegment(StaticFileSegment),
+    /// Provider error.
+    #[error(transparent)]
+    Provider(#[from] ProviderError),
+}
+
+/// Verifies that static files match their headers and the canonical chain, and repairs corrupted
+/// tails of static files.
+#[derive(Debug)]
+pub struct StaticFileVerifier<DB> {
+    /// Provider factory
+    provider_factory: ProviderFactory<DB>,
+    /// Compression of the static files produced again on repair, per segment.
+    compression: StaticFileCompressionConfig,
+}
+
+impl<DB: Database> StaticFileVerifier<DB> {
+    /// Creates a new [`StaticFileVerifier`].
+    pub fn new(provider_factory: ProviderFactory<DB>) -> Self {
+        Self { provider_factory, compression: Default::default() }
+    }
+
+    /// Sets the compression of the static files produced again on repair, per segment. It should
+    /// match the compression of the static file producer.
+    pub fn with_compression(mut self, compression: StaticFileCompressionConfig) -> Self {
+        self.compression = compression;
+        self
+    }
+
+    /// Verifies the static files of all segments.
+    pub fn verify(&self) -> ProviderResult<Vec<StaticFileReport>> {
+        let mut reports = Vec::new();
+        for segment in StaticFileSegment::iter() {
+            reports.extend(self.verify_segment(segment)?);
+        }
+        Ok(reports)
+    }
+
+    /// Verifies the static files of the given segment, in the order of their block ranges.
+    ///
+    /// For every file, it checks that
+    /// - the header matches the file name and continues where the previous file ended,
+    /// - the offsets and the number of rows match the ranges of the header,
+    /// - every row can be read and decompressed,
+    /// - header and transaction hashes match the canonical chain and are part of the inclusion
+    ///   filter, if the file has one. Header hashes are also compared with the canonical hashes of
+    ///   [`tables::CanonicalHeaders`], for the blocks that are still in the database.
+    pub fn verify_segment(
+        &self,
+        segment: StaticFileSegment,
+    ) -> ProviderResult<Vec<StaticFileReport>> {
+        let static_file_provider = self.provider_factory.static_file_provider();
+        let provider = self.provider_factory.provider()?;
+
+        let mut reports = Vec::new();
+        let mut next_start: Option<u64> = None;
+        let mut parent_hash = None;
+        for (path, expected_range) in segment_files(static_file_provider.directory(), segment)? {
+            let report = verify_file(
+                &provider,
+                &path,
+                segment,
+                expected_range,
+                next_start,
+                &mut parent_hash,
+            )?;
+
+            next_start = report.header.as_ref().and_then(|header| {
+                if segment.is_tx_based() {
+                    header.tx_end().map(|tx| tx + 1)
+                } else {
+                    header.block_end().map(|block| block + 1)
+                }
+            });
+
+            if report.is_ok() {
+                debug!(target: "static_file::verifier", path = ?report.path, rows = report.rows, "Verified static file");
+            } else {
+                for issue in &report.issues {
+                    warn!(target: "static_file::verifier", path = ?report.path, %issue, "Inconsistent static file");
+                }
+            }
+            reports.push(report);
+        }
+
+        Ok(reports)
+    }
+
+    /// Repairs the latest static file of the given segment.
+    ///
+    /// If the file has a corrupted tail, all blocks starting with the first corrupted one are
+    /// truncated using [`SegmentHeader::prune`], and produced again from the database.
+    ///
+    /// Returns the block range that was produced again, or `None` if the file was consistent.
+    pub fn repair(
+        &self,
+        segment: StaticFileSegment,
+    ) -> Result<Option<RangeInclusive<BlockNumber>>, StaticFileRepairError> {
+        let Some(report) = self.verify_segment(segment)?.pop() else { return Ok(None) };
+        if report.is_ok() {
+            return Ok(None)
+        }
+
+        let (Some(first_row), Some(header)) = (report.corrupted_tail(), report.header.as_ref())
+        else {
+            return Err(StaticFileRepairError::NotRepairable(report.path))
+        };
+        let Some(block_end) = header.block_end() else {
+            return Err(StaticFileRepairError::NotRepairable(report.path))
+        };
+
+        let provider = self.provider_factory.provider()?;
+        let static_file_provider = self.provider_factory.static_file_provider();
+        let mut writer = static_file_provider.latest_writer(segment)?;
+
+        // Truncate whole blocks, starting with the block of the first corrupted row
+        let first_block = if segment.is_tx_based() {
+            let Some(tx_start) = header.tx_start() else {
+                return Err(StaticFileRepairError::NotRepairable(report.path))
+            };
+            let Some(first_block) = provider.transaction_block(tx_start + first_row)? else {
+                return Err(StaticFileRepairError::NotRepairable(report.path))
+            };
+            let first_block_tx = provider
+                .block_body_indices(first_block)?
+                .ok_or(ProviderError::BlockBodyIndicesNotFound(first_block))?
+                .first_tx_num();
+            let to_delete = header.tx_end().map_or(0, |tx_end| tx_end + 1 - first_block_tx);
+            let last_block = first_block.saturating_sub(1);
+            match segment {
+                StaticFileSegment::Transactions => {
+                    writer.prune_transactions(to_delete, last_block)?
+                }
+                StaticFileSegment::Receipts => writer.prune_receipts(to_delete, last_block)?,
+                segment => return Err(StaticFileRepairError::UnsupportedSegment(segment)),
+            }
+            first_block
+        } else {
+            let Some(block_start) = header.block_start() else {
+                return Err(StaticFileRepairError::NotRepairable(report.path))
+            };
+            let first_block = block_start + first_row;
+            match segment {
+                StaticFileSegment::Headers => writer.prune_headers(block_end + 1 - first_block)?,
+                segment => return Err(StaticFileRepairError::UnsupportedSegment(segment)),
+            }
+            first_block
+        };
+        writer.commit()?;
+        drop(writer);
+
+        let block_range = first_block..=block_end;
+        info!(target: "static_file::verifier", %segment, ?block_range, "Producing truncated static file tail again");
+        produce(
+            &self.provider_factory,
+            &static_file_provider,
+            segment,
+            self.compression.segment_config(segment),
+            block_range.clone(),
+        )?;
+
+        Ok(Some(block_range))
+    }
+}
+
+/// Returns the paths and the block ranges of the static files of the given segment, sorted by
+/// block range.
+pub(crate) fn segment_files(
+    directory: &Path,
+    segment: StaticFileSegment,
+) -> ProviderResult<Vec<(PathBuf, SegmentRangeInclusive)>> {
+    let mut files = Vec::new();
+    for entry in reth_fs_util::read_dir(directory)? {
+        let path = entry.map_err(|err| FsPathError::read_dir(err, directory))?.path();
+        // Offsets and configuration files have an extension, and are not matched
+        let Some((file_segment, range)) =
+            path.file_name().and_then(|name| StaticFileSegment::parse_filename(name.to_str()?))
+        else {
+            continue
+        };
+        if file_segment == segment {
+            files.push((path, range));
+        }
+    }
+    files.sort_unstable_by_key(|(_, range)| range.start());
+    Ok(files)
+}
+
+/// Verifies a single static file.
+///
+/// `expected_start` is the block or transaction the file should start at, and `parent_hash` the
+/// hash of the last header verified before it.
+fn verify_file<DB: Database>(
+    provider: &DatabaseProviderRO<DB>,
+    path: &Path,
+    segment: StaticFileSegment,
+    expected_range: SegmentRangeInclusive,
+    expected_start: Option<u64>,
+    parent_hash: &mut Option<B256>,
+) -> ProviderResult<StaticFileReport> {
+    let mut report = StaticFileReport {
+        path: path.to_path_buf(),
+        segment,
+        header: None,
+        rows: 0,
+        issues: Vec::new(),
+    };
+
+    let jar = match NippyJar::<SegmentHeader>::load(path) {
+        Ok(jar) => jar,
+        Err(err) => {
+            report.issues.push(StaticFileIssue::Unreadable(err.to_string()));
+            return Ok(report)
+        }
+    };
+    let header = jar.user_header().clone();
+    report.header = Some(header.clone());
+
+    // The header has to match the file name and the fixed ranges of static files
+    let header_expected_range =
+        SegmentRangeInclusive::new(header.expected_block_start(), header.expected_block_end());
+    if header_expected_range != expected_range ||
+        find_fixed_range(expected_range.start()) != expected_range
+    {
+        report.issues.push(StaticFileIssue::ExpectedRangeMismatch {
+            expected: expected_range,
+            found: header_expected_range,
+        });
+    }
+
+    // The file has to continue where the previous one ended
+    let start = if segment.is_tx_based() { header.tx_start() } else { header.block_start() };
+    if let (Some(expected), Some(found)) = (expected_start, start) {
+        if expected != found {
+            report.issues.push(StaticFileIssue::NotContiguous { expected, found });
+        }
+    }
+
+    let expected_rows =
+        if segment.is_tx_based() { header.tx_len() } else { header.block_len() }.unwrap_or(0);
+    if jar.rows() as u64 != expected_rows {
+        report.issues.push(StaticFileIssue::RowCountMismatch {
+            expected: expected_rows,
+            found: jar.rows() as u64,
+        });
+    }
+
+    // Every value has an offset, followed by the offset of the end of the data
+    let columns = jar.columns();
+    let valid_rows = match DataReader::new(path) {
+        Ok(reader) => {
+            let mut valid_rows = 0;
+            let mut previous_offset = 0;
+            'rows: for row in 0..jar.rows() {
+                for column in 0..columns {
+                    let index = row * columns + column;
+                    if index + 1 >= reader.offsets_count() {
+                        break 'rows
+                    }
+                    let (start, end) = (reader.offset(index), reader.offset(index + 1));
+                    if start < previous_offset || end < start || end as usize > reader.size() {
+                        break 'rows
+                    }
+                    previous_offset = start;
+                }
+                valid_rows += 1;
+            }
+            valid_rows
+        }
+        Err(err) => {
+            report.issues.push(StaticFileIssue::Unreadable(err.to_string()));
+            return Ok(report)
+        }
+    };
+    if valid_rows < jar.rows() {
+        report.issues.push(StaticFileIssue::InvalidOffsets { row: valid_rows as u64 });
+    }
+
+    let mut cursor = match NippyJarCursor::new(&jar) {
+        Ok(cursor) => cursor,
+        Err(err) => {
+            report.issues.push(StaticFileIssue::Unreadable(err.to_string()));
+            return Ok(report)
+        }
+    };
+    for row in 0..valid_rows as u64 {
+        let values = match cursor.next_row() {
+            Ok(Some(values)) => values,
+            Ok(None) => break,
+            Err(err) => {
+                report.issues.push(StaticFileIssue::UnreadableRow { row, error: err.to_string() });
+                break
+            }
+        };
+
+        let hash = match segment {
+            StaticFileSegment::Headers => {
+                let expected_number = header.block_start().unwrap_or_default() + row;
+                match verify_header_row(&values, row, expected_number) {
+                    Ok((hash, parent)) => {
+                        if let Some(expected) = parent_hash.filter(|expected| *expected != parent) {
+                            report.issues.push(StaticFileIssue::HashMismatch {
+                                row,
+                                expected,
+                                found: parent,
+                            });
+                            break
+                        }
+                        if let Some(expected) = provider
+                            .tx_ref()
+                            .get::<tables::CanonicalHeaders>(expected_number)?
+                            .filter(|expected| *expected != hash)
+                        {
+                            report.issues.push(StaticFileIssue::HashMismatch {
+                                row,
+                                expected,
+                                found: hash,
+                            });
+                            break
+                        }
+                        *parent_hash = Some(hash);
+                        Some(hash)
+                    }
+                    Err(issue) => {
+                        report.issues.push(issue);
+                        break
+                    }
+                }
+            }
+            StaticFileSegment::Transactions => {
+                let hash = match TransactionSignedNoHash::decompress(values[0]) {
+                    Ok(transaction) => transaction.hash(),
+                    Err(err) => {
+                        report
+                            .issues
+                            .push(StaticFileIssue::UnreadableRow { row, error: err.to_string() });
+                        break
+                    }
+                };
+                // Only transactions that are indexed by the lookup table can be compared
+                let tx_number = header.tx_start().unwrap_or_default() + row;
+                if let Some(indexed) = provider.transaction_id(hash)? {
+                    if indexed != tx_number {
+                        report.issues.push(StaticFileIssue::TransactionNumberMismatch {
+                            row,
+                            expected: indexed,
+                            found: tx_number,
+                        });
+                        break
+                    }
+                }
+                Some(hash)
+            }
+            _ => None,
+        };
+
+        if let Some(hash) = hash {
+            match jar.contains(hash.as_slice()) {
+                Ok(true) | Err(NippyJarError::FilterMissing) => {}
+                Ok(false) => {
+                    report.issues.push(StaticFileIssue::FilterMiss { row });
+                    break
+                }
+                Err(err) => {
+                    report.issues.push(StaticFileIssue::Unreadable(err.to_string()));
+                    break
+                }
+            }
+        }
+
+        report.rows += 1;
+    }
+
+    Ok(report)
+}
+
+/// Decodes a row of the headers segment, and checks that the recomputed hash of the header
+/// matches the canonical hash stored alongside in the static file.
+///
+/// Returns the hash and the parent hash of the header.
+fn verify_header_row(
+    values: &[&[u8]],
+    row: u64,
+    expected_number: BlockNumber,
+) -> Result<(B256, B256), StaticFileIssue> {
+    let unreadable = |err: reth_db_api::DatabaseError| StaticFileIssue::UnreadableRow {
+        row,
+        error: err.to_string(),
+    };
+    let header = Header::decompress(values[0]).map_err(unreadable)?;
+    let canonical_hash = B256::decompress(values[2]).map_err(unreadable)?;
+
+    if header.number != expected_number {
+        return Err(StaticFileIssue::BlockNumberMismatch {
+            row,
+            expected: expected_number,
+            found: header.number,
+        })
+    }
+
+    let hash = header.hash_slow();
+    if hash != canonical_hash {
+        return Err(StaticFileIssue::HashMismatch { row, expected: canonical_hash, found: hash })
+    }
+
+    Ok((hash, header.parent_hash))
+}
+
+/// Copies the given block range of the segment from the database to static files, with the given
+/// segment configuration.
+fn produce<DB: Database>(
+    provider_factory: &ProviderFactory<DB>,
+    static_file_provider: &StaticFileProvider,
+    segment: StaticFileSegment,
+    config: SegmentConfig,
+    block_range: RangeInclusive<BlockNumber>,
+) -> ProviderResult<()> {
+    let producer: Box<dyn Segment<DB>> = match segment {
+        StaticFileSegment::Headers => Box::new(segments::Headers),
+        StaticFileSegment::Transactions => Box::new(segments::Transactions),
+        StaticFileSegment::Receipts => Box::new(segments::Receipts),
+        StaticFileSegment::Senders => Box::new(segments::Senders),
+        StaticFileSegment::BlockBodies => Box::new(segments::BlockBodies),
+        StaticFileSegment::AccountChangeSets => Box::new(segments::AccountChangeSets),
+        StaticFileSegment::StorageChangeSets => Box::new(segments::StorageChangeSets),
+    };
+
+    let provider = provider_factory.provider()?.disable_long_read_transaction_safety();
+    producer.copy_to_static_files(
+        provider,
+        static_file_provider.clone(),
+        config,
+        block_range.clone(),
+    )?;
+    static_file_provider.commit()?;
+    static_file_provider.update_index(segment, Some(*block_range.end()))?;
+
+    Ok(())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::StaticFileProducerInner;
+    use reth_db::{test_utils::TempDatabase, DatabaseEnv};
+    use reth_db_api::transaction::DbTxMut;
+    use reth_provider::BlockHashReader;
+    use reth_prune_types::PruneModes;
+    use reth_stages::test_utils::{StorageKind, TestStageDB};
+    use reth_static_file_types::HighestStaticFiles;
+    use reth_testing_utils::generators::{self, random_block_range};
+    use std::{fs::OpenOptions, sync::Arc};
+    use tempfile::TempDir;
+
+    /// Sets up a database with headers in static files, and moves its transactions to static
+    /// files.
+    fn setup() -> (ProviderFactory<Arc<TempDatabase<DatabaseEnv>>>, TempDir) {
+        let mut rng = generators::rng();
+        let db = TestStageDB::default();
+        let blocks = random_block_range(&mut rng, 0..=3, B256::ZERO, 2..3);
+        db.insert_blocks(blocks.iter(), StorageKind::Database(None)).expect("insert blocks");
+
+        let static_file_producer =
+            StaticFileProducerInner::new(db.factory.clone(), PruneModes::default());
+        let targets = static_file_producer
+            .get_static_file_targets(HighestStaticFiles {
+                transactions: Some(3),
+                ..Default::default()
+            })
+            .expect("get static file targets");
+        static_file_producer.run(targets).expect("run static file producer");
+
+        (db.factory, db.temp_static_files_dir)
+    }
+
+    #[test]
+    fn verify_and_repair() {
+        let (provider_factory, _temp_static_files_dir) = setup();
+        let verifier = StaticFileVerifier::new(provider_factory.clone());
+
+        let reports = verifier.verify().expect("verify static files");
+        assert!(!reports.is_empty());
+        assert!(reports.iter().all(StaticFileReport::is_ok), "{reports:?}");
+        assert_eq!(verifier.repair(StaticFileSegment::Transactions).unwrap(), None);
+
+        // Cut off the data of the last transaction, as an unclean shutdown would
+        let path = reports
+            .iter()
+            .find(|report| report.segment == StaticFileSegment::Transactions)
+            .map(|report| report.path.clone())
+            .unwrap();
+        let file = OpenOptions::new().write(true).open(&path).unwrap();
+        file.set_len(file.metadata().unwrap().len() - 1).unwrap();
+        drop(file);
+
+        let report = verifier
+            .verify_segment(StaticFileSegment::Transactions)
+            .expect("verify static files")
+            .pop()
+            .unwrap();
+        assert!(!report.is_ok());
+        let tx_len = report.header.as_ref().and_then(SegmentHeader::tx_len).unwrap();
+        assert_eq!(report.corrupted_tail(), Some(tx_len - 1));
+
+        // The tail is truncated to the last complete block and produced again
+        let repaired = verifier.repair(StaticFileSegment::Transactions).unwrap().unwrap();
+        assert_eq!(*repaired.end(), 3);
+        let report = verifier
+            .verify_segment(StaticFileSegment::Transactions)
+            .expect("verify static files")
+            .pop()
+            .unwrap();
+        assert!(report.is_ok(), "{report:?}");
+        assert_eq!(report.header.as_ref().and_then(SegmentHeader::tx_len), Some(tx_len));
+    }
+
+    #[test]
+    fn verify_canonical_hashes() {
+        let (provider_factory, _temp_static_files_dir) = setup();
+        let verifier = StaticFileVerifier::new(provider_factory.clone());
+        let provider = provider_factory.provider().unwrap();
+        let (hash_1, hash_2) =
+            (provider.block_hash(1).unwrap().unwrap(), provider.block_hash(2).unwrap().unwrap());
+        drop(provider);
+
+        // Canonical hashes that are still in the database have to match the static files
+        let tx = provider_factory.db_ref().tx_mut().expect("init tx");
+        tx.put::<tables::CanonicalHeaders>(1, hash_1).unwrap();
+        tx.commit().expect("commit tx");
+        let report = verifier
+            .verify_segment(StaticFileSegment::Headers)
+            .expect("verify static files")
+            .pop()
+            .unwrap();
+        assert!(report.is_ok(), "{report:?}");
+
+        let tx = provider_factory.db_ref().tx_mut().expect("init tx");
+        tx.put::<tables::CanonicalHeaders>(2, B256::with_last_byte(1)).unwrap();
+        tx.commit().expect("commit tx");
+        let report = verifier
+            .verify_segment(StaticFileSegment::Headers)
+            .expect("verify static files")
+            .pop()
+            .unwrap();
+        assert_eq!(
+            report.issues,
+            vec![StaticFileIssue::HashMismatch {
+                row: 2,
+                expected: B256::with_last_byte(1),
+                found: hash_2
+            }]
+        );
+        assert_eq!(report.corrupted_tail(), Some(2));
+    }
+}
@@ -0,0 +1,203 @@
+//! Writer of the static file segments whose rows are built per block or per transaction by this
+//! crate, rather than by the static file provider.
+
+use crate::compression::{with_compression, write_dictionary_version};
+use alloy_primitives::{Address, BlockNumber, TxNumber};
+use reth_db_api::{
+    models::{AccountBeforeTx, StoredBlockBodyIndices, StoredBlockOmmers, StoredBlockWithdrawals},
+    table::Compress,
+};
+use reth_nippy_jar::{NippyJar, NippyJarWriter};
+use reth_primitives::StorageEntry;
+use reth_static_file_types::{find_fixed_range, Compression, SegmentHeader, StaticFileSegment};
+use reth_storage_errors::provider::{ProviderError, ProviderResult};
+use std::path::{Path, PathBuf};
+
+/// Appends rows of the [`StaticFileSegment::Senders`], [`StaticFileSegment::BlockBodies`],
+/// [`StaticFileSegment::AccountChangeSets`] and [`StaticFileSegment::StorageChangeSets`]
+/// segments to static files.
+///
+/// Static files of these segments cover the same fixed block ranges as the ones of the other
+/// segments, see [`find_fixed_range`]. The writer moves on to the next static file once a block
+/// of the next range is appended. Rows are encoded the same way as by
+/// [`Segment::create_static_file_file`](crate::segments::Segment::create_static_file_file), and
+/// can be read with [`StaticFileSegmentReader`](crate::StaticFileSegmentReader).
+///
+/// Rows are appended one at a time, so dictionaries can't be sampled for
+/// [`Compression::ZstdWithDictionary`], which falls back to [`Compression::Zstd`]. Static files
+/// can be recompressed with dictionaries offline, with
+/// [`recompress_segment`](crate::recompress_segment).
+#[derive(Debug)]
+pub struct StaticFileSegmentWriter {
+    /// Directory of the static files.
+    directory: PathBuf,
+    /// Segment the rows are appended to.
+    segment: StaticFileSegment,
+    /// Compression of the static files created by the writer.
+    compression: Compression,
+    /// Writer of the static file of the current block range.
+    writer: NippyJarWriter<SegmentHeader>,
+}
+
+impl StaticFileSegmentWriter {
+    /// Creates a new [`StaticFileSegmentWriter`] that appends rows to the static file of the
+    /// segment containing `block`, which is created with the given compression if it doesn't
+    /// exist yet.
+    ///
+    /// Static files that already exist keep their compression.
+    pub fn new(
+        directory: impl AsRef<Path>,
+        segment: StaticFileSegment,
+        compression: Compression,
+        block: BlockNumber,
+    ) -> ProviderResult<Self> {
+        let directory = directory.as_ref().to_path_buf();
+        let compression = match compression {
+            Compression::ZstdWithDictionary => Compression::Zstd,
+            compression => compression,
+        };
+        let writer = Self::open(&directory, segment, compression, block)?;
+        Ok(Self { directory, segment, compression, writer })
+    }
+
+    /// Opens the static file of the segment containing `block`, or creates it.
+    fn open(
+        directory: &Path,
+        segment: StaticFileSegment,
+        compression: Compression,
+        block: BlockNumber,
+    ) -> ProviderResult<NippyJarWriter<SegmentHeader>> {
+        let block_range = find_fixed_range(block);
+        let path = directory.join(segment.filename(&block_range));
+
+        let jar = if path.exists() {
+            NippyJar::load(&path).map_err(|e| ProviderError::NippyJar(e.to_string()))?
+        } else {
+            write_dictionary_version(&path, None)?;
+            with_compression(
+                NippyJar::new(
+                    segment.columns(),
+                    &path,
+                    SegmentHeader::new(block_range, None, None, segment),
+                ),
+                compression,
+                None,
+            )?
+        };
+
+        NippyJarWriter::new(jar).map_err(|e| ProviderError::NippyJar(e.to_string()))
+    }
+
+    /// Returns the segment the rows are appended to.
+    pub const fn segment(&self) -> StaticFileSegment {
+        self.segment
+    }
+
+    /// Appends `block` to the block range of the static file, moving on to the static file of
+    /// the next block range if needed.
+    ///
+    /// Blocks have to be appended in order, without gaps.
+    pub fn increment_block(&mut self, block: BlockNumber) -> ProviderResult<BlockNumber> {
+        if block > self.writer.user_header().expected_block_end() {
+            self.commit()?;
+            self.writer = Self::open(&self.directory, self.segment, self.compression, block)?;
+        }
+
+        let header = self.writer.user_header();
+        let next_block = header.block_end().map_or(header.expected_block_start(), |end| end + 1);
+        if block != next_block {
+            return Err(ProviderError::UnexpectedStaticFileBlockNumber(
+                self.segment,
+                block,
+                next_block,
+            ))
+        }
+
+        Ok(self.writer.user_header_mut().increment_block())
+    }
+
+    /// Appends the sender of the transaction `tx_num` of the current block.
+    ///
+    /// Transactions have to be appended in order, without gaps.
+    pub fn append_transaction_sender(
+        &mut self,
+        tx_num: TxNumber,
+        sender: Address,
+    ) -> ProviderResult<TxNumber> {
+        debug_assert_eq!(self.segment, StaticFileSegment::Senders);
+
+        let header = self.writer.user_header_mut();
+        match header.tx_end() {
+            Some(tx_end) if tx_num != tx_end + 1 => {
+                return Err(ProviderError::UnexpectedStaticFileTxNumber(
+                    self.segment,
+                    tx_num,
+                    tx_end + 1,
+                ))
+            }
+            Some(_) => header.increment_tx(),
+            None => header.set_tx_range(tx_num, tx_num),
+        }
+
+        self.append_row([sender.compress().into()])?;
+        Ok(tx_num)
+    }
+
+    /// Appends the body of `block`.
+    pub fn append_block_body(
+        &mut self,
+        block: BlockNumber,
+        indices: StoredBlockBodyIndices,
+        ommers: StoredBlockOmmers,
+        withdrawals: StoredBlockWithdrawals,
+    ) -> ProviderResult<BlockNumber> {
+        debug_assert_eq!(self.segment, StaticFileSegment::BlockBodies);
+
+        let block = self.increment_block(block)?;
+        self.append_row(crate::segments::BlockBodies::row(indices, ommers, withdrawals))?;
+        Ok(block)
+    }
+
+    /// Appends all account changes of `block`.
+    pub fn append_account_changeset(
+        &mut self,
+        block: BlockNumber,
+        changeset: Vec<AccountBeforeTx>,
+    ) -> ProviderResult<BlockNumber> {
+        debug_assert_eq!(self.segment, StaticFileSegment::AccountChangeSets);
+
+        let block = self.increment_block(block)?;
+        self.append_row(crate::segments::AccountChangeSets::row(changeset))?;
+        Ok(block)
+    }
+
+    /// Appends all storage changes of `block`, ordered by address and storage key.
+    pub fn append_storage_changeset(
+        &mut self,
+        block: BlockNumber,
+        changeset: Vec<(Address, StorageEntry)>,
+    ) -> ProviderResult<BlockNumber> {
+        debug_assert_eq!(self.segment, StaticFileSegment::StorageChangeSets);
+
+        let block = self.increment_block(block)?;
+        self.append_row(crate::segments::StorageChangeSets::row(changeset))?;
+        Ok(block)
+    }
+
+    /// Appends a value to every column of the static file.
+    fn append_row<const COLUMNS: usize>(&mut self, row: [Vec<u8>; COLUMNS]) -> ProviderResult<()> {
+        debug_assert_eq!(COLUMNS, self.segment.columns());
+
+        for value in row {
+            self.writer
+                .append_column(Some(Ok::<_, Box<dyn std::error::Error + Send + Sync>>(value)))
+                .map_err(|e| ProviderError::NippyJar(e.to_string()))?;
+        }
+        Ok(())
+    }
+
+    /// Commits the appended rows and the header to disk.
+    pub fn commit(&mut self) -> ProviderResult<()> {
+        self.writer.commit().map_err(|e| ProviderError::NippyJar(e.to_string()))
+    }
+}
@@ -1,8 +1,11 @@
+use crate::{SegmentConfig, StaticFileSegment};
+use serde::{Deserialize, Serialize};
+use std::collections::BTreeMap;
 use strum::AsRefStr;
 
 /// Static File compression types.
 /// Defines the different types of compression that can be applied to static files.
-#[derive(Debug, Copy, Clone, Default, AsRefStr)]
+#[derive(Debug, Copy, Clone, Default, PartialEq, Eq, AsRefStr, Serialize, Deserialize)]
 #[cfg_attr(feature = "clap", derive(clap::ValueEnum))]
 pub enum Compression {
     /// LZ4 compression algorithm.
@@ -11,20 +14,52 @@ pub enum Compression {
     /// ITis a fast compression algorithm that offers a good balance between speed and compression ratio.
     /// LZ4 only uses a dictionary-matching stage 
     #[strum(serialize = "lz4")]
+    #[serde(rename = "lz4")]
     Lz4,
     /// Zstandard (Zstd) compression algorithm.
     /// Zstandard is a lossless data compression algorithm
     /// Known for high compression ratios and fast decompression speeds.
     #[strum(serialize = "zstd")]
+    #[serde(rename = "zstd")]
     Zstd,
     /// Zstandard (Zstd) compression algorithm with a dictionary.
     /// Zstd with dictionary is an enhanced compression method using a predefined dictionary for better compression performance
     /// When utilizing a dictionary, Zstd can effectively compress data by referencing pre-sampled data patterns contained within the dictionary
     #[strum(serialize = "zstd-dict")]
+    #[serde(rename = "zstd-dict")]
+    #[cfg_attr(feature = "clap", value(name = "zstd-dict"))]
     ZstdWithDictionary,
     /// No compression.
     /// Indicates that the static file is not compressed.
     #[strum(serialize = "uncompressed")]
+    #[serde(rename = "uncompressed")]
     #[default]
     Uncompressed,
 }
+
+/// Compression of static file segments, as configured by the node.
+#[derive(Debug, Clone, Default, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(default)]
+pub struct StaticFileCompressionConfig {
+    /// Compression per segment. Segments without an entry use the compression of
+    /// [`StaticFileSegment::config`].
+    pub segments: BTreeMap<StaticFileSegment, Compression>,
+}
+
+impl StaticFileCompressionConfig {
+    /// Sets the compression of the segment.
+    pub fn with_segment(mut self, segment: StaticFileSegment, compression: Compression) -> Self {
+        self.segments.insert(segment, compression);
+        self
+    }
+
+    /// Returns the compression of the segment.
+    pub fn compression(&self, segment: StaticFileSegment) -> Compression {
+        self.segments.get(&segment).copied().unwrap_or_else(|| segment.config().compression)
+    }
+
+    /// Returns the configuration of the segment, with the configured compression.
+    pub fn segment_config(&self, segment: StaticFileSegment) -> SegmentConfig {
+        SegmentConfig { compression: self.compression(segment), ..segment.config() }
+    }
+}
@@ -11,7 +11,7 @@ mod filters;
 mod segment;
 
 use alloy_primitives::BlockNumber;
-pub use compression::Compression;
+pub use compression::{Compression, StaticFileCompressionConfig};
 pub use filters::{Filters, InclusionFilter, PerfectHashingFunction};
 pub use segment::{SegmentConfig, SegmentHeader, SegmentRangeInclusive, StaticFileSegment};
 
@@ -33,6 +33,18 @@ pub struct HighestStaticFiles {
     /// Highest static file block of transactions.
     /// If `None`, no static file is available for transactions.
     pub transactions: Option<BlockNumber>,
+    /// Highest static file block of transaction senders.
+    /// If `None`, no static file is available for transaction senders.
+    pub senders: Option<BlockNumber>,
+    /// Highest static file block of block bodies.
+    /// If `None`, no static file is available for block bodies.
+    pub block_bodies: Option<BlockNumber>,
+    /// Highest static file block of account changesets.
+    /// If `None`, no static file is available for account changesets.
+    pub account_changesets: Option<BlockNumber>,
+    /// Highest static file block of storage changesets.
+    /// If `None`, no static file is available for storage changesets.
+    pub storage_changesets: Option<BlockNumber>,
 }
 
 impl HighestStaticFiles {
@@ -42,6 +54,10 @@ impl HighestStaticFiles {
             StaticFileSegment::Headers => self.headers,
             StaticFileSegment::Transactions => self.transactions,
             StaticFileSegment::Receipts => self.receipts,
+            StaticFileSegment::Senders => self.senders,
+            StaticFileSegment::BlockBodies => self.block_bodies,
+            StaticFileSegment::AccountChangeSets => self.account_changesets,
+            StaticFileSegment::StorageChangeSets => self.storage_changesets,
         }
     }
 
@@ -51,17 +67,34 @@ impl HighestStaticFiles {
             StaticFileSegment::Headers => &mut self.headers,
             StaticFileSegment::Transactions => &mut self.transactions,
             StaticFileSegment::Receipts => &mut self.receipts,
+            StaticFileSegment::Senders => &mut self.senders,
+            StaticFileSegment::BlockBodies => &mut self.block_bodies,
+            StaticFileSegment::AccountChangeSets => &mut self.account_changesets,
+            StaticFileSegment::StorageChangeSets => &mut self.storage_changesets,
         }
     }
 
     /// Returns the minimum block number among all segments.
     pub fn min(&self) -> Option<u64> {
-        [self.headers, self.transactions, self.receipts].iter().filter_map(|&option| option).min()
+        self.all().into_iter().flatten().min()
     }
 
     /// Returns the maximum block number among all segments.
     pub fn max(&self) -> Option<u64> {
-        [self.headers, self.transactions, self.receipts].iter().filter_map(|&option| option).max()
+        self.all().into_iter().flatten().max()
+    }
+
+    /// Returns the highest static file block numbers of all segments.
+    const fn all(&self) -> [Option<BlockNumber>; 7] {
+        [
+            self.headers,
+            self.transactions,
+            self.receipts,
+            self.senders,
+            self.block_bodies,
+            self.account_changesets,
+            self.storage_changesets,
+        ]
     }
 }
 
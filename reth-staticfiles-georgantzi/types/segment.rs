@@ -39,6 +39,25 @@ pub enum StaticFileSegment {
     #[strum(serialize = "receipts")]
     /// Static File segment responsible for the `Receipts` table.
     Receipts,
+
+    #[strum(serialize = "senders")]
+    /// Static File segment responsible for the `TransactionSenders` table.
+    Senders,
+
+    #[strum(serialize = "block-bodies")]
+    /// Static File segment responsible for the `BlockBodyIndices`, `BlockOmmers` and
+    /// `BlockWithdrawals` tables.
+    BlockBodies,
+
+    #[strum(serialize = "account-changesets")]
+    /// Static File segment responsible for the `AccountChangeSets` table. Each row holds all
+    /// account changes of a block.
+    AccountChangeSets,
+
+    #[strum(serialize = "storage-changesets")]
+    /// Static File segment responsible for the `StorageChangeSets` table. Each row holds all
+    /// storage changes of a block.
+    StorageChangeSets,
 }
 
 impl StaticFileSegment {
@@ -48,6 +67,10 @@ impl StaticFileSegment {
             Self::Headers => "headers",
             Self::Transactions => "transactions",
             Self::Receipts => "receipts",
+            Self::Senders => "senders",
+            Self::BlockBodies => "block-bodies",
+            Self::AccountChangeSets => "account-changesets",
+            Self::StorageChangeSets => "storage-changesets",
         }
     }
 
@@ -61,16 +84,25 @@ impl StaticFileSegment {
             compression: Compression::Lz4,
         };
 
+        // Rows of these segments are never looked up by hash, so filters are of no use
+        let unfiltered_config =
+            SegmentConfig { filters: Filters::WithoutFilters, compression: Compression::Lz4 };
+
         match self {
             Self::Headers | Self::Transactions | Self::Receipts => default_config,
+            Self::Senders |
+            Self::BlockBodies |
+            Self::AccountChangeSets |
+            Self::StorageChangeSets => unfiltered_config,
         }
     }
 
     /// Returns the number of columns for the segment.
     pub const fn columns(&self) -> usize {
         match self {
-            Self::Headers => 3,
-            Self::Transactions | Self::Receipts => 1,
+            Self::Headers | Self::BlockBodies => 3,
+            Self::StorageChangeSets => 2,
+            Self::Transactions | Self::Receipts | Self::Senders | Self::AccountChangeSets => 1,
         }
     }
 
@@ -124,9 +156,18 @@ impl StaticFileSegment {
     pub const fn is_receipts(&self) -> bool {
         matches!(self, Self::Receipts)
     }
+
+    /// Returns `true` if the rows of the segment are indexed by transaction number rather than by
+    /// block number.
+    pub const fn is_tx_based(&self) -> bool {
+        matches!(self, Self::Transactions | Self::Receipts | Self::Senders)
+    }
 }
 
 /// A segment header that contains information common to all segments. Used for storage.
+///
+/// NOTE: the header is encoded with bincode in the configuration of every static file, so its
+/// layout can't change without breaking existing static files.
 #[derive(Debug, Serialize, Deserialize, Eq, PartialEq, Hash, Clone)]
 pub struct SegmentHeader {
     expected_block_range: SegmentRangeInclusive,
@@ -233,14 +274,11 @@ impl SegmentHeader {
     /// Increments tx end range depending on segment.
     /// Modifies the end boundary of the transaction range (tx_range) in the SegmentHeader struct.
     pub fn increment_tx(&mut self) {
-        match self.segment {
-            StaticFileSegment::Headers => (),
-            StaticFileSegment::Transactions | StaticFileSegment::Receipts => {
-                if let Some(tx_range) = &mut self.tx_range {
-                    tx_range.end += 1;
-                } else {
-                    self.tx_range = Some(SegmentRangeInclusive::new(0, 0));
-                }
+        if self.segment.is_tx_based() {
+            if let Some(tx_range) = &mut self.tx_range {
+                tx_range.end += 1;
+            } else {
+                self.tx_range = Some(SegmentRangeInclusive::new(0, 0));
             }
         }
     }
@@ -253,7 +291,10 @@ impl SegmentHeader {
 
     pub fn prune(&mut self, num: u64) {
         match self.segment {
-            StaticFileSegment::Headers => {
+            StaticFileSegment::Headers |
+            StaticFileSegment::BlockBodies |
+            StaticFileSegment::AccountChangeSets |
+            StaticFileSegment::StorageChangeSets => {
                 if let Some(range) = &mut self.block_range {
                     if num > range.end {
                         self.block_range = None;
@@ -262,7 +303,9 @@ impl SegmentHeader {
                     }
                 };
             }
-            StaticFileSegment::Transactions | StaticFileSegment::Receipts => {
+            StaticFileSegment::Transactions |
+            StaticFileSegment::Receipts |
+            StaticFileSegment::Senders => {
                 if let Some(range) = &mut self.tx_range {
                     if num > range.end {
                         self.tx_range = None;
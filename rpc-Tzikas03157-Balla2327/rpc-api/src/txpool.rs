@@ -1,6 +1,9 @@
 use jsonrpsee::{core::RpcResult, proc_macros::rpc};
-use reth_primitives::Address;
-use reth_rpc_types::txpool::{TxpoolContent, TxpoolContentFrom, TxpoolInspect, TxpoolStatus};
+use reth_primitives::{Address, TxHash};
+use reth_rpc_types::{
+    txpool::{TxpoolContent, TxpoolContentFrom, TxpoolInspect, TxpoolStatus},
+    TxpoolAuditEntry,
+};
 
 /// Txpool rpc interface.
 #[cfg_attr(not(feature = "client"), rpc(server, namespace = "txpool"))]
@@ -33,4 +36,11 @@ pub trait TxPoolApi {
     /// See [here](https://geth.ethereum.org/docs/rpc/ns-txpool#txpool_content) for more details
     #[method(name = "content")]
     async fn txpool_content(&self) -> RpcResult<TxpoolContent>;
+
+    /// Returns why and when the transaction with the given hash left the txpool.
+    ///
+    /// Returns `None` if the transaction is still pending or queued, was mined, is unknown, or its
+    /// record was already evicted from the bounded audit log.
+    #[method(name = "audit")]
+    async fn txpool_audit(&self, hash: TxHash) -> RpcResult<Option<TxpoolAuditEntry>>;
 }
@@ -14,6 +14,7 @@ mod eth;
 mod mev;
 mod peer;
 mod rpc;
+mod txpool_audit;
 
 // re-export for convenience
 pub use alloy_rpc_types::serde_helpers;
@@ -54,3 +55,4 @@ pub use eth::{
 pub use mev::*;
 pub use peer::*;
 pub use rpc::*;
+pub use txpool_audit::*;
@@ -0,0 +1,93 @@
+//! Types for the `txpool_audit` endpoint.
+
+use alloy_primitives::{Address, TxHash};
+use serde::{Deserialize, Serialize};
+
+/// A record of a transaction that left the transaction pool without being mined.
+#[derive(Clone, Debug, Serialize, Deserialize, PartialEq, Eq)]
+#[serde(rename_all = "camelCase")]
+pub struct TxpoolAuditEntry {
+    /// Hash of the removed transaction.
+    pub hash: TxHash,
+    /// Sender of the removed transaction.
+    pub from: Address,
+    /// Nonce of the removed transaction.
+    #[serde(with = "alloy_rpc_types::serde_helpers::quantity")]
+    pub nonce: u64,
+    /// Where the transaction originated from.
+    pub origin: TxpoolTransactionOrigin,
+    /// Why the transaction was removed.
+    pub reason: TxpoolRemovalReason,
+    /// The block the pool was tracking when the transaction was removed.
+    #[serde(with = "alloy_rpc_types::serde_helpers::quantity")]
+    pub block_number: u64,
+    /// Unix timestamp in milliseconds at which the transaction was removed.
+    #[serde(with = "alloy_rpc_types::serde_helpers::quantity")]
+    pub timestamp: u64,
+}
+
+/// Where a transaction of the transaction pool originated from.
+#[derive(Clone, Copy, Debug, Serialize, Deserialize, PartialEq, Eq)]
+#[serde(rename_all = "camelCase")]
+pub enum TxpoolTransactionOrigin {
+    /// Submitted locally, e.g. via RPC.
+    Local,
+    /// Received from the network.
+    External,
+    /// Submitted locally and not propagated to the network.
+    Private,
+}
+
+/// The reason a transaction was removed from the transaction pool.
+#[derive(Clone, Copy, Debug, Serialize, Deserialize, PartialEq, Eq)]
+#[serde(tag = "type", rename_all = "camelCase")]
+pub enum TxpoolRemovalReason {
+    /// Replaced by a transaction of the same sender and nonce that pays a higher price.
+    #[serde(rename_all = "camelCase")]
+    Replaced {
+        /// Hash of the replacing transaction.
+        replaced_by: TxHash,
+    },
+    /// Evicted because the sub-pool it resided in exceeded its limits.
+    DiscardedForSize,
+    /// Evicted because a transaction of the same sender with a lower nonce was removed.
+    NonceGap,
+    /// Invalidated by the state of the given block.
+    #[serde(rename_all = "camelCase")]
+    InvalidatedByBlock {
+        /// Number of the block that invalidated the transaction.
+        #[serde(with = "alloy_rpc_types::serde_helpers::quantity")]
+        block_number: u64,
+    },
+    /// Evicted because its fee cap was below the pending base fee.
+    #[serde(rename_all = "camelCase")]
+    UnderpricedAfterBaseFeeChange {
+        /// The pending base fee at the time of the removal.
+        #[serde(with = "alloy_rpc_types::serde_helpers::quantity")]
+        pending_base_fee: u64,
+    },
+    /// Explicitly removed from the pool.
+    Removed,
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn serde_txpool_audit_entry() {
+        let s = r#"{"hash":"0x4e4d3d0f2b1e8d9f0ea8a5dc3a8e0f3f2a1f0e8d5c6b7a8b9c0d1e2f3a4b5c6d","from":"0x0000000000000000000000000000000000000001","nonce":"0x2","origin":"external","reason":{"type":"replaced","replacedBy":"0x0000000000000000000000000000000000000000000000000000000000000001"},"blockNumber":"0x10","timestamp":"0x18bcfe56800"}"#;
+        let entry: TxpoolAuditEntry = serde_json::from_str(s).unwrap();
+        assert_eq!(entry.nonce, 2);
+        assert_eq!(entry.origin, TxpoolTransactionOrigin::External);
+        assert_eq!(
+            entry.reason,
+            TxpoolRemovalReason::Replaced { replaced_by: TxHash::with_last_byte(1) }
+        );
+        assert_eq!(serde_json::to_string(&entry).unwrap(), s);
+
+        let reason: TxpoolRemovalReason =
+            serde_json::from_str(r#"{"type":"invalidatedByBlock","blockNumber":"0x10"}"#).unwrap();
+        assert_eq!(reason, TxpoolRemovalReason::InvalidatedByBlock { block_number: 16 });
+    }
+}
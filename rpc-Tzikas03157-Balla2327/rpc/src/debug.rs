@@ -22,7 +22,10 @@ use reth_revm::database::StateProviderDatabase;
 use reth_rpc_api::DebugApiServer;
 use reth_rpc_eth_api::helpers::{Call, EthApiSpec, EthTransactions, TraceExt};
 use reth_rpc_eth_types::{revm_utils::prepare_call_env, EthApiError, EthResult, StateCacheDb};
-use reth_rpc_server_types::{result::internal_rpc_err, ToRpcResult};
+use reth_rpc_server_types::{
+    result::{internal_rpc_err, invalid_params_rpc_err},
+    ToRpcResult,
+};
 use reth_rpc_types::{
     state::EvmOverrides,
     trace::geth::{
@@ -1060,12 +1063,22 @@ where
         Ok(())
     }
 
-    async fn debug_verbosity(&self, _level: usize) -> RpcResult<()> {
-        Ok(())
+    /// Handler for `debug_verbosity`
+    ///
+    /// Sets the verbosity ceiling of all log outputs, from `0` (off) to `5` (trace).
+    async fn debug_verbosity(&self, level: usize) -> RpcResult<()> {
+        log_filter_handle()?
+            .set_verbosity(reth_tracing::verbosity_level(level))
+            .map_err(|err| internal_rpc_err(err.to_string()))
     }
 
-    async fn debug_vmodule(&self, _pattern: String) -> RpcResult<()> {
-        Ok(())
+    /// Handler for `debug_vmodule`
+    ///
+    /// Sets the per target log verbosity, e.g. `net/eth=5,sync::stages=debug`.
+    async fn debug_vmodule(&self, pattern: String) -> RpcResult<()> {
+        log_filter_handle()?
+            .set_vmodule(&pattern)
+            .map_err(|err| invalid_params_rpc_err(err.to_string()))
     }
 
     async fn debug_write_block_profile(&self, _file: String) -> RpcResult<()> {
@@ -1102,3 +1115,10 @@ struct DebugApiInner<Provider, Eth> {
     /// Restricts the number of concurrent calls to blocking tasks.
     blocking_task_guard: BlockingTaskGuard,
 }
+
+/// Returns the handle to the log filters of the node, or an error if the node was started without
+/// reloadable log filters.
+fn log_filter_handle() -> RpcResult<reth_tracing::LogFilterHandle> {
+    reth_tracing::log_filter_handle()
+        .ok_or_else(|| internal_rpc_err("log filters are not reloadable"))
+}
@@ -1,12 +1,15 @@
 use async_trait::async_trait;
 use jsonrpsee::core::RpcResult as Result;
-use reth_primitives::Address;
+use reth_primitives::{Address, TxHash};
 use reth_rpc_api::TxPoolApiServer;
 use reth_rpc_types::{
     txpool::{TxpoolContent, TxpoolContentFrom, TxpoolInspect, TxpoolInspectSummary, TxpoolStatus},
-    Transaction,
+    Transaction, TxpoolAuditEntry, TxpoolRemovalReason, TxpoolTransactionOrigin,
+};
+use reth_transaction_pool::{
+    AllPoolTransactions, PoolTransaction, TransactionAuditEntry, TransactionOrigin,
+    TransactionPool, TransactionRemovalReason,
 };
-use reth_transaction_pool::{AllPoolTransactions, PoolTransaction, TransactionPool};
 use std::collections::BTreeMap;
 use tracing::trace;
 
@@ -143,6 +146,42 @@ where
         trace!(target: "rpc::eth", "Serving txpool_content");
         Ok(self.content())
     }
+
+    /// Handler for `txpool_audit`.
+    ///
+    /// Returns why and when the transaction with the given hash left the txpool.
+    async fn txpool_audit(&self, hash: TxHash) -> Result<Option<TxpoolAuditEntry>> {
+        trace!(target: "rpc::eth", ?hash, "Serving txpool_audit");
+        Ok(self.pool.transaction_audit(&hash).map(to_rpc_audit_entry))
+    }
+}
+
+/// Converts an entry of the pool's audit log into its RPC representation.
+fn to_rpc_audit_entry(entry: TransactionAuditEntry) -> TxpoolAuditEntry {
+    let TransactionAuditEntry { hash, sender, nonce, origin, reason, block_number, timestamp } =
+        entry;
+
+    let origin = match origin {
+        TransactionOrigin::Local => TxpoolTransactionOrigin::Local,
+        TransactionOrigin::External => TxpoolTransactionOrigin::External,
+        TransactionOrigin::Private => TxpoolTransactionOrigin::Private,
+    };
+    let reason = match reason {
+        TransactionRemovalReason::Replaced(replaced_by) => {
+            TxpoolRemovalReason::Replaced { replaced_by }
+        }
+        TransactionRemovalReason::DiscardedForSize => TxpoolRemovalReason::DiscardedForSize,
+        TransactionRemovalReason::NonceGap => TxpoolRemovalReason::NonceGap,
+        TransactionRemovalReason::InvalidatedByBlock(block_number) => {
+            TxpoolRemovalReason::InvalidatedByBlock { block_number }
+        }
+        TransactionRemovalReason::UnderpricedAfterBaseFeeChange(pending_base_fee) => {
+            TxpoolRemovalReason::UnderpricedAfterBaseFeeChange { pending_base_fee }
+        }
+        TransactionRemovalReason::Removed => TxpoolRemovalReason::Removed,
+    };
+
+    TxpoolAuditEntry { hash, from: sender, nonce, origin, reason, block_number, timestamp }
 }
 
 impl<Pool> std::fmt::Debug for TxPoolApi<Pool> {
@@ -13,6 +13,57 @@ use std::{
 /// Imports the "Sleep" type from the Tokio crate, used for asynchronously waiting until a specified instant in time.
 use tokio::time::Sleep;
 
+mod keyed;
+mod sliding_window;
+mod stream;
+mod token_bucket;
+
+#[cfg(feature = "tower")]
+mod layer;
+
+pub use keyed::KeyedRateLimiter;
+pub use sliding_window::SlidingWindow;
+pub use stream::{KeyedRateLimitedStream, RateLimited, RateLimitedStream};
+pub use token_bucket::TokenBucket;
+
+#[cfg(feature = "tower")]
+pub use layer::{
+    KeyedRequestLimiter, RateLimitExceeded, RateLimitFuture, RateLimitLayer, RateLimitService,
+    RequestLimiter, SharedLimiter,
+};
+
+/// A policy that limits how often an action can be performed.
+///
+/// Like [`RateLimit`], a limiter is driven in two steps: callers wait until
+/// [`Limiter::poll_ready`] returns [`Poll::Ready`], and then record the action with
+/// [`Limiter::tick`].
+pub trait Limiter {
+    /// Checks if the limiter allows a new action, and registers the current task for wakeup if
+    /// it doesn't.
+    fn poll_ready(&mut self, cx: &mut Context<'_>) -> Poll<()>;
+
+    /// Records a new action.
+    ///
+    /// # Panics
+    ///
+    /// May panic if [`Limiter::poll_ready`] did not return [`Poll::Ready`] first.
+    fn tick(&mut self);
+
+    /// Records a new action if the limiter allows it right away, without registering for
+    /// wakeup.
+    ///
+    /// Returns `false` if the action is over the limit.
+    fn try_tick(&mut self) -> bool;
+
+    /// Wait until the limiter allows a new action.
+    fn wait(&mut self) -> impl Future<Output = ()> + '_
+    where
+        Self: Sized,
+    {
+        poll_fn(move |cx| self.poll_ready(cx))
+    }
+}
+
 /// Given a [Rate] this type enforces a rate limit.
 #[derive(Debug)]
 
@@ -116,6 +167,29 @@ impl RateLimit {
     }
 }
 
+impl Limiter for RateLimit {
+    fn poll_ready(&mut self, cx: &mut Context<'_>) -> Poll<()> {
+        Self::poll_ready(self, cx)
+    }
+
+    fn tick(&mut self) {
+        Self::tick(self)
+    }
+
+    fn try_tick(&mut self) -> bool {
+        if let State::Limited = self.state {
+            let now = tokio::time::Instant::now();
+            if now < self.sleep.deadline() {
+                return false
+            }
+            self.state =
+                State::Ready { until: now + self.rate.duration(), remaining: self.rate.limit() };
+        }
+        Self::tick(self);
+        true
+    }
+}
+
 /// Tracks the state of the [`RateLimit`]
 #[derive(Debug)]
 
@@ -0,0 +1,173 @@
+//! A rate limiter that manages a separate limiter per key.
+
+use super::Limiter;
+use std::{
+    collections::HashMap,
+    fmt,
+    hash::Hash,
+    task::{Context, Poll},
+    time::Duration,
+};
+use tokio::time::Instant;
+
+/// Default duration after which the limiter of an unused key is dropped.
+const DEFAULT_IDLE_TIMEOUT: Duration = Duration::from_secs(300);
+
+/// Manages a separate [Limiter] for every key, e.g. per peer ID or per IP address.
+///
+/// Limiters are created on first use of a key with the given function, and are dropped again
+/// once the key was not used for the idle timeout. The idle timeout should be longer than the
+/// period of the limiters, otherwise a key can regain its full budget by staying idle.
+pub struct KeyedRateLimiter<K, L, F = fn() -> L> {
+    /// Creates the limiter for a new key.
+    new_limiter: F,
+    /// The limiters by key.
+    limiters: HashMap<K, KeyedEntry<L>>,
+    /// Duration after which the limiter of an unused key is dropped.
+    idle_timeout: Duration,
+    /// When idle limiters were last evicted.
+    last_eviction: Instant,
+}
+
+/// The limiter of a single key.
+#[derive(Debug)]
+struct KeyedEntry<L> {
+    limiter: L,
+    last_used: Instant,
+}
+
+// === impl KeyedRateLimiter ===
+
+impl<K, L, F> KeyedRateLimiter<K, L, F>
+where
+    K: Eq + Hash + Clone,
+    L: Limiter,
+    F: Fn() -> L,
+{
+    /// Creates a new keyed rate limiter that creates the limiter of a new key with the given
+    /// function.
+    pub fn new(new_limiter: F) -> Self {
+        Self {
+            new_limiter,
+            limiters: HashMap::new(),
+            idle_timeout: DEFAULT_IDLE_TIMEOUT,
+            last_eviction: Instant::now(),
+        }
+    }
+
+    /// Sets the duration after which the limiter of an unused key is dropped.
+    pub const fn with_idle_timeout(mut self, idle_timeout: Duration) -> Self {
+        self.idle_timeout = idle_timeout;
+        self
+    }
+
+    /// Returns the number of keys with a limiter.
+    pub fn len(&self) -> usize {
+        self.limiters.len()
+    }
+
+    /// Returns `true` if there are no keys with a limiter.
+    pub fn is_empty(&self) -> bool {
+        self.limiters.is_empty()
+    }
+
+    /// Checks if the limiter of the key allows a new action, see [`Limiter::poll_ready`].
+    pub fn poll_ready(&mut self, key: &K, cx: &mut Context<'_>) -> Poll<()> {
+        self.entry(key).limiter.poll_ready(cx)
+    }
+
+    /// Records a new action for the key, see [`Limiter::tick`].
+    pub fn tick(&mut self, key: &K) {
+        self.entry(key).limiter.tick()
+    }
+
+    /// Records a new action for the key if its limiter allows it right away, see
+    /// [`Limiter::try_tick`].
+    pub fn try_tick(&mut self, key: &K) -> bool {
+        self.entry(key).limiter.try_tick()
+    }
+
+    /// Wait until the limiter of the key allows a new action.
+    pub async fn wait(&mut self, key: &K) {
+        std::future::poll_fn(|cx| self.poll_ready(key, cx)).await
+    }
+
+    /// Drops the limiter of the key, e.g. when a peer disconnects.
+    ///
+    /// Returns `true` if the key had a limiter.
+    pub fn remove(&mut self, key: &K) -> bool {
+        self.limiters.remove(key).is_some()
+    }
+
+    /// Drops the limiters of all keys that were not used for the idle timeout, and returns the
+    /// number of dropped limiters.
+    ///
+    /// This is done periodically on access, so it's only required to call this if the limiter
+    /// is not used for a while.
+    pub fn evict_idle(&mut self) -> usize {
+        let now = Instant::now();
+        let idle_timeout = self.idle_timeout;
+        let len = self.limiters.len();
+        self.limiters
+            .retain(|_, entry| now.saturating_duration_since(entry.last_used) < idle_timeout);
+        self.last_eviction = now;
+        len - self.limiters.len()
+    }
+
+    /// Returns the entry of the key, and creates it if it doesn't exist yet.
+    fn entry(&mut self, key: &K) -> &mut KeyedEntry<L> {
+        let now = Instant::now();
+        if now.saturating_duration_since(self.last_eviction) >= self.idle_timeout {
+            self.evict_idle();
+        }
+
+        if !self.limiters.contains_key(key) {
+            let limiter = (self.new_limiter)();
+            self.limiters.insert(key.clone(), KeyedEntry { limiter, last_used: now });
+        }
+        let entry = self.limiters.get_mut(key).expect("entry exists");
+        entry.last_used = now;
+        entry
+    }
+}
+
+impl<K: fmt::Debug, L: fmt::Debug, F> fmt::Debug for KeyedRateLimiter<K, L, F> {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.debug_struct("KeyedRateLimiter")
+            .field("limiters", &self.limiters)
+            .field("idle_timeout", &self.idle_timeout)
+            .field("last_eviction", &self.last_eviction)
+            .finish_non_exhaustive()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::ratelimit::{Rate, TokenBucket};
+
+    #[tokio::test(start_paused = true)]
+    async fn test_keyed_rate_limiter() {
+        let mut limiter =
+            KeyedRateLimiter::new(|| TokenBucket::new(1, Rate::new(1, Duration::from_secs(60))))
+                .with_idle_timeout(Duration::from_millis(100));
+
+        // every key has its own budget
+        assert!(limiter.try_tick(&1));
+        assert!(!limiter.try_tick(&1));
+        assert!(limiter.try_tick(&2));
+        assert_eq!(limiter.len(), 2);
+
+        assert!(limiter.remove(&2));
+        assert!(!limiter.remove(&2));
+
+        tokio::time::advance(Duration::from_millis(99)).await;
+        assert_eq!(limiter.evict_idle(), 0);
+        tokio::time::advance(Duration::from_millis(1)).await;
+        assert_eq!(limiter.evict_idle(), 1);
+        assert!(limiter.is_empty());
+
+        // an evicted key starts with a new limiter
+        assert!(limiter.try_tick(&1));
+    }
+}
@@ -0,0 +1,274 @@
+//! A [tower] layer that applies rate limits to the requests of a service, e.g. an RPC server.
+
+use super::{KeyedRateLimiter, Limiter};
+use pin_project::pin_project;
+use std::{
+    fmt,
+    future::Future,
+    hash::Hash,
+    pin::Pin,
+    sync::{Arc, Mutex, PoisonError},
+    task::{ready, Context, Poll},
+};
+use tower::{BoxError, Layer, Service};
+
+/// Decides if a request is within the rate limit.
+///
+/// Implementations are shared by all services created by a [`RateLimitLayer`].
+pub trait RequestLimiter<Request> {
+    /// Records the request and returns `true` if it is within the rate limit.
+    fn try_acquire(&self, request: &Request) -> bool;
+}
+
+/// A [Limiter] that is shared by all requests.
+#[derive(Debug)]
+pub struct SharedLimiter<L>(Arc<Mutex<L>>);
+
+impl<L> Clone for SharedLimiter<L> {
+    fn clone(&self) -> Self {
+        Self(Arc::clone(&self.0))
+    }
+}
+
+impl<Request, L: Limiter> RequestLimiter<Request> for SharedLimiter<L> {
+    fn try_acquire(&self, _request: &Request) -> bool {
+        self.0.lock().unwrap_or_else(PoisonError::into_inner).try_tick()
+    }
+}
+
+/// A [`KeyedRateLimiter`] that is shared by all requests, and applies the limit of the key
+/// returned by a function of the request, e.g. the IP address of the client.
+pub struct KeyedRequestLimiter<K, L, F, KF> {
+    limiter: Arc<Mutex<KeyedRateLimiter<K, L, F>>>,
+    key: Arc<KF>,
+}
+
+impl<K, L, F, KF> Clone for KeyedRequestLimiter<K, L, F, KF> {
+    fn clone(&self) -> Self {
+        Self { limiter: Arc::clone(&self.limiter), key: Arc::clone(&self.key) }
+    }
+}
+
+impl<Request, K, L, F, KF> RequestLimiter<Request> for KeyedRequestLimiter<K, L, F, KF>
+where
+    K: Eq + Hash + Clone,
+    L: Limiter,
+    F: Fn() -> L,
+    KF: Fn(&Request) -> K,
+{
+    fn try_acquire(&self, request: &Request) -> bool {
+        let key = (self.key)(request);
+        self.limiter.lock().unwrap_or_else(PoisonError::into_inner).try_tick(&key)
+    }
+}
+
+impl<K: fmt::Debug, L: fmt::Debug, F, KF> fmt::Debug for KeyedRequestLimiter<K, L, F, KF> {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.debug_struct("KeyedRequestLimiter")
+            .field("limiter", &self.limiter)
+            .finish_non_exhaustive()
+    }
+}
+
+/// The error returned by a [`RateLimitService`] for requests over the rate limit.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct RateLimitExceeded;
+
+impl fmt::Display for RateLimitExceeded {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.write_str("rate limit exceeded")
+    }
+}
+
+impl std::error::Error for RateLimitExceeded {}
+
+/// A [tower] layer that rejects the requests over a rate limit with [`RateLimitExceeded`].
+///
+/// The limit is shared by all services created by the layer, and their clones. Requests are
+/// rejected instead of delayed, so that clients over the limit do not hold on to server
+/// resources.
+///
+/// # Example
+///
+/// ```
+/// use reth_tokio_util::ratelimit::{Rate, RateLimitLayer, TokenBucket};
+/// use std::time::Duration;
+///
+/// # async fn f() {
+/// // allow bursts of 100 requests, and 10 requests per second on average
+/// let layer = RateLimitLayer::new(TokenBucket::new(100, Rate::new(10, Duration::from_secs(1))));
+/// let middleware = tower::ServiceBuilder::new().layer(layer);
+/// # }
+/// ```
+#[derive(Debug, Clone)]
+pub struct RateLimitLayer<C> {
+    limiter: C,
+}
+
+impl<L> RateLimitLayer<SharedLimiter<L>> {
+    /// Creates a new layer that applies the limiter to all requests.
+    pub fn new(limiter: L) -> Self {
+        Self { limiter: SharedLimiter(Arc::new(Mutex::new(limiter))) }
+    }
+}
+
+impl<K, L, F, KF> RateLimitLayer<KeyedRequestLimiter<K, L, F, KF>> {
+    /// Creates a new layer that applies a separate limit to the requests of every key, using the
+    /// given function to get the key of a request.
+    pub fn keyed(limiter: KeyedRateLimiter<K, L, F>, key: KF) -> Self {
+        Self {
+            limiter: KeyedRequestLimiter {
+                limiter: Arc::new(Mutex::new(limiter)),
+                key: Arc::new(key),
+            },
+        }
+    }
+}
+
+impl<C> RateLimitLayer<C> {
+    /// Creates a new layer with a custom [`RequestLimiter`].
+    pub const fn with_limiter(limiter: C) -> Self {
+        Self { limiter }
+    }
+}
+
+impl<S, C: Clone> Layer<S> for RateLimitLayer<C> {
+    type Service = RateLimitService<S, C>;
+
+    fn layer(&self, inner: S) -> Self::Service {
+        RateLimitService { inner, limiter: self.limiter.clone() }
+    }
+}
+
+/// A service that rejects the requests over a rate limit, see [`RateLimitLayer`].
+#[derive(Debug, Clone)]
+pub struct RateLimitService<S, C> {
+    inner: S,
+    limiter: C,
+}
+
+impl<S, C, Request> Service<Request> for RateLimitService<S, C>
+where
+    S: Service<Request>,
+    S::Error: Into<BoxError>,
+    C: RequestLimiter<Request>,
+{
+    type Response = S::Response;
+    type Error = BoxError;
+    type Future = RateLimitFuture<S::Future>;
+
+    fn poll_ready(&mut self, cx: &mut Context<'_>) -> Poll<Result<(), Self::Error>> {
+        self.inner.poll_ready(cx).map_err(Into::into)
+    }
+
+    fn call(&mut self, request: Request) -> Self::Future {
+        if self.limiter.try_acquire(&request) {
+            RateLimitFuture::Allowed { fut: self.inner.call(request) }
+        } else {
+            RateLimitFuture::Limited
+        }
+    }
+}
+
+/// Response future of a [`RateLimitService`].
+#[pin_project(project = RateLimitFutureProj)]
+#[derive(Debug)]
+pub enum RateLimitFuture<F> {
+    /// The request is within the rate limit and was passed to the inner service.
+    Allowed {
+        /// The response future of the inner service.
+        #[pin]
+        fut: F,
+    },
+    /// The request was over the rate limit.
+    Limited,
+}
+
+impl<F, T, E> Future for RateLimitFuture<F>
+where
+    F: Future<Output = Result<T, E>>,
+    E: Into<BoxError>,
+{
+    type Output = Result<T, BoxError>;
+
+    fn poll(self: Pin<&mut Self>, cx: &mut Context<'_>) -> Poll<Self::Output> {
+        match self.project() {
+            RateLimitFutureProj::Allowed { fut } => {
+                Poll::Ready(ready!(fut.poll(cx)).map_err(Into::into))
+            }
+            RateLimitFutureProj::Limited => Poll::Ready(Err(RateLimitExceeded.into())),
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::ratelimit::{Rate, RateLimit, TokenBucket};
+    use std::{convert::Infallible, time::Duration};
+    use tower::{service_fn, ServiceExt};
+
+    /// Calls the service with the request, and returns `true` if it was within the rate limit.
+    async fn call<S, Request>(service: &mut S, request: Request) -> bool
+    where
+        S: Service<Request, Error = BoxError>,
+    {
+        match service.ready().await.unwrap().call(request).await {
+            Ok(_) => true,
+            Err(err) => {
+                assert!(err.is::<RateLimitExceeded>(), "{err}");
+                false
+            }
+        }
+    }
+
+    #[tokio::test(start_paused = true)]
+    async fn test_rate_limit_layer() {
+        let layer =
+            RateLimitLayer::new(TokenBucket::new(2, Rate::new(1, Duration::from_millis(100))));
+        let mut service =
+            layer.layer(service_fn(|request: u32| async move { Ok::<_, Infallible>(request) }));
+        // clones and other services of the layer share the limit
+        let mut clone = service.clone();
+        let mut other =
+            layer.layer(service_fn(|request: u32| async move { Ok::<_, Infallible>(request) }));
+
+        assert_eq!(service.ready().await.unwrap().call(1).await.unwrap(), 1);
+        assert!(call(&mut clone, 2).await);
+        assert!(!call(&mut service, 3).await);
+        assert!(!call(&mut other, 4).await);
+
+        tokio::time::advance(Duration::from_millis(110)).await;
+        assert!(call(&mut other, 5).await);
+        assert!(!call(&mut service, 6).await);
+    }
+
+    #[tokio::test]
+    async fn test_keyed_rate_limit_layer() {
+        let layer = RateLimitLayer::keyed(
+            KeyedRateLimiter::new(|| RateLimit::new(Rate::new(1, Duration::from_secs(60)))),
+            |request: &(&'static str, u32)| request.0,
+        );
+        let mut service = layer.layer(service_fn(|request: (&'static str, u32)| async move {
+            Ok::<_, Infallible>(request)
+        }));
+
+        assert!(call(&mut service, ("a", 0)).await);
+        assert!(call(&mut service, ("b", 1)).await);
+        assert!(!call(&mut service, ("a", 2)).await);
+    }
+
+    #[tokio::test]
+    async fn test_rate_limit_service_inner_error() {
+        let layer = RateLimitLayer::new(TokenBucket::new(1, Rate::new(1, Duration::from_secs(60))));
+        let mut service = layer.layer(service_fn(|_request: ()| async move {
+            Err::<(), _>(std::io::Error::other("inner"))
+        }));
+
+        // errors of the inner service are passed through, and the request counts towards the
+        // limit
+        let err = service.ready().await.unwrap().call(()).await.unwrap_err();
+        assert!(err.is::<std::io::Error>());
+        assert!(!call(&mut service, ()).await);
+    }
+}
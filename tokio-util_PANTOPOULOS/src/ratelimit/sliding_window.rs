@@ -0,0 +1,143 @@
+//! A sliding window log rate limiter.
+
+use super::{Limiter, Rate};
+use std::{
+    collections::VecDeque,
+    future::Future,
+    pin::Pin,
+    task::{ready, Context, Poll},
+};
+use tokio::time::{Instant, Sleep};
+
+/// Allows at most `limit` actions within any window of `duration`, as configured by a [Rate].
+///
+/// [`RateLimit`](super::RateLimit) counts actions in fixed windows, so up to twice the limit can
+/// happen around the boundary of two windows. The sliding window keeps a log of the instants of
+/// the last `limit` actions instead, and only allows a new action once the oldest one is older
+/// than `duration`.
+#[derive(Debug)]
+pub struct SlidingWindow {
+    /// The number of actions allowed per window.
+    rate: Rate,
+    /// Instants of the actions in the current window, oldest first.
+    log: VecDeque<Instant>,
+    /// Timer to wake up the task once the oldest action leaves the window.
+    sleep: Pin<Box<Sleep>>,
+}
+
+// === impl SlidingWindow ===
+
+impl SlidingWindow {
+    /// Creates a new sliding window limiter for the given [Rate].
+    ///
+    /// # Panics
+    ///
+    /// Panics if the limit of the rate is zero, since the window would never allow an action.
+    pub fn new(rate: Rate) -> Self {
+        assert!(rate.limit() > 0, "SlidingWindow rate must be non-zero");
+
+        Self {
+            rate,
+            log: VecDeque::new(),
+            sleep: Box::pin(tokio::time::sleep_until(Instant::now())),
+        }
+    }
+
+    /// Returns the configured limit of actions per window.
+    pub const fn limit(&self) -> u64 {
+        self.rate.limit()
+    }
+
+    /// Returns the number of actions that are still allowed in the current window.
+    pub fn remaining(&mut self) -> u64 {
+        self.evict(Instant::now());
+        self.rate.limit().saturating_sub(self.log.len() as u64)
+    }
+
+    /// Removes all actions that are no longer within the window.
+    fn evict(&mut self, now: Instant) {
+        while let Some(oldest) = self.log.front() {
+            if now.saturating_duration_since(*oldest) < self.rate.duration() {
+                break
+            }
+            self.log.pop_front();
+        }
+    }
+
+    /// Returns `true` if another action fits into the window.
+    fn has_capacity(&self) -> bool {
+        (self.log.len() as u64) < self.rate.limit()
+    }
+}
+
+impl Limiter for SlidingWindow {
+    fn poll_ready(&mut self, cx: &mut Context<'_>) -> Poll<()> {
+        loop {
+            self.evict(Instant::now());
+            if self.has_capacity() {
+                return Poll::Ready(())
+            }
+
+            // the limit is non-zero, so a full window holds at least one action
+            let oldest = self.log.front().expect("full window is not empty");
+            let deadline = *oldest + self.rate.duration();
+            self.sleep.as_mut().reset(deadline);
+            ready!(self.sleep.as_mut().poll(cx));
+        }
+    }
+
+    fn tick(&mut self) {
+        let now = Instant::now();
+        self.evict(now);
+        assert!(self.has_capacity(), "SlidingWindow limited; poll_ready must be called first");
+        self.log.push_back(now);
+    }
+
+    fn try_tick(&mut self) -> bool {
+        let now = Instant::now();
+        self.evict(now);
+        if !self.has_capacity() {
+            return false
+        }
+        self.log.push_back(now);
+        true
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use std::{future::poll_fn, time::Duration};
+
+    #[tokio::test(start_paused = true)]
+    async fn test_sliding_window() {
+        let mut window = SlidingWindow::new(Rate::new(2, Duration::from_millis(300)));
+
+        assert!(window.try_tick());
+        tokio::time::advance(Duration::from_millis(150)).await;
+        assert!(window.try_tick());
+        assert!(!window.try_tick());
+        assert_eq!(window.remaining(), 0);
+
+        poll_fn(|cx| {
+            assert!(Limiter::poll_ready(&mut window, cx).is_pending());
+            Poll::Ready(())
+        })
+        .await;
+
+        // only the first action leaves the window
+        tokio::time::advance(Duration::from_millis(149)).await;
+        assert_eq!(window.remaining(), 0);
+        tokio::time::advance(Duration::from_millis(1)).await;
+        Limiter::wait(&mut window).await;
+        assert_eq!(window.remaining(), 1);
+        Limiter::tick(&mut window);
+        assert!(!window.try_tick());
+    }
+
+    #[test]
+    #[should_panic(expected = "SlidingWindow rate must be non-zero")]
+    fn test_sliding_window_zero_rate() {
+        SlidingWindow::new(Rate::new(0, Duration::from_secs(1)));
+    }
+}
@@ -0,0 +1,185 @@
+//! Stream adapters that apply rate limits to the items of a stream.
+
+use super::{KeyedRateLimiter, Limiter};
+use std::{
+    fmt,
+    hash::Hash,
+    pin::Pin,
+    task::{ready, Context, Poll},
+};
+use tokio_stream::Stream;
+
+/// A stream that yields the items of the inner stream no faster than its [Limiter] allows.
+///
+/// Items are delayed, never dropped: the inner stream is not polled until the limiter is ready.
+#[derive(Debug)]
+pub struct RateLimitedStream<S, L> {
+    inner: S,
+    limiter: L,
+}
+
+// === impl RateLimitedStream ===
+
+impl<S, L> RateLimitedStream<S, L> {
+    /// Creates a new stream that applies the limiter to the items of the inner stream.
+    pub const fn new(inner: S, limiter: L) -> Self {
+        Self { inner, limiter }
+    }
+
+    /// Returns a reference to the limiter.
+    pub const fn limiter(&self) -> &L {
+        &self.limiter
+    }
+
+    /// Returns the inner stream.
+    pub fn into_inner(self) -> S {
+        self.inner
+    }
+}
+
+impl<S, L> Stream for RateLimitedStream<S, L>
+where
+    S: Stream + Unpin,
+    L: Limiter + Unpin,
+{
+    type Item = S::Item;
+
+    fn poll_next(self: Pin<&mut Self>, cx: &mut Context<'_>) -> Poll<Option<Self::Item>> {
+        let this = self.get_mut();
+        ready!(this.limiter.poll_ready(cx));
+
+        let item = ready!(Pin::new(&mut this.inner).poll_next(cx));
+        if item.is_some() {
+            this.limiter.tick();
+        }
+        Poll::Ready(item)
+    }
+}
+
+/// An item that was over the rate limit of its key.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct RateLimited<T>(pub T);
+
+impl<T> RateLimited<T> {
+    /// Returns the item that was over the rate limit.
+    pub fn into_inner(self) -> T {
+        self.0
+    }
+}
+
+/// A stream that applies a separate rate limit to the items of every key, e.g. to the messages
+/// of every peer.
+///
+/// Items are never delayed, so that a single key can not hold back the items of all other keys.
+/// Instead, items over the limit of their key are yielded as [`RateLimited`] errors, so that the
+/// caller can drop them and penalize the sender. Every item is yielded together with its key.
+pub struct KeyedRateLimitedStream<S, K, L, F, KF> {
+    inner: S,
+    limiter: KeyedRateLimiter<K, L, F>,
+    /// Returns the key of an item.
+    key: KF,
+}
+
+// === impl KeyedRateLimitedStream ===
+
+impl<S, K, L, F, KF> KeyedRateLimitedStream<S, K, L, F, KF> {
+    /// Creates a new stream that applies the keyed limiter to the items of the inner stream,
+    /// using the given function to get the key of an item.
+    pub const fn new(inner: S, limiter: KeyedRateLimiter<K, L, F>, key: KF) -> Self {
+        Self { inner, limiter, key }
+    }
+
+    /// Returns a mutable reference to the keyed limiter, e.g. to remove the limiter of a
+    /// disconnected peer.
+    pub fn limiter_mut(&mut self) -> &mut KeyedRateLimiter<K, L, F> {
+        &mut self.limiter
+    }
+
+    /// Returns the inner stream.
+    pub fn into_inner(self) -> S {
+        self.inner
+    }
+}
+
+impl<S, K, L, F, KF> Stream for KeyedRateLimitedStream<S, K, L, F, KF>
+where
+    S: Stream + Unpin,
+    K: Eq + Hash + Clone + Unpin,
+    L: Limiter + Unpin,
+    F: Fn() -> L + Unpin,
+    KF: Fn(&S::Item) -> K + Unpin,
+{
+    type Item = (K, Result<S::Item, RateLimited<S::Item>>);
+
+    fn poll_next(self: Pin<&mut Self>, cx: &mut Context<'_>) -> Poll<Option<Self::Item>> {
+        let this = self.get_mut();
+        let Some(item) = ready!(Pin::new(&mut this.inner).poll_next(cx)) else {
+            return Poll::Ready(None)
+        };
+
+        let key = (this.key)(&item);
+        let item = if this.limiter.try_tick(&key) { Ok(item) } else { Err(RateLimited(item)) };
+        Poll::Ready(Some((key, item)))
+    }
+}
+
+impl<S, K, L, F, KF> fmt::Debug for KeyedRateLimitedStream<S, K, L, F, KF>
+where
+    S: fmt::Debug,
+    K: fmt::Debug,
+    L: fmt::Debug,
+{
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.debug_struct("KeyedRateLimitedStream")
+            .field("inner", &self.inner)
+            .field("limiter", &self.limiter)
+            .finish_non_exhaustive()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::ratelimit::{Rate, RateLimit};
+    use std::{future::poll_fn, time::Duration};
+    use tokio_stream::StreamExt;
+
+    /// Polls the stream once, and returns `true` if no item is ready.
+    async fn is_pending<S: Stream + Unpin>(stream: &mut S) -> bool {
+        poll_fn(|cx| Poll::Ready(Pin::new(&mut *stream).poll_next(cx).is_pending())).await
+    }
+
+    #[tokio::test(start_paused = true)]
+    async fn test_rate_limited_stream() {
+        let limiter = RateLimit::new(Rate::new(2, Duration::from_millis(200)));
+        let mut stream = RateLimitedStream::new(tokio_stream::iter(0..3), limiter);
+
+        assert_eq!(stream.next().await, Some(0));
+        assert_eq!(stream.next().await, Some(1));
+
+        // the third item has to wait for the next window
+        assert!(is_pending(&mut stream).await);
+        tokio::time::advance(Duration::from_millis(199)).await;
+        assert!(is_pending(&mut stream).await);
+        tokio::time::advance(Duration::from_millis(1)).await;
+        assert_eq!(stream.next().await, Some(2));
+        assert_eq!(stream.next().await, None);
+    }
+
+    #[tokio::test]
+    async fn test_keyed_rate_limited_stream() {
+        let limiter =
+            KeyedRateLimiter::new(|| RateLimit::new(Rate::new(1, Duration::from_secs(60))));
+        let messages = tokio_stream::iter([("a", 0), ("b", 1), ("a", 2)]);
+        let stream =
+            KeyedRateLimitedStream::new(messages, limiter, |message: &(&'static str, i32)| {
+                message.0
+            });
+
+        let items = stream.collect::<Vec<_>>().await;
+        assert_eq!(
+            items,
+            vec![("a", Ok(("a", 0))), ("b", Ok(("b", 1))), ("a", Err(RateLimited(("a", 2))))]
+        );
+    }
+}
@@ -0,0 +1,161 @@
+//! A token bucket rate limiter that allows bursts.
+
+use super::{Limiter, Rate};
+use std::{
+    future::Future,
+    pin::Pin,
+    task::{ready, Context, Poll},
+    time::Duration,
+};
+use tokio::time::{Instant, Sleep};
+
+/// A token bucket that refills at a [Rate] and holds up to `capacity` tokens.
+///
+/// Every action takes one token from the bucket. Unlike [`RateLimit`](super::RateLimit), which
+/// resets its whole budget at the end of every window, the bucket refills continuously, and up to
+/// `capacity` actions can happen in a burst after the bucket was idle.
+#[derive(Debug)]
+pub struct TokenBucket {
+    /// Maximum number of tokens the bucket can hold.
+    capacity: u64,
+    /// The rate at which tokens are added to the bucket.
+    rate: Rate,
+    /// Tokens currently in the bucket, including a fraction of the next token.
+    tokens: f64,
+    /// When tokens were last added to the bucket.
+    last_refill: Instant,
+    /// Timer to wake up the task once the next token is available.
+    sleep: Pin<Box<Sleep>>,
+}
+
+// === impl TokenBucket ===
+
+impl TokenBucket {
+    /// Creates a new, full token bucket that holds up to `capacity` tokens and refills at the
+    /// given [Rate].
+    ///
+    /// # Panics
+    ///
+    /// Panics if `capacity` or the limit of the rate is zero, since the bucket would never allow
+    /// an action.
+    pub fn new(capacity: u64, rate: Rate) -> Self {
+        assert!(capacity > 0, "TokenBucket capacity must be non-zero");
+        assert!(rate.limit() > 0, "TokenBucket rate must be non-zero");
+
+        let now = Instant::now();
+        Self {
+            capacity,
+            rate,
+            tokens: capacity as f64,
+            last_refill: now,
+            sleep: Box::pin(tokio::time::sleep_until(now)),
+        }
+    }
+
+    /// Returns the maximum number of tokens the bucket can hold.
+    pub const fn capacity(&self) -> u64 {
+        self.capacity
+    }
+
+    /// Returns the number of whole tokens currently in the bucket.
+    pub fn available(&mut self) -> u64 {
+        self.refill(Instant::now());
+        self.tokens as u64
+    }
+
+    /// Number of tokens added to the bucket per second.
+    fn tokens_per_second(&self) -> f64 {
+        self.rate.limit() as f64 / self.rate.duration().as_secs_f64()
+    }
+
+    /// Adds the tokens accumulated since the last refill.
+    fn refill(&mut self, now: Instant) {
+        let elapsed = now.saturating_duration_since(self.last_refill).as_secs_f64();
+        if elapsed > 0.0 {
+            let tokens = self.tokens + elapsed * self.tokens_per_second();
+            self.tokens = tokens.min(self.capacity as f64);
+        }
+        self.last_refill = now;
+    }
+}
+
+impl Limiter for TokenBucket {
+    fn poll_ready(&mut self, cx: &mut Context<'_>) -> Poll<()> {
+        loop {
+            let now = Instant::now();
+            self.refill(now);
+            if self.tokens >= 1.0 {
+                return Poll::Ready(())
+            }
+
+            let missing = Duration::from_secs_f64((1.0 - self.tokens) / self.tokens_per_second());
+            self.sleep.as_mut().reset(now + missing);
+            ready!(self.sleep.as_mut().poll(cx));
+        }
+    }
+
+    fn tick(&mut self) {
+        self.refill(Instant::now());
+        assert!(self.tokens >= 1.0, "TokenBucket empty; poll_ready must be called first");
+        self.tokens -= 1.0;
+    }
+
+    fn try_tick(&mut self) -> bool {
+        self.refill(Instant::now());
+        if self.tokens < 1.0 {
+            return false
+        }
+        self.tokens -= 1.0;
+        true
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use std::future::poll_fn;
+
+    #[tokio::test(start_paused = true)]
+    async fn test_token_bucket_burst() {
+        let mut bucket = TokenBucket::new(3, Rate::new(1, Duration::from_millis(100)));
+
+        // a full bucket allows a burst of `capacity` actions
+        for _ in 0..3 {
+            assert!(bucket.try_tick());
+        }
+        assert!(!bucket.try_tick());
+
+        poll_fn(|cx| {
+            assert!(Limiter::poll_ready(&mut bucket, cx).is_pending());
+            Poll::Ready(())
+        })
+        .await;
+
+        // one token is added after a single period
+        Limiter::wait(&mut bucket).await;
+        Limiter::tick(&mut bucket);
+        assert_eq!(bucket.available(), 0);
+
+        // tokens are added continuously
+        tokio::time::advance(Duration::from_millis(50)).await;
+        assert_eq!(bucket.available(), 0);
+        tokio::time::advance(Duration::from_millis(60)).await;
+        assert_eq!(bucket.available(), 1);
+
+        // the bucket never holds more than `capacity` tokens
+        tokio::time::advance(Duration::from_millis(500)).await;
+        assert_eq!(bucket.available(), 3);
+    }
+
+    #[test]
+    #[should_panic(expected = "TokenBucket rate must be non-zero")]
+    fn test_token_bucket_zero_rate() {
+        TokenBucket::new(1, Rate::new(0, Duration::from_secs(1)));
+    }
+
+    #[test]
+    #[should_panic(expected = "TokenBucket capacity must be non-zero")]
+    fn test_token_bucket_zero_capacity() {
+        TokenBucket::new(0, Rate::new(1, Duration::from_secs(1)));
+    }
+}
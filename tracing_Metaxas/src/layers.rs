@@ -3,10 +3,10 @@
 /// and setting up the tracing subscriber.
 
 /// Importing Path and PathBuf from the standard library for file system path manipulation.
-use std::path::{Path, PathBuf};
-
-/// Importing RollingConditionBasic and RollingFileAppender from the rolling_file crate for log file rotation.
-use rolling_file::{RollingConditionBasic, RollingFileAppender};
+use std::{
+    path::{Path, PathBuf},
+    time::Duration,
+};
 
 /// Importing WorkerGuard from the tracing_appender crate to ensure logs are flushed on shutdown.
 use tracing_appender::non_blocking::WorkerGuard;
@@ -17,6 +17,16 @@ use tracing_subscriber::{filter::Directive, EnvFilter, Layer, Registry};
 /// Importing the LogFormat enum from the crate's formatter module.
 use crate::formatter::LogFormat;
 
+/// Importing the handle that makes the filters of the layers reloadable.
+use crate::reload::LogFilterHandle;
+
+/// Importing the log file writer that rotates, compresses and removes log files.
+use crate::rotation::{LogCompression, LogRotation, RotatingFileWriter};
+
+/// Importing the OTLP exporter configuration from the crate's otlp module.
+#[cfg(feature = "otlp")]
+use crate::otlp::OtlpConfig;
+
 
 
 /// This section defines a type alias for a worker guard used in file logging.
@@ -67,12 +77,20 @@ const DEFAULT_ENV_FILTER_DIRECTIVES: [&str; 5] = [
 /// Each layer can be configured separately and then combined into a tracing subscriber.
 pub(crate) struct Layers {
     inner: Vec<BoxedLayer<Registry>>,
+    /// Handle to reload the filters of all layers.
+    filters: LogFilterHandle,
 }
 
 impl Layers {
     /// Creates a new `Layers` instance.
     pub(crate) fn new() -> Self {
-        Self { inner: vec![] }
+        Self::with_filter_handle(LogFilterHandle::default())
+    }
+
+    /// Creates a new `Layers` instance that registers the filters of its layers with the given
+    /// handle.
+    pub(crate) fn with_filter_handle(filters: LogFilterHandle) -> Self {
+        Self { inner: vec![], filters }
     }
 
     /// Consumes the `Layers` instance, returning the inner vector of layers.
@@ -88,7 +106,7 @@ impl Layers {
     /// # Returns
     /// An `eyre::Result<()>` indicating the success or failure of the operation.
     pub(crate) fn journald(&mut self, filter: &str) -> eyre::Result<()> {
-        let journald_filter = build_env_filter(None, filter)?;
+        let journald_filter = self.filters.layer_filter(None, filter)?;
         let layer = tracing_journald::layer()?.with_filter(journald_filter).boxed();
         self.inner.push(layer);
         Ok(())
@@ -114,7 +132,7 @@ impl Layers {
         filters: &str,
         color: Option<String>,
     ) -> eyre::Result<()> {
-        let filter = build_env_filter(Some(default_directive), filters)?;
+        let filter = self.filters.layer_filter(Some(default_directive), filters)?;
         let layer = format.apply(filter, color, None);
         self.inner.push(layer.boxed());
         Ok(())
@@ -136,11 +154,28 @@ impl Layers {
         file_info: FileInfo,
     ) -> eyre::Result<FileWorkerGuard> {
         let (writer, guard) = file_info.create_log_writer();
-        let file_filter = build_env_filter(None, filter)?;
+        let file_filter = self.filters.layer_filter(None, filter)?;
         let layer = format.apply(file_filter, None, Some(writer));
         self.inner.push(layer);
         Ok(guard)
     }
+
+    /// Adds a layer that exports spans to an OTLP collector.
+    ///
+    /// # Arguments
+    /// * `config` - The OTLP exporter configuration, including endpoint, protocol and sampler.
+    /// * `filter` - Filter directives as a string, to select the targets whose spans are exported.
+    ///
+    /// # Returns
+    /// An `eyre::Result<()>` indicating the success or failure of the operation.
+    #[cfg(feature = "otlp")]
+    pub(crate) fn otlp(&mut self, config: &OtlpConfig, filter: &str) -> eyre::Result<()> {
+        let tracer = config.build_tracer()?;
+        let otlp_filter = self.filters.layer_filter(None, filter)?;
+        let layer = tracing_opentelemetry::layer().with_tracer(tracer).with_filter(otlp_filter);
+        self.inner.push(layer.boxed());
+        Ok(())
+    }
 }
 
 /// FileInfo Struct Definition
@@ -151,10 +186,24 @@ impl Layers {
 /// Contains details about the log file's path, name, size, and rotation strategy.
 #[derive(Debug, Clone)]
 pub struct FileInfo {
-    dir: PathBuf,           /// Directory where the log files will be stored.
-    file_name: String,      /// Name of the log file.
-    max_size_bytes: u64,    /// Maximum size of the log file in bytes before it gets rotated.
-    max_files: usize,       /// Maximum number of rotated log files to keep.
+    /// Directory where the log files will be stored.
+    pub(crate) dir: PathBuf,
+    /// Name of the log file.
+    pub(crate) file_name: String,
+    /// Maximum size of the log file in bytes before it gets rotated.
+    pub(crate) max_size_bytes: u64,
+    /// Maximum number of rotated log files to keep.
+    pub(crate) max_files: usize,
+    /// Maximum total size of the rotated log files in bytes.
+    pub(crate) max_total_bytes: Option<u64>,
+    /// Maximum age of the rotated log files.
+    pub(crate) max_age: Option<Duration>,
+    /// When the log file is rotated, in addition to rotating it by size.
+    pub(crate) rotation: LogRotation,
+    /// The compression of rotated log files.
+    pub(crate) compression: LogCompression,
+    /// The chain the logs are written for, included in the names of rotated log files.
+    pub(crate) chain: Option<String>,
 }
 
 
@@ -173,10 +222,80 @@ impl FileInfo {
             dir, 
             file_name: RETH_LOG_FILE_NAME.to_string(), // Default log file name
             max_size_bytes, 
-            max_files 
+            max_files,
+            max_total_bytes: None,
+            max_age: None,
+            rotation: LogRotation::default(),
+            compression: LogCompression::default(),
+            chain: None,
+        }
+    }
+
+    /// Rotates the log file by time, in addition to rotating it by size.
+    ///
+    /// # Arguments
+    /// * `rotation` - When the log file is rotated.
+    pub const fn with_rotation(mut self, rotation: LogRotation) -> Self {
+        self.rotation = rotation;
+        self
+    }
+
+    /// Compresses rotated log files.
+    ///
+    /// # Arguments
+    /// * `compression` - The compression of rotated log files.
+    pub const fn with_compression(mut self, compression: LogCompression) -> Self {
+        self.compression = compression;
+        self
+    }
+
+    /// Removes the oldest rotated log files once their total size exceeds the limit.
+    ///
+    /// # Arguments
+    /// * `max_total_bytes` - The maximum total size of the rotated log files in bytes.
+    pub const fn with_max_total_bytes(mut self, max_total_bytes: u64) -> Self {
+        self.max_total_bytes = Some(max_total_bytes);
+        self
+    }
+
+    /// Removes rotated log files that are older than the maximum age.
+    ///
+    /// # Arguments
+    /// * `max_age` - The maximum age of the rotated log files.
+    pub const fn with_max_age(mut self, max_age: Duration) -> Self {
+        self.max_age = Some(max_age);
+        self
+    }
+
+    /// Includes the chain in the names of rotated log files, e.g. `reth-mainnet-<timestamp>.log`.
+    ///
+    /// # Arguments
+    /// * `chain` - The chain the logs are written for.
+    pub fn with_chain(mut self, chain: impl Into<String>) -> Self {
+        self.chain = Some(chain.into());
+        self
+    }
+
+    /// Returns the prefix of the names of rotated log files, e.g. `reth-mainnet-`.
+    pub(crate) fn rotated_prefix(&self) -> String {
+        let stem = Path::new(&self.file_name)
+            .file_stem()
+            .map(|stem| stem.to_string_lossy().into_owned())
+            .unwrap_or_default();
+        match &self.chain {
+            Some(chain) => format!("{stem}-{chain}-"),
+            None => format!("{stem}-"),
         }
     }
 
+    /// Returns the extension of the log file including the leading dot, e.g. `.log`.
+    pub(crate) fn extension(&self) -> String {
+        Path::new(&self.file_name)
+            .extension()
+            .map(|extension| format!(".{}", extension.to_string_lossy()))
+            .unwrap_or_default()
+    }
+
     /// Creates the log directory if it doesn't exist.
     ///
     /// Ensures that the directory specified in `dir` exists, creating it if necessary.
@@ -193,18 +312,14 @@ impl FileInfo {
 
     /// Creates a non-blocking writer for the log file.
     ///
-    /// Initializes a non-blocking log writer with file rotation based on size.
+    /// Initializes a non-blocking log writer with file rotation based on size and time.
     ///
     /// # Returns
     /// A tuple containing the non-blocking writer and its associated worker guard.
     fn create_log_writer(&self) -> (tracing_appender::non_blocking::NonBlocking, WorkerGuard) {
-        let log_dir = self.create_log_dir();
+        self.create_log_dir();
         let (writer, guard) = tracing_appender::non_blocking(
-            RollingFileAppender::new(
-                log_dir.join(&self.file_name),                  // Path to the log file
-                RollingConditionBasic::new().max_size(self.max_size_bytes), // Rotation condition based on file size
-                self.max_files,                                // Number of rotated files to keep
-            ).expect("Could not initialize file logging"),
+            RotatingFileWriter::new(self.clone()).expect("Could not initialize file logging"),
         );
         (writer, guard)
     }
@@ -224,7 +339,7 @@ impl FileInfo {
 ///
 /// # Returns
 /// An `eyre::Result<EnvFilter>` that can be used to configure a tracing subscriber.
-fn build_env_filter(
+pub(crate) fn build_env_filter(
     default_directive: Option<Directive>,
     directives: &str,
 ) -> eyre::Result<EnvFilter> {
@@ -2,9 +2,9 @@
 //!  The `tracing` module provides functionalities for setting up and configuring logging.
 //!
 //!  It includes structures and functions to create and manage various logging layers: stdout,
-//!  file, or journald. The module's primary entry point is the `Tracer` struct, which can be
-//!  configured to use different logging formats and destinations. If no layer is specified, it will
-//!  default to stdout.
+//!  file, journald, or OTLP span export (with the `otlp` feature). The module's primary entry
+//!  point is the `Tracer` struct, which can be configured to use different logging formats and
+//!  destinations. If no layer is specified, it will default to stdout.
 //!
 //!  # Examples
 //!
@@ -67,16 +67,31 @@ pub use formatter::LogFormat;
 /// Re-export FileInfo and FileWorkerGuard from the layers module
 pub use layers::{FileInfo, FileWorkerGuard};
 
+/// Re-export the rotation and compression of log files from the rotation module
+pub use rotation::{LogCompression, LogRotation};
+
 /// Re-export TestTracer from the test_tracer module
 pub use test_tracer::TestTracer;
 
+/// Re-export the reloadable log filters from the reload module
+pub use reload::{log_filter_handle, verbosity_level, LogFilterHandle};
+
+/// Re-export the OTLP exporter configuration from the otlp module
+#[cfg(feature = "otlp")]
+pub use otlp::{shutdown_otlp, OtlpConfig, OtlpProtocol, OtlpSampler};
+
 /// Internal modules
 mod formatter;
 mod layers;
 mod test_tracer;
+mod reload;
+mod rotation;
+#[cfg(feature = "otlp")]
+mod otlp;
 
 /// External crates
 use crate::layers::Layers;
+use std::path::PathBuf;
 use tracing::level_filters::LevelFilter;
 use tracing_appender::non_blocking::WorkerGuard;
 use tracing_subscriber::{layer::SubscriberExt, util::SubscriberInitExt};
@@ -96,6 +111,16 @@ pub struct RethTracer {
 
     /// Optional configuration for the file logging layer, including file information.
     file: Option<(LayerInfo, FileInfo)>,
+
+    /// Optional configuration for the OTLP span export layer, including exporter configuration.
+    #[cfg(feature = "otlp")]
+    otlp: Option<(LayerInfo, OtlpConfig)>,
+
+    /// Optional file with additional filter directives, re-read when the filters are reloaded.
+    filter_file: Option<PathBuf>,
+
+    /// Handle to reload the filters of all layers at runtime.
+    filter_handle: LogFilterHandle,
 }
 
 
@@ -109,6 +134,10 @@ impl RethTracer {
             stdout: LayerInfo::default(),
             journald: None,
             file: None,
+            #[cfg(feature = "otlp")]
+            otlp: None,
+            filter_file: None,
+            filter_handle: LogFilterHandle::default(),
         }
     }
 
@@ -148,6 +177,46 @@ impl RethTracer {
         self.file = Some((config, file_info));
         self
     }
+
+    /// Sets the OTLP span export layer configuration.
+    ///
+    /// Only the filters of the `LayerInfo` are used, to select the targets whose spans are
+    /// exported.
+    ///
+    /// # Arguments
+    /// * `config` - The `LayerInfo` to use for the OTLP layer.
+    /// * `otlp` - The `OtlpConfig` containing details about the collector and sampling.
+    ///
+    /// # Returns
+    /// A new `RethTracer` instance with the updated OTLP layer configuration.
+    #[cfg(feature = "otlp")]
+    pub fn with_otlp(mut self, config: LayerInfo, otlp: OtlpConfig) -> Self {
+        self.otlp = Some((config, otlp));
+        self
+    }
+
+    /// Sets a file with additional filter directives for all layers.
+    ///
+    /// The file uses the same format as `RUST_LOG`, and is re-read on
+    /// [`LogFilterHandle::reload`]. A missing file is treated as empty.
+    ///
+    /// # Arguments
+    /// * `path` - The path of the filter file.
+    ///
+    /// # Returns
+    /// A new `RethTracer` instance with the updated filter file.
+    pub fn with_filter_file(mut self, path: PathBuf) -> Self {
+        self.filter_file = Some(path);
+        self
+    }
+
+    /// Returns the handle to reload the filters of all layers at runtime.
+    ///
+    /// The handle controls the filters once the tracer is initialized, and is also available
+    /// through [`log_filter_handle`] afterwards.
+    pub fn filter_handle(&self) -> LogFilterHandle {
+        self.filter_handle.clone()
+    }
 }
 
 
@@ -250,8 +319,8 @@ impl Tracer for RethTracer {
     /// An `eyre::Result` which is `Ok` with an optional `WorkerGuard` if a file layer is used,
     /// or an `Err` in case of an error during initialization.
     fn init(self) -> eyre::Result<Option<WorkerGuard>> {
-        /// Create a new Layers instance to manage logging layers
-        let mut layers = Layers::new();
+        /// Create a new Layers instance to manage logging layers with reloadable filters
+        let mut layers = Layers::with_filter_handle(self.filter_handle.clone());
 
         /// Add stdout layer with specified format, default directive, filters, and color
         layers.stdout(
@@ -273,9 +342,22 @@ impl Tracer for RethTracer {
             None
         };
 
+        /// Optionally add OTLP span export layer with specified filters and exporter config
+        #[cfg(feature = "otlp")]
+        if let Some((config, otlp)) = self.otlp {
+            layers.otlp(&otlp, &config.filters)?;
+        }
+
+        /// Apply the directives of the filter file to all layers
+        if let Some(path) = self.filter_file {
+            self.filter_handle.set_filter_file(path)?;
+        }
+
         /// Attempt to initialize the tracing subscriber with the configured layers
         /// Ignore the error if the global default subscriber is already set
-        let _ = tracing_subscriber::registry().with(layers.into_inner()).try_init();
+        if tracing_subscriber::registry().with(layers.into_inner()).try_init().is_ok() {
+            reload::set_log_filter_handle(self.filter_handle);
+        }
 
         /// Return the file guard if file logging was enabled
         Ok(file_guard)
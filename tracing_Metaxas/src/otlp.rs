@@ -0,0 +1,339 @@
+//! OpenTelemetry span export.
+//!
+//! This module configures an OTLP exporter that sends the spans of the application to a trace
+//! backend, e.g. an OpenTelemetry collector, Jaeger or Tempo.
+
+use std::{fmt, str::FromStr, sync::OnceLock, time::Duration};
+
+use clap::ValueEnum;
+use opentelemetry::KeyValue;
+use opentelemetry_otlp::{SpanExporterBuilder, WithExportConfig};
+use opentelemetry_sdk::{
+    runtime,
+    trace::{self, Sampler, Tracer},
+    Resource,
+};
+use tokio::runtime::Runtime;
+
+/// Default endpoint of an OTLP collector using gRPC.
+pub const DEFAULT_OTLP_GRPC_ENDPOINT: &str = "http://localhost:4317";
+
+/// Default endpoint of an OTLP collector using HTTP.
+pub const DEFAULT_OTLP_HTTP_ENDPOINT: &str = "http://localhost:4318";
+
+/// Default name of the service the spans are exported for.
+pub const DEFAULT_OTLP_SERVICE_NAME: &str = "reth";
+
+/// The runtime that drives the batch span exporter.
+///
+/// Tracing is initialized before the runtime of the node is created, so the exporter runs on a
+/// dedicated runtime that lives for the rest of the process.
+static OTLP_RUNTIME: OnceLock<Runtime> = OnceLock::new();
+
+/// The protocol used to export spans to the collector.
+#[derive(Debug, Copy, Clone, Default, ValueEnum, Eq, PartialEq)]
+pub enum OtlpProtocol {
+    /// Export spans with gRPC.
+    #[default]
+    Grpc,
+
+    /// Export spans with HTTP and binary protobuf payloads.
+    Http,
+}
+
+impl OtlpProtocol {
+    /// Returns the default collector endpoint for the protocol.
+    pub const fn default_endpoint(&self) -> &'static str {
+        match self {
+            Self::Grpc => DEFAULT_OTLP_GRPC_ENDPOINT,
+            Self::Http => DEFAULT_OTLP_HTTP_ENDPOINT,
+        }
+    }
+}
+
+impl fmt::Display for OtlpProtocol {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            Self::Grpc => f.write_str("grpc"),
+            Self::Http => f.write_str("http"),
+        }
+    }
+}
+
+/// Decides which traces are exported.
+///
+/// The sampler only decides for root spans, child spans follow the decision of their parent, so
+/// that traces are either exported completely or not at all.
+#[derive(Debug, Copy, Clone, Default, PartialEq)]
+pub enum OtlpSampler {
+    /// Export all traces.
+    #[default]
+    AlwaysOn,
+
+    /// Export no traces.
+    AlwaysOff,
+
+    /// Export the given ratio of traces, between `0.0` and `1.0`.
+    TraceIdRatio(f64),
+}
+
+impl OtlpSampler {
+    /// Returns the parent based [Sampler] of the OpenTelemetry SDK.
+    fn sampler(&self) -> Sampler {
+        let root = match *self {
+            Self::AlwaysOn => Sampler::AlwaysOn,
+            Self::AlwaysOff => Sampler::AlwaysOff,
+            Self::TraceIdRatio(ratio) => Sampler::TraceIdRatioBased(ratio),
+        };
+        Sampler::ParentBased(Box::new(root))
+    }
+}
+
+impl FromStr for OtlpSampler {
+    type Err = eyre::Report;
+
+    /// Parses `always_on`, `always_off`, or a ratio between `0.0` and `1.0`.
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        match s {
+            "always_on" => Ok(Self::AlwaysOn),
+            "always_off" => Ok(Self::AlwaysOff),
+            ratio => {
+                let ratio: f64 = ratio.parse().map_err(|_| {
+                    eyre::eyre!(
+                        "invalid sampler {ratio:?}, expected always_on, always_off or a ratio"
+                    )
+                })?;
+                eyre::ensure!(
+                    (0.0..=1.0).contains(&ratio),
+                    "sampling ratio {ratio} is not between 0 and 1"
+                );
+                Ok(Self::TraceIdRatio(ratio))
+            }
+        }
+    }
+}
+
+impl fmt::Display for OtlpSampler {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            Self::AlwaysOn => f.write_str("always_on"),
+            Self::AlwaysOff => f.write_str("always_off"),
+            Self::TraceIdRatio(ratio) => write!(f, "{ratio}"),
+        }
+    }
+}
+
+/// Configuration for exporting spans with OTLP.
+#[derive(Debug, Clone)]
+pub struct OtlpConfig {
+    /// Endpoint of the collector.
+    endpoint: String,
+
+    /// The protocol used to export spans.
+    protocol: OtlpProtocol,
+
+    /// Decides which traces are exported.
+    sampler: OtlpSampler,
+
+    /// Timeout of a single export request.
+    timeout: Duration,
+
+    /// Attributes of the resource that produces the spans, e.g. the chain or the version.
+    resource: Vec<(String, String)>,
+}
+
+impl OtlpConfig {
+    /// Creates a new `OtlpConfig` that exports spans to the default endpoint of the protocol.
+    pub fn new(protocol: OtlpProtocol) -> Self {
+        Self {
+            endpoint: protocol.default_endpoint().to_string(),
+            protocol,
+            sampler: OtlpSampler::default(),
+            timeout: Duration::from_secs(10),
+            resource: vec![("service.name".to_string(), DEFAULT_OTLP_SERVICE_NAME.to_string())],
+        }
+    }
+
+    /// Sets the endpoint of the collector.
+    ///
+    /// For HTTP, this is the base URL of the collector; `/v1/traces` is appended to it.
+    pub fn with_endpoint(mut self, endpoint: impl Into<String>) -> Self {
+        self.endpoint = endpoint.into();
+        self
+    }
+
+    /// Sets the sampler that decides which traces are exported.
+    pub const fn with_sampler(mut self, sampler: OtlpSampler) -> Self {
+        self.sampler = sampler;
+        self
+    }
+
+    /// Sets the timeout of a single export request.
+    pub const fn with_timeout(mut self, timeout: Duration) -> Self {
+        self.timeout = timeout;
+        self
+    }
+
+    /// Sets an attribute of the resource that produces the spans, e.g. `service.version`.
+    ///
+    /// An existing attribute with the same key is replaced.
+    pub fn with_resource_attribute(
+        mut self,
+        key: impl Into<String>,
+        value: impl Into<String>,
+    ) -> Self {
+        let key = key.into();
+        self.resource.retain(|(existing, _)| *existing != key);
+        self.resource.push((key, value.into()));
+        self
+    }
+
+    /// Returns the endpoint of the collector.
+    pub fn endpoint(&self) -> &str {
+        &self.endpoint
+    }
+
+    /// Returns the protocol used to export spans.
+    pub const fn protocol(&self) -> OtlpProtocol {
+        self.protocol
+    }
+
+    /// Returns the attributes of the resource that produces the spans.
+    pub fn resource_attributes(&self) -> &[(String, String)] {
+        &self.resource
+    }
+
+    /// Builds the tracer that exports spans in batches to the collector.
+    ///
+    /// The tracer provider is installed globally, so that it can be flushed with
+    /// [`shutdown_otlp`].
+    pub(crate) fn build_tracer(&self) -> eyre::Result<Tracer> {
+        let exporter: SpanExporterBuilder = match self.protocol {
+            OtlpProtocol::Grpc => opentelemetry_otlp::new_exporter()
+                .tonic()
+                .with_endpoint(&self.endpoint)
+                .with_timeout(self.timeout)
+                .into(),
+            OtlpProtocol::Http => opentelemetry_otlp::new_exporter()
+                .http()
+                .with_protocol(opentelemetry_otlp::Protocol::HttpBinary)
+                .with_endpoint(&self.endpoint)
+                .with_timeout(self.timeout)
+                .into(),
+        };
+
+        let resource = Resource::new(
+            self.resource.iter().map(|(key, value)| KeyValue::new(key.clone(), value.clone())),
+        );
+
+        let exporter_runtime = match OTLP_RUNTIME.get() {
+            Some(exporter_runtime) => exporter_runtime,
+            None => {
+                let runtime = tokio::runtime::Builder::new_multi_thread()
+                    .worker_threads(1)
+                    .thread_name("otlp-exporter")
+                    .enable_all()
+                    .build()?;
+                OTLP_RUNTIME.get_or_init(|| runtime)
+            }
+        };
+        let _guard = exporter_runtime.enter();
+
+        let tracer = opentelemetry_otlp::new_pipeline()
+            .tracing()
+            .with_exporter(exporter)
+            .with_trace_config(
+                trace::config().with_sampler(self.sampler.sampler()).with_resource(resource),
+            )
+            .install_batch(runtime::Tokio)?;
+        Ok(tracer)
+    }
+}
+
+impl Default for OtlpConfig {
+    fn default() -> Self {
+        Self::new(OtlpProtocol::default())
+    }
+}
+
+/// Exports all pending spans and shuts down the OTLP exporter.
+///
+/// Spans are exported in batches, so this should be called before the application exits to not
+/// lose the spans of the last batch. Does nothing if no OTLP exporter was configured.
+pub fn shutdown_otlp() {
+    if OTLP_RUNTIME.get().is_some() {
+        opentelemetry::global::shutdown_tracer_provider();
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::layers::Layers;
+    use std::{
+        io::{BufRead, BufReader, Read, Write},
+        net::TcpListener,
+    };
+    use tracing_subscriber::layer::SubscriberExt;
+
+    #[test]
+    fn parse_sampler() {
+        assert_eq!("always_on".parse::<OtlpSampler>().unwrap(), OtlpSampler::AlwaysOn);
+        assert_eq!("always_off".parse::<OtlpSampler>().unwrap(), OtlpSampler::AlwaysOff);
+        assert_eq!("0.25".parse::<OtlpSampler>().unwrap(), OtlpSampler::TraceIdRatio(0.25));
+        assert!("1.5".parse::<OtlpSampler>().is_err());
+        assert!("sometimes".parse::<OtlpSampler>().is_err());
+    }
+
+    /// Exports a span to a stand-in for an OTLP collector that accepts a single HTTP request.
+    #[test]
+    fn export_spans_over_http() {
+        let listener = TcpListener::bind("127.0.0.1:0").unwrap();
+        let endpoint = format!("http://{}", listener.local_addr().unwrap());
+
+        let collector = std::thread::spawn(move || {
+            let (stream, _) = listener.accept().unwrap();
+            let mut reader = BufReader::new(stream);
+
+            let mut request_line = String::new();
+            reader.read_line(&mut request_line).unwrap();
+
+            let mut content_length = 0;
+            loop {
+                let mut header = String::new();
+                reader.read_line(&mut header).unwrap();
+                if header.trim().is_empty() {
+                    break
+                }
+                if let Some((name, value)) = header.split_once(':') {
+                    if name.eq_ignore_ascii_case("content-length") {
+                        content_length = value.trim().parse().unwrap();
+                    }
+                }
+            }
+            let mut body = vec![0; content_length];
+            reader.read_exact(&mut body).unwrap();
+
+            reader.get_mut().write_all(b"HTTP/1.1 200 OK\r\ncontent-length: 0\r\n\r\n").unwrap();
+            (request_line, body)
+        });
+
+        let config = OtlpConfig::new(OtlpProtocol::Http)
+            .with_endpoint(endpoint)
+            .with_resource_attribute("chain", "mainnet");
+        let mut layers = Layers::new();
+        layers.otlp(&config, "debug").unwrap();
+
+        let subscriber = tracing_subscriber::registry().with(layers.into_inner());
+        tracing::subscriber::with_default(subscriber, || {
+            tracing::info_span!(target: "rpc::engine", "engine_newPayload").in_scope(|| {});
+        });
+        shutdown_otlp();
+
+        let (request_line, body) = collector.join().unwrap();
+        assert!(request_line.starts_with("POST /v1/traces"), "{request_line}");
+        let body = String::from_utf8_lossy(&body);
+        assert!(body.contains("engine_newPayload"));
+        assert!(body.contains("mainnet"));
+    }
+}
@@ -0,0 +1,313 @@
+//! Runtime reloadable log filters.
+//!
+//! The filter of every layer configured by [`RethTracer`](crate::RethTracer) is wrapped in a
+//! [`reload`] layer, so that the filters can be changed without restarting the application, e.g.
+//! through the `debug_verbosity` and `debug_vmodule` RPC endpoints or on `SIGHUP`.
+
+use std::{
+    path::{Path, PathBuf},
+    sync::{Arc, Mutex, OnceLock, PoisonError},
+};
+
+use tracing::level_filters::LevelFilter;
+use tracing_subscriber::{filter::Directive, reload, EnvFilter, Registry};
+
+use crate::layers::build_env_filter;
+
+/// The handle of the filters of the globally installed [`RethTracer`](crate::RethTracer).
+static LOG_FILTER_HANDLE: OnceLock<LogFilterHandle> = OnceLock::new();
+
+/// A reloadable filter of a single layer.
+pub(crate) type ReloadableFilter = reload::Layer<EnvFilter, Registry>;
+
+/// Returns the handle of the filters of the globally installed [`RethTracer`](crate::RethTracer),
+/// or `None` if no tracer was initialized.
+pub fn log_filter_handle() -> Option<LogFilterHandle> {
+    LOG_FILTER_HANDLE.get().cloned()
+}
+
+/// Installs the handle of the filters of the global tracer.
+pub(crate) fn set_log_filter_handle(handle: LogFilterHandle) {
+    let _ = LOG_FILTER_HANDLE.set(handle);
+}
+
+/// A handle to change the filters of all layers at runtime.
+///
+/// The filters of a layer are rebuilt from the configuration the layer was created with, and the
+/// runtime overrides in this order:
+///
+/// 1. The verbosity ceiling set with [`LogFilterHandle::set_verbosity`], which replaces the default
+///    level and the configured directives of all layers.
+/// 2. The directives of the filter file, see [`LogFilterHandle::set_filter_file`].
+/// 3. The per target directives set with [`LogFilterHandle::set_vmodule`].
+#[derive(Debug, Clone, Default)]
+pub struct LogFilterHandle {
+    inner: Arc<Mutex<LogFilterState>>,
+}
+
+/// The filters of all layers and the runtime overrides.
+#[derive(Debug, Default)]
+struct LogFilterState {
+    /// The filters of all layers.
+    layers: Vec<LayerFilter>,
+
+    /// Verbosity ceiling of all layers.
+    verbosity: Option<LevelFilter>,
+
+    /// Per target directives set at runtime.
+    vmodule: Vec<Directive>,
+
+    /// File with additional directives that is re-read on [`LogFilterHandle::reload`].
+    filter_file: Option<PathBuf>,
+
+    /// Directives read from the filter file.
+    file_directives: Vec<Directive>,
+}
+
+/// The configuration of the filter of a single layer.
+#[derive(Debug)]
+struct LayerFilter {
+    /// Default directive the layer was created with.
+    default_directive: Option<Directive>,
+
+    /// Directives the layer was created with.
+    directives: String,
+
+    /// Handle to replace the filter of the layer.
+    handle: reload::Handle<EnvFilter, Registry>,
+}
+
+impl LogFilterHandle {
+    /// Creates a reloadable filter for a new layer, and registers it with this handle.
+    ///
+    /// # Arguments
+    /// * `default_directive` - An optional `Directive` that sets the default directive.
+    /// * `directives` - Additional directives as a comma-separated string.
+    pub(crate) fn layer_filter(
+        &self,
+        default_directive: Option<Directive>,
+        directives: &str,
+    ) -> eyre::Result<ReloadableFilter> {
+        let mut state = self.lock();
+        let filter = state.build_filter(default_directive.clone(), directives)?;
+        let (filter, handle) = reload::Layer::new(filter);
+        state.layers.push(LayerFilter {
+            default_directive,
+            directives: directives.to_string(),
+            handle,
+        });
+        Ok(filter)
+    }
+
+    /// Sets the verbosity ceiling of all layers.
+    ///
+    /// This replaces the default level and the configured directives of every layer. Per target
+    /// directives set with [`LogFilterHandle::set_vmodule`] still apply.
+    pub fn set_verbosity(&self, level: LevelFilter) -> eyre::Result<()> {
+        let mut state = self.lock();
+        state.verbosity = Some(level);
+        state.apply()
+    }
+
+    /// Sets per target directives of all layers, replacing the previously set ones.
+    ///
+    /// The pattern is a comma separated list of `target=level` pairs, where the level is either
+    /// a level name, or a geth style verbosity from `0` (off) to `5` (trace). Geth style module
+    /// paths like `net/eth/*` are accepted and converted to `net::eth`. An empty pattern removes
+    /// all per target directives.
+    pub fn set_vmodule(&self, pattern: &str) -> eyre::Result<()> {
+        let vmodule = parse_vmodule(pattern)?;
+        let mut state = self.lock();
+        state.vmodule = vmodule;
+        state.apply()
+    }
+
+    /// Sets a file with additional directives, in the same format as `RUST_LOG`, and applies it.
+    ///
+    /// The file is re-read on every [`LogFilterHandle::reload`].
+    pub fn set_filter_file(&self, path: impl Into<PathBuf>) -> eyre::Result<()> {
+        let path = path.into();
+        let file_directives = read_filter_file(Some(&path))?;
+        let mut state = self.lock();
+        state.filter_file = Some(path);
+        state.file_directives = file_directives;
+        state.apply()
+    }
+
+    /// Re-reads the log configuration: drops the verbosity ceiling and per target directives set
+    /// at runtime, and re-reads the filter file.
+    ///
+    /// If the filter file can't be read or parsed, the configuration is left unchanged.
+    pub fn reload(&self) -> eyre::Result<()> {
+        let mut state = self.lock();
+        let file_directives = read_filter_file(state.filter_file.as_deref())?;
+        state.verbosity = None;
+        state.vmodule.clear();
+        state.file_directives = file_directives;
+        state.apply()
+    }
+
+    /// Locks the state of the filters.
+    fn lock(&self) -> std::sync::MutexGuard<'_, LogFilterState> {
+        self.inner.lock().unwrap_or_else(PoisonError::into_inner)
+    }
+}
+
+impl LogFilterState {
+    /// Builds the filter of a layer with the runtime overrides.
+    fn build_filter(
+        &self,
+        default_directive: Option<Directive>,
+        directives: &str,
+    ) -> eyre::Result<EnvFilter> {
+        let mut filter = match self.verbosity {
+            Some(level) => build_env_filter(Some(level.into()), "")?,
+            None => build_env_filter(default_directive, directives)?,
+        };
+        for directive in self.file_directives.iter().chain(&self.vmodule) {
+            filter = filter.add_directive(directive.clone());
+        }
+        Ok(filter)
+    }
+
+    /// Rebuilds the filters of all layers with the current runtime overrides.
+    fn apply(&self) -> eyre::Result<()> {
+        for layer in &self.layers {
+            let filter = self.build_filter(layer.default_directive.clone(), &layer.directives)?;
+            layer.handle.reload(filter)?;
+        }
+        Ok(())
+    }
+}
+
+/// Reads the directives of the filter file, if any.
+///
+/// A missing file has no directives.
+fn read_filter_file(path: Option<&Path>) -> eyre::Result<Vec<Directive>> {
+    let Some(path) = path else { return Ok(Vec::new()) };
+    if !path.exists() {
+        return Ok(Vec::new())
+    }
+    parse_filter_file(path)
+}
+
+/// Parses the directives of a filter file.
+///
+/// Directives are separated by commas or new lines, and lines starting with `#` are ignored.
+fn parse_filter_file(path: &Path) -> eyre::Result<Vec<Directive>> {
+    std::fs::read_to_string(path)?
+        .lines()
+        .map(str::trim)
+        .filter(|line| !line.starts_with('#'))
+        .flat_map(|line| line.split(','))
+        .map(str::trim)
+        .filter(|directive| !directive.is_empty())
+        .map(|directive| Ok(directive.parse()?))
+        .collect()
+}
+
+/// Parses a geth style `vmodule` pattern into directives.
+fn parse_vmodule(pattern: &str) -> eyre::Result<Vec<Directive>> {
+    pattern
+        .split(',')
+        .map(str::trim)
+        .filter(|module| !module.is_empty())
+        .map(|module| {
+            let (target, level) = module
+                .split_once('=')
+                .ok_or_else(|| eyre::eyre!("invalid vmodule {module:?}, expected TARGET=LEVEL"))?;
+            let target =
+                target.trim().trim_end_matches("/*").trim_end_matches(".go").replace('/', "::");
+            let level = match level.trim().parse::<usize>() {
+                Ok(verbosity) => verbosity_level(verbosity).to_string(),
+                Err(_) => level.trim().to_string(),
+            };
+            Ok(format!("{target}={level}").parse()?)
+        })
+        .collect()
+}
+
+/// Converts a geth style verbosity from `0` (off) to `5` (trace) into a [`LevelFilter`].
+pub fn verbosity_level(verbosity: usize) -> LevelFilter {
+    match verbosity {
+        0 => LevelFilter::OFF,
+        1 => LevelFilter::ERROR,
+        2 => LevelFilter::WARN,
+        3 => LevelFilter::INFO,
+        4 => LevelFilter::DEBUG,
+        _ => LevelFilter::TRACE,
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use std::sync::atomic::{AtomicUsize, Ordering};
+    use tracing_subscriber::{layer::SubscriberExt, Layer};
+
+    /// A layer that counts the events it receives.
+    struct CountingLayer(Arc<AtomicUsize>);
+
+    impl<S: tracing::Subscriber> Layer<S> for CountingLayer {
+        fn on_event(&self, _: &tracing::Event<'_>, _: tracing_subscriber::layer::Context<'_, S>) {
+            self.0.fetch_add(1, Ordering::Relaxed);
+        }
+    }
+
+    #[test]
+    fn parse_geth_vmodule() {
+        let directives = parse_vmodule("net/eth/*=5, reth::cli=warn,").unwrap();
+        let directives = directives.iter().map(ToString::to_string).collect::<Vec<_>>();
+        assert_eq!(directives, vec!["net::eth=trace", "reth::cli=warn"]);
+
+        assert!(parse_vmodule("").unwrap().is_empty());
+        assert!(parse_vmodule("net").is_err());
+    }
+
+    #[test]
+    fn reload_filters() {
+        let handle = LogFilterHandle::default();
+        let events = Arc::new(AtomicUsize::new(0));
+        let filter = handle.layer_filter(Some(LevelFilter::INFO.into()), "").unwrap();
+        let subscriber = tracing_subscriber::registry()
+            .with(CountingLayer(Arc::clone(&events)).with_filter(filter));
+
+        tracing::subscriber::with_default(subscriber, || {
+            let emit = || {
+                tracing::debug!(target: "sync::stages", "debug");
+                tracing::trace!(target: "net::eth", "trace");
+            };
+
+            emit();
+            assert_eq!(events.swap(0, Ordering::Relaxed), 0);
+
+            handle.set_vmodule("net/eth=5").unwrap();
+            emit();
+            assert_eq!(events.swap(0, Ordering::Relaxed), 1);
+
+            handle.set_verbosity(LevelFilter::DEBUG).unwrap();
+            emit();
+            assert_eq!(events.swap(0, Ordering::Relaxed), 2);
+
+            let dir = std::env::temp_dir().join(format!("reth-log-filter-{}", std::process::id()));
+            std::fs::create_dir_all(&dir).unwrap();
+            let file = dir.join("filter");
+            std::fs::write(&file, "# enable stages\nsync::stages=debug\n").unwrap();
+            handle.set_filter_file(&file).unwrap();
+
+            // reloading drops the runtime overrides, but keeps the filter file
+            handle.reload().unwrap();
+            emit();
+            assert_eq!(events.swap(0, Ordering::Relaxed), 1);
+
+            // an invalid filter file keeps the previous configuration
+            handle.set_vmodule("net/eth=5").unwrap();
+            std::fs::write(&file, "sync::stages=loud\n").unwrap();
+            assert!(handle.reload().is_err());
+            emit();
+            assert_eq!(events.swap(0, Ordering::Relaxed), 2);
+
+            std::fs::remove_dir_all(dir).unwrap();
+        });
+    }
+}
@@ -0,0 +1,596 @@
+//! Log file rotation by size and time.
+//!
+//! The active log file keeps its configured name, e.g. `reth.log`. Rotated files are renamed to
+//! `<name>-<chain>-<timestamp>.log`, optionally compressed, and removed according to the retention
+//! policy of the [`FileInfo`], so that the log directory can be shipped by a log collector as is.
+
+use std::{
+    ffi::OsString,
+    fmt,
+    fs::{File, OpenOptions},
+    io::{self, Write},
+    path::{Path, PathBuf},
+    thread::JoinHandle,
+    time::SystemTime,
+};
+
+use chrono::{DateTime, DurationRound, NaiveDateTime, TimeDelta, Utc};
+use clap::ValueEnum;
+
+use crate::layers::FileInfo;
+
+/// Format of the timestamps in the names of rotated log files, e.g. `20240101T000000Z`.
+const TIMESTAMP_FORMAT: &str = "%Y%m%dT%H%M%SZ";
+
+/// Length of the timestamps in the names of rotated log files.
+const TIMESTAMP_LEN: usize = "20240101T000000Z".len();
+
+/// Suffix of files that are still being compressed.
+const TMP_SUFFIX: &str = ".tmp";
+
+/// When log files are rotated, in addition to rotating them by size.
+#[derive(Debug, Copy, Clone, Default, ValueEnum, Eq, PartialEq)]
+pub enum LogRotation {
+    /// Rotate log files only by size.
+    #[default]
+    Never,
+
+    /// Rotate log files at the start of every hour, in UTC.
+    Hourly,
+
+    /// Rotate log files at midnight, in UTC.
+    Daily,
+}
+
+impl LogRotation {
+    /// Returns the length of a rotation period, or `None` if log files are not rotated by time.
+    fn period(&self) -> Option<TimeDelta> {
+        match self {
+            Self::Never => None,
+            Self::Hourly => Some(TimeDelta::hours(1)),
+            Self::Daily => Some(TimeDelta::days(1)),
+        }
+    }
+
+    /// Returns the start of the rotation period that contains `time`.
+    fn period_start(&self, time: DateTime<Utc>) -> Option<DateTime<Utc>> {
+        time.duration_trunc(self.period()?).ok()
+    }
+
+    /// Returns when a log file that was opened at `time` is rotated.
+    fn next_rotation(&self, time: DateTime<Utc>) -> Option<DateTime<Utc>> {
+        Some(self.period_start(time)? + self.period()?)
+    }
+}
+
+impl fmt::Display for LogRotation {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            Self::Never => f.write_str("never"),
+            Self::Hourly => f.write_str("hourly"),
+            Self::Daily => f.write_str("daily"),
+        }
+    }
+}
+
+/// The compression of rotated log files.
+#[derive(Debug, Copy, Clone, Default, ValueEnum, Eq, PartialEq)]
+pub enum LogCompression {
+    /// Keep rotated log files uncompressed.
+    #[default]
+    None,
+
+    /// Compress rotated log files with gzip, adding the `.gz` extension.
+    Gzip,
+
+    /// Compress rotated log files with zstd, adding the `.zst` extension.
+    Zstd,
+}
+
+impl LogCompression {
+    /// Extensions added to compressed log files.
+    const EXTENSIONS: [&'static str; 2] = [".gz", ".zst"];
+
+    /// Returns the extension added to compressed log files.
+    const fn extension(&self) -> Option<&'static str> {
+        match self {
+            Self::None => None,
+            Self::Gzip => Some(Self::EXTENSIONS[0]),
+            Self::Zstd => Some(Self::EXTENSIONS[1]),
+        }
+    }
+
+    /// Compresses the log file at `path`, and replaces it with the compressed file.
+    fn compress(&self, path: &Path) -> io::Result<()> {
+        let Some(extension) = self.extension() else { return Ok(()) };
+        let compressed = with_suffix(path, extension);
+        let tmp = with_suffix(&compressed, TMP_SUFFIX);
+
+        let mut source = File::open(path)?;
+        let target = File::create(&tmp)?;
+        match self {
+            Self::None => {}
+            Self::Gzip => {
+                let mut encoder =
+                    flate2::write::GzEncoder::new(target, flate2::Compression::default());
+                io::copy(&mut source, &mut encoder)?;
+                encoder.finish()?;
+            }
+            Self::Zstd => zstd::stream::copy_encode(source, target, 0)?,
+        }
+
+        std::fs::rename(tmp, compressed)?;
+        std::fs::remove_file(path)
+    }
+}
+
+impl fmt::Display for LogCompression {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            Self::None => f.write_str("none"),
+            Self::Gzip => f.write_str("gzip"),
+            Self::Zstd => f.write_str("zstd"),
+        }
+    }
+}
+
+/// A writer to the log file that rotates it by size and time.
+///
+/// Rotated files are compressed and the retention policy is applied on a background thread, so
+/// that writing logs is not blocked by it.
+#[derive(Debug)]
+pub(crate) struct RotatingFileWriter {
+    /// The configuration of the log files.
+    info: FileInfo,
+    /// Path of the active log file.
+    path: PathBuf,
+    /// The active log file, opened on the first write after a rotation.
+    file: Option<File>,
+    /// Size of the active log file.
+    size: u64,
+    /// When the active log file was created.
+    opened_at: DateTime<Utc>,
+    /// When the active log file is rotated by time.
+    rotate_at: Option<DateTime<Utc>>,
+    /// The thread that compresses the last rotated file and applies the retention policy.
+    maintenance: Option<JoinHandle<()>>,
+}
+
+impl RotatingFileWriter {
+    /// Creates a new writer that appends to the log file of the given [`FileInfo`].
+    ///
+    /// An existing log file that was last written in an earlier rotation period is rotated
+    /// right away.
+    pub(crate) fn new(info: FileInfo) -> io::Result<Self> {
+        let path = info.dir.join(&info.file_name);
+        let now = Utc::now();
+        let (size, opened_at, modified_at) = match std::fs::metadata(&path) {
+            Ok(metadata) => {
+                let modified = metadata.modified().map(DateTime::from).unwrap_or(now);
+                let created = metadata.created().map(DateTime::from).unwrap_or(modified);
+                (metadata.len(), created, modified)
+            }
+            Err(err) if err.kind() == io::ErrorKind::NotFound => (0, now, now),
+            Err(err) => return Err(err),
+        };
+
+        let mut writer = Self {
+            rotate_at: info.rotation.next_rotation(modified_at),
+            info,
+            path,
+            file: None,
+            size,
+            opened_at,
+            maintenance: None,
+        };
+        if writer.rotate_at.is_some_and(|at| at <= now) && size > 0 {
+            writer.rotate()?;
+        }
+        Ok(writer)
+    }
+
+    /// Returns `true` if the active log file should be rotated before writing `len` bytes.
+    fn should_rotate(&mut self, len: usize) -> bool {
+        let now = Utc::now();
+        let due = self.rotate_at.is_some_and(|at| at <= now);
+        if self.size == 0 {
+            // an empty log file is continued in the next period
+            if due {
+                self.opened_at = now;
+                self.rotate_at = self.info.rotation.next_rotation(now);
+            }
+            return false
+        }
+        due || self.size.saturating_add(len as u64) > self.info.max_size_bytes
+    }
+
+    /// Renames the active log file, and spawns a thread to compress it and apply the retention
+    /// policy.
+    fn rotate(&mut self) -> io::Result<()> {
+        // the log file is closed before it is renamed, which is required on Windows
+        if let Some(mut file) = self.file.take() {
+            file.flush()?;
+        }
+        let rotated = self.rotated_path();
+        std::fs::rename(&self.path, &rotated)?;
+
+        let now = Utc::now();
+        self.size = 0;
+        self.opened_at = now;
+        self.rotate_at = self.info.rotation.next_rotation(now);
+
+        let info = self.info.clone();
+        let previous = self.maintenance.take();
+        let maintenance =
+            std::thread::Builder::new().name("log-rotation".to_string()).spawn(move || {
+                // rotated files are processed in order, so that the retention policy never
+                // removes a file that is still being compressed
+                if let Some(previous) = previous {
+                    let _ = previous.join();
+                }
+                if let Err(err) = info.compression.compress(&rotated) {
+                    eprintln!("Failed to compress log file {}: {err}", rotated.display());
+                }
+                if let Err(err) = remove_expired(&info) {
+                    eprintln!("Failed to remove expired log files: {err}");
+                }
+            })?;
+        self.maintenance = Some(maintenance);
+        Ok(())
+    }
+
+    /// Returns an unused path for the active log file once it is rotated.
+    fn rotated_path(&self) -> PathBuf {
+        let timestamp = self.info.rotation.period_start(self.opened_at).unwrap_or(self.opened_at);
+        let prefix = self.info.rotated_prefix();
+        let extension = self.info.extension();
+        let timestamp = timestamp.format(TIMESTAMP_FORMAT);
+
+        let mut path = self.info.dir.join(format!("{prefix}{timestamp}{extension}"));
+        let mut counter = 0;
+        while [""].into_iter().chain(LogCompression::EXTENSIONS).any(|ext| {
+            let candidate = with_suffix(&path, ext);
+            candidate.exists() || with_suffix(&candidate, TMP_SUFFIX).exists()
+        }) {
+            counter += 1;
+            path = self.info.dir.join(format!("{prefix}{timestamp}-{counter}{extension}"));
+        }
+        path
+    }
+
+    /// Returns the active log file, opening it if necessary.
+    fn file(&mut self) -> io::Result<&mut File> {
+        match self.file {
+            Some(ref mut file) => Ok(file),
+            None => {
+                let file = OpenOptions::new().create(true).append(true).open(&self.path)?;
+                Ok(self.file.insert(file))
+            }
+        }
+    }
+}
+
+impl Drop for RotatingFileWriter {
+    fn drop(&mut self) {
+        // the last rotated file is compressed completely before the process exits
+        if let Some(maintenance) = self.maintenance.take() {
+            let _ = maintenance.join();
+        }
+    }
+}
+
+impl Write for RotatingFileWriter {
+    fn write(&mut self, buf: &[u8]) -> io::Result<usize> {
+        if self.should_rotate(buf.len()) {
+            if let Err(err) = self.rotate() {
+                eprintln!("Failed to rotate log file {}: {err}", self.path.display());
+                // retry once the next limit is reached, instead of on every write
+                self.size = 0;
+                self.rotate_at = self.info.rotation.next_rotation(Utc::now());
+            }
+        }
+        let written = self.file()?.write(buf)?;
+        self.size += written as u64;
+        Ok(written)
+    }
+
+    fn flush(&mut self) -> io::Result<()> {
+        match &mut self.file {
+            Some(file) => file.flush(),
+            None => Ok(()),
+        }
+    }
+}
+
+/// Returns `true` if `name` is the name of a log file rotated with the given [`FileInfo`], i.e.
+/// `<prefix><timestamp>[-<counter>]<extension>`, optionally with the extension of a compression.
+///
+/// Rotated files of other chains in the same directory, e.g. `reth-sepolia-<timestamp>.log` for
+/// the prefix `reth-`, don't match.
+fn is_rotated(info: &FileInfo, name: &str) -> bool {
+    let Some(rest) = name.strip_prefix(&info.rotated_prefix()) else { return false };
+    let Some(timestamp) = rest.get(..TIMESTAMP_LEN) else { return false };
+    if NaiveDateTime::parse_from_str(timestamp, TIMESTAMP_FORMAT).is_err() {
+        return false
+    }
+    let rest = &rest[TIMESTAMP_LEN..];
+
+    let rest = match rest.strip_prefix('-') {
+        Some(rest) => {
+            let counter_len = rest.find(|c: char| !c.is_ascii_digit()).unwrap_or(rest.len());
+            if counter_len == 0 {
+                return false
+            }
+            &rest[counter_len..]
+        }
+        None => rest,
+    };
+    let Some(rest) = rest.strip_prefix(info.extension().as_str()) else { return false };
+    rest.is_empty() || LogCompression::EXTENSIONS.contains(&rest)
+}
+
+/// Removes the rotated log files that exceed the retention policy of the [`FileInfo`].
+///
+/// The newest files are kept, up to the maximum number of files and total size of the rotated
+/// files. Files older than the maximum age are always removed, as are partially compressed files
+/// left behind by an interrupted compression.
+fn remove_expired(info: &FileInfo) -> io::Result<()> {
+    let mut rotated = Vec::new();
+    for entry in std::fs::read_dir(&info.dir)? {
+        let entry = entry?;
+        let name = entry.file_name().to_string_lossy().into_owned();
+        if let Some(compressed) = name.strip_suffix(TMP_SUFFIX) {
+            // files are compressed one at a time, before the retention policy is applied, so a
+            // temporary file is never still being written
+            if LogCompression::EXTENSIONS.iter().any(|ext| compressed.ends_with(ext)) &&
+                is_rotated(info, compressed)
+            {
+                remove_file(&info.dir.join(&name))?;
+            }
+            continue
+        }
+        if !is_rotated(info, &name) {
+            continue
+        }
+        let metadata = entry.metadata()?;
+        if metadata.is_file() {
+            let modified = metadata.modified().unwrap_or(SystemTime::UNIX_EPOCH);
+            rotated.push((modified, name, metadata.len()));
+        }
+    }
+
+    // newest files first
+    rotated.sort_unstable_by(|a, b| b.cmp(a));
+
+    let now = SystemTime::now();
+    let mut total_bytes = 0u64;
+    for (index, (modified, name, len)) in rotated.into_iter().enumerate() {
+        total_bytes = total_bytes.saturating_add(len);
+        let expired = index >= info.max_files ||
+            info.max_total_bytes.is_some_and(|max| total_bytes > max) ||
+            info.max_age
+                .is_some_and(|max| now.duration_since(modified).is_ok_and(|age| age > max));
+        if expired {
+            remove_file(&info.dir.join(name))?;
+        }
+    }
+    Ok(())
+}
+
+/// Removes the file, if it still exists.
+fn remove_file(path: &Path) -> io::Result<()> {
+    match std::fs::remove_file(path) {
+        Err(err) if err.kind() != io::ErrorKind::NotFound => Err(err),
+        _ => Ok(()),
+    }
+}
+
+/// Returns the path with the suffix appended to its file name.
+fn with_suffix(path: &Path, suffix: &str) -> PathBuf {
+    let mut path = OsString::from(path);
+    path.push(suffix);
+    path.into()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use std::{io::Read, time::Duration};
+
+    /// Creates an empty directory for the log files of a test.
+    fn test_dir(name: &str) -> PathBuf {
+        let dir = std::env::temp_dir().join(format!("reth-{name}-{}", std::process::id()));
+        let _ = std::fs::remove_dir_all(&dir);
+        std::fs::create_dir_all(&dir).unwrap();
+        dir
+    }
+
+    /// Creates a file of `len` bytes that was last modified `age` ago.
+    fn create_file(dir: &Path, name: &str, len: usize, age: Duration) {
+        let file = File::create(dir.join(name)).unwrap();
+        file.set_len(len as u64).unwrap();
+        file.set_modified(SystemTime::now() - age).unwrap();
+    }
+
+    /// Returns the sorted names of the files in the directory.
+    fn file_names(dir: &Path) -> Vec<String> {
+        let mut names = std::fs::read_dir(dir)
+            .unwrap()
+            .map(|entry| entry.unwrap().file_name().into_string().unwrap())
+            .collect::<Vec<_>>();
+        names.sort();
+        names
+    }
+
+    #[test]
+    fn rotation_periods() {
+        let time = DateTime::parse_from_rfc3339("2024-03-05T17:42:11Z").unwrap().to_utc();
+
+        assert_eq!(LogRotation::Never.next_rotation(time), None);
+        assert_eq!(
+            LogRotation::Hourly.next_rotation(time).unwrap().to_rfc3339(),
+            "2024-03-05T18:00:00+00:00"
+        );
+        assert_eq!(
+            LogRotation::Daily.next_rotation(time).unwrap().to_rfc3339(),
+            "2024-03-06T00:00:00+00:00"
+        );
+        assert_eq!(
+            LogRotation::Daily.period_start(time).unwrap().format(TIMESTAMP_FORMAT).to_string(),
+            "20240305T000000Z"
+        );
+    }
+
+    #[test]
+    fn rotate_and_compress_by_size() {
+        let dir = std::env::temp_dir().join(format!("reth-log-rotation-{}", std::process::id()));
+        let _ = std::fs::remove_dir_all(&dir);
+        std::fs::create_dir_all(&dir).unwrap();
+
+        let info = FileInfo::new(dir.clone(), 10, 2)
+            .with_chain("mainnet")
+            .with_compression(LogCompression::Gzip);
+        let mut writer = RotatingFileWriter::new(info).unwrap();
+        for line in ["first line\n", "second line\n", "third line\n", "fourth line\n"] {
+            writer.write_all(line.as_bytes()).unwrap();
+        }
+        writer.flush().unwrap();
+        // dropping the writer waits for the last rotated file to be compressed
+        drop(writer);
+
+        let mut rotated = std::fs::read_dir(&dir)
+            .unwrap()
+            .map(|entry| entry.unwrap().file_name().into_string().unwrap())
+            .filter(|name| name != "reth.log")
+            .collect::<Vec<_>>();
+        rotated.sort();
+
+        // the oldest rotated file is removed
+        assert_eq!(rotated.len(), 2, "{rotated:?}");
+        for name in &rotated {
+            assert!(name.starts_with("reth-mainnet-") && name.ends_with(".log.gz"), "{name}");
+        }
+        assert_eq!(std::fs::read_to_string(dir.join("reth.log")).unwrap(), "fourth line\n");
+
+        let mut contents = rotated
+            .iter()
+            .map(|name| {
+                let file = File::open(dir.join(name)).unwrap();
+                let mut contents = String::new();
+                flate2::read::GzDecoder::new(file).read_to_string(&mut contents).unwrap();
+                contents
+            })
+            .collect::<Vec<_>>();
+        contents.sort();
+        assert_eq!(contents, vec!["second line\n", "third line\n"]);
+
+        std::fs::remove_dir_all(dir).unwrap();
+    }
+
+    #[test]
+    fn rotate_by_time() {
+        let dir = test_dir("log-rotation-time");
+
+        let info = FileInfo::new(dir.clone(), 1024, 5).with_rotation(LogRotation::Hourly);
+        let mut writer = RotatingFileWriter::new(info).unwrap();
+        writer.write_all(b"first line\n").unwrap();
+        let period_start = LogRotation::Hourly.period_start(writer.opened_at).unwrap();
+
+        // nothing is rotated before the end of the period
+        writer.write_all(b"second line\n").unwrap();
+        assert!(writer.maintenance.is_none());
+
+        writer.rotate_at = Some(Utc::now() - TimeDelta::seconds(1));
+        writer.write_all(b"third line\n").unwrap();
+        writer.flush().unwrap();
+        writer.maintenance.take().unwrap().join().unwrap();
+
+        let rotated = format!("reth-{}.log", period_start.format(TIMESTAMP_FORMAT));
+        assert_eq!(file_names(&dir), vec![rotated.clone(), "reth.log".to_string()]);
+        assert_eq!(
+            std::fs::read_to_string(dir.join(rotated)).unwrap(),
+            "first line\nsecond line\n"
+        );
+        assert_eq!(std::fs::read_to_string(dir.join("reth.log")).unwrap(), "third line\n");
+        assert!(writer.rotate_at.unwrap() > Utc::now());
+
+        std::fs::remove_dir_all(dir).unwrap();
+    }
+
+    #[test]
+    fn rotated_file_names() {
+        let info = FileInfo::new(PathBuf::new(), 1024, 5);
+        assert!(is_rotated(&info, "reth-20240101T000000Z.log"));
+        assert!(is_rotated(&info, "reth-20240101T000000Z-2.log"));
+        assert!(is_rotated(&info, "reth-20240101T000000Z.log.gz"));
+        assert!(is_rotated(&info, "reth-20240101T000000Z-2.log.zst"));
+        assert!(!is_rotated(&info, "reth.log"));
+        assert!(!is_rotated(&info, "reth-mainnet-20240101T000000Z.log"));
+        assert!(!is_rotated(&info, "reth-20240101T000000Z.log.gz.tmp"));
+        assert!(!is_rotated(&info, "reth-20240101T000000Z-.log"));
+        assert!(!is_rotated(&info, "reth-20241301T000000Z.log"));
+        assert!(!is_rotated(&info, "reth-20240101T000000Z.txt"));
+
+        let info = info.with_chain("mainnet");
+        assert!(is_rotated(&info, "reth-mainnet-20240101T000000Z.log"));
+        assert!(!is_rotated(&info, "reth-20240101T000000Z.log"));
+        assert!(!is_rotated(&info, "reth-sepolia-20240101T000000Z.log"));
+    }
+
+    #[test]
+    fn remove_files_older_than_max_age() {
+        let dir = test_dir("log-rotation-max-age");
+        let hour = Duration::from_secs(60 * 60);
+
+        create_file(&dir, "reth-20240101T000000Z.log", 10, 3 * hour);
+        create_file(&dir, "reth-20240101T010000Z.log.gz", 10, 2 * hour);
+        create_file(&dir, "reth-20240101T020000Z.log", 10, hour / 2);
+        // partially compressed files are removed regardless of their age
+        create_file(&dir, "reth-20240101T020000Z.log.zst.tmp", 10, hour / 2);
+        // files of other chains and other files in the directory are kept
+        create_file(&dir, "reth-sepolia-20240101T000000Z.log", 10, 3 * hour);
+        create_file(&dir, "reth-sepolia-20240101T000000Z.log.gz.tmp", 10, 3 * hour);
+        create_file(&dir, "reth-notes.txt", 10, 3 * hour);
+
+        let info = FileInfo::new(dir.clone(), 1024, 5).with_max_age(hour);
+        remove_expired(&info).unwrap();
+
+        assert_eq!(
+            file_names(&dir),
+            vec![
+                "reth-20240101T020000Z.log",
+                "reth-notes.txt",
+                "reth-sepolia-20240101T000000Z.log",
+                "reth-sepolia-20240101T000000Z.log.gz.tmp"
+            ]
+        );
+
+        std::fs::remove_dir_all(dir).unwrap();
+    }
+
+    #[test]
+    fn remove_oldest_files_over_max_total_bytes() {
+        let dir = test_dir("log-rotation-max-total-bytes");
+        let minute = Duration::from_secs(60);
+
+        create_file(&dir, "reth-mainnet-20240101T000000Z.log", 10, 3 * minute);
+        create_file(&dir, "reth-mainnet-20240101T000000Z-1.log", 10, 2 * minute);
+        create_file(&dir, "reth-mainnet-20240101T000000Z-2.log", 10, minute);
+        create_file(&dir, "reth-sepolia-20240101T000000Z.log", 10, 3 * minute);
+
+        let info =
+            FileInfo::new(dir.clone(), 1024, 5).with_chain("mainnet").with_max_total_bytes(25);
+        remove_expired(&info).unwrap();
+
+        assert_eq!(
+            file_names(&dir),
+            vec![
+                "reth-mainnet-20240101T000000Z-1.log",
+                "reth-mainnet-20240101T000000Z-2.log",
+                "reth-sepolia-20240101T000000Z.log"
+            ]
+        );
+
+        std::fs::remove_dir_all(dir).unwrap();
+    }
+}
@@ -0,0 +1,418 @@
+//! Admission policies of the transaction pool.
+//!
+//! An [`AdmissionPolicy`] decides whether a transaction that passed validation may enter the pool.
+//! Policies are evaluated in [`Pool::add_transactions`](crate::Pool) right before a validated
+//! transaction is inserted, and a rejection is reported as
+//! [`PoolErrorKind::AdmissionRejected`](crate::error::PoolErrorKind::AdmissionRejected).
+//!
+//! Multiple policies can be composed with [`AdmissionPolicies`], which rejects a transaction as
+//! soon as any of its policies rejects it.
+//!
+//! Once an admitted transaction was inserted into the pool, the policies are notified with
+//! [`AdmissionPolicy::on_inserted`], so that transactions that are rejected by another policy or
+//! by the pool itself are never accounted for.
+
+use crate::{PoolTransaction, TransactionOrigin};
+use parking_lot::Mutex;
+use reth_primitives::{hex, Address, TxHash};
+use std::{
+    collections::{HashMap, HashSet},
+    fmt,
+    sync::Arc,
+    time::Instant,
+};
+
+/// The length of a function selector at the start of the calldata.
+const SELECTOR_LEN: usize = 4;
+
+/// A policy that decides whether a validated transaction may enter the pool.
+#[auto_impl::auto_impl(&, Arc, Box)]
+pub trait AdmissionPolicy: fmt::Debug + Send + Sync {
+    /// Returns an error if the transaction must not enter the pool.
+    fn admit(&self, request: &AdmissionRequest<'_>) -> Result<(), AdmissionRejection>;
+
+    /// Called once an admitted transaction was inserted into the pool.
+    fn on_inserted(&self, _request: &AdmissionRequest<'_>) {}
+}
+
+/// A transaction that is about to be admitted to the pool.
+#[derive(Debug, Clone, Copy)]
+pub struct AdmissionRequest<'a> {
+    /// Hash of the transaction.
+    pub hash: &'a TxHash,
+    /// Where the transaction originated from.
+    pub origin: TransactionOrigin,
+    /// Sender of the transaction.
+    pub sender: Address,
+    /// Recipient of the transaction, `None` for contract creations.
+    pub to: Option<Address>,
+    /// Nonce of the transaction.
+    pub nonce: u64,
+    /// Type identifier of the transaction.
+    pub tx_type: u8,
+    /// Calldata of the transaction.
+    pub input: &'a [u8],
+}
+
+impl<'a> AdmissionRequest<'a> {
+    /// Creates a new request for the given transaction.
+    pub fn new<T: PoolTransaction>(transaction: &'a T, origin: TransactionOrigin) -> Self {
+        Self {
+            hash: transaction.hash(),
+            origin,
+            sender: transaction.sender(),
+            to: transaction.to(),
+            nonce: transaction.nonce(),
+            tx_type: transaction.tx_type(),
+            input: transaction.input(),
+        }
+    }
+
+    /// Returns the function selector of the calldata, if the calldata is long enough to contain
+    /// one.
+    pub fn selector(&self) -> Option<[u8; SELECTOR_LEN]> {
+        self.input.get(..SELECTOR_LEN)?.try_into().ok()
+    }
+}
+
+/// The reason an [`AdmissionPolicy`] rejected a transaction.
+#[derive(Debug, Clone, PartialEq, Eq, thiserror::Error)]
+pub enum AdmissionRejection {
+    /// The sender is on a deny list, or not on an allow list.
+    #[error("sender {0} is not allowed")]
+    SenderNotAllowed(Address),
+    /// The recipient is on a deny list, or not on an allow list.
+    #[error("recipient {0} is not allowed")]
+    RecipientNotAllowed(Address),
+    /// The transaction creates a contract, but only an allow list of recipients is accepted.
+    #[error("contract creation is not allowed")]
+    ContractCreationNotAllowed,
+    /// The function selector of the calldata is blocked.
+    #[error("function selector 0x{} is blocked", hex::encode(.0))]
+    SelectorBlocked([u8; SELECTOR_LEN]),
+    /// The sender submitted more transactions than its admission rate allows.
+    #[error("sender {0} exceeded its admission rate")]
+    RateLimited(Address),
+    /// Rejected by a custom policy.
+    #[error("{0}")]
+    Custom(String),
+}
+
+/// A composition of [`AdmissionPolicy`]s.
+///
+/// The policies are evaluated in the order they were added, and the first rejection is returned.
+/// An empty set of policies admits every transaction.
+#[derive(Debug, Clone, Default)]
+pub struct AdmissionPolicies {
+    policies: Vec<Arc<dyn AdmissionPolicy>>,
+}
+
+impl AdmissionPolicies {
+    /// Creates an empty set of policies, which admits every transaction.
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Adds a policy to the set.
+    pub fn with_policy(mut self, policy: impl AdmissionPolicy + 'static) -> Self {
+        self.policies.push(Arc::new(policy));
+        self
+    }
+
+    /// Returns `true` if there are no policies.
+    pub fn is_empty(&self) -> bool {
+        self.policies.is_empty()
+    }
+}
+
+impl AdmissionPolicy for AdmissionPolicies {
+    fn admit(&self, request: &AdmissionRequest<'_>) -> Result<(), AdmissionRejection> {
+        self.policies.iter().try_for_each(|policy| policy.admit(request))
+    }
+
+    fn on_inserted(&self, request: &AdmissionRequest<'_>) {
+        self.policies.iter().for_each(|policy| policy.on_inserted(request))
+    }
+}
+
+/// Whether an [`AddressFilter`] lists the only admitted addresses or the rejected ones.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+enum AddressFilterMode {
+    Allow,
+    Deny,
+}
+
+/// Which address of the transaction an [`AddressFilter`] applies to.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+enum AddressFilterTarget {
+    Sender,
+    Recipient,
+}
+
+/// An allow or deny list of transaction senders or recipients.
+///
+/// Contract creations have no recipient, so they are always admitted by a deny list of
+/// recipients and always rejected by an allow list of recipients.
+#[derive(Debug, Clone)]
+pub struct AddressFilter {
+    mode: AddressFilterMode,
+    target: AddressFilterTarget,
+    addresses: HashSet<Address>,
+}
+
+impl AddressFilter {
+    /// Only admits transactions sent by the given addresses.
+    pub fn allow_senders(addresses: impl IntoIterator<Item = Address>) -> Self {
+        Self::new(AddressFilterMode::Allow, AddressFilterTarget::Sender, addresses)
+    }
+
+    /// Rejects transactions sent by the given addresses.
+    pub fn deny_senders(addresses: impl IntoIterator<Item = Address>) -> Self {
+        Self::new(AddressFilterMode::Deny, AddressFilterTarget::Sender, addresses)
+    }
+
+    /// Only admits transactions sent to the given addresses.
+    pub fn allow_recipients(addresses: impl IntoIterator<Item = Address>) -> Self {
+        Self::new(AddressFilterMode::Allow, AddressFilterTarget::Recipient, addresses)
+    }
+
+    /// Rejects transactions sent to the given addresses.
+    pub fn deny_recipients(addresses: impl IntoIterator<Item = Address>) -> Self {
+        Self::new(AddressFilterMode::Deny, AddressFilterTarget::Recipient, addresses)
+    }
+
+    fn new(
+        mode: AddressFilterMode,
+        target: AddressFilterTarget,
+        addresses: impl IntoIterator<Item = Address>,
+    ) -> Self {
+        Self { mode, target, addresses: addresses.into_iter().collect() }
+    }
+}
+
+impl AdmissionPolicy for AddressFilter {
+    fn admit(&self, request: &AdmissionRequest<'_>) -> Result<(), AdmissionRejection> {
+        let (address, rejection) = match self.target {
+            AddressFilterTarget::Sender => {
+                (request.sender, AdmissionRejection::SenderNotAllowed(request.sender))
+            }
+            AddressFilterTarget::Recipient => match request.to {
+                Some(to) => (to, AdmissionRejection::RecipientNotAllowed(to)),
+                None if self.mode == AddressFilterMode::Allow => {
+                    return Err(AdmissionRejection::ContractCreationNotAllowed)
+                }
+                None => return Ok(()),
+            },
+        };
+
+        let listed = self.addresses.contains(&address);
+        match self.mode {
+            AddressFilterMode::Allow if !listed => Err(rejection),
+            AddressFilterMode::Deny if listed => Err(rejection),
+            _ => Ok(()),
+        }
+    }
+}
+
+/// Rejects transactions that call one of the blocked function selectors.
+#[derive(Debug, Clone, Default)]
+pub struct SelectorBlocklist {
+    selectors: HashSet<[u8; SELECTOR_LEN]>,
+}
+
+impl SelectorBlocklist {
+    /// Creates a blocklist of the given function selectors.
+    pub fn new(selectors: impl IntoIterator<Item = [u8; SELECTOR_LEN]>) -> Self {
+        Self { selectors: selectors.into_iter().collect() }
+    }
+}
+
+impl AdmissionPolicy for SelectorBlocklist {
+    fn admit(&self, request: &AdmissionRequest<'_>) -> Result<(), AdmissionRejection> {
+        match request.selector() {
+            Some(selector) if self.selectors.contains(&selector) => {
+                Err(AdmissionRejection::SelectorBlocked(selector))
+            }
+            _ => Ok(()),
+        }
+    }
+}
+
+/// Limits the rate at which each sender can add transactions to the pool with a token bucket.
+///
+/// Every sender starts with a full bucket of `burst` tokens, and every transaction that is
+/// inserted into the pool takes one token. A transaction is only admitted if its sender has a
+/// token left. The bucket is refilled at `per_second` tokens per second, up to `burst` tokens.
+///
+/// Transactions of [`TransactionOrigin::Local`] origin are not limited.
+#[derive(Debug)]
+pub struct SenderRateLimit {
+    burst: f64,
+    per_second: f64,
+    buckets: Mutex<HashMap<Address, TokenBucket>>,
+}
+
+impl SenderRateLimit {
+    /// Creates a new rate limit, that admits `per_second` transactions per second for each sender,
+    /// with bursts of up to `burst` transactions.
+    ///
+    /// # Panics
+    ///
+    /// If `per_second` is not positive.
+    pub fn new(per_second: f64, burst: u32) -> Self {
+        assert!(per_second > 0.0, "sender rate limit must be positive, got {per_second}");
+        Self { burst: burst.max(1) as f64, per_second, buckets: Default::default() }
+    }
+
+    /// Returns the tokens of the sender's bucket, refilled up to now.
+    fn refill<'a>(
+        &self,
+        buckets: &'a mut HashMap<Address, TokenBucket>,
+        sender: Address,
+    ) -> &'a mut f64 {
+        let now = Instant::now();
+
+        // forget senders with full buckets, so that the map doesn't grow unbounded
+        if buckets.len() >= MAX_TRACKED_SENDERS {
+            buckets.retain(|_, bucket| bucket.tokens_at(now, self.per_second) < self.burst);
+        }
+
+        let bucket =
+            buckets.entry(sender).or_insert(TokenBucket { tokens: self.burst, updated_at: now });
+        bucket.tokens = bucket.tokens_at(now, self.per_second).min(self.burst);
+        bucket.updated_at = now;
+        &mut bucket.tokens
+    }
+}
+
+impl AdmissionPolicy for SenderRateLimit {
+    fn admit(&self, request: &AdmissionRequest<'_>) -> Result<(), AdmissionRejection> {
+        if request.origin.is_local() {
+            return Ok(())
+        }
+
+        let mut buckets = self.buckets.lock();
+        if *self.refill(&mut buckets, request.sender) < 1.0 {
+            return Err(AdmissionRejection::RateLimited(request.sender))
+        }
+        Ok(())
+    }
+
+    fn on_inserted(&self, request: &AdmissionRequest<'_>) {
+        if request.origin.is_local() {
+            return
+        }
+
+        // concurrently admitted transactions may take the bucket below zero, which delays the
+        // next admission of the sender accordingly
+        let mut buckets = self.buckets.lock();
+        *self.refill(&mut buckets, request.sender) -= 1.0;
+    }
+}
+
+/// The number of senders tracked by a [`SenderRateLimit`] before senders with full buckets are
+/// forgotten.
+const MAX_TRACKED_SENDERS: usize = 10_000;
+
+/// The state of the token bucket of a single sender.
+#[derive(Debug, Clone, Copy)]
+struct TokenBucket {
+    /// The number of available tokens.
+    tokens: f64,
+    /// The last time the bucket was refilled.
+    updated_at: Instant,
+}
+
+impl TokenBucket {
+    /// Returns the number of tokens at the given time, without the cap of the bucket size.
+    fn tokens_at(&self, now: Instant, per_second: f64) -> f64 {
+        self.tokens + now.duration_since(self.updated_at).as_secs_f64() * per_second
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::test_utils::MockTransaction;
+
+    fn request(transaction: &MockTransaction, origin: TransactionOrigin) -> AdmissionRequest<'_> {
+        AdmissionRequest::new(transaction, origin)
+    }
+
+    #[test]
+    fn address_filters() {
+        let tx = MockTransaction::eip1559();
+        let external = request(&tx, TransactionOrigin::External);
+
+        assert!(AddressFilter::allow_senders([tx.sender()]).admit(&external).is_ok());
+        assert_eq!(
+            AddressFilter::deny_senders([tx.sender()]).admit(&external),
+            Err(AdmissionRejection::SenderNotAllowed(tx.sender()))
+        );
+        assert_eq!(
+            AddressFilter::allow_recipients([Address::random()]).admit(&external),
+            Err(AdmissionRejection::RecipientNotAllowed(tx.to().unwrap()))
+        );
+        assert!(AddressFilter::deny_recipients([Address::random()]).admit(&external).is_ok());
+    }
+
+    #[test]
+    fn selector_blocklist() {
+        let selector = [0xa9, 0x05, 0x9c, 0xbb];
+        let tx = MockTransaction::eip1559().with_input(selector.to_vec().into());
+        let blocklist = SelectorBlocklist::new([selector]);
+
+        assert_eq!(
+            blocklist.admit(&request(&tx, TransactionOrigin::External)),
+            Err(AdmissionRejection::SelectorBlocked(selector))
+        );
+
+        // calldata shorter than a selector is never blocked
+        let tx = tx.with_input(selector[..2].to_vec().into());
+        assert!(blocklist.admit(&request(&tx, TransactionOrigin::External)).is_ok());
+    }
+
+    #[test]
+    fn sender_rate_limit() {
+        let limit = SenderRateLimit::new(1e-6, 2);
+        let tx = MockTransaction::eip1559();
+        let external = request(&tx, TransactionOrigin::External);
+
+        // only inserted transactions take a token
+        assert!(limit.admit(&external).is_ok());
+        assert!(limit.admit(&external).is_ok());
+        limit.on_inserted(&external);
+        assert!(limit.admit(&external).is_ok());
+        limit.on_inserted(&external);
+        assert_eq!(limit.admit(&external), Err(AdmissionRejection::RateLimited(tx.sender())));
+
+        // local transactions are not limited
+        let local = request(&tx, TransactionOrigin::Local);
+        limit.on_inserted(&local);
+        assert!(limit.admit(&local).is_ok());
+
+        // other senders have their own bucket
+        let other = MockTransaction::eip1559();
+        assert!(limit.admit(&request(&other, TransactionOrigin::External)).is_ok());
+    }
+
+    #[test]
+    #[should_panic(expected = "sender rate limit must be positive")]
+    fn sender_rate_limit_must_be_positive() {
+        SenderRateLimit::new(0.0, 2);
+    }
+
+    #[test]
+    fn composed_policies() {
+        let tx = MockTransaction::eip1559();
+        let policies = AdmissionPolicies::new()
+            .with_policy(AddressFilter::deny_senders([Address::random()]))
+            .with_policy(AddressFilter::deny_recipients(tx.to()));
+
+        assert_eq!(
+            policies.admit(&request(&tx, TransactionOrigin::External)),
+            Err(AdmissionRejection::RecipientNotAllowed(tx.to().unwrap()))
+        );
+        assert!(AdmissionPolicies::new().admit(&request(&tx, TransactionOrigin::External)).is_ok());
+    }
+}
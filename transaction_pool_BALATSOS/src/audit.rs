@@ -0,0 +1,193 @@
+//! Audit log of transactions that left the pool.
+//!
+//! Transaction events are only delivered to listeners that subscribed before the transaction was
+//! removed. The [`TransactionAuditLog`] instead keeps a bounded record of every transaction that
+//! was replaced, discarded or evicted, so that the reason can be queried after the fact, e.g. via
+//! [`TransactionPool::transaction_audit`](crate::TransactionPool::transaction_audit).
+//!
+//! Mined transactions are not recorded.
+
+use crate::{
+    snapshot::unix_timestamp_millis, PoolTransaction, TransactionOrigin, ValidPoolTransaction,
+};
+use reth_primitives::{Address, TxHash};
+use schnellru::{ByLength, LruMap};
+use std::fmt;
+
+/// The reason a transaction was removed from the pool.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum TransactionRemovalReason {
+    /// Replaced by the transaction with the given hash, which has the same sender and nonce but
+    /// pays a higher price.
+    Replaced(TxHash),
+    /// Evicted because the sub-pool it resided in exceeded its configured limits.
+    DiscardedForSize,
+    /// Evicted because a transaction of the same sender with a lower nonce was removed, which
+    /// left a nonce gap.
+    NonceGap,
+    /// Invalidated by the state of the block with the given number, e.g. because the block
+    /// consumed the transaction's nonce.
+    InvalidatedByBlock(u64),
+    /// Evicted because the pool exceeded its limits while the transaction's fee cap was below the
+    /// given pending base fee.
+    UnderpricedAfterBaseFeeChange(u64),
+    /// Explicitly removed from the pool.
+    Removed,
+}
+
+impl fmt::Display for TransactionRemovalReason {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            Self::Replaced(hash) => write!(f, "replaced by {hash}"),
+            Self::DiscardedForSize => f.write_str("discarded due to sub-pool limits"),
+            Self::NonceGap => f.write_str("discarded due to nonce gap"),
+            Self::InvalidatedByBlock(number) => write!(f, "invalidated by block {number}"),
+            Self::UnderpricedAfterBaseFeeChange(base_fee) => {
+                write!(f, "underpriced for pending base fee {base_fee}")
+            }
+            Self::Removed => f.write_str("removed"),
+        }
+    }
+}
+
+/// A record of a transaction that left the pool.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct TransactionAuditEntry {
+    /// Hash of the removed transaction.
+    pub hash: TxHash,
+    /// Sender of the removed transaction.
+    pub sender: Address,
+    /// Nonce of the removed transaction.
+    pub nonce: u64,
+    /// Where the transaction originated from.
+    pub origin: TransactionOrigin,
+    /// Why the transaction was removed.
+    pub reason: TransactionRemovalReason,
+    /// The block the pool was tracking when the transaction was removed.
+    pub block_number: u64,
+    /// Unix timestamp in milliseconds at which the transaction was removed.
+    pub timestamp: u64,
+}
+
+impl TransactionAuditEntry {
+    /// Creates a new entry for the given transaction that was removed just now.
+    pub fn new<T: PoolTransaction>(
+        tx: &ValidPoolTransaction<T>,
+        reason: TransactionRemovalReason,
+        block_number: u64,
+    ) -> Self {
+        Self {
+            hash: *tx.hash(),
+            sender: tx.sender(),
+            nonce: tx.nonce(),
+            origin: tx.origin,
+            reason,
+            block_number,
+            timestamp: unix_timestamp_millis(),
+        }
+    }
+}
+
+/// A bounded log of removed transactions, keyed by transaction hash.
+///
+/// Once the log is full, the oldest entries are evicted first. A log with a capacity of `0`
+/// records nothing.
+pub struct TransactionAuditLog {
+    /// The recorded entries, most recently recorded first.
+    entries: LruMap<TxHash, TransactionAuditEntry, ByLength>,
+}
+
+impl TransactionAuditLog {
+    /// Creates a new log that keeps at most `max_entries` entries.
+    pub fn new(max_entries: u32) -> Self {
+        Self { entries: LruMap::new(ByLength::new(max_entries)) }
+    }
+
+    /// Records the given entry.
+    ///
+    /// If the transaction was recorded before, the previous entry is replaced.
+    pub fn record(&mut self, entry: TransactionAuditEntry) {
+        self.entries.insert(entry.hash, entry);
+    }
+
+    /// Removes the entry of the given transaction, e.g. because it re-entered the pool.
+    pub fn remove(&mut self, hash: &TxHash) -> Option<TransactionAuditEntry> {
+        self.entries.remove(hash)
+    }
+
+    /// Returns the entry of the given transaction, if it was recorded.
+    pub fn get(&self, hash: &TxHash) -> Option<&TransactionAuditEntry> {
+        self.entries.peek(hash)
+    }
+
+    /// Returns all recorded entries, most recently recorded first.
+    pub fn entries(&self) -> impl Iterator<Item = &TransactionAuditEntry> + '_ {
+        self.entries.iter().map(|(_, entry)| entry)
+    }
+
+    /// Returns the number of recorded entries.
+    pub fn len(&self) -> usize {
+        self.entries.len()
+    }
+
+    /// Returns `true` if no entries are recorded.
+    pub fn is_empty(&self) -> bool {
+        self.entries.is_empty()
+    }
+}
+
+impl fmt::Debug for TransactionAuditLog {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.debug_struct("TransactionAuditLog").field("len", &self.len()).finish_non_exhaustive()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn entry(hash: TxHash, reason: TransactionRemovalReason) -> TransactionAuditEntry {
+        TransactionAuditEntry {
+            hash,
+            sender: Address::ZERO,
+            nonce: 0,
+            origin: TransactionOrigin::External,
+            reason,
+            block_number: 1,
+            timestamp: 0,
+        }
+    }
+
+    #[test]
+    fn evicts_oldest_entries() {
+        let mut log = TransactionAuditLog::new(2);
+        let hashes =
+            [TxHash::with_last_byte(1), TxHash::with_last_byte(2), TxHash::with_last_byte(3)];
+        for hash in hashes {
+            log.record(entry(hash, TransactionRemovalReason::DiscardedForSize));
+        }
+
+        assert_eq!(log.len(), 2);
+        assert!(log.get(&hashes[0]).is_none());
+        let recorded = log.entries().map(|entry| entry.hash).collect::<Vec<_>>();
+        assert_eq!(recorded, vec![hashes[2], hashes[1]]);
+    }
+
+    #[test]
+    fn replaces_existing_entry() {
+        let mut log = TransactionAuditLog::new(2);
+        let hash = TxHash::with_last_byte(1);
+        log.record(entry(hash, TransactionRemovalReason::NonceGap));
+        log.record(entry(hash, TransactionRemovalReason::InvalidatedByBlock(2)));
+
+        assert_eq!(log.len(), 1);
+        assert_eq!(log.get(&hash).unwrap().reason, TransactionRemovalReason::InvalidatedByBlock(2));
+    }
+
+    #[test]
+    fn disabled_log_records_nothing() {
+        let mut log = TransactionAuditLog::new(0);
+        log.record(entry(TxHash::with_last_byte(1), TransactionRemovalReason::Removed));
+        assert!(log.is_empty());
+    }
+}
@@ -6,6 +6,7 @@ pub use disk::{DiskFileBlobStore, DiskFileBlobStoreConfig, OpenDiskFileBlobStore
 pub use mem::InMemoryBlobStore;
 pub use noop::NoopBlobStore;
 use reth_primitives::{BlobTransactionSidecar, B256};
+pub use segment::{OpenSegmentBlobStore, SegmentBlobStore, SegmentBlobStoreConfig};
 use std::{
     fmt,
     sync::atomic::{AtomicUsize, Ordering},
@@ -15,6 +16,7 @@ pub use tracker::{BlobStoreCanonTracker, BlobStoreUpdates};
 pub mod disk;
 mod mem;
 mod noop;
+pub mod segment;
 mod tracker;
 
 /// A blob store that can be used to store blob data of EIP4844 transactions.
@@ -40,7 +42,7 @@ pub trait BlobStore: fmt::Debug + Send + Sync + 'static {
     /// the number of successfully deleted blobs and the number of failed deletions.
     ///
     /// This is intended to be called in the background to clean up any old or unused data, in case
-    /// the store uses deferred cleanup: [`DiskFileBlobStore`]
+    /// the store uses deferred cleanup: [`DiskFileBlobStore`], [`SegmentBlobStore`]
     fn cleanup(&self) -> BlobStoreCleanupStat;
 
     /// Retrieves the decoded blob data for the given transaction hash.
@@ -0,0 +1,1195 @@
+//! A blob store that packs blobs into rolling segment files.
+//!
+//! Unlike the [`DiskFileBlobStore`](crate::blobstore::DiskFileBlobStore), which writes one file
+//! per blob transaction, the [`SegmentBlobStore`] appends all sidecars to a small number of
+//! segment files:
+//!
+//!  - Every record of a segment consists of the transaction hash, the big endian `u32` length of
+//!    the data and the RLP encoded sidecar.
+//!  - Once the active segment would exceed the configured maximum size, a new segment is started.
+//!  - The location of every blob is tracked in an append-only index journal. Deletions are appended
+//!    to the journal as well, so nothing is removed from the segments right away.
+//!  - [`BlobStore::cleanup`] applies the deferred deletions and compacts sealed segments whose
+//!    share of deleted data reached the configured threshold, by copying their remaining blobs to
+//!    new segments and removing the segment files. The blobs are copied without holding the lock of
+//!    the segments, which is only taken to swap the index to the new locations.
+//!
+//! Blob files of the [`DiskFileBlobStore`](crate::blobstore::DiskFileBlobStore) that are found in
+//! the directory are imported when the store is opened with
+//! [`OpenSegmentBlobStore::Migrate`].
+
+use crate::blobstore::{
+    disk::DEFAULT_MAX_CACHED_BLOBS, BlobStore, BlobStoreCleanupStat, BlobStoreError, BlobStoreSize,
+};
+use alloy_rlp::{Decodable, Encodable};
+use parking_lot::{Mutex, RwLock};
+use reth_primitives::{BlobTransactionSidecar, TxHash, B256};
+use schnellru::{ByLength, LruMap};
+use std::{
+    collections::{BTreeMap, HashMap, HashSet},
+    fmt,
+    fs::{self, File, OpenOptions},
+    io::{self, Read, Seek, SeekFrom, Write},
+    path::{Path, PathBuf},
+    str::FromStr,
+    sync::Arc,
+};
+use tracing::{debug, trace};
+
+/// The default maximum size of a segment file in bytes.
+pub const DEFAULT_MAX_SEGMENT_SIZE: u64 = 64 * 1024 * 1024;
+
+/// The default share of deleted data (in %) at which a sealed segment is compacted.
+pub const DEFAULT_COMPACTION_THRESHOLD: u8 = 50;
+
+/// The file name of the index journal.
+const INDEX_FILE_NAME: &str = "index";
+
+/// The file extension of segment files.
+const SEGMENT_FILE_EXTENSION: &str = "seg";
+
+/// The length of the header of a segment record: the transaction hash and the data length.
+const RECORD_HEADER_LEN: u64 = 32 + 4;
+
+/// The length of an entry of the index journal.
+const INDEX_ENTRY_LEN: usize = 1 + 32 + 4 + 8 + 4;
+
+/// A blob store that packs blobs into rolling segment files.
+///
+/// Like the [`DiskFileBlobStore`](crate::blobstore::DiskFileBlobStore), the type uses deferred
+/// deletion: deleted blobs are only removed once the maintenance task calls
+/// [`BlobStore::cleanup`], which also compacts the segments.
+#[derive(Clone, Debug)]
+pub struct SegmentBlobStore {
+    inner: Arc<SegmentBlobStoreInner>,
+}
+
+impl SegmentBlobStore {
+    /// Opens and initializes a new segment blob store according to the given options.
+    pub fn open(
+        blob_dir: impl Into<PathBuf>,
+        opts: SegmentBlobStoreConfig,
+    ) -> Result<Self, SegmentBlobStoreError> {
+        let blob_dir = blob_dir.into();
+        let SegmentBlobStoreConfig {
+            max_cached_entries,
+            max_segment_size,
+            compaction_threshold,
+            open,
+        } = opts;
+
+        if open == OpenSegmentBlobStore::Clear {
+            match fs::remove_dir_all(&blob_dir) {
+                Ok(_) => {
+                    debug!(target:"txpool::blob", ?blob_dir, "Removed blob store directory");
+                }
+                Err(err) if err.kind() == io::ErrorKind::NotFound => {}
+                Err(err) => return Err(SegmentBlobStoreError::Open(blob_dir, err)),
+            }
+        }
+        debug!(target:"txpool::blob", ?blob_dir, "Creating blob store");
+        fs::create_dir_all(&blob_dir)
+            .map_err(|err| SegmentBlobStoreError::Open(blob_dir.clone(), err))?;
+
+        let segments = Segments::open(&blob_dir, max_segment_size)?;
+        let size_tracker = BlobStoreSize::default();
+        size_tracker.add_size(segments.data_size());
+        size_tracker.update_len(segments.index.len());
+
+        let inner = SegmentBlobStoreInner {
+            blob_dir,
+            compaction_threshold,
+            blob_cache: Mutex::new(LruMap::new(ByLength::new(max_cached_entries))),
+            size_tracker,
+            segments: RwLock::new(segments),
+            txs_to_delete: Default::default(),
+            compaction: Mutex::new(()),
+        };
+
+        if open == OpenSegmentBlobStore::Migrate {
+            inner.migrate_blob_files()?;
+        }
+
+        Ok(Self { inner: Arc::new(inner) })
+    }
+
+    /// Compacts all sealed segments whose share of deleted data reached the configured threshold.
+    ///
+    /// Returns the number of compacted segments. This is also done by [`BlobStore::cleanup`].
+    pub fn compact(&self) -> Result<usize, SegmentBlobStoreError> {
+        self.inner.compact()
+    }
+
+    /// Returns the number of segment files.
+    pub fn segments_len(&self) -> usize {
+        self.inner.segments.read().segments.len()
+    }
+
+    /// Clears the in-memory blob cache (for testing purposes)
+    #[cfg(test)]
+    fn clear_cache(&self) {
+        self.inner.blob_cache.lock().clear()
+    }
+}
+
+impl BlobStore for SegmentBlobStore {
+    fn insert(&self, tx: B256, data: BlobTransactionSidecar) -> Result<(), BlobStoreError> {
+        self.inner.insert_many(vec![(tx, data)])
+    }
+
+    fn insert_all(&self, txs: Vec<(B256, BlobTransactionSidecar)>) -> Result<(), BlobStoreError> {
+        if txs.is_empty() {
+            return Ok(())
+        }
+        self.inner.insert_many(txs)
+    }
+
+    fn delete(&self, tx: B256) -> Result<(), BlobStoreError> {
+        self.inner.txs_to_delete.write().insert(tx);
+        Ok(())
+    }
+
+    fn delete_all(&self, txs: Vec<B256>) -> Result<(), BlobStoreError> {
+        self.inner.txs_to_delete.write().extend(txs);
+        Ok(())
+    }
+
+    fn cleanup(&self) -> BlobStoreCleanupStat {
+        let txs_to_delete = {
+            let mut txs_to_delete = self.inner.txs_to_delete.write();
+            std::mem::take(&mut *txs_to_delete)
+        };
+        let mut stat = BlobStoreCleanupStat::default();
+        let mut subsize = 0;
+        debug!(target:"txpool::blob", num_blobs=%txs_to_delete.len(), "Removing blobs from segments");
+
+        {
+            let mut segments = self.inner.segments.write();
+            let mut cache = self.inner.blob_cache.lock();
+            for tx in txs_to_delete {
+                cache.remove(&tx);
+                match segments.remove(&self.inner.blob_dir, tx) {
+                    Ok(Some(size)) => {
+                        stat.delete_succeed += 1;
+                        subsize += size;
+                    }
+                    Ok(None) => {
+                        stat.delete_failed += 1;
+                        debug!(target:"txpool::blob", ?tx, "Blob to delete not found");
+                    }
+                    Err(err) => {
+                        stat.delete_failed += 1;
+                        debug!(target:"txpool::blob", %err);
+                    }
+                }
+            }
+        }
+        self.inner.size_tracker.sub_size(subsize);
+        self.inner.size_tracker.sub_len(stat.delete_succeed);
+
+        if let Err(err) = self.inner.compact() {
+            debug!(target:"txpool::blob", %err, "Failed to compact segments");
+        }
+
+        stat
+    }
+
+    fn get(&self, tx: B256) -> Result<Option<BlobTransactionSidecar>, BlobStoreError> {
+        self.inner.get_one(tx)
+    }
+
+    fn contains(&self, tx: B256) -> Result<bool, BlobStoreError> {
+        if self.inner.blob_cache.lock().get(&tx).is_some() {
+            return Ok(true)
+        }
+        Ok(self.inner.segments.read().index.contains_key(&tx))
+    }
+
+    fn get_all(
+        &self,
+        txs: Vec<B256>,
+    ) -> Result<Vec<(B256, BlobTransactionSidecar)>, BlobStoreError> {
+        if txs.is_empty() {
+            return Ok(Vec::new())
+        }
+        self.inner.get_all(txs)
+    }
+
+    fn get_exact(&self, txs: Vec<B256>) -> Result<Vec<BlobTransactionSidecar>, BlobStoreError> {
+        let mut res = Vec::with_capacity(txs.len());
+        for tx in txs {
+            let blob = self.inner.get_one(tx)?.ok_or(BlobStoreError::MissingSidecar(tx))?;
+            res.push(blob)
+        }
+        Ok(res)
+    }
+
+    fn data_size_hint(&self) -> Option<usize> {
+        Some(self.inner.size_tracker.data_size())
+    }
+
+    fn blobs_len(&self) -> usize {
+        self.inner.size_tracker.blobs_len()
+    }
+}
+
+/// Inner implementation of the [`SegmentBlobStore`].
+struct SegmentBlobStoreInner {
+    /// Directory of the segment files and the index journal.
+    blob_dir: PathBuf,
+    /// Share of deleted data (in %) at which a sealed segment is compacted.
+    compaction_threshold: u8,
+    /// In-memory LRU cache for blobs.
+    blob_cache: Mutex<LruMap<TxHash, BlobTransactionSidecar, ByLength>>,
+    /// Tracks the size and number of blobs.
+    size_tracker: BlobStoreSize,
+    /// The segment files and the index of all blobs.
+    segments: RwLock<Segments>,
+    /// Transactions marked for deletion.
+    txs_to_delete: RwLock<HashSet<B256>>,
+    /// Held while segments are compacted, so that only one compaction runs at a time.
+    compaction: Mutex<()>,
+}
+
+impl SegmentBlobStoreInner {
+    /// Ensures the blobs are in the blob cache and written to the active segment.
+    fn insert_many(&self, txs: Vec<(B256, BlobTransactionSidecar)>) -> Result<(), BlobStoreError> {
+        let raw = txs
+            .iter()
+            .map(|(tx, data)| {
+                let mut buf = Vec::with_capacity(data.fields_len());
+                data.encode(&mut buf);
+                (*tx, buf)
+            })
+            .collect::<Vec<_>>();
+
+        {
+            let mut cache = self.blob_cache.lock();
+            for (tx, data) in txs {
+                cache.insert(tx, data);
+            }
+        }
+
+        let mut add = 0;
+        let mut num = 0;
+        let res = {
+            let mut segments = self.segments.write();
+            raw.into_iter().try_for_each(|(tx, data)| {
+                if segments.append(&self.blob_dir, tx, &data)? {
+                    add += data.len();
+                    num += 1;
+                } else {
+                    trace!(target:"txpool::blob", ?tx, "Blob already exists");
+                }
+                Ok::<_, SegmentBlobStoreError>(())
+            })
+        };
+        self.size_tracker.add_size(add);
+        self.size_tracker.inc_len(num);
+
+        res.map_err(Into::into)
+    }
+
+    /// Retrieves the blob for the given transaction hash from the blob cache or the segments.
+    fn get_one(&self, tx: B256) -> Result<Option<BlobTransactionSidecar>, BlobStoreError> {
+        if let Some(blob) = self.blob_cache.lock().get(&tx) {
+            return Ok(Some(blob.clone()))
+        }
+        let Some(data) = self.segments.read().read(&self.blob_dir, tx)? else { return Ok(None) };
+        let blob = BlobTransactionSidecar::decode(&mut data.as_slice())?;
+        self.blob_cache.lock().insert(tx, blob.clone());
+        Ok(Some(blob))
+    }
+
+    /// Retrieves all blobs for the given transaction hashes that are in the blob cache or the
+    /// segments.
+    fn get_all(
+        &self,
+        txs: Vec<B256>,
+    ) -> Result<Vec<(B256, BlobTransactionSidecar)>, BlobStoreError> {
+        let mut res = Vec::with_capacity(txs.len());
+        let mut cache_miss = Vec::new();
+        {
+            let mut cache = self.blob_cache.lock();
+            for tx in txs {
+                if let Some(blob) = cache.get(&tx) {
+                    res.push((tx, blob.clone()));
+                } else {
+                    cache_miss.push(tx)
+                }
+            }
+        }
+        if cache_miss.is_empty() {
+            return Ok(res)
+        }
+
+        let mut from_segments = Vec::with_capacity(cache_miss.len());
+        {
+            let segments = self.segments.read();
+            for tx in cache_miss {
+                match segments.read(&self.blob_dir, tx) {
+                    Ok(Some(data)) => match BlobTransactionSidecar::decode(&mut data.as_slice()) {
+                        Ok(blob) => from_segments.push((tx, blob)),
+                        Err(err) => {
+                            debug!(target:"txpool::blob", %err, ?tx, "Failed to decode blob")
+                        }
+                    },
+                    Ok(None) => {}
+                    Err(err) => debug!(target:"txpool::blob", %err, ?tx, "Failed to read blob"),
+                }
+            }
+        }
+
+        let mut cache = self.blob_cache.lock();
+        for (tx, data) in from_segments {
+            cache.insert(tx, data.clone());
+            res.push((tx, data));
+        }
+        Ok(res)
+    }
+
+    /// Compacts all sealed segments whose share of deleted data reached the compaction threshold.
+    ///
+    /// The remaining blobs of the segments are copied to new segment files without holding the
+    /// lock of the segments, so inserts and lookups are only blocked while the index is swapped to
+    /// the new locations.
+    fn compact(&self) -> Result<usize, SegmentBlobStoreError> {
+        // the compacted segments must not be removed by another compaction while they are copied
+        let _compaction = self.compaction.lock();
+
+        let Some(compaction) = self.segments.read().plan_compaction(self.compaction_threshold)
+        else {
+            return Ok(0)
+        };
+
+        let mut writers: Vec<SegmentWriter> = Vec::new();
+        let mut moved = Vec::with_capacity(compaction.blobs.len());
+        for (tx, old) in compaction.blobs {
+            // sealed segments are never written to, so the blob can be read without the lock
+            let data = read_record(&self.blob_dir, tx, old)?;
+            let full = writers.last().map_or(true, |writer| {
+                writer.size > 0 && writer.size + old.record_len() > compaction.max_segment_size
+            });
+            if full {
+                let id = self.segments.write().reserve_segment();
+                writers.push(SegmentWriter::create(&self.blob_dir, id)?);
+            }
+            let writer = writers.last_mut().expect("segment was created");
+            moved.push((tx, old, writer.write(&self.blob_dir, tx, &data)?));
+        }
+
+        // the new segments must be durable before the journal points to them
+        for writer in &writers {
+            writer.sync(&self.blob_dir)?;
+        }
+
+        self.segments.write().finish_compaction(
+            &self.blob_dir,
+            &compaction.segments,
+            writers.iter().map(|writer| (writer.id, writer.size)),
+            moved,
+        )?;
+
+        // no blob is located in the compacted segments anymore, and the journal that says so is
+        // durable
+        for id in &compaction.segments {
+            let path = segment_path(&self.blob_dir, *id);
+            if let Err(err) = fs::remove_file(&path) {
+                debug!(target:"txpool::blob", %err, ?path, "Failed to remove compacted segment");
+            }
+        }
+        debug!(
+            target:"txpool::blob",
+            segments = compaction.segments.len(),
+            new_segments = writers.len(),
+            "Compacted blob segments"
+        );
+
+        Ok(compaction.segments.len())
+    }
+
+    /// Imports the blob files of a [`DiskFileBlobStore`](crate::blobstore::DiskFileBlobStore) in
+    /// the blob directory into the segments, and removes them.
+    fn migrate_blob_files(&self) -> Result<(), SegmentBlobStoreError> {
+        let entries = fs::read_dir(&self.blob_dir)
+            .map_err(|err| SegmentBlobStoreError::Open(self.blob_dir.clone(), err))?;
+
+        let mut segments = self.segments.write();
+        let mut migrated = 0;
+        for entry in entries {
+            let path = entry
+                .map_err(|err| SegmentBlobStoreError::Migrate(self.blob_dir.clone(), err))?
+                .path();
+            // blob files are named after the hex encoded transaction hash
+            let Some(tx) = path
+                .file_name()
+                .and_then(|name| name.to_str())
+                .filter(|name| name.len() == 64)
+                .and_then(|name| B256::from_str(name).ok())
+            else {
+                continue
+            };
+
+            let data =
+                fs::read(&path).map_err(|err| SegmentBlobStoreError::Migrate(path.clone(), err))?;
+            if segments.append(&self.blob_dir, tx, &data)? {
+                self.size_tracker.add_size(data.len());
+                self.size_tracker.inc_len(1);
+            }
+            fs::remove_file(&path).map_err(|err| SegmentBlobStoreError::Migrate(path, err))?;
+            migrated += 1;
+        }
+
+        debug!(target:"txpool::blob", migrated, "Migrated blob files to segments");
+        Ok(())
+    }
+}
+
+impl fmt::Debug for SegmentBlobStoreInner {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.debug_struct("SegmentBlobStoreInner")
+            .field("blob_dir", &self.blob_dir)
+            .field("compaction_threshold", &self.compaction_threshold)
+            .field("cached_blobs", &self.blob_cache.try_lock().map(|lock| lock.len()))
+            .field("segments", &self.segments.try_read().map(|segments| segments.segments.len()))
+            .field("txs_to_delete", &self.txs_to_delete.try_read())
+            .finish()
+    }
+}
+
+/// The location of a blob in a segment.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+struct BlobLocation {
+    /// Identifier of the segment.
+    segment: u32,
+    /// Offset of the blob data in the segment.
+    offset: u64,
+    /// Length of the blob data.
+    len: u32,
+}
+
+impl BlobLocation {
+    /// Returns the length of the entire record of the blob, including its header.
+    const fn record_len(&self) -> u64 {
+        RECORD_HEADER_LEN + self.len as u64
+    }
+}
+
+/// An entry of the index journal.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+enum IndexEntry {
+    /// The blob was written to the given location.
+    Insert(TxHash, BlobLocation),
+    /// The blob was deleted.
+    Delete(TxHash),
+}
+
+impl IndexEntry {
+    /// Encodes the entry into its fixed size journal representation.
+    fn encode(&self) -> [u8; INDEX_ENTRY_LEN] {
+        let mut buf = [0u8; INDEX_ENTRY_LEN];
+        let (kind, tx, location) = match self {
+            Self::Insert(tx, location) => (0, tx, Some(location)),
+            Self::Delete(tx) => (1, tx, None),
+        };
+        buf[0] = kind;
+        buf[1..33].copy_from_slice(tx.as_slice());
+        if let Some(location) = location {
+            buf[33..37].copy_from_slice(&location.segment.to_be_bytes());
+            buf[37..45].copy_from_slice(&location.offset.to_be_bytes());
+            buf[45..49].copy_from_slice(&location.len.to_be_bytes());
+        }
+        buf
+    }
+
+    /// Decodes an entry from its fixed size journal representation.
+    fn decode(buf: &[u8]) -> Option<Self> {
+        let tx = TxHash::from_slice(&buf[1..33]);
+        match buf[0] {
+            0 => Some(Self::Insert(
+                tx,
+                BlobLocation {
+                    segment: u32::from_be_bytes(buf[33..37].try_into().ok()?),
+                    offset: u64::from_be_bytes(buf[37..45].try_into().ok()?),
+                    len: u32::from_be_bytes(buf[45..49].try_into().ok()?),
+                },
+            )),
+            1 => Some(Self::Delete(tx)),
+            _ => None,
+        }
+    }
+}
+
+/// Size and deleted data of a segment file.
+#[derive(Debug, Clone, Copy, Default)]
+struct SegmentStats {
+    /// Size of the segment file in bytes.
+    size: u64,
+    /// Bytes of the segment file that belong to deleted blobs.
+    deleted: u64,
+}
+
+impl SegmentStats {
+    /// Returns `true` if the share of deleted data reached the given threshold (in %).
+    const fn should_compact(&self, threshold: u8) -> bool {
+        self.size == 0 || self.deleted * 100 >= self.size * threshold as u64
+    }
+}
+
+/// The sealed segments selected for compaction.
+#[derive(Debug)]
+struct Compaction {
+    /// Identifiers of the compacted segments.
+    segments: HashSet<u32>,
+    /// The remaining blobs of the compacted segments, in the order they were written.
+    blobs: Vec<(TxHash, BlobLocation)>,
+    /// Maximum size of a new segment file in bytes.
+    max_segment_size: u64,
+}
+
+/// A new segment file that the blobs of compacted segments are copied to.
+struct SegmentWriter {
+    /// Identifier of the segment.
+    id: u32,
+    /// The segment file.
+    file: File,
+    /// Size of the segment file in bytes.
+    size: u64,
+}
+
+impl SegmentWriter {
+    /// Creates the segment file with the given identifier.
+    fn create(dir: &Path, id: u32) -> Result<Self, SegmentBlobStoreError> {
+        Ok(Self { id, file: create_segment(dir, id)?, size: 0 })
+    }
+
+    /// Appends the blob to the segment, and returns its location.
+    fn write(
+        &mut self,
+        dir: &Path,
+        tx: TxHash,
+        data: &[u8],
+    ) -> Result<BlobLocation, SegmentBlobStoreError> {
+        let location = write_record(&mut self.file, dir, self.id, self.size, tx, data)?;
+        self.size += location.record_len();
+        Ok(location)
+    }
+
+    /// Flushes the segment file to disk.
+    fn sync(&self, dir: &Path) -> Result<(), SegmentBlobStoreError> {
+        self.file
+            .sync_all()
+            .map_err(|err| SegmentBlobStoreError::SyncSegment(segment_path(dir, self.id), err))
+    }
+}
+
+/// The segment files of the store and the index of all blobs.
+struct Segments {
+    /// Location of every stored blob.
+    index: HashMap<TxHash, BlobLocation>,
+    /// Stats of every segment file, by segment id.
+    segments: BTreeMap<u32, SegmentStats>,
+    /// Maximum size of a segment file in bytes.
+    max_segment_size: u64,
+    /// Identifier of the segment new blobs are appended to.
+    active: u32,
+    /// Identifier of the next segment file to create.
+    next_segment: u32,
+    /// The active segment file.
+    active_file: File,
+    /// The index journal.
+    journal: File,
+    /// Number of entries in the index journal.
+    journal_len: usize,
+}
+
+impl Segments {
+    /// Opens the segments in the given directory and restores the index from the journal.
+    ///
+    /// New blobs are always appended to a new segment, so that a segment that was only partially
+    /// written before a crash is never extended.
+    fn open(dir: &Path, max_segment_size: u64) -> Result<Self, SegmentBlobStoreError> {
+        let journal_path = dir.join(INDEX_FILE_NAME);
+        let index_err = |err| SegmentBlobStoreError::Index(journal_path.clone(), err);
+
+        // replay the journal, a partially written last entry is ignored
+        let mut index = HashMap::new();
+        match fs::read(&journal_path) {
+            Ok(journal) => {
+                for entry in journal.chunks_exact(INDEX_ENTRY_LEN).filter_map(IndexEntry::decode) {
+                    match entry {
+                        IndexEntry::Insert(tx, location) => index.insert(tx, location),
+                        IndexEntry::Delete(tx) => index.remove(&tx),
+                    };
+                }
+            }
+            Err(err) if err.kind() == io::ErrorKind::NotFound => {}
+            Err(err) => return Err(index_err(err)),
+        }
+
+        let mut segments = BTreeMap::new();
+        let entries =
+            fs::read_dir(dir).map_err(|err| SegmentBlobStoreError::Open(dir.to_path_buf(), err))?;
+        for entry in entries {
+            let path =
+                entry.map_err(|err| SegmentBlobStoreError::Open(dir.to_path_buf(), err))?.path();
+            let Some(id) = segment_id(&path) else { continue };
+            let size = fs::metadata(&path)
+                .map_err(|err| SegmentBlobStoreError::Open(path.clone(), err))?
+                .len();
+            segments.insert(id, SegmentStats { size, deleted: size });
+        }
+
+        // blobs of missing segments can't be read, all other data of a segment is deleted
+        index.retain(|_, location| match segments.get_mut(&location.segment) {
+            Some(stats) if location.offset + location.len as u64 <= stats.size => {
+                stats.deleted = stats.deleted.saturating_sub(location.record_len());
+                true
+            }
+            _ => false,
+        });
+
+        let active = segments.keys().next_back().map_or(0, |id| id + 1);
+        let active_file = create_segment(dir, active)?;
+        segments.insert(active, SegmentStats::default());
+
+        let mut this = Self {
+            index,
+            segments,
+            max_segment_size,
+            active,
+            active_file,
+            next_segment: active + 1,
+            journal: OpenOptions::new()
+                .append(true)
+                .create(true)
+                .open(&journal_path)
+                .map_err(index_err)?,
+            journal_len: 0,
+        };
+        this.rewrite_journal(dir)?;
+        this.remove_unused_segments(dir);
+
+        Ok(this)
+    }
+
+    /// Returns the total size of all stored blobs.
+    fn data_size(&self) -> usize {
+        self.index.values().map(|location| location.len as usize).sum()
+    }
+
+    /// Appends the blob to the active segment.
+    ///
+    /// Returns `false` if the blob is already stored.
+    fn append(
+        &mut self,
+        dir: &Path,
+        tx: TxHash,
+        data: &[u8],
+    ) -> Result<bool, SegmentBlobStoreError> {
+        if self.index.contains_key(&tx) {
+            return Ok(false)
+        }
+
+        let record_len = RECORD_HEADER_LEN + data.len() as u64;
+        let active = self.segments[&self.active];
+        if active.size > 0 && active.size + record_len > self.max_segment_size {
+            self.roll(dir)?;
+        }
+
+        let stats = self.segments.entry(self.active).or_default();
+        let location = write_record(&mut self.active_file, dir, self.active, stats.size, tx, data)?;
+        stats.size += record_len;
+        self.append_journal(dir, IndexEntry::Insert(tx, location))?;
+        self.index.insert(tx, location);
+
+        Ok(true)
+    }
+
+    /// Reads the encoded blob of the given transaction.
+    fn read(&self, dir: &Path, tx: TxHash) -> Result<Option<Vec<u8>>, SegmentBlobStoreError> {
+        let Some(location) = self.index.get(&tx) else { return Ok(None) };
+        read_record(dir, tx, *location).map(Some)
+    }
+
+    /// Marks the blob of the given transaction as deleted.
+    ///
+    /// Returns the size of the deleted blob, or `None` if it isn't stored.
+    fn remove(&mut self, dir: &Path, tx: TxHash) -> Result<Option<usize>, SegmentBlobStoreError> {
+        let Some(location) = self.index.get(&tx).copied() else { return Ok(None) };
+        self.append_journal(dir, IndexEntry::Delete(tx))?;
+        self.index.remove(&tx);
+        if let Some(stats) = self.segments.get_mut(&location.segment) {
+            stats.deleted += location.record_len();
+        }
+        Ok(Some(location.len as usize))
+    }
+
+    /// Selects all sealed segments that reached the compaction threshold, and their remaining
+    /// blobs.
+    ///
+    /// Returns `None` if there is nothing to compact.
+    fn plan_compaction(&self, threshold: u8) -> Option<Compaction> {
+        let segments = self
+            .segments
+            .iter()
+            .filter(|(id, stats)| **id != self.active && stats.should_compact(threshold))
+            .map(|(id, _)| *id)
+            .collect::<HashSet<_>>();
+        if segments.is_empty() {
+            return None
+        }
+
+        let mut blobs = self
+            .index
+            .iter()
+            .filter(|(_, location)| segments.contains(&location.segment))
+            .map(|(tx, location)| (*tx, *location))
+            .collect::<Vec<_>>();
+        // preserve the order of the blobs
+        blobs.sort_unstable_by_key(|(_, location)| (location.segment, location.offset));
+
+        Some(Compaction { segments, blobs, max_segment_size: self.max_segment_size })
+    }
+
+    /// Reserves the identifier of a new segment file.
+    fn reserve_segment(&mut self) -> u32 {
+        let id = self.next_segment;
+        self.next_segment += 1;
+        id
+    }
+
+    /// Points the index to the new locations of the copied blobs, and forgets the compacted
+    /// segments.
+    ///
+    /// Blobs that were deleted while they were copied are counted as deleted data of the new
+    /// segments.
+    fn finish_compaction(
+        &mut self,
+        dir: &Path,
+        compacted: &HashSet<u32>,
+        new_segments: impl IntoIterator<Item = (u32, u64)>,
+        moved: Vec<(TxHash, BlobLocation, BlobLocation)>,
+    ) -> Result<(), SegmentBlobStoreError> {
+        for (id, size) in new_segments {
+            self.segments.insert(id, SegmentStats { size, deleted: 0 });
+        }
+        for (tx, old, new) in moved {
+            if self.index.get(&tx) == Some(&old) {
+                self.append_journal(dir, IndexEntry::Insert(tx, new))?;
+                self.index.insert(tx, new);
+            } else if let Some(stats) = self.segments.get_mut(&new.segment) {
+                stats.deleted += new.record_len();
+            }
+        }
+        self.segments.retain(|id, _| !compacted.contains(id));
+
+        // drop the entries of moved and deleted blobs from the journal
+        if self.journal_len > 2 * self.index.len() {
+            self.rewrite_journal(dir)
+        } else {
+            self.sync_journal(dir)
+        }
+    }
+
+    /// Seals the active segment and starts a new one.
+    fn roll(&mut self, dir: &Path) -> Result<(), SegmentBlobStoreError> {
+        let next = self.reserve_segment();
+        self.active_file = create_segment(dir, next)?;
+        self.active = next;
+        self.segments.insert(next, SegmentStats::default());
+        trace!(target:"txpool::blob", segment = next, "Started new blob segment");
+        Ok(())
+    }
+
+    /// Appends the entry to the index journal.
+    ///
+    /// The entry is not synced, since losing it in a crash only affects that blob. The entries
+    /// that move blobs out of compacted segments are synced before the segments are removed.
+    fn append_journal(
+        &mut self,
+        dir: &Path,
+        entry: IndexEntry,
+    ) -> Result<(), SegmentBlobStoreError> {
+        self.journal
+            .write_all(&entry.encode())
+            .map_err(|err| SegmentBlobStoreError::Index(dir.join(INDEX_FILE_NAME), err))?;
+        self.journal_len += 1;
+        Ok(())
+    }
+
+    /// Replaces the index journal with one that only contains the stored blobs.
+    fn rewrite_journal(&mut self, dir: &Path) -> Result<(), SegmentBlobStoreError> {
+        let path = dir.join(INDEX_FILE_NAME);
+        let tmp_path = path.with_extension("tmp");
+        let index_err = |err| SegmentBlobStoreError::Index(path.clone(), err);
+
+        let mut journal = Vec::with_capacity(self.index.len() * INDEX_ENTRY_LEN);
+        for (tx, location) in &self.index {
+            journal.extend_from_slice(&IndexEntry::Insert(*tx, *location).encode());
+        }
+        let mut file = File::create(&tmp_path).map_err(index_err)?;
+        file.write_all(&journal).and_then(|_| file.sync_all()).map_err(index_err)?;
+        drop(file);
+        fs::rename(&tmp_path, &path).map_err(index_err)?;
+        sync_dir(dir).map_err(index_err)?;
+
+        self.journal = OpenOptions::new().append(true).open(&path).map_err(index_err)?;
+        self.journal_len = self.index.len();
+        Ok(())
+    }
+
+    /// Flushes the index journal to disk, along with the directory so that all created segment
+    /// files are durable as well.
+    fn sync_journal(&self, dir: &Path) -> Result<(), SegmentBlobStoreError> {
+        let index_err = |err| SegmentBlobStoreError::Index(dir.join(INDEX_FILE_NAME), err);
+        self.journal.sync_all().map_err(index_err)?;
+        sync_dir(dir).map_err(index_err)
+    }
+
+    /// Removes all sealed segment files without any stored blobs.
+    fn remove_unused_segments(&mut self, dir: &Path) {
+        let active = self.active;
+        self.segments.retain(|id, stats| {
+            if *id == active || stats.deleted < stats.size {
+                return true
+            }
+            let path = segment_path(dir, *id);
+            if let Err(err) = fs::remove_file(&path) {
+                debug!(target:"txpool::blob", %err, ?path, "Failed to remove unused segment");
+            }
+            false
+        });
+    }
+}
+
+/// Returns the path of the segment file with the given identifier.
+fn segment_path(dir: &Path, id: u32) -> PathBuf {
+    dir.join(format!("{id:010}.{SEGMENT_FILE_EXTENSION}"))
+}
+
+/// Returns the identifier of the segment file at the given path, if it is a segment file.
+fn segment_id(path: &Path) -> Option<u32> {
+    if path.extension()? != SEGMENT_FILE_EXTENSION {
+        return None
+    }
+    path.file_stem()?.to_str()?.parse().ok()
+}
+
+/// Appends a record with the blob to the segment file of the given size, and returns the location
+/// of the blob.
+fn write_record(
+    file: &mut File,
+    dir: &Path,
+    segment: u32,
+    size: u64,
+    tx: TxHash,
+    data: &[u8],
+) -> Result<BlobLocation, SegmentBlobStoreError> {
+    let mut record = Vec::with_capacity(RECORD_HEADER_LEN as usize + data.len());
+    record.extend_from_slice(tx.as_slice());
+    record.extend_from_slice(&(data.len() as u32).to_be_bytes());
+    record.extend_from_slice(data);
+    file.write_all(&record)
+        .map_err(|err| SegmentBlobStoreError::WriteSegment(tx, segment_path(dir, segment), err))?;
+
+    Ok(BlobLocation { segment, offset: size + RECORD_HEADER_LEN, len: data.len() as u32 })
+}
+
+/// Reads the blob at the given location.
+fn read_record(
+    dir: &Path,
+    tx: TxHash,
+    location: BlobLocation,
+) -> Result<Vec<u8>, SegmentBlobStoreError> {
+    let path = segment_path(dir, location.segment);
+    let read = || {
+        let mut file = File::open(&path)?;
+        file.seek(SeekFrom::Start(location.offset))?;
+        let mut data = vec![0; location.len as usize];
+        file.read_exact(&mut data)?;
+        Ok::<_, io::Error>(data)
+    };
+    read().map_err(|err| SegmentBlobStoreError::ReadSegment(tx, path, err))
+}
+
+/// Flushes the directory to disk, persisting the creation, rename and removal of its files.
+fn sync_dir(dir: &Path) -> io::Result<()> {
+    #[cfg(unix)]
+    File::open(dir)?.sync_all()?;
+    #[cfg(not(unix))]
+    let _ = dir;
+    Ok(())
+}
+
+/// Creates a new, empty segment file.
+fn create_segment(dir: &Path, id: u32) -> Result<File, SegmentBlobStoreError> {
+    let path = segment_path(dir, id);
+    File::create(&path).map_err(|err| SegmentBlobStoreError::Open(path, err))
+}
+
+/// Errors that can occur when interacting with a segment blob store.
+#[derive(Debug, thiserror::Error)]
+pub enum SegmentBlobStoreError {
+    /// Failure while opening the blob store directory or a segment file.
+    #[error("failed to open blobstore at {0}: {1}")]
+    Open(PathBuf, io::Error),
+    /// Failure while reading a blob from a segment.
+    #[error("[{0}] failed to read blob from segment {1}: {2}")]
+    ReadSegment(TxHash, PathBuf, io::Error),
+    /// Failure while writing a blob to a segment.
+    #[error("[{0}] failed to write blob to segment {1}: {2}")]
+    WriteSegment(TxHash, PathBuf, io::Error),
+    /// Failure while flushing a segment to disk.
+    #[error("failed to sync segment {0}: {1}")]
+    SyncSegment(PathBuf, io::Error),
+    /// Failure while reading or writing the index journal.
+    #[error("failed to access blob index at {0}: {1}")]
+    Index(PathBuf, io::Error),
+    /// Failure while migrating a blob file.
+    #[error("failed to migrate blob file at {0}: {1}")]
+    Migrate(PathBuf, io::Error),
+}
+
+impl From<SegmentBlobStoreError> for BlobStoreError {
+    fn from(value: SegmentBlobStoreError) -> Self {
+        Self::Other(Box::new(value))
+    }
+}
+
+/// Configuration for a segment blob store.
+#[derive(Debug, Clone)]
+pub struct SegmentBlobStoreConfig {
+    /// The maximum number of blobs to keep in the in memory blob cache.
+    pub max_cached_entries: u32,
+    /// The maximum size of a segment file in bytes.
+    pub max_segment_size: u64,
+    /// The share of deleted data (in %) at which a sealed segment is compacted.
+    pub compaction_threshold: u8,
+    /// How to open the blob store.
+    pub open: OpenSegmentBlobStore,
+}
+
+impl Default for SegmentBlobStoreConfig {
+    fn default() -> Self {
+        Self {
+            max_cached_entries: DEFAULT_MAX_CACHED_BLOBS,
+            max_segment_size: DEFAULT_MAX_SEGMENT_SIZE,
+            compaction_threshold: DEFAULT_COMPACTION_THRESHOLD,
+            open: Default::default(),
+        }
+    }
+}
+
+impl SegmentBlobStoreConfig {
+    /// Set maximum number of blobs to keep in the in memory blob cache.
+    pub const fn with_max_cached_entries(mut self, max_cached_entries: u32) -> Self {
+        self.max_cached_entries = max_cached_entries;
+        self
+    }
+
+    /// Set the maximum size of a segment file in bytes.
+    pub const fn with_max_segment_size(mut self, max_segment_size: u64) -> Self {
+        self.max_segment_size = max_segment_size;
+        self
+    }
+
+    /// Set the share of deleted data (in %) at which a sealed segment is compacted.
+    pub const fn with_compaction_threshold(mut self, compaction_threshold: u8) -> Self {
+        self.compaction_threshold = compaction_threshold;
+        self
+    }
+
+    /// Set how to open the blob store.
+    pub const fn with_open(mut self, open: OpenSegmentBlobStore) -> Self {
+        self.open = open;
+        self
+    }
+}
+
+/// How to open a segment blob store.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default)]
+pub enum OpenSegmentBlobStore {
+    /// Clear everything in the blob store.
+    #[default]
+    Clear,
+    /// Keep the existing segments and restore the index.
+    Reopen,
+    /// Keep the existing segments, and import the blob files of a
+    /// [`DiskFileBlobStore`](crate::blobstore::DiskFileBlobStore) in the same directory.
+    Migrate,
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn tmp_store(config: SegmentBlobStoreConfig) -> (SegmentBlobStore, tempfile::TempDir) {
+        let dir = tempfile::tempdir().unwrap();
+        let store = SegmentBlobStore::open(dir.path(), config).unwrap();
+        (store, dir)
+    }
+
+    fn rng_blobs(num: usize) -> Vec<(TxHash, BlobTransactionSidecar)> {
+        let mut rng = rand::thread_rng();
+        (0..num)
+            .map(|_| {
+                let tx = TxHash::random_with(&mut rng);
+                let blob =
+                    BlobTransactionSidecar { blobs: vec![], commitments: vec![], proofs: vec![] };
+                (tx, blob)
+            })
+            .collect()
+    }
+
+    #[test]
+    fn segment_insert_all_get_all() {
+        let (store, _dir) = tmp_store(Default::default());
+
+        let blobs = rng_blobs(10);
+        let all_hashes = blobs.iter().map(|(tx, _)| *tx).collect::<Vec<_>>();
+        store.insert_all(blobs.clone()).unwrap();
+        store.clear_cache();
+
+        for (tx, blob) in &blobs {
+            assert_eq!(store.get(*tx).unwrap().unwrap(), *blob);
+        }
+        store.clear_cache();
+        let all = store.get_all(all_hashes.clone()).unwrap();
+        assert_eq!(all.len(), blobs.len());
+        for (tx, blob) in all {
+            assert!(blobs.contains(&(tx, blob)), "missing blob {tx:?}");
+        }
+        assert_eq!(store.blobs_len(), 10);
+
+        store.delete_all(all_hashes.clone()).unwrap();
+        // deletion is deferred
+        assert!(store.contains(all_hashes[0]).unwrap());
+        let stat = store.cleanup();
+        assert_eq!(stat.delete_succeed, 10);
+        assert_eq!(stat.delete_failed, 0);
+
+        assert!(!store.contains(all_hashes[0]).unwrap());
+        assert!(store.get_all(all_hashes.clone()).unwrap().is_empty());
+        assert!(store.get_exact(all_hashes).is_err());
+        assert_eq!(store.data_size_hint(), Some(0));
+        assert_eq!(store.blobs_len(), 0);
+    }
+
+    #[test]
+    fn segment_rolls_and_compacts() {
+        let config = SegmentBlobStoreConfig::default()
+            .with_max_segment_size(256)
+            .with_compaction_threshold(25);
+        let (store, _dir) = tmp_store(config);
+
+        let blobs = rng_blobs(20);
+        store.insert_all(blobs.clone()).unwrap();
+        let segments = store.segments_len();
+        assert!(segments > 2);
+
+        // delete every other blob, then unknown blobs are reported as failed
+        let deleted = blobs.iter().step_by(2).map(|(tx, _)| *tx).collect::<Vec<_>>();
+        store.delete_all(deleted.clone()).unwrap();
+        store.delete(TxHash::random()).unwrap();
+        let stat = store.cleanup();
+        assert_eq!(stat.delete_succeed, 10);
+        assert_eq!(stat.delete_failed, 1);
+
+        // all sealed segments were compacted into new ones
+        assert!(store.segments_len() < segments);
+        store.clear_cache();
+        for (i, (tx, blob)) in blobs.iter().enumerate() {
+            let stored = store.get(*tx).unwrap();
+            if i % 2 == 0 {
+                assert!(stored.is_none());
+            } else {
+                assert_eq!(stored.unwrap(), *blob);
+            }
+        }
+    }
+
+    #[test]
+    fn segment_compacts_into_new_segments() {
+        let dir = tempfile::tempdir().unwrap();
+        let config = SegmentBlobStoreConfig::default()
+            .with_max_segment_size(256)
+            .with_compaction_threshold(25);
+        let store = SegmentBlobStore::open(dir.path(), config.clone()).unwrap();
+
+        let blobs = rng_blobs(20);
+        store.insert_all(blobs[..10].to_vec()).unwrap();
+        store.delete_all(blobs.iter().take(10).step_by(2).map(|(tx, _)| *tx).collect()).unwrap();
+        store.cleanup();
+        assert_eq!(store.compact().unwrap(), 0);
+
+        // blobs inserted after the compaction go to segments that don't collide with the new ones
+        store.insert_all(blobs[10..].to_vec()).unwrap();
+        drop(store);
+
+        let store =
+            SegmentBlobStore::open(dir.path(), config.with_open(OpenSegmentBlobStore::Reopen))
+                .unwrap();
+        assert_eq!(store.blobs_len(), 15);
+        for (i, (tx, blob)) in blobs.iter().enumerate() {
+            let stored = store.get(*tx).unwrap();
+            if i < 10 && i % 2 == 0 {
+                assert!(stored.is_none());
+            } else {
+                assert_eq!(stored.unwrap(), *blob);
+            }
+        }
+    }
+
+    #[test]
+    fn segment_reopen() {
+        let dir = tempfile::tempdir().unwrap();
+        let config = SegmentBlobStoreConfig::default().with_max_segment_size(256);
+        let blobs = rng_blobs(10);
+        {
+            let store = SegmentBlobStore::open(dir.path(), config.clone()).unwrap();
+            store.insert_all(blobs.clone()).unwrap();
+            store.delete(blobs[0].0).unwrap();
+            store.cleanup();
+        }
+
+        let store = SegmentBlobStore::open(
+            dir.path(),
+            config.clone().with_open(OpenSegmentBlobStore::Reopen),
+        )
+        .unwrap();
+        assert_eq!(store.blobs_len(), 9);
+        assert!(store.get(blobs[0].0).unwrap().is_none());
+        for (tx, blob) in &blobs[1..] {
+            assert_eq!(store.get(*tx).unwrap().unwrap(), *blob);
+        }
+        drop(store);
+
+        // clearing removes all blobs
+        let store = SegmentBlobStore::open(dir.path(), config).unwrap();
+        assert_eq!(store.blobs_len(), 0);
+        assert!(store.get(blobs[1].0).unwrap().is_none());
+    }
+
+    #[test]
+    fn segment_migrate_blob_files() {
+        let dir = tempfile::tempdir().unwrap();
+        let blobs = rng_blobs(5);
+        for (tx, blob) in &blobs {
+            let mut buf = Vec::new();
+            blob.encode(&mut buf);
+            fs::write(dir.path().join(format!("{tx:x}")), buf).unwrap();
+        }
+        // unrelated files are left alone
+        fs::write(dir.path().join("other"), b"other").unwrap();
+
+        let config = SegmentBlobStoreConfig::default().with_open(OpenSegmentBlobStore::Migrate);
+        let store = SegmentBlobStore::open(dir.path(), config).unwrap();
+        assert_eq!(store.blobs_len(), 5);
+        for (tx, blob) in &blobs {
+            assert!(!dir.path().join(format!("{tx:x}")).exists());
+            assert_eq!(store.get(*tx).unwrap().unwrap(), *blob);
+        }
+        assert!(dir.path().join("other").exists());
+    }
+}
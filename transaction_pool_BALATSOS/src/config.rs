@@ -1,4 +1,4 @@
-use crate::{PoolSize, TransactionOrigin};
+use crate::{admission::AdmissionPolicies, PoolSize, TransactionOrigin};
 use reth_primitives::{Address, EIP4844_TX_TYPE_ID};
 use std::collections::HashSet;
 /// Guarantees max transactions for one sender, compatible with geth/erigon
@@ -18,6 +18,9 @@ pub const DEFAULT_PRICE_BUMP: u128 = 10;
 /// This enforces that a blob transaction requires a 100% price bump to be replaced
 pub const REPLACE_BLOB_PRICE_BUMP: u128 = 100;
 
+/// The default maximum number of removed transactions kept in the pool's audit log.
+pub const TXPOOL_AUDIT_LOG_MAX_ENTRIES_DEFAULT: u32 = 10_000;
+
 /// Configuration options for the Transaction pool.
 #[derive(Debug, Clone)]
 pub struct PoolConfig {
@@ -36,6 +39,10 @@ pub struct PoolConfig {
     /// How to handle locally received transactions:
     /// [`TransactionOrigin::Local`](crate::TransactionOrigin).
     pub local_transactions_config: LocalTransactionConfig,
+    /// Policies that decide whether a validated transaction may enter the pool.
+    pub admission_policies: AdmissionPolicies,
+    /// Max number of removed transactions kept in the audit log, `0` disables the log.
+    pub audit_log_max_entries: u32,
 }
 
 impl PoolConfig {
@@ -59,6 +66,8 @@ impl Default for PoolConfig {
             max_account_slots: TXPOOL_MAX_ACCOUNT_SLOTS_PER_SENDER,
             price_bumps: Default::default(),
             local_transactions_config: Default::default(),
+            admission_policies: Default::default(),
+            audit_log_max_entries: TXPOOL_AUDIT_LOG_MAX_ENTRIES_DEFAULT,
         }
     }
 }
@@ -1,5 +1,6 @@
 //! Transaction pool errors
 
+use crate::admission::AdmissionRejection;
 use reth_primitives::{Address, BlobTransactionValidationError, InvalidTransactionError, TxHash};
 
 /// Transaction pool result type.
@@ -59,6 +60,10 @@ pub enum PoolErrorKind {
     /// Thrown if the mutual exclusivity constraint (blob vs normal transaction) is violated.
     #[error("transaction type {1} conflicts with existing transaction for {0}")]
     ExistingConflictingTransactionType(Address, u8),
+    /// Thrown if the transaction was rejected by the pool's
+    /// [`AdmissionPolicy`](crate::admission::AdmissionPolicy).
+    #[error("transaction rejected by admission policy: {0}")]
+    AdmissionRejected(#[from] AdmissionRejection),
     /// Any other error that occurred while inserting/validating a transaction. e.g. IO database
     /// error
     #[error(transparent)]
@@ -133,6 +138,10 @@ impl PoolError {
                 // exclusivity (blob vs normal tx) for all senders
                 false
             }
+            PoolErrorKind::AdmissionRejected(_) => {
+                // rejected by a local policy of this node, the transaction itself is valid
+                false
+            }
         }
     }
 }
@@ -160,14 +160,19 @@ use tokio::sync::mpsc::Receiver;
 use tracing::{instrument, trace};
 
 pub use crate::{
+    audit::{TransactionAuditEntry, TransactionRemovalReason},
     blobstore::{BlobStore, BlobStoreError},
     config::{
         LocalTransactionConfig, PoolConfig, PriceBumpConfig, SubPoolLimit, DEFAULT_PRICE_BUMP,
-        REPLACE_BLOB_PRICE_BUMP, TXPOOL_MAX_ACCOUNT_SLOTS_PER_SENDER,
-        TXPOOL_SUBPOOL_MAX_SIZE_MB_DEFAULT, TXPOOL_SUBPOOL_MAX_TXS_DEFAULT,
+        REPLACE_BLOB_PRICE_BUMP, TXPOOL_AUDIT_LOG_MAX_ENTRIES_DEFAULT,
+        TXPOOL_MAX_ACCOUNT_SLOTS_PER_SENDER, TXPOOL_SUBPOOL_MAX_SIZE_MB_DEFAULT,
+        TXPOOL_SUBPOOL_MAX_TXS_DEFAULT,
     },
     error::PoolResult,
-    ordering::{CoinbaseTipOrdering, Priority, TransactionOrdering},
+    ordering::{
+        CoinbaseTipOrdering, ConfiguredOrdering, FifoOrdering, LocalBoostOrdering, Priority,
+        TipThenArrivalOrdering, TransactionOrdering, TransactionOrderingKind, DEFAULT_LOCAL_BOOST,
+    },
     pool::{
         blob_tx_priority, fee_delta, state::SubPool, AllTransactionsEvents, FullTransactionEvent,
         TransactionEvent, TransactionEvents,
@@ -179,11 +184,14 @@ pub use crate::{
     },
 };
 
+pub mod admission;
+pub mod audit;
 pub mod error;
 pub mod maintain;
 pub mod metrics;
 pub mod noop;
 pub mod pool;
+pub mod snapshot;
 pub mod validate;
 
 pub mod blobstore;
@@ -451,6 +459,21 @@ where
         self.pool.all_transactions()
     }
 
+    fn subpool_transactions(
+        &self,
+        subpool: SubPool,
+    ) -> Vec<Arc<ValidPoolTransaction<Self::Transaction>>> {
+        self.pool.subpool_transactions(subpool)
+    }
+
+    fn transaction_audit(&self, tx_hash: &TxHash) -> Option<TransactionAuditEntry> {
+        self.pool.transaction_audit(tx_hash)
+    }
+
+    fn transaction_audit_log(&self) -> Vec<TransactionAuditEntry> {
+        self.pool.transaction_audit_log()
+    }
+
     fn remove_transactions(
         &self,
         hashes: Vec<TxHash>,
@@ -4,8 +4,9 @@ use crate::{
     blobstore::{BlobStoreCanonTracker, BlobStoreUpdates},
     error::PoolError,
     metrics::MaintainPoolMetrics,
+    snapshot::PoolSnapshot,
     traits::{CanonicalStateUpdate, ChangedAccount, TransactionPool, TransactionPoolExt},
-    BlockInfo,
+    BlockInfo, TransactionOrigin,
 };
 use futures_util::{
     future::{BoxFuture, Fuse, FusedFuture},
@@ -27,7 +28,9 @@ use std::{
     borrow::Borrow,
     collections::HashSet,
     hash::{Hash, Hasher},
+    io::Write,
     path::{Path, PathBuf},
+    time::Duration,
 };
 use tokio::sync::oneshot;
 use tracing::{debug, error, info, trace, warn};
@@ -66,6 +69,40 @@ impl LocalTransactionBackupConfig {
     }
 }
 
+/// Settings for the transaction pool snapshot task
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct TransactionPoolSnapshotConfig {
+    /// Path to the snapshot file
+    pub snapshot_path: PathBuf,
+    /// Interval at which snapshots are written while the node is running.
+    ///
+    /// If `None`, a snapshot is only written on shutdown.
+    pub interval: Option<Duration>,
+    /// Path to a local transactions backup file written by the
+    /// [`backup_local_transactions_task`], which is migrated into the pool on startup.
+    pub legacy_transactions_path: Option<PathBuf>,
+}
+
+impl TransactionPoolSnapshotConfig {
+    /// Returns a config that writes a snapshot to the given path on shutdown
+    pub const fn new(snapshot_path: PathBuf) -> Self {
+        Self { snapshot_path, interval: None, legacy_transactions_path: None }
+    }
+
+    /// Also reinserts the local transactions of the given backup file on startup, so that they
+    /// are carried over into the snapshot
+    pub fn with_legacy_transactions_path(mut self, transactions_path: PathBuf) -> Self {
+        self.legacy_transactions_path = Some(transactions_path);
+        self
+    }
+
+    /// Also writes a snapshot periodically at the given interval
+    pub const fn with_interval(mut self, interval: Duration) -> Self {
+        self.interval = Some(interval);
+        self
+    }
+}
+
 /// Returns a spawnable future for maintaining the state of the transaction pool.
 pub fn maintain_transaction_pool_future<Client, P, St, Tasks>(
     client: Client,
@@ -647,6 +684,12 @@ pub enum TransactionsBackupError {
     /// Error adding transactions to the transaction pool
     #[error("failed to insert transactions to the transactions pool. Encountered pool error: {0}")]
     Pool(#[from] PoolError),
+    /// The file is not a transaction pool snapshot
+    #[error("failed to apply transaction pool snapshot. The file is not a snapshot")]
+    InvalidSnapshot,
+    /// The snapshot was written in an unknown version of the file format
+    #[error("failed to apply transaction pool snapshot. Unsupported snapshot version: {0}")]
+    UnsupportedSnapshotVersion(u8),
 }
 
 /// Task which manages saving local transactions to the persistent file in case of shutdown.
@@ -675,6 +718,147 @@ pub async fn backup_local_transactions_task<P>(
     drop(graceful_guard)
 }
 
+/// Loads a snapshot of the entire pool from a file, and reinserts its transactions into the pool
+/// on node boot up.
+///
+/// The transactions are revalidated by the pool's validator, and reinserted with their original
+/// origin and in the order they were originally added to the pool.
+/// The file is removed after the transactions have been processed.
+async fn load_and_reinsert_snapshot<P>(
+    pool: P,
+    file_path: &Path,
+) -> Result<(), TransactionsBackupError>
+where
+    P: TransactionPool,
+{
+    if !file_path.exists() {
+        return Ok(())
+    }
+
+    debug!(target: "txpool", snapshot_file =?file_path, "Check persistent storage for transaction pool snapshot");
+    let data = reth_fs_util::read(file_path)?;
+
+    if data.is_empty() {
+        return Ok(())
+    }
+
+    let mut snapshot = PoolSnapshot::decode(&data)?;
+    let num_txs = snapshot.len();
+    snapshot.transactions.sort_by_key(|entry| entry.timestamp);
+
+    let mut num_reinserted = 0;
+    for origin in
+        [TransactionOrigin::Local, TransactionOrigin::Private, TransactionOrigin::External]
+    {
+        let pool_transactions = snapshot
+            .transactions
+            .iter()
+            .filter(|entry| entry.origin == origin)
+            .filter_map(|entry| entry.transaction.clone().try_into_ecrecovered().ok())
+            .map(<P as TransactionPool>::Transaction::from_recovered_pooled_transaction)
+            .collect::<Vec<_>>();
+        if pool_transactions.is_empty() {
+            continue
+        }
+
+        let outcome = pool.add_transactions(origin, pool_transactions).await;
+        num_reinserted += outcome.iter().filter(|res| res.is_ok()).count();
+    }
+
+    info!(target: "txpool", snapshot_file =?file_path, %num_txs, %num_reinserted, "Reinserted transactions from transaction pool snapshot");
+    reth_fs_util::remove_file(file_path)?;
+    Ok(())
+}
+
+fn save_pool_snapshot<P>(pool: &P, file_path: &Path)
+where
+    P: TransactionPool,
+{
+    let snapshot = PoolSnapshot::from_pool(pool);
+    let num_txs = snapshot.len();
+    debug!(target: "txpool", snapshot_file =?file_path, %num_txs, "Saving transaction pool snapshot");
+
+    let result = file_path
+        .parent()
+        .map(std::fs::create_dir_all)
+        .transpose()
+        .and_then(|_| write_snapshot_file(file_path, &snapshot.encode()));
+
+    match result {
+        Ok(_) => {
+            info!(target: "txpool", snapshot_file=?file_path, %num_txs, "Wrote transaction pool snapshot to file");
+        }
+        Err(err) => {
+            warn!(target: "txpool", %err, snapshot_file=?file_path, "Failed to write transaction pool snapshot to file");
+        }
+    }
+}
+
+/// Writes the snapshot to a temporary file first and renames it to the given path, so that a crash
+/// never leaves a partial snapshot behind.
+///
+/// The temporary file is synced before the rename, and the parent directory after it.
+fn write_snapshot_file(file_path: &Path, data: &[u8]) -> std::io::Result<()> {
+    let tmp_path = file_path.with_extension("tmp");
+    let mut file = std::fs::File::create(&tmp_path)?;
+    file.write_all(data)?;
+    file.sync_all()?;
+    drop(file);
+
+    std::fs::rename(&tmp_path, file_path)?;
+    #[cfg(unix)]
+    std::fs::File::open(file_path.parent().unwrap_or_else(|| Path::new(".")))?.sync_all()?;
+    Ok(())
+}
+
+/// Task which manages saving snapshots of the entire pool to a persistent file, periodically and
+/// in case of shutdown.
+/// Reloads the transactions from the snapshot on the boot up and reinserts them into the pool,
+/// along with the transactions of the legacy local transactions backup file, if any.
+pub async fn pool_snapshot_task<P>(
+    shutdown: reth_tasks::shutdown::GracefulShutdown,
+    pool: P,
+    config: TransactionPoolSnapshotConfig,
+) where
+    P: TransactionPool + Clone,
+{
+    let TransactionPoolSnapshotConfig { snapshot_path, interval, legacy_transactions_path } =
+        config;
+
+    if let Err(err) = load_and_reinsert_snapshot(pool.clone(), &snapshot_path).await {
+        error!(target: "txpool", "{}", err)
+    }
+    if let Some(transactions_path) = legacy_transactions_path {
+        // the backup file is removed once its transactions are in the pool, and they are part of
+        // the snapshot from now on
+        if let Err(err) = load_and_reinsert_transactions(pool.clone(), &transactions_path).await {
+            error!(target: "txpool", "{}", err)
+        }
+    }
+
+    let graceful_guard = match interval {
+        Some(period) => {
+            let mut interval =
+                tokio::time::interval_at(tokio::time::Instant::now() + period, period);
+            interval.set_missed_tick_behavior(tokio::time::MissedTickBehavior::Delay);
+
+            let mut shutdown = std::pin::pin!(shutdown);
+            loop {
+                tokio::select! {
+                    guard = &mut shutdown => break guard,
+                    _ = interval.tick() => save_pool_snapshot(&pool, &snapshot_path),
+                }
+            }
+        }
+        None => shutdown.await,
+    };
+
+    // write transactions to disk
+    save_pool_snapshot(&pool, &snapshot_path);
+
+    drop(graceful_guard)
+}
+
 #[cfg(test)]
 mod tests {
     use super::*;
@@ -748,4 +932,90 @@ mod tests {
 
         temp_dir.close().unwrap();
     }
+
+    #[tokio::test(flavor = "multi_thread")]
+    async fn test_pool_snapshot() {
+        let temp_dir = tempfile::tempdir().unwrap();
+        let snapshot_path = temp_dir.path().join("test_pool_snapshot");
+        let tx_bytes = hex!("02f87201830655c2808505ef61f08482565f94388c818ca8b9251b393131c08a736a67ccb192978801049e39c4b5b1f580c001a01764ace353514e8abdfb92446de356b260e3c1225b73fc4c8876a6258d12a129a04f02294aa61ca7676061cd99f29275491218b4754b46a0248e5e42bc5091f507");
+        let tx = PooledTransactionsElement::decode_enveloped(&mut &tx_bytes[..]).unwrap();
+        let provider = MockEthProvider::default();
+        let transaction = EthPooledTransaction::from_recovered_pooled_transaction(
+            tx.try_into_ecrecovered().unwrap(),
+        );
+        let sender = hex!("1f9090aaE28b8a3dCeaDf281B0F12828e676c326").into();
+        provider.add_account(sender, ExtendedAccount::new(42, U256::MAX));
+        let blob_store = InMemoryBlobStore::default();
+        let validator = EthTransactionValidatorBuilder::new(MAINNET.clone())
+            .build(provider, blob_store.clone());
+
+        let txpool = Pool::new(
+            validator.clone(),
+            CoinbaseTipOrdering::default(),
+            blob_store.clone(),
+            Default::default(),
+        );
+        txpool.add_transaction(TransactionOrigin::External, transaction.clone()).await.unwrap();
+
+        save_pool_snapshot(&txpool, &snapshot_path);
+
+        let snapshot = PoolSnapshot::decode(&fs::read(&snapshot_path).unwrap()).unwrap();
+        assert_eq!(snapshot.len(), 1);
+        assert_eq!(snapshot.transactions[0].origin, TransactionOrigin::External);
+
+        // external transactions survive a restart
+        let restored =
+            Pool::new(validator, CoinbaseTipOrdering::default(), blob_store, Default::default());
+        load_and_reinsert_snapshot(restored.clone(), &snapshot_path).await.unwrap();
+
+        let mut txns = restored.get_external_transactions();
+        let tx_on_restart = txns.pop().expect("there should be 1 transaction");
+        assert_eq!(*transaction.hash(), *tx_on_restart.hash());
+        assert!(!snapshot_path.exists());
+
+        temp_dir.close().unwrap();
+    }
+    #[tokio::test(flavor = "multi_thread")]
+    async fn test_pool_snapshot_migrates_local_txs_backup() {
+        let temp_dir = tempfile::tempdir().unwrap();
+        let snapshot_path = temp_dir.path().join("test_pool_snapshot");
+        let transactions_path = temp_dir.path().join(FILENAME).with_extension(EXTENSION);
+        let tx_bytes = hex!("02f87201830655c2808505ef61f08482565f94388c818ca8b9251b393131c08a736a67ccb192978801049e39c4b5b1f580c001a01764ace353514e8abdfb92446de356b260e3c1225b73fc4c8876a6258d12a129a04f02294aa61ca7676061cd99f29275491218b4754b46a0248e5e42bc5091f507");
+        let tx = PooledTransactionsElement::decode_enveloped(&mut &tx_bytes[..]).unwrap();
+        let provider = MockEthProvider::default();
+        let transaction = EthPooledTransaction::from_recovered_pooled_transaction(
+            tx.try_into_ecrecovered().unwrap(),
+        );
+        let sender = hex!("1f9090aaE28b8a3dCeaDf281B0F12828e676c326").into();
+        provider.add_account(sender, ExtendedAccount::new(42, U256::MAX));
+        let blob_store = InMemoryBlobStore::default();
+        let validator = EthTransactionValidatorBuilder::new(MAINNET.clone())
+            .build(provider, blob_store.clone());
+        let txpool =
+            Pool::new(validator, CoinbaseTipOrdering::default(), blob_store, Default::default());
+
+        // a backup written before snapshots were enabled
+        let mut buf = Vec::new();
+        alloy_rlp::encode_list(&[transaction.to_recovered_transaction().into_signed()], &mut buf);
+        fs::write(&transactions_path, buf).unwrap();
+
+        let handle = tokio::runtime::Handle::current();
+        let manager = TaskManager::new(handle);
+        let config = TransactionPoolSnapshotConfig::new(snapshot_path.clone())
+            .with_legacy_transactions_path(transactions_path.clone());
+        manager.executor().spawn_critical_with_graceful_shutdown_signal("test task", |shutdown| {
+            pool_snapshot_task(shutdown, txpool.clone(), config)
+        });
+
+        // shutdown the executor
+        manager.graceful_shutdown();
+
+        assert!(!transactions_path.exists());
+        let snapshot = PoolSnapshot::decode(&fs::read(&snapshot_path).unwrap()).unwrap();
+        assert_eq!(snapshot.len(), 1);
+        assert_eq!(snapshot.transactions[0].origin, TransactionOrigin::Local);
+        assert_eq!(*snapshot.transactions[0].transaction.hash(), *transaction.hash());
+
+        temp_dir.close().unwrap();
+    }
 }
@@ -13,8 +13,9 @@ use crate::{
     validate::ValidTransaction,
     AllPoolTransactions, AllTransactionsEvents, BestTransactions, BlockInfo, EthPoolTransaction,
     EthPooledTransaction, NewTransactionEvent, PoolResult, PoolSize, PoolTransaction,
-    PooledTransactionsElement, PropagatedTransactions, TransactionEvents, TransactionOrigin,
-    TransactionPool, TransactionValidationOutcome, TransactionValidator, ValidPoolTransaction,
+    PooledTransactionsElement, PropagatedTransactions, SubPool, TransactionAuditEntry,
+    TransactionEvents, TransactionOrigin, TransactionPool, TransactionValidationOutcome,
+    TransactionValidator, ValidPoolTransaction,
 };
 use reth_eth_wire_types::HandleMempoolData;
 use reth_primitives::{Address, BlobTransactionSidecar, TxHash, U256};
@@ -173,6 +174,21 @@ impl TransactionPool for NoopTransactionPool {
         AllPoolTransactions::default()
     }
 
+    fn subpool_transactions(
+        &self,
+        _subpool: SubPool,
+    ) -> Vec<Arc<ValidPoolTransaction<Self::Transaction>>> {
+        vec![]
+    }
+
+    fn transaction_audit(&self, _tx_hash: &TxHash) -> Option<TransactionAuditEntry> {
+        None
+    }
+
+    fn transaction_audit_log(&self) -> Vec<TransactionAuditEntry> {
+        vec![]
+    }
+
     fn remove_transactions(
         &self,
         _hashes: Vec<TxHash>,
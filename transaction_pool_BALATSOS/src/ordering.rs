@@ -1,6 +1,6 @@
-use crate::traits::PoolTransaction;
+use crate::{traits::PoolTransaction, ValidPoolTransaction};
 use reth_primitives::U256;
-use std::{fmt, marker::PhantomData};
+use std::{cmp::Reverse, fmt, marker::PhantomData, time::Instant};
 
 /// Priority of the transaction that can be missing.
 ///
@@ -39,6 +39,19 @@ pub trait TransactionOrdering: Send + Sync + 'static {
         transaction: &Self::Transaction,
         base_fee: u64,
     ) -> Priority<Self::PriorityValue>;
+
+    /// Returns the priority score for the given transaction of the pool.
+    ///
+    /// Unlike [`Self::priority`], this has access to the metadata the pool tracks for the
+    /// transaction, like its origin and the time it was added to the pool. This is what the pool
+    /// uses to order its pending transactions, and defaults to [`Self::priority`].
+    fn pool_priority(
+        &self,
+        transaction: &ValidPoolTransaction<Self::Transaction>,
+        base_fee: u64,
+    ) -> Priority<Self::PriorityValue> {
+        self.priority(&transaction.transaction, base_fee)
+    }
 }
 
 /// Default ordering for the pool.
@@ -79,3 +92,374 @@ impl<T> Clone for CoinbaseTipOrdering<T> {
         Self::default()
     }
 }
+
+/// Ranks transactions that were added to the pool earlier higher.
+///
+/// The arrival time of a transaction is only known once it is in the pool, so
+/// [`TransactionOrdering::priority`] of the orderings that use it falls back to the lowest rank.
+#[derive(Debug, Clone, Copy)]
+struct ArrivalRank {
+    /// The instant arrival times are measured from.
+    epoch: Instant,
+}
+
+impl ArrivalRank {
+    fn new() -> Self {
+        Self { epoch: Instant::now() }
+    }
+
+    /// Returns the rank of the given transaction, which is higher the earlier the transaction was
+    /// added to the pool.
+    fn rank<T: PoolTransaction>(&self, transaction: &ValidPoolTransaction<T>) -> Reverse<u64> {
+        Reverse(transaction.timestamp.saturating_duration_since(self.epoch).as_nanos() as u64)
+    }
+
+    /// The rank of transactions with an unknown arrival time.
+    const fn unknown() -> Reverse<u64> {
+        Reverse(u64::MAX)
+    }
+}
+
+/// Strict first-in-first-out ordering.
+///
+/// The transactions are ordered by the time they were added to the pool, regardless of their
+/// fees. This is mostly useful for private sequencers, which must include transactions in the
+/// order they were received.
+#[derive(Debug)]
+pub struct FifoOrdering<T> {
+    arrival: ArrivalRank,
+    _phantom: PhantomData<T>,
+}
+
+impl<T> TransactionOrdering for FifoOrdering<T>
+where
+    T: PoolTransaction + 'static,
+{
+    type PriorityValue = Reverse<u64>;
+    type Transaction = T;
+
+    fn priority(
+        &self,
+        _transaction: &Self::Transaction,
+        _base_fee: u64,
+    ) -> Priority<Self::PriorityValue> {
+        Priority::Value(ArrivalRank::unknown())
+    }
+
+    fn pool_priority(
+        &self,
+        transaction: &ValidPoolTransaction<Self::Transaction>,
+        _base_fee: u64,
+    ) -> Priority<Self::PriorityValue> {
+        Priority::Value(self.arrival.rank(transaction))
+    }
+}
+
+impl<T> Default for FifoOrdering<T> {
+    fn default() -> Self {
+        Self { arrival: ArrivalRank::new(), _phantom: PhantomData }
+    }
+}
+
+impl<T> Clone for FifoOrdering<T> {
+    fn clone(&self) -> Self {
+        Self { arrival: self.arrival, _phantom: PhantomData }
+    }
+}
+
+/// Orders the transactions by their coinbase tip, like [`CoinbaseTipOrdering`], and transactions
+/// with the same tip by the time they were added to the pool.
+#[derive(Debug)]
+pub struct TipThenArrivalOrdering<T> {
+    arrival: ArrivalRank,
+    _phantom: PhantomData<T>,
+}
+
+impl<T> TransactionOrdering for TipThenArrivalOrdering<T>
+where
+    T: PoolTransaction + 'static,
+{
+    type PriorityValue = (U256, Reverse<u64>);
+    type Transaction = T;
+
+    fn priority(
+        &self,
+        transaction: &Self::Transaction,
+        base_fee: u64,
+    ) -> Priority<Self::PriorityValue> {
+        transaction
+            .effective_tip_per_gas(base_fee)
+            .map(|tip| (U256::from(tip), ArrivalRank::unknown()))
+            .into()
+    }
+
+    fn pool_priority(
+        &self,
+        transaction: &ValidPoolTransaction<Self::Transaction>,
+        base_fee: u64,
+    ) -> Priority<Self::PriorityValue> {
+        transaction
+            .transaction
+            .effective_tip_per_gas(base_fee)
+            .map(|tip| (U256::from(tip), self.arrival.rank(transaction)))
+            .into()
+    }
+}
+
+impl<T> Default for TipThenArrivalOrdering<T> {
+    fn default() -> Self {
+        Self { arrival: ArrivalRank::new(), _phantom: PhantomData }
+    }
+}
+
+impl<T> Clone for TipThenArrivalOrdering<T> {
+    fn clone(&self) -> Self {
+        Self { arrival: self.arrival, _phantom: PhantomData }
+    }
+}
+
+/// The default boost (in %) of [`LocalBoostOrdering`].
+pub const DEFAULT_LOCAL_BOOST: u64 = 100;
+
+/// Orders the transactions by their coinbase tip, like [`CoinbaseTipOrdering`], but boosts the
+/// tip of [`TransactionOrigin::Local`](crate::TransactionOrigin::Local) and
+/// [`TransactionOrigin::Private`](crate::TransactionOrigin::Private) transactions by a
+/// configurable percentage.
+///
+/// With the default boost of 100%, a local transaction is ranked like an external transaction
+/// that pays twice its tip.
+#[derive(Debug)]
+pub struct LocalBoostOrdering<T> {
+    /// The boost (in %) of the tip of local transactions.
+    boost: u64,
+    _phantom: PhantomData<T>,
+}
+
+impl<T> LocalBoostOrdering<T> {
+    /// Creates a new ordering that boosts the tip of local transactions by the given percentage.
+    pub const fn new(boost: u64) -> Self {
+        Self { boost, _phantom: PhantomData }
+    }
+
+    /// Returns the boost (in %) of the tip of local transactions.
+    pub const fn boost(&self) -> u64 {
+        self.boost
+    }
+}
+
+impl<T> TransactionOrdering for LocalBoostOrdering<T>
+where
+    T: PoolTransaction + 'static,
+{
+    type PriorityValue = U256;
+    type Transaction = T;
+
+    fn priority(
+        &self,
+        transaction: &Self::Transaction,
+        base_fee: u64,
+    ) -> Priority<Self::PriorityValue> {
+        transaction.effective_tip_per_gas(base_fee).map(U256::from).into()
+    }
+
+    fn pool_priority(
+        &self,
+        transaction: &ValidPoolTransaction<Self::Transaction>,
+        base_fee: u64,
+    ) -> Priority<Self::PriorityValue> {
+        let tip = transaction.transaction.effective_tip_per_gas(base_fee).map(U256::from);
+        if transaction.origin.is_external() {
+            return tip.into()
+        }
+
+        tip.map(|tip| tip.saturating_mul(U256::from(100 + self.boost)) / U256::from(100)).into()
+    }
+}
+
+impl<T> Default for LocalBoostOrdering<T> {
+    fn default() -> Self {
+        Self::new(DEFAULT_LOCAL_BOOST)
+    }
+}
+
+impl<T> Clone for LocalBoostOrdering<T> {
+    fn clone(&self) -> Self {
+        Self::new(self.boost)
+    }
+}
+
+/// The built-in orderings that can be selected at runtime, see [`ConfiguredOrdering`].
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq)]
+pub enum TransactionOrderingKind {
+    /// See [`CoinbaseTipOrdering`].
+    #[default]
+    CoinbaseTip,
+    /// See [`FifoOrdering`].
+    Fifo,
+    /// See [`TipThenArrivalOrdering`].
+    TipThenArrival,
+    /// See [`LocalBoostOrdering`], with the given boost (in %).
+    LocalBoost(u64),
+}
+
+/// An ordering that is selected at runtime, for example through the CLI.
+///
+/// All built-in orderings share the same [`TransactionOrdering::PriorityValue`] of a tip and an
+/// arrival rank, so that a pool with this ordering can be configured with any of them. Orderings
+/// that don't take the arrival time into account leave ties to the pool.
+#[derive(Debug)]
+pub struct ConfiguredOrdering<T> {
+    kind: TransactionOrderingKind,
+    arrival: ArrivalRank,
+    _phantom: PhantomData<T>,
+}
+
+impl<T> ConfiguredOrdering<T> {
+    /// Creates a new ordering of the given kind.
+    pub fn new(kind: TransactionOrderingKind) -> Self {
+        Self { kind, arrival: ArrivalRank::new(), _phantom: PhantomData }
+    }
+
+    /// Returns the kind of the ordering.
+    pub const fn kind(&self) -> TransactionOrderingKind {
+        self.kind
+    }
+}
+
+impl<T> TransactionOrdering for ConfiguredOrdering<T>
+where
+    T: PoolTransaction + 'static,
+{
+    type PriorityValue = (U256, Reverse<u64>);
+    type Transaction = T;
+
+    fn priority(
+        &self,
+        transaction: &Self::Transaction,
+        base_fee: u64,
+    ) -> Priority<Self::PriorityValue> {
+        match self.kind {
+            TransactionOrderingKind::Fifo => Priority::Value((U256::ZERO, ArrivalRank::unknown())),
+            _ => transaction
+                .effective_tip_per_gas(base_fee)
+                .map(|tip| (U256::from(tip), ArrivalRank::unknown()))
+                .into(),
+        }
+    }
+
+    fn pool_priority(
+        &self,
+        transaction: &ValidPoolTransaction<Self::Transaction>,
+        base_fee: u64,
+    ) -> Priority<Self::PriorityValue> {
+        let tip = match self.kind {
+            TransactionOrderingKind::CoinbaseTip => {
+                CoinbaseTipOrdering::default().pool_priority(transaction, base_fee)
+            }
+            TransactionOrderingKind::LocalBoost(boost) => {
+                LocalBoostOrdering::new(boost).pool_priority(transaction, base_fee)
+            }
+            TransactionOrderingKind::Fifo => {
+                return Priority::Value((U256::ZERO, self.arrival.rank(transaction)))
+            }
+            TransactionOrderingKind::TipThenArrival => {
+                return transaction
+                    .transaction
+                    .effective_tip_per_gas(base_fee)
+                    .map(|tip| (U256::from(tip), self.arrival.rank(transaction)))
+                    .into()
+            }
+        };
+
+        match tip {
+            Priority::Value(tip) => Priority::Value((tip, ArrivalRank::unknown())),
+            Priority::None => Priority::None,
+        }
+    }
+}
+
+impl<T> Default for ConfiguredOrdering<T> {
+    fn default() -> Self {
+        Self::new(TransactionOrderingKind::default())
+    }
+}
+
+impl<T> Clone for ConfiguredOrdering<T> {
+    fn clone(&self) -> Self {
+        Self { kind: self.kind, arrival: self.arrival, _phantom: PhantomData }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::{
+        identifier::{SenderId, TransactionId},
+        test_utils::MockTransaction,
+        TransactionOrigin,
+    };
+    use std::time::Duration;
+
+    fn tx_with_tip(tip: u128) -> MockTransaction {
+        MockTransaction::eip1559().with_max_fee(tip).with_priority_fee(tip)
+    }
+
+    fn pool_transaction(
+        transaction: MockTransaction,
+        origin: TransactionOrigin,
+        timestamp: Instant,
+    ) -> ValidPoolTransaction<MockTransaction> {
+        ValidPoolTransaction {
+            transaction_id: TransactionId::new(SenderId::from(0), transaction.get_nonce()),
+            transaction,
+            propagate: true,
+            timestamp,
+            origin,
+        }
+    }
+
+    #[test]
+    fn fifo_ranks_earlier_transactions_higher() {
+        let ordering = FifoOrdering::default();
+        let now = Instant::now();
+        let cheap = pool_transaction(tx_with_tip(1), TransactionOrigin::External, now);
+        let expensive = pool_transaction(
+            tx_with_tip(100),
+            TransactionOrigin::External,
+            now + Duration::from_secs(1),
+        );
+
+        assert!(ordering.pool_priority(&cheap, 0) > ordering.pool_priority(&expensive, 0));
+    }
+
+    #[test]
+    fn tip_then_arrival_breaks_ties_by_arrival() {
+        let ordering = TipThenArrivalOrdering::default();
+        let now = Instant::now();
+        let first = pool_transaction(tx_with_tip(10), TransactionOrigin::External, now);
+        let second = pool_transaction(
+            tx_with_tip(10),
+            TransactionOrigin::External,
+            now + Duration::from_secs(1),
+        );
+        let expensive = pool_transaction(
+            tx_with_tip(20),
+            TransactionOrigin::External,
+            now + Duration::from_secs(2),
+        );
+
+        assert!(ordering.pool_priority(&first, 0) > ordering.pool_priority(&second, 0));
+        assert!(ordering.pool_priority(&expensive, 0) > ordering.pool_priority(&first, 0));
+    }
+
+    #[test]
+    fn local_boost() {
+        let ordering = LocalBoostOrdering::new(100);
+        let now = Instant::now();
+        let local = pool_transaction(tx_with_tip(10), TransactionOrigin::Local, now);
+        let external = pool_transaction(tx_with_tip(15), TransactionOrigin::External, now);
+
+        assert_eq!(ordering.pool_priority(&local, 0), Priority::Value(U256::from(20)));
+        assert!(ordering.pool_priority(&local, 0) > ordering.pool_priority(&external, 0));
+    }
+}
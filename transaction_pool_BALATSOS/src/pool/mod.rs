@@ -66,6 +66,8 @@
 //!    category (2.) and become pending.
 
 use crate::{
+    admission::{AdmissionPolicy, AdmissionRequest},
+    audit::{TransactionAuditEntry, TransactionAuditLog, TransactionRemovalReason},
     error::{PoolError, PoolErrorKind, PoolResult},
     identifier::{SenderId, SenderIdentifiers, TransactionId},
     pool::{
@@ -149,6 +151,8 @@ where
     blob_transaction_sidecar_listener: Mutex<Vec<BlobTransactionSidecarListener>>,
     /// Metrics for the blob store
     blob_store_metrics: BlobStoreMetrics,
+    /// Bounded record of transactions that left the pool.
+    audit_log: Mutex<TransactionAuditLog>,
 }
 
 // === impl PoolInner ===
@@ -169,6 +173,7 @@ where
             pending_transaction_listener: Default::default(),
             transaction_listener: Default::default(),
             blob_transaction_sidecar_listener: Default::default(),
+            audit_log: Mutex::new(TransactionAuditLog::new(config.audit_log_max_entries)),
             config,
             blob_store,
             blob_store_metrics: Default::default(),
@@ -386,6 +391,10 @@ where
         // This will discard outdated transactions based on the account's nonce
         self.delete_discarded_blobs(outcome.discarded.iter());
 
+        self.record_removals(outcome.discarded.iter().map(|tx| {
+            (tx, TransactionRemovalReason::InvalidatedByBlock(block_info.last_seen_block_number))
+        }));
+
         // notify listeners about updates
         self.notify_on_new_state(outcome);
     }
@@ -405,6 +414,13 @@ where
         // This deletes outdated blob txs from the blob store, based on the account's nonce. This is
         // called during txpool maintenance when the pool drifted.
         self.delete_discarded_blobs(discarded.iter());
+
+        let block_number = self.block_info().last_seen_block_number;
+        self.record_removals(
+            discarded
+                .iter()
+                .map(|tx| (tx, TransactionRemovalReason::InvalidatedByBlock(block_number))),
+        );
     }
 
     /// Add a single validated transaction into the pool.
@@ -423,6 +439,17 @@ where
                 transaction,
                 propagate,
             } => {
+                if let Err(rejection) = self
+                    .config
+                    .admission_policies
+                    .admit(&AdmissionRequest::new(transaction.transaction(), origin))
+                {
+                    let hash = *transaction.hash();
+                    trace!(target: "txpool", %hash, %rejection, "Transaction rejected by admission policy");
+                    self.event_listener.write().discarded(&hash);
+                    return Err(PoolError::new(hash, rejection))
+                }
+
                 let sender_id = self.get_sender_id(transaction.sender());
                 let transaction_id = TransactionId::new(sender_id, transaction.nonce());
 
@@ -449,6 +476,11 @@ where
                 let added = self.pool.write().add_transaction(tx, balance, state_nonce)?;
                 let hash = *added.hash();
 
+                // only transactions that made it into the pool count against the policies
+                self.config
+                    .admission_policies
+                    .on_inserted(&AdmissionRequest::new(&added.transaction().transaction, origin));
+
                 // transaction was successfully inserted into the pool
                 if let Some(sidecar) = maybe_sidecar {
                     // notify blob sidecar listeners
@@ -474,6 +506,8 @@ where
                     self.delete_discarded_blobs(discarded.iter());
                 }
 
+                self.record_added_removals(&added);
+
                 // Notify listeners for _all_ transactions
                 self.on_new_transaction(added.into_new_transaction_event());
 
@@ -679,6 +713,14 @@ where
         }
     }
 
+    /// Returns all transactions in the given sub-pool
+    pub(crate) fn subpool_transactions(
+        &self,
+        subpool: SubPool,
+    ) -> Vec<Arc<ValidPoolTransaction<T::Transaction>>> {
+        self.get_pool_data().subpool_transactions(subpool)
+    }
+
     /// Removes and returns all matching transactions from the pool.
     pub(crate) fn remove_transactions(
         &self,
@@ -693,6 +735,8 @@ where
 
         removed.iter().for_each(|tx| listener.discarded(tx.hash()));
 
+        self.record_removals(removed.iter().map(|tx| (tx, TransactionRemovalReason::Removed)));
+
         removed
     }
 
@@ -776,15 +820,91 @@ where
     /// If some of the transactions are blob transactions, they are also removed from the blob
     /// store.
     pub(crate) fn discard_worst(&self) -> HashSet<TxHash> {
-        let discarded = self.pool.write().discard_worst();
+        let (block_info, discarded) = {
+            let mut pool = self.pool.write();
+            (pool.block_info(), pool.discard_worst())
+        };
 
         // delete any blobs associated with discarded blob transactions
         self.delete_discarded_blobs(discarded.iter());
 
+        // only the transaction with the lowest nonce of each sender was discarded because of the
+        // limits, all others were discarded because of the nonce gap this left
+        let mut lowest_nonces = HashMap::<SenderId, u64>::with_capacity(discarded.len());
+        for tx in &discarded {
+            lowest_nonces
+                .entry(tx.sender_id())
+                .and_modify(|nonce| *nonce = (*nonce).min(tx.nonce()))
+                .or_insert_with(|| tx.nonce());
+        }
+        self.record_removals(discarded.iter().map(|tx| {
+            let reason = if lowest_nonces[&tx.sender_id()] < tx.nonce() {
+                TransactionRemovalReason::NonceGap
+            } else if tx.max_fee_per_gas() < block_info.pending_basefee as u128 {
+                TransactionRemovalReason::UnderpricedAfterBaseFeeChange(block_info.pending_basefee)
+            } else {
+                TransactionRemovalReason::DiscardedForSize
+            };
+            (tx, reason)
+        }));
+
         // then collect into tx hashes
         discarded.into_iter().map(|tx| *tx.hash()).collect()
     }
 
+    /// Records the transactions that were removed when the given transaction was added.
+    ///
+    /// The previous entry of the added transaction itself is removed, since a transaction that
+    /// re-entered the pool no longer has a removal reason.
+    fn record_added_removals(&self, added: &AddedTransaction<T::Transaction>) {
+        if self.config.audit_log_max_entries != 0 {
+            self.audit_log.lock().remove(added.hash());
+        }
+
+        let replaced = added
+            .replaced()
+            .map(|tx| (tx, TransactionRemovalReason::Replaced(*added.hash())))
+            .into_iter();
+
+        let block_number = self.block_info().last_seen_block_number;
+        let discarded = added
+            .discarded_transactions()
+            .unwrap_or_default()
+            .iter()
+            .map(|tx| (tx, TransactionRemovalReason::InvalidatedByBlock(block_number)));
+
+        self.record_removals(replaced.chain(discarded));
+    }
+
+    /// Records the removal of the given transactions in the audit log.
+    fn record_removals<'a>(
+        &self,
+        removed: impl IntoIterator<
+            Item = (&'a Arc<ValidPoolTransaction<T::Transaction>>, TransactionRemovalReason),
+        >,
+    ) {
+        let mut removed = removed.into_iter().peekable();
+        if self.config.audit_log_max_entries == 0 || removed.peek().is_none() {
+            return
+        }
+
+        let block_number = self.block_info().last_seen_block_number;
+        let mut audit_log = self.audit_log.lock();
+        for (tx, reason) in removed {
+            audit_log.record(TransactionAuditEntry::new(tx, reason, block_number));
+        }
+    }
+
+    /// Returns the audit log entry of the given transaction if it left the pool.
+    pub(crate) fn transaction_audit(&self, tx_hash: &TxHash) -> Option<TransactionAuditEntry> {
+        self.audit_log.lock().get(tx_hash).cloned()
+    }
+
+    /// Returns all audit log entries, most recently removed first.
+    pub(crate) fn transaction_audit_log(&self) -> Vec<TransactionAuditEntry> {
+        self.audit_log.lock().entries().cloned().collect()
+    }
+
     /// Inserts a blob transaction into the blob store
     fn insert_blob(&self, hash: TxHash, blob: BlobTransactionSidecar) {
         if let Err(err) = self.blob_store.insert(hash, blob) {
@@ -1049,6 +1169,14 @@ impl<T: PoolTransaction> AddedTransaction<T> {
         self.replaced().filter(|tx| tx.transaction.is_eip4844()).map(|tx| *tx.transaction.hash())
     }
 
+    /// Returns the inserted transaction
+    pub(crate) const fn transaction(&self) -> &Arc<ValidPoolTransaction<T>> {
+        match self {
+            Self::Pending(tx) => &tx.transaction,
+            Self::Parked { transaction, .. } => transaction,
+        }
+    }
+
     /// Returns the hash of the transaction
     pub(crate) fn hash(&self) -> &TxHash {
         match self {
@@ -1135,7 +1263,8 @@ mod tests {
         blobstore::{BlobStore, InMemoryBlobStore},
         test_utils::{MockTransaction, TestPoolBuilder},
         validate::ValidTransaction,
-        BlockInfo, PoolConfig, SubPoolLimit, TransactionOrigin, TransactionValidationOutcome, U256,
+        BlockInfo, PoolConfig, SubPoolLimit, TransactionOrigin, TransactionRemovalReason,
+        TransactionValidationOutcome, U256,
     };
     use reth_primitives::{kzg::Blob, transaction::generate_blob_sidecar};
     use std::{fs, path::PathBuf};
@@ -1219,4 +1348,77 @@ mod tests {
         // Assert that the pool's blob store matches the expected blob store.
         assert_eq!(*test_pool.blob_store(), blob_store);
     }
+
+    #[test]
+    fn test_audit_discard_worst() {
+        let no_txs = SubPoolLimit::new(0, usize::MAX);
+        let test_pool = &TestPoolBuilder::default()
+            .with_config(PoolConfig {
+                pending_limit: no_txs,
+                basefee_limit: no_txs,
+                ..Default::default()
+            })
+            .pool;
+        test_pool.set_block_info(BlockInfo { pending_basefee: 100, ..Default::default() });
+
+        let first = MockTransaction::eip1559().with_gas_price(1_000);
+        let second = first.next();
+        let underpriced = MockTransaction::eip1559().with_gas_price(10);
+        for tx in [first.clone(), second.clone(), underpriced.clone()] {
+            test_pool
+                .add_transaction(
+                    TransactionOrigin::External,
+                    TransactionValidationOutcome::Valid {
+                        balance: U256::MAX,
+                        state_nonce: 0,
+                        transaction: ValidTransaction::Valid(tx),
+                        propagate: true,
+                    },
+                )
+                .unwrap();
+        }
+
+        let discarded = test_pool.discard_worst();
+        assert_eq!(discarded.len(), 3);
+
+        let reason =
+            |tx: &MockTransaction| test_pool.transaction_audit(&tx.get_hash()).unwrap().reason;
+        assert_eq!(reason(&first), TransactionRemovalReason::DiscardedForSize);
+        assert_eq!(reason(&second), TransactionRemovalReason::NonceGap);
+        assert_eq!(
+            reason(&underpriced),
+            TransactionRemovalReason::UnderpricedAfterBaseFeeChange(100)
+        );
+    }
+
+    #[test]
+    fn test_audit_reinserted_transaction() {
+        let test_pool = &TestPoolBuilder::default().pool;
+        let tx = MockTransaction::eip1559();
+        let add = |tx: MockTransaction| {
+            test_pool.add_transaction(
+                TransactionOrigin::External,
+                TransactionValidationOutcome::Valid {
+                    balance: U256::MAX,
+                    state_nonce: 0,
+                    transaction: ValidTransaction::Valid(tx),
+                    propagate: true,
+                },
+            )
+        };
+
+        add(tx.clone()).unwrap();
+        assert!(test_pool.transaction_audit(&tx.get_hash()).is_none());
+
+        test_pool.remove_transactions(vec![tx.get_hash()]);
+        assert_eq!(
+            test_pool.transaction_audit(&tx.get_hash()).unwrap().reason,
+            TransactionRemovalReason::Removed
+        );
+
+        // the transaction is live again
+        add(tx.clone()).unwrap();
+        assert!(test_pool.transaction_audit(&tx.get_hash()).is_none());
+        assert!(test_pool.transaction_audit_log().is_empty());
+    }
 }
@@ -327,6 +327,19 @@ impl<T: TransactionOrdering> TxPool<T> {
         self.basefee_pool.all().chain(self.queued_pool.all()).collect()
     }
 
+    /// Returns all transactions that currently reside in the given sub-pool
+    pub(crate) fn subpool_transactions(
+        &self,
+        subpool: SubPool,
+    ) -> Vec<Arc<ValidPoolTransaction<T::Transaction>>> {
+        self.all_transactions
+            .txs
+            .values()
+            .filter(|tx| tx.subpool == subpool)
+            .map(|tx| Arc::clone(&tx.transaction))
+            .collect()
+    }
+
     /// Returns queued and pending transactions for the specified sender
     pub fn queued_and_pending_txs_by_sender(
         &self,
@@ -0,0 +1,242 @@
+//! Snapshots of the entire transaction pool.
+//!
+//! Unlike the local transactions backup, a snapshot covers the transactions of all origins and
+//! sub-pools, together with the metadata that is needed to restore them after a restart.
+//!
+//! A snapshot file starts with the [`POOL_SNAPSHOT_MAGIC`] bytes, followed by a single version
+//! byte and the RLP encoded list of [`PoolSnapshotEntry`]s. Transactions are stored in their
+//! network encoding, so blob transactions include their sidecar.
+
+use crate::{maintain::TransactionsBackupError, SubPool, TransactionOrigin, TransactionPool};
+use alloy_rlp::{BufMut, Decodable, Encodable, Header};
+use reth_primitives::PooledTransactionsElement;
+use std::time::{SystemTime, UNIX_EPOCH};
+
+/// The magic bytes every snapshot file starts with.
+pub const POOL_SNAPSHOT_MAGIC: [u8; 4] = *b"rtxp";
+
+/// The current version of the snapshot file format.
+pub const POOL_SNAPSHOT_VERSION: u8 = 1;
+
+/// The sub-pools that are included in a snapshot, in the order they are written.
+const SNAPSHOT_SUBPOOLS: [SubPool; 4] =
+    [SubPool::Pending, SubPool::BaseFee, SubPool::Queued, SubPool::Blob];
+
+/// A snapshot of all transactions in the pool.
+#[derive(Debug, Clone, Default, PartialEq, Eq)]
+pub struct PoolSnapshot {
+    /// The transactions of the pool, grouped by sub-pool.
+    pub transactions: Vec<PoolSnapshotEntry>,
+}
+
+impl PoolSnapshot {
+    /// Takes a snapshot of all transactions in the given pool.
+    ///
+    /// Transactions that are removed from the pool while the snapshot is taken are skipped.
+    pub fn from_pool<P>(pool: &P) -> Self
+    where
+        P: TransactionPool,
+    {
+        let now = unix_timestamp_millis();
+
+        let mut transactions = Vec::new();
+        for subpool in SNAPSHOT_SUBPOOLS {
+            for tx in pool.subpool_transactions(subpool) {
+                let Some(transaction) = pool.get_pooled_transaction_element(*tx.hash()) else {
+                    continue
+                };
+
+                transactions.push(PoolSnapshotEntry {
+                    transaction,
+                    origin: tx.origin,
+                    subpool,
+                    timestamp: now.saturating_sub(tx.timestamp.elapsed().as_millis() as u64),
+                });
+            }
+        }
+
+        Self { transactions }
+    }
+
+    /// Returns the number of transactions in the snapshot.
+    pub fn len(&self) -> usize {
+        self.transactions.len()
+    }
+
+    /// Returns `true` if the snapshot contains no transactions.
+    pub fn is_empty(&self) -> bool {
+        self.transactions.is_empty()
+    }
+
+    /// Encodes the snapshot in the current version of the file format.
+    pub fn encode(&self) -> Vec<u8> {
+        let mut buf = Vec::with_capacity(POOL_SNAPSHOT_MAGIC.len() + 1);
+        buf.extend_from_slice(&POOL_SNAPSHOT_MAGIC);
+        buf.push(POOL_SNAPSHOT_VERSION);
+        alloy_rlp::encode_list(&self.transactions, &mut buf);
+        buf
+    }
+
+    /// Decodes a snapshot from the given file contents.
+    pub fn decode(data: &[u8]) -> Result<Self, TransactionsBackupError> {
+        let data = data
+            .strip_prefix(&POOL_SNAPSHOT_MAGIC)
+            .ok_or(TransactionsBackupError::InvalidSnapshot)?;
+        let (&version, mut data) =
+            data.split_first().ok_or(TransactionsBackupError::InvalidSnapshot)?;
+        if version != POOL_SNAPSHOT_VERSION {
+            return Err(TransactionsBackupError::UnsupportedSnapshotVersion(version))
+        }
+
+        let transactions = Vec::<PoolSnapshotEntry>::decode(&mut data)?;
+        Ok(Self { transactions })
+    }
+}
+
+/// A single transaction of a [`PoolSnapshot`].
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct PoolSnapshotEntry {
+    /// The transaction in its network encoding.
+    pub transaction: PooledTransactionsElement,
+    /// Where the transaction originated from.
+    pub origin: TransactionOrigin,
+    /// The sub-pool the transaction resided in when the snapshot was taken.
+    pub subpool: SubPool,
+    /// Unix timestamp in milliseconds at which the transaction was added to the pool.
+    pub timestamp: u64,
+}
+
+impl PoolSnapshotEntry {
+    /// Returns the length of the RLP encoded fields of the entry.
+    fn fields_len(&self) -> usize {
+        self.transaction.length() +
+            encode_origin(self.origin).length() +
+            (self.subpool as u8).length() +
+            self.timestamp.length()
+    }
+}
+
+impl Encodable for PoolSnapshotEntry {
+    fn encode(&self, out: &mut dyn BufMut) {
+        Header { list: true, payload_length: self.fields_len() }.encode(out);
+        self.transaction.encode(out);
+        encode_origin(self.origin).encode(out);
+        (self.subpool as u8).encode(out);
+        self.timestamp.encode(out);
+    }
+
+    fn length(&self) -> usize {
+        let payload_length = self.fields_len();
+        payload_length + alloy_rlp::length_of_length(payload_length)
+    }
+}
+
+impl Decodable for PoolSnapshotEntry {
+    fn decode(buf: &mut &[u8]) -> alloy_rlp::Result<Self> {
+        let header = Header::decode(buf)?;
+        if !header.list {
+            return Err(alloy_rlp::Error::UnexpectedString)
+        }
+        let remaining = buf.len();
+
+        let transaction = PooledTransactionsElement::decode(buf)?;
+        let origin = decode_origin(u8::decode(buf)?)
+            .ok_or(alloy_rlp::Error::Custom("invalid transaction origin"))?;
+        let subpool =
+            decode_subpool(u8::decode(buf)?).ok_or(alloy_rlp::Error::Custom("invalid sub-pool"))?;
+        let timestamp = u64::decode(buf)?;
+
+        let consumed = remaining - buf.len();
+        if consumed != header.payload_length {
+            return Err(alloy_rlp::Error::ListLengthMismatch {
+                expected: header.payload_length,
+                got: consumed,
+            })
+        }
+
+        Ok(Self { transaction, origin, subpool, timestamp })
+    }
+}
+
+/// Returns the identifier of the origin in the snapshot file format.
+const fn encode_origin(origin: TransactionOrigin) -> u8 {
+    match origin {
+        TransactionOrigin::Local => 0,
+        TransactionOrigin::External => 1,
+        TransactionOrigin::Private => 2,
+    }
+}
+
+/// Returns the origin with the given identifier in the snapshot file format.
+const fn decode_origin(origin: u8) -> Option<TransactionOrigin> {
+    match origin {
+        0 => Some(TransactionOrigin::Local),
+        1 => Some(TransactionOrigin::External),
+        2 => Some(TransactionOrigin::Private),
+        _ => None,
+    }
+}
+
+/// Returns the sub-pool with the given identifier in the snapshot file format.
+const fn decode_subpool(subpool: u8) -> Option<SubPool> {
+    match subpool {
+        0 => Some(SubPool::Queued),
+        1 => Some(SubPool::BaseFee),
+        2 => Some(SubPool::Blob),
+        3 => Some(SubPool::Pending),
+        _ => None,
+    }
+}
+
+/// Returns the current unix timestamp in milliseconds.
+pub(crate) fn unix_timestamp_millis() -> u64 {
+    SystemTime::now().duration_since(UNIX_EPOCH).unwrap_or_default().as_millis() as u64
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use reth_primitives::hex;
+
+    fn entry(origin: TransactionOrigin, subpool: SubPool) -> PoolSnapshotEntry {
+        let tx_bytes = hex!("02f87201830655c2808505ef61f08482565f94388c818ca8b9251b393131c08a736a67ccb192978801049e39c4b5b1f580c001a01764ace353514e8abdfb92446de356b260e3c1225b73fc4c8876a6258d12a129a04f02294aa61ca7676061cd99f29275491218b4754b46a0248e5e42bc5091f507");
+        let transaction = PooledTransactionsElement::decode_enveloped(&mut &tx_bytes[..]).unwrap();
+        PoolSnapshotEntry { transaction, origin, subpool, timestamp: 1_700_000_000_000 }
+    }
+
+    #[test]
+    fn snapshot_roundtrip() {
+        let snapshot = PoolSnapshot {
+            transactions: vec![
+                entry(TransactionOrigin::Local, SubPool::Pending),
+                entry(TransactionOrigin::External, SubPool::BaseFee),
+                entry(TransactionOrigin::Private, SubPool::Queued),
+            ],
+        };
+
+        let decoded = PoolSnapshot::decode(&snapshot.encode()).unwrap();
+        assert_eq!(decoded, snapshot);
+    }
+
+    #[test]
+    fn rejects_unknown_versions() {
+        let next_version = POOL_SNAPSHOT_VERSION + 1;
+        let mut data = PoolSnapshot::default().encode();
+        data[POOL_SNAPSHOT_MAGIC.len()] = next_version;
+        let err = PoolSnapshot::decode(&data).unwrap_err();
+        assert!(
+            matches!(err, TransactionsBackupError::UnsupportedSnapshotVersion(v) if v == next_version)
+        );
+
+        // the RLP encoded local transactions backup is not a valid snapshot
+        let mut backup = Vec::new();
+        alloy_rlp::encode_list(
+            &[entry(TransactionOrigin::Local, SubPool::Pending).transaction],
+            &mut backup,
+        );
+        assert!(matches!(
+            PoolSnapshot::decode(&backup),
+            Err(TransactionsBackupError::InvalidSnapshot)
+        ));
+    }
+}
@@ -1,6 +1,7 @@
 #![allow(deprecated)]
 
 use crate::{
+    audit::TransactionAuditEntry,
     blobstore::BlobStoreError,
     error::PoolResult,
     pool::{state::SubPool, BestTransactionFilter, TransactionEvents},
@@ -289,6 +290,16 @@ pub trait TransactionPool: Send + Sync + Clone {
     /// Consumer: RPC
     fn all_transactions(&self) -> AllPoolTransactions<Self::Transaction>;
 
+    /// Returns all transactions that currently reside in the given [SubPool].
+    ///
+    /// Unlike [Self::queued_transactions], this also covers the blob sub-pool.
+    ///
+    /// Consumer: Utility
+    fn subpool_transactions(
+        &self,
+        subpool: SubPool,
+    ) -> Vec<Arc<ValidPoolTransaction<Self::Transaction>>>;
+
     /// Removes all transactions corresponding to the given hashes.
     ///
     /// Also removes all _dependent_ transactions.
@@ -299,6 +310,20 @@ pub trait TransactionPool: Send + Sync + Clone {
         hashes: Vec<TxHash>,
     ) -> Vec<Arc<ValidPoolTransaction<Self::Transaction>>>;
 
+    /// Returns the audit log entry that records why the transaction with the given hash left the
+    /// pool.
+    ///
+    /// Returns `None` if the transaction is still in the pool, was mined, or its entry was already
+    /// evicted from the bounded audit log.
+    ///
+    /// Consumer: RPC
+    fn transaction_audit(&self, tx_hash: &TxHash) -> Option<TransactionAuditEntry>;
+
+    /// Returns all entries of the audit log, most recently removed first.
+    ///
+    /// Consumer: RPC
+    fn transaction_audit_log(&self) -> Vec<TransactionAuditEntry>;
+
     /// Retains only those hashes that are unknown to the pool.
     /// In other words, removes all transactions from the given set that are currently present in
     /// the pool. Returns hashes already known to the pool.
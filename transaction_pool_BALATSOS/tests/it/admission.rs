@@ -0,0 +1,46 @@
+// Tests:
+// 1) txpool_admission_policies:
+//      - Creates a transaction pool that denies a single sender.
+//      - Verifies that transactions of the denied sender are rejected with an admission error, and
+//        are not added to the pool.
+//      - Verifies that transactions of other senders are added as usual.
+//
+
+use assert_matches::assert_matches;
+use reth_transaction_pool::{
+    admission::{AddressFilter, AdmissionPolicies, AdmissionRejection},
+    error::PoolErrorKind,
+    test_utils::{MockTransactionFactory, TestPoolBuilder},
+    PoolConfig, TransactionOrigin, TransactionPool,
+};
+
+#[tokio::test(flavor = "multi_thread")]
+async fn txpool_admission_policies() {
+    let mut mock_tx_factory = MockTransactionFactory::default();
+    let denied = mock_tx_factory.create_eip1559();
+    let allowed = mock_tx_factory.create_eip1559();
+
+    let denied_sender = denied.transaction.get_sender();
+    let pool_config = PoolConfig {
+        admission_policies: AdmissionPolicies::new()
+            .with_policy(AddressFilter::deny_senders([denied_sender])),
+        ..Default::default()
+    };
+    let txpool = TestPoolBuilder::default().with_config(pool_config);
+
+    let result =
+        txpool.add_transaction(TransactionOrigin::External, denied.transaction.clone()).await;
+    assert_matches!(
+        result,
+        Err(err) if matches!(
+            err.kind,
+            PoolErrorKind::AdmissionRejected(AdmissionRejection::SenderNotAllowed(sender))
+                if sender == denied_sender
+        )
+    );
+    assert!(!txpool.contains(&denied.transaction.get_hash()));
+
+    let result =
+        txpool.add_transaction(TransactionOrigin::External, allowed.transaction.clone()).await;
+    assert_matches!(result, Ok(hash) if hash == allowed.transaction.get_hash());
+}
@@ -0,0 +1,47 @@
+// Tests:
+// 1) txpool_audit_log:
+//      - Adds a transaction and replaces it with a higher priced transaction of the same sender and
+//        nonce.
+//      - Verifies that the audit log records the replacement together with the replacing hash.
+//      - Removes the replacing transaction and verifies that its removal is recorded as well, most
+//        recent entries first.
+//
+
+use reth_transaction_pool::{
+    test_utils::{MockTransactionFactory, TestPoolBuilder},
+    TransactionOrigin, TransactionPool, TransactionRemovalReason,
+};
+
+#[tokio::test(flavor = "multi_thread")]
+async fn txpool_audit_log() {
+    let txpool = TestPoolBuilder::default();
+    let mut mock_tx_factory = MockTransactionFactory::default();
+    let original = mock_tx_factory.create_eip1559();
+    let replacement = original.transaction.inc_price_by(10_000).rng_hash();
+
+    let original_hash = txpool
+        .add_transaction(TransactionOrigin::External, original.transaction.clone())
+        .await
+        .unwrap();
+    assert!(txpool.transaction_audit(&original_hash).is_none());
+
+    let replacement_hash =
+        txpool.add_transaction(TransactionOrigin::External, replacement).await.unwrap();
+    assert!(!txpool.contains(&original_hash));
+
+    let entry = txpool.transaction_audit(&original_hash).unwrap();
+    assert_eq!(entry.reason, TransactionRemovalReason::Replaced(replacement_hash));
+    assert_eq!(entry.sender, original.transaction.get_sender());
+    assert_eq!(entry.nonce, original.transaction.get_nonce());
+    assert_eq!(entry.origin, TransactionOrigin::External);
+
+    let removed = txpool.remove_transactions(vec![replacement_hash]);
+    assert_eq!(removed.len(), 1);
+
+    let entry = txpool.transaction_audit(&replacement_hash).unwrap();
+    assert_eq!(entry.reason, TransactionRemovalReason::Removed);
+
+    let log = txpool.transaction_audit_log();
+    let hashes = log.iter().map(|entry| entry.hash).collect::<Vec<_>>();
+    assert_eq!(hashes, vec![replacement_hash, original_hash]);
+}
@@ -8,6 +8,14 @@
 /// transactions, processing listeners, and managing pending transactions.
 ///
 
+/// Integration tests for transaction admission policies
+#[cfg(feature = "test-utils")]
+mod admission;
+
+/// Integration tests for the audit log of removed transactions
+#[cfg(feature = "test-utils")]
+mod audit;
+
 /// Integration tests for handling blob transactions
 #[cfg(feature = "test-utils")]
 mod blobs;